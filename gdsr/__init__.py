@@ -7,10 +7,18 @@ from .logging import setup_logger
 setup_logger()
 
 from ._gdsr import (
+    Box,
     Cell,
+    CellDiff,
+    DrcRule,
+    DrcViolation,
+    GdsCellIterator,
+    GdsElementIterator,
     Grid,
     HorizontalPresentation,
     Library,
+    LibraryDiff,
+    Node,
     Path,
     PathType,
     Point,
@@ -19,17 +27,38 @@ from ._gdsr import (
     Reference,
     Text,
     VerticalPresentation,
+    boolean,
+    drc,
+    offset,
+)
+from .typings import (
+    BooleanOperation,
+    InputPointsLike,
+    MaxPointsMode,
+    OffsetJoin,
+    PointLike,
+    Timestamp,
 )
-from .typings import InputPointsLike, PointLike
 
 __all__ = [
+    "BooleanOperation",
+    "Box",
     "Cell",
+    "CellDiff",
+    "DrcRule",
+    "DrcViolation",
     "Element",
+    "GdsCellIterator",
+    "GdsElementIterator",
     "Grid",
     "HorizontalPresentation",
     "InputPointsLike",
     "Instance",
     "Library",
+    "LibraryDiff",
+    "MaxPointsMode",
+    "Node",
+    "OffsetJoin",
     "Path",
     "PathType",
     "Point",
@@ -38,17 +67,21 @@ __all__ = [
     "Polygon",
     "Reference",
     "Text",
+    "Timestamp",
     "VerticalPresentation",
+    "boolean",
+    "drc",
+    "offset",
 ]
 
-BaseElement: TypeAlias = "Polygon | Path | Text"
+BaseElement: TypeAlias = "Box | Node | Polygon | Path | Text"
 """Type alias for a base GDSII element."""
 
 
 Instance: TypeAlias = "Cell | Element"
 """Type alias for a GDSII instance."""
 
-Element: TypeAlias = "Reference[Instance] | Polygon | Path | Text"
+Element: TypeAlias = "Reference[Instance] | Box | Node | Polygon | Path | Text"
 """Type alias for a GDSII element."""
 
 __version__ = "0.1.0"
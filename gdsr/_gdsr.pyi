@@ -1,4 +1,5 @@
 import sys
+from datetime import datetime
 from enum import Enum
 from typing import Generic, Iterator, Literal, Mapping, TypeAlias, TypeVar
 
@@ -8,12 +9,17 @@ else:
     from typing_extensions import Self
 
 from .typings import (
+    BooleanOperation,
     DataType,
     InputPointsLike,
     Layer,
     LayerDataType,
+    MaxPointsMode,
+    OffsetJoin,
     PathLike,
     PointLike,
+    Properties,
+    Timestamp,
 )
 
 def set_epsilon(epsilon: float) -> None:
@@ -32,6 +38,78 @@ def get_epsilon() -> float:
     :return: Epsilon value
     """
 
+def boolean(
+    a: list[Polygon],
+    b: list[Polygon],
+    operation: BooleanOperation,
+    layer: Layer = 0,
+    data_type: DataType = 0,
+    grid: float = 1e-4,
+) -> list[Polygon]:
+    """Perform a boolean operation between two groups of polygons.
+
+    Polygons within each group are merged before the operation is applied.
+    Holes in the result are connected to their outer boundary with a keyhole
+    so that every resulting polygon can be written to a GDSII file.
+
+    :param list[Polygon] a: First group of polygons.
+    :param list[Polygon] b: Second group of polygons.
+    :param BooleanOperation operation: Operation to perform, one of
+    ``"union"``, ``"intersection"``, ``"difference"`` or ``"xor"``.
+    :param Layer layer: Layer of the resulting polygons, defaults to 0
+    :param DataType data_type: Data type of the resulting polygons, defaults to 0
+    :param float grid: Grid the result is snapped to, in user units, defaults to
+    1e-4, the database unit of a default library. Use precision/units for
+    other libraries.
+    :return: The resulting polygons.
+    :raises ValueError: If the polygons are too large to fit on the grid
+    """
+
+def offset(
+    polygons: list[Polygon],
+    distance: float,
+    join: OffsetJoin = "miter",
+    miter_limit: float = 2.0,
+    layer: Layer = 0,
+    data_type: DataType = 0,
+    grid: float = 1e-4,
+) -> list[Polygon]:
+    """Grow or shrink polygons by a distance.
+
+    The polygons are merged before being offset, so overlapping results are
+    returned as a single polygon.
+
+    :param list[Polygon] polygons: Polygons to offset.
+    :param float distance: Distance to offset by, positive values grow the
+    polygons and negative values shrink them.
+    :param OffsetJoin join: Style of the offset corners, one of ``"miter"``,
+    ``"round"`` or ``"bevel"``, defaults to ``"miter"``
+    :param float miter_limit: Maximum ratio of the miter length to the offset
+    distance, corners exceeding it are bevelled, defaults to 2.0
+    :param Layer layer: Layer of the resulting polygons, defaults to 0
+    :param DataType data_type: Data type of the resulting polygons, defaults to 0
+    :param float grid: Grid the result is snapped to, in user units, defaults to
+    1e-4, the database unit of a default library. Use precision/units for
+    other libraries.
+    :return: The resulting polygons.
+    :raises ValueError: If the polygons are too large to fit on the grid
+    """
+
+def drc(
+    polygons: list[Polygon], rules: list[DrcRule], grid: float = 1e-4
+) -> list[DrcViolation]:
+    """Check polygons against design rules.
+
+    Polygons on the same layer and data type are merged before checking, so
+    touching or overlapping polygons are measured as a single shape.
+
+    :param list[Polygon] polygons: Polygons to check.
+    :param list[DrcRule] rules: Rules to check the polygons against.
+    :param float grid: Grid the polygons are merged on, in user units, defaults
+    to 1e-4
+    :return: The violations of every rule, in rule order.
+    """
+
 class PointIterator(Iterator[float]):
     def __next__(self) -> float: ...
 
@@ -177,6 +255,10 @@ class Grid:
     """Angle of the grid."""
     x_reflection: bool
     """X reflection of the grid."""
+    absolute_magnification: bool
+    """Whether the magnification ignores the magnification of parent references."""
+    absolute_angle: bool
+    """Whether the angle ignores the angle of parent references."""
     def __init__(
         self,
         origin: PointLike = Point(0, 0),
@@ -187,6 +269,8 @@ class Grid:
         magnification: float = 1.0,
         angle: float = 0.0,
         x_reflection: bool = False,
+        absolute_magnification: bool = False,
+        absolute_angle: bool = False,
     ) -> None:
         """Initialize the Grid with origin, columns, rows and spacing.
 
@@ -197,6 +281,13 @@ class Grid:
         Point(0, 0)
         :param PointLike spacing_y: The spacing in the y direction, defaults to
         Point(0, 0)
+        :param float magnification: The magnification, defaults to 1.0
+        :param float angle: The angle in degrees, defaults to 0.0
+        :param bool x_reflection: The x reflection, defaults to False
+        :param bool absolute_magnification: Whether the magnification ignores parent
+        references when flattened, defaults to False
+        :param bool absolute_angle: Whether the angle ignores parent references when
+        flattened, defaults to False
         """
     def copy(self) -> Self:
         """Return a copy of the grid."""
@@ -249,12 +340,23 @@ class Reference(Generic[T_Instance]):
     """The instance to reference."""
     grid: Grid
     """The grid to reference the cell."""
-    def __init__(self, instance: T_Instance, grid: Grid = Grid()) -> None:
+    properties: Properties
+    """The GDSII properties of the reference."""
+    def __init__(
+        self,
+        instance: T_Instance,
+        grid: Grid = Grid(),
+        properties: Properties | None = None,
+    ) -> None:
         """Initialize the Reference with an instance and a grid.
 
         :param Instance instance: The instance to reference.
         :param Grid grid: The grid to reference the cell.
+        :param Properties properties: GDSII properties, defaults to no properties.
         """
+    def set_properties(self, properties: Properties) -> Self:
+        """Set the GDSII properties of the reference."""
+
     @property
     def bounding_box(self) -> tuple[Point, Point]:
         """Return the bounding box of the reference."""
@@ -313,6 +415,7 @@ class PathType(Enum):
     Square = 0
     Round = 1
     Overlap = 2
+    Custom = 4
 
     @staticmethod
     def values() -> list[PathType]:
@@ -329,6 +432,11 @@ class Path:
     data_type: DataType
     path_type: PathType | None
     width: float | None
+    properties: Properties
+    begin_extension: float
+    """Extension past the first point, used by PathType.Custom."""
+    end_extension: float
+    """Extension past the last point, used by PathType.Custom."""
     def __init__(
         self,
         points: InputPointsLike,
@@ -336,13 +444,34 @@ class Path:
         data_type: DataType = 0,
         path_type: PathType | None = None,
         width: float | None = None,
+        properties: Properties | None = None,
+        begin_extension: float = 0.0,
+        end_extension: float = 0.0,
     ) -> None: ...
+    def to_polygons(self, grid: float = 1e-4) -> list[Polygon]:
+        """Return the filled outline of the path as polygons.
+
+        Ends follow the path type: ``Square`` ends are flush, ``Round`` ends are
+        semicircular, ``Overlap`` ends extend by half the width and ``Custom`` ends
+        extend by ``begin_extension`` and ``end_extension``. Sharp joins are mitered
+        and bevelled once they become too acute.
+
+        A path without a width has no outline and returns an empty list.
+
+        :param float grid: Grid the outline is snapped to, defaults to 1e-4.
+        """
+    def to_polygon(self, grid: float = 1e-4) -> Polygon:
+        """Return the filled outline of the path as a single polygon.
+
+        :param float grid: Grid the outline is snapped to, defaults to 1e-4.
+        :raises ValueError: If the path has no width.
+        """
     @property
     def length(self) -> float:
         """Return the length of the path."""
     @property
     def bounding_box(self) -> tuple[Point, Point]:
-        """Return the bounding box of the path."""
+        """Return the bounding box of the path outline."""
     def set_points(self, points: InputPointsLike) -> Self:
         """Set the points of the path."""
     def set_layer(self, layer: Layer) -> Self:
@@ -353,6 +482,12 @@ class Path:
         """Set the path type of the path."""
     def set_width(self, width: float | None) -> Self:
         """Set the width of the path."""
+    def set_begin_extension(self, begin_extension: float) -> Self:
+        """Set the extension past the first point of the path."""
+    def set_end_extension(self, end_extension: float) -> Self:
+        """Set the extension past the last point of the path."""
+    def set_properties(self, properties: Properties) -> Self:
+        """Set the GDSII properties of the path."""
 
     def copy(self) -> Self:
         """Return a copy of the path."""
@@ -408,8 +543,14 @@ class Polygon:
     """The layer of the polygon."""
     data_type: DataType
     """The data type of the polygon."""
+    properties: Properties
+    """The GDSII properties of the polygon."""
     def __init__(
-        self, points: InputPointsLike, layer: Layer = 0, data_type: DataType = 0
+        self,
+        points: InputPointsLike,
+        layer: Layer = 0,
+        data_type: DataType = 0,
+        properties: Properties | None = None,
     ) -> None:
         """Initialize the Polygon.
 
@@ -420,6 +561,8 @@ class Polygon:
         indexable at 0 and 1. Must not be empty
         :param Layer layer: Polygon layer, defaults to 0
         :param DataType data_type: Polygon data_type, defaults to 0
+        :param Properties properties: Polygon GDSII properties, defaults to no
+        properties
         """
     @property
     def bounding_box(self) -> tuple[Point, Point]:
@@ -443,6 +586,8 @@ class Polygon:
         """Set the layer of the polygon."""
     def set_data_type(self, data_type: DataType) -> Self:
         """Set the data type of the polygon."""
+    def set_properties(self, properties: Properties) -> Self:
+        """Set the GDSII properties of the polygon."""
 
     def contains(self, point: PointLike) -> bool:
         """Return True if the polygon contains the point."""
@@ -458,6 +603,41 @@ class Polygon:
         """Return True if any of the points are on the edge of the polygon."""
     def intersects(self, other: Polygon) -> bool:
         """Return True if the polygon intersects with another polygon."""
+    def boolean(
+        self, other: Polygon, operation: BooleanOperation, grid: float = 1e-4
+    ) -> list[Polygon]:
+        """Perform a boolean operation between this polygon and another polygon.
+
+        The resulting polygons are on the same layer and data type as this polygon.
+
+        :param Polygon other: Polygon to perform the operation with.
+        :param BooleanOperation operation: Operation to perform, one of
+        ``"union"``, ``"intersection"``, ``"difference"`` or ``"xor"``.
+        :param float grid: Grid the result is snapped to, in user units, defaults
+        to 1e-4
+        :return: The resulting polygons.
+        """
+    def offset(
+        self,
+        distance: float,
+        join: OffsetJoin = "miter",
+        miter_limit: float = 2.0,
+        grid: float = 1e-4,
+    ) -> list[Polygon]:
+        """Grow or shrink the polygon by a distance.
+
+        The resulting polygons are on the same layer and data type as this polygon.
+
+        :param float distance: Distance to offset by, positive values grow the
+        polygon and negative values shrink it.
+        :param OffsetJoin join: Style of the offset corners, one of ``"miter"``,
+        ``"round"`` or ``"bevel"``, defaults to ``"miter"``
+        :param float miter_limit: Maximum ratio of the miter length to the offset
+        distance, corners exceeding it are bevelled, defaults to 2.0
+        :param float grid: Grid the result is snapped to, in user units, defaults
+        to 1e-4
+        :return: The resulting polygons.
+        """
     def visualize(self) -> None:
         """Visualises the polygon in your default web browser."""
     def copy(self) -> Self:
@@ -533,6 +713,26 @@ class Polygon:
         :param int layer: Layer of the ellipse, defaults to 0.
         :param int data_type: Data type of the ellipse, defaults to 0.
         """
+    @staticmethod
+    def text(
+        text: str,
+        size: float = 1.0,
+        origin: PointLike = Point(0, 0),
+        angle: float = 0.0,
+        layer: int = 0,
+        data_type: int = 0,
+        grid: float = 1e-4,
+    ) -> list[Polygon]:
+        """Return polygons spelling out text in the built-in stroke font.
+
+        :param str text: Text to draw, new lines start a new line of text.
+        :param float size: Height of a capital letter, defaults to 1.0.
+        :param PointLike origin: Lower left corner of the text, defaults to Point(0, 0).
+        :param float angle: Angle of the text in degrees, defaults to 0.
+        :param int layer: Layer of the polygons, defaults to 0.
+        :param int data_type: Data type of the polygons, defaults to 0.
+        :param float grid: Grid the polygons are snapped to, defaults to 1e-4.
+        """
     def __str__(self) -> str:
         """Return a string representation of the polygon."""
     def __repr__(self) -> str:
@@ -569,6 +769,8 @@ class Text:
         """Set the text origin."""
     layer: Layer
     """Text layer."""
+    text_type: DataType
+    """Text type, written to the TEXTTYPE record."""
     magnification: float
     """Text magnification."""
     angle: float
@@ -579,22 +781,33 @@ class Text:
     """Text vertical presentation."""
     horizontal_presentation: HorizontalPresentation
     """Text horizontal presentation."""
+    properties: Properties
+    """Text GDSII properties."""
+    absolute_magnification: bool
+    """Whether the magnification ignores the magnification of parent references."""
+    absolute_angle: bool
+    """Whether the angle ignores the angle of parent references."""
     def __init__(
         self,
         text: str,
         origin: PointLike = Point(0, 0),
         layer: Layer = 0,
+        text_type: DataType = 0,
         magnification: float = 1.0,
         angle: float = 0.0,
         x_reflection: bool = False,
         vertical_presentation: VerticalPresentation = VerticalPresentation.Middle,
         horizontal_presentation: HorizontalPresentation = HorizontalPresentation.Centre,
+        properties: Properties | None = None,
+        absolute_magnification: bool = False,
+        absolute_angle: bool = False,
     ) -> None:
         """Initialize the Text with text and origin.
 
         :param str text: Text content.
         :param PointLike origin: Text origin, defaults to Point(0, 0).
         :param Layer layer: Text layer, defaults to 0.
+        :param DataType text_type: Text type, defaults to 0.
         :param float magnification: Text magnification, defaults to 1.0.
         :param float angle: Text angle in degrees, defaults to 0.0.
         :param bool x_reflection: Text x reflection, defaults to False.
@@ -602,22 +815,51 @@ class Text:
         defaults to VerticalPresentation.Middle.
         :param HorizontalPresentation horizontal_presentation: Text horizontal
         presentation, defaults to HorizontalPresentation.Centre.
+        :param Properties properties: Text GDSII properties, defaults to no properties.
+        :param bool absolute_magnification: Whether the magnification ignores parent
+        references when flattened, defaults to False.
+        :param bool absolute_angle: Whether the angle ignores parent references when
+        flattened, defaults to False.
         """
     @property
     def bounding_box(self) -> tuple[Point, Point]:
-        """Return the bounding box of the text."""
+        """Return the bounding box of the text.
+
+        The extent comes from the built-in stroke font, where the magnification is the
+        height of a capital letter. It accounts for the presentation, angle and x
+        reflection of the text, and contains the outline from to_polygons with the
+        default stroke width.
+        """
+    def to_polygons(
+        self, width: float | None = None, grid: float = 1e-4
+    ) -> list[Polygon]:
+        """Return the text drawn with the built-in stroke font as polygons.
+
+        The polygons follow the presentation, angle, x reflection and magnification of
+        the text, and take its layer, text type and properties.
+
+        :param float | None width: Stroke width, defaults to a sixth of the
+        magnification.
+        :param float grid: Grid the polygons are snapped to, defaults to 1e-4.
+        """
     def set_text(self, text: str) -> Self:
         """Set the text content."""
     def set_origin(self, origin: PointLike) -> Self:
         """Set the origin of the text."""
     def set_layer(self, layer: Layer) -> Self:
         """Set the layer of the text."""
+    def set_text_type(self, text_type: DataType) -> Self:
+        """Set the text type of the text."""
     def set_magnification(self, magnification: float) -> Self:
         """Set the magnification of the text."""
     def set_angle(self, angle: float) -> Self:
         """Set the angle of the text."""
     def set_x_reflection(self, x_reflection: bool) -> Self:
         """Set the x reflection of the text."""
+    def set_absolute_magnification(self, absolute_magnification: bool) -> Self:
+        """Set whether the magnification of the text is absolute."""
+    def set_absolute_angle(self, absolute_angle: bool) -> Self:
+        """Set whether the angle of the text is absolute."""
     def set_vertical_presentation(
         self, vertical_presentation: VerticalPresentation
     ) -> Self:
@@ -626,6 +868,8 @@ class Text:
         self, horizontal_presentation: HorizontalPresentation
     ) -> Self:
         """Set the horizontal presentation of the text."""
+    def set_properties(self, properties: Properties) -> Self:
+        """Set the GDSII properties of the text."""
     def copy(self) -> Self:
         """Return a copy of the text."""
     def move_to(self, point: PointLike) -> Self:
@@ -659,7 +903,7 @@ class Text:
         :param PointLike centre: Centre point of scaling, defaults to (0, 0).
         """
     def is_on(self, *layer_data_types: LayerDataType) -> bool:
-        """Return True if the text is on any of the layer, data_type pairs."""
+        """Return True if the text is on any of the layer, text_type pairs."""
     def __str__(self) -> str:
         """Return a string representation of the text."""
     def __repr__(self) -> str:
@@ -667,11 +911,182 @@ class Text:
     def __eq__(self, value: object) -> bool:
         """Return True if the text is equal to another object."""
 
-BaseElement: TypeAlias = Path | Polygon | Text
+class Box:
+    """GDSII BOX element, a four cornered outline kept separate from polygons."""
+
+    @property
+    def points(self) -> list[Point]:
+        """Return the points of the box."""
+    @points.setter
+    def points(self, points: InputPointsLike) -> None:
+        """Set the points of the box."""
+    layer: Layer
+    """The layer of the box."""
+    box_type: DataType
+    """The box type of the box."""
+    properties: Properties
+    """The GDSII properties of the box."""
+    def __init__(
+        self,
+        points: InputPointsLike,
+        layer: Layer = 0,
+        box_type: DataType = 0,
+        properties: Properties | None = None,
+    ) -> None:
+        """Initialize the Box.
+
+        The first corner is appended to the end to close the outline.
+
+        :param InputPointsLike points: The four corners of the box, a closing point equal to the first corner is
+        optional.
+        :param Layer layer: Box layer, defaults to 0
+        :param DataType box_type: Box box type, defaults to 0
+        :param Properties properties: Box GDSII properties, defaults to no
+        properties
+        """
+    @property
+    def bounding_box(self) -> tuple[Point, Point]:
+        """Return the bounding box of the box."""
+    def set_points(self, points: InputPointsLike) -> Self:
+        """Set the points of the box."""
+    def set_layer(self, layer: Layer) -> Self:
+        """Set the layer of the box."""
+    def set_box_type(self, box_type: DataType) -> Self:
+        """Set the box type of the box."""
+    def set_properties(self, properties: Properties) -> Self:
+        """Set the GDSII properties of the box."""
+    def copy(self) -> Self:
+        """Return a copy of the box."""
+    def move_to(self, point: PointLike) -> Self:
+        """Move the box so that its first point is at a point.
+
+        This method modifies the box in place and returns itself.
+
+        :param PointLike point: Point to move the box to.
+        """
+    def move_by(self, vector: PointLike) -> Self:
+        """Move the box by a vector.
+
+        This method modifies the box in place and returns itself.
+
+        :param PointLike vector: Vector to move the box by.
+        """
+    def rotate(self, angle: float, centre: PointLike = Point(0, 0)) -> Self:
+        """Rotate the box by an angle around a centre point.
+
+        This method modifies the box in place and returns itself.
+
+        :param float angle: Counter-clockwise rotation angle in degrees.
+        :param PointLike centre: Centre point of rotation, defaults to (0, 0).
+        """
+    def scale(self, factor: float, centre: PointLike = Point(0, 0)) -> Self:
+        """Scale the box by a factor around a centre point.
+
+        This method modifies the box in place and returns itself.
+
+        :param float factor: Scaling factor.
+        :param PointLike centre: Centre point of scaling, defaults to (0, 0).
+        """
+    def is_on(self, *layer_data_types: LayerDataType) -> bool:
+        """Return True if the box is on any of the layer, box_type pairs."""
+    def __str__(self) -> str:
+        """Return a string representation of the box."""
+    def __repr__(self) -> str:
+        """Return a string representation of the box."""
+    def __eq__(self, value: object) -> bool:
+        """Return True if the box is equal to another object."""
+
+class Node:
+    """GDSII NODE element, a set of points used for electrical connectivity."""
+
+    @property
+    def points(self) -> list[Point]:
+        """Return the points of the node."""
+    @points.setter
+    def points(self, points: InputPointsLike) -> None:
+        """Set the points of the node."""
+    layer: Layer
+    """The layer of the node."""
+    node_type: DataType
+    """The node type of the node."""
+    properties: Properties
+    """The GDSII properties of the node."""
+    def __init__(
+        self,
+        points: InputPointsLike,
+        layer: Layer = 0,
+        node_type: DataType = 0,
+        properties: Properties | None = None,
+    ) -> None:
+        """Initialize the Node.
+
+        :param InputPointsLike points: Between 1 and 50 points of the node.
+        :param Layer layer: Node layer, defaults to 0
+        :param DataType node_type: Node node type, defaults to 0
+        :param Properties properties: Node GDSII properties, defaults to no
+        properties
+        """
+    @property
+    def bounding_box(self) -> tuple[Point, Point]:
+        """Return the bounding box of the node."""
+    def set_points(self, points: InputPointsLike) -> Self:
+        """Set the points of the node."""
+    def set_layer(self, layer: Layer) -> Self:
+        """Set the layer of the node."""
+    def set_node_type(self, node_type: DataType) -> Self:
+        """Set the node type of the node."""
+    def set_properties(self, properties: Properties) -> Self:
+        """Set the GDSII properties of the node."""
+    def copy(self) -> Self:
+        """Return a copy of the node."""
+    def move_to(self, point: PointLike) -> Self:
+        """Move the node so that its first point is at a point.
+
+        This method modifies the node in place and returns itself.
+
+        :param PointLike point: Point to move the node to.
+        """
+    def move_by(self, vector: PointLike) -> Self:
+        """Move the node by a vector.
+
+        This method modifies the node in place and returns itself.
+
+        :param PointLike vector: Vector to move the node by.
+        """
+    def rotate(self, angle: float, centre: PointLike = Point(0, 0)) -> Self:
+        """Rotate the node by an angle around a centre point.
+
+        This method modifies the node in place and returns itself.
+
+        :param float angle: Counter-clockwise rotation angle in degrees.
+        :param PointLike centre: Centre point of rotation, defaults to (0, 0).
+        """
+    def scale(self, factor: float, centre: PointLike = Point(0, 0)) -> Self:
+        """Scale the node by a factor around a centre point.
+
+        This method modifies the node in place and returns itself.
+
+        :param float factor: Scaling factor.
+        :param PointLike centre: Centre point of scaling, defaults to (0, 0).
+        """
+    def is_on(self, *layer_data_types: LayerDataType) -> bool:
+        """Return True if the node is on any of the layer, node_type pairs."""
+    def __str__(self) -> str:
+        """Return a string representation of the node."""
+    def __repr__(self) -> str:
+        """Return a string representation of the node."""
+    def __eq__(self, value: object) -> bool:
+        """Return True if the node is equal to another object."""
+
+BaseElement: TypeAlias = Box | Node | Path | Polygon | Text
 Element: TypeAlias = Reference[Instance] | BaseElement
 
 class Cell:
     name: str
+    modification_date: datetime | None
+    """Last modification date, read from GDS files and written with ``"preserve"``."""
+    access_date: datetime | None
+    """Last access date, read from GDS files and written with ``"preserve"``."""
     @property
     def polygons(self) -> list[Polygon]: ...
     @property
@@ -680,14 +1095,149 @@ class Cell:
     def references(self) -> list[Reference[Instance]]: ...
     @property
     def texts(self) -> list[Text]: ...
+    @property
+    def nodes(self) -> list[Node]: ...
+    @property
+    def boxes(self) -> list[Box]: ...
     def __init__(self, name: str) -> None:
         """Initialize the Cell with a name.
 
         :param str name: Cell name
         """
-    @property
-    def bounding_box(self) -> tuple[Point, Point]:
-        """Return the bounding box of the cell."""
+    def bounding_box(
+        self, layers: list[LayerDataType] | None = None
+    ) -> tuple[Point, Point]:
+        """Return the bounding box of the cell, including everything it references.
+
+        The result is cached on the cell. A geometry change only clears the cached
+        results of the cells that hold the changed element or reference its cell,
+        directly or through other cells.
+        A cell with nothing on the requested layers returns an infinite, inverted box.
+
+        :param list[LayerDataType] | None layers: Only include elements on these
+        layer, data type pairs, defaults to every layer.
+        """
+    def query(
+        self,
+        region_min: PointLike,
+        region_max: PointLike,
+        layers: list[LayerDataType] | None = None,
+        depth: int | None = None,
+    ) -> list[Element]:
+        """Return the elements whose bounding box overlaps a region.
+
+        Each cell keeps an R-tree of its elements and reference extents, so only the
+        parts of the hierarchy that overlap the region are visited. The R-tree of a
+        cell is rebuilt after a change to the cell or to anything it references,
+        changes elsewhere leave it in place. Elements found
+        through references are returned as transformed copies, as with
+        :meth:`get_elements`. References deeper than ``depth`` are returned as
+        references.
+
+        :param PointLike region_min: One corner of the region.
+        :param PointLike region_max: The opposite corner of the region.
+        :param list[LayerDataType] | None layers: Only include elements on these
+        layer, data type pairs, defaults to every layer.
+        :param int | None depth: Number of reference levels to expand, defaults to
+        every level.
+        """
+    def clip(
+        self,
+        region: Polygon | PointLike,
+        region_max: PointLike | None = None,
+        grid: float = 1e-4,
+    ) -> Cell:
+        """Return a new cell with only the geometry inside a region.
+
+        The region is either a polygon or the two corners of a box. Polygons and
+        paths crossing the boundary are cut into polygons, while texts, nodes and
+        boxes are kept only when they lie inside the region. Reference instances
+        entirely inside the region stay as references, arrays being reduced to the
+        instances that are kept, and instances crossing the boundary are flattened
+        and cut.
+
+        :param Polygon | PointLike region: Clip polygon, or one corner of a box.
+        :param PointLike | None region_max: The opposite corner of the box.
+        :param float grid: Grid cut polygons are snapped to, in user units,
+        defaults to 1e-4
+        """
+    def fill(
+        self,
+        layer: LayerDataType,
+        tile: Polygon | PointLike,
+        pitch: PointLike,
+        target_density: float,
+        keep_out_layers: list[LayerDataType] = [],
+        spacing: float = 0.0,
+        window_size: float | None = None,
+        fill_layer: LayerDataType | None = None,
+        name: str | None = None,
+        grid: float = 1e-4,
+    ) -> Cell:
+        """Return a new cell of dummy fill tiles for a layer.
+
+        The bounding box of the cell is split into square density windows and each
+        window is filled until the geometry on ``layer`` plus the fill covers
+        ``target_density`` of it, or no free tile sites are left. Tiles sit on a
+        lattice with the given pitch, are kept entirely inside a window and are
+        kept ``spacing`` away from the geometry on ``layer`` and
+        ``keep_out_layers``. Rows are picked spread across each window.
+
+        The fill cell holds references with grid arrays to a single tile cell, so
+        add both to a library to write them out.
+
+        :param LayerDataType layer: Layer and data type whose density is filled.
+        :param Polygon | PointLike tile: Tile polygon, or the width and height of
+        a rectangular tile.
+        :param PointLike pitch: Horizontal and vertical distance between tiles.
+        :param float target_density: Density to fill each window up to, greater
+        than 0 and at most 1.
+        :param list[LayerDataType] keep_out_layers: Layer and data type pairs
+        tiles must stay away from, defaults to []
+        :param float spacing: Minimum distance between tiles and existing
+        geometry, defaults to 0.0
+        :param float | None window_size: Size of the density windows, defaults to
+        a single window covering the cell.
+        :param LayerDataType | None fill_layer: Layer and data type of the tiles,
+        defaults to ``layer``.
+        :param str | None name: Name of the fill cell, defaults to the cell name
+        followed by ``_FILL``. The tile cell is named after it with ``_TILE``.
+        :param float grid: Grid the density and spacing are computed on, in user
+        units, defaults to 1e-4
+        """
+    def drc(self, rules: list[DrcRule], grid: float = 1e-4) -> list[DrcViolation]:
+        """Check the cell against design rules.
+
+        Polygons and paths on the layers of the rules are collected through every
+        reference, then merged per layer and data type before checking.
+
+        :param list[DrcRule] rules: Rules to check the cell against.
+        :param float grid: Grid the polygons are merged on, in user units,
+        defaults to 1e-4
+        :return: The violations of every rule, in rule order.
+        """
+    def xor(
+        self,
+        other: Cell,
+        layers: list[LayerDataType] | None = None,
+        tolerance: float = 0.0,
+        grid: float = 1e-4,
+    ) -> dict[LayerDataType, tuple[list[Polygon], float]]:
+        """Return the geometric differences between this cell and another.
+
+        Polygons and paths are collected through every reference, so the result
+        does not depend on hierarchy, element order or vertex order. The cells are
+        equivalent when the result is empty.
+
+        :param Cell other: Cell to compare against.
+        :param list[LayerDataType] | None layers: Layers to compare, defaults to
+        every layer.
+        :param float tolerance: Differences narrower than this are ignored,
+        defaults to 0.0
+        :param float grid: Grid the XOR is computed on, in user units, defaults
+        to 1e-4
+        :return: The XOR polygons and their total area on each layer that differs.
+        """
     def add(self, *elements: Element) -> None:
         """Add elements to the cell."""
     def remove(self, *elements: Element) -> None:
@@ -757,12 +1307,24 @@ class Cell:
         file_name: PathLike | None = None,
         units: float = 1e-6,
         precision: float = 1e-10,
+        max_points_mode: MaxPointsMode = "fracture",
+        timestamp: Timestamp | None = None,
     ) -> str:
-        """Write the Cell to a GDS file.
+        """Write the Cell and every cell it references to a GDS file.
+
+        Each cell is written once, after the cells it references. A ValueError is
+        raised when different cells share a name or the references form a cycle.
 
         :param PathLike file_name: Output GDS file name.
         :param float units: GDS file units in meters, defaults to 1e-6.
         :param float precision: GDS file precision, defaults to 1e-10.
+        :param MaxPointsMode max_points_mode: How to write polygons and paths with
+        more than 8191 points, ``"fracture"`` splits them into several elements,
+        ``"error"`` raises a ValueError and ``"extended"`` writes consecutive XY
+        records which not all readers support, defaults to ``"fracture"``.
+        :param Timestamp timestamp: Dates written to the BGNLIB and BGNSTR records,
+        a fixed datetime, ``"now"``, ``"preserve"`` to reuse the dates read by
+        ``from_gds`` or ``"epoch"``, defaults to the current time.
         :return: GDS file name
         """
     def is_on(self, *layer_data_types: LayerDataType) -> bool:
@@ -782,13 +1344,40 @@ class Cell:
 
 class Library:
     name: str
+    modification_date: datetime | None
+    """Last modification date, read from GDS files and written with ``"preserve"``."""
+    access_date: datetime | None
+    """Last access date, read from GDS files and written with ``"preserve"``."""
     @property
     def cells(self) -> Mapping[str, Cell]:
         """Return the cells in the library."""
-    def __init__(self, name: str = "library") -> None:
-        """Initialize the Library with a name.
+    @property
+    def units(self) -> float:
+        """Size of one library unit in meters."""
+    @property
+    def precision(self) -> float:
+        """Size of one database unit in meters."""
+    def __init__(
+        self, name: str = "Library", units: float = 1e-6, precision: float = 1e-10
+    ) -> None:
+        """Initialize the Library with a name, units and precision.
 
         :param str name: Library name
+        :param float units: Size of one library unit in meters, defaults to 1e-6.
+        :param float precision: Size of one database unit in meters, defaults to
+        1e-10.
+        """
+    def set_units(self, units: float, precision: float | None = None) -> None:
+        """Change the library units, rescaling all geometry to match.
+
+        Coordinates, spacings and path widths in every cell, including cells only
+        reachable through references, are multiplied by the ratio of the old units
+        to the new units and rounded to the new database grid. Shared cells and
+        elements are only rescaled once.
+
+        :param float units: New size of one library unit in meters.
+        :param float precision: New size of one database unit in meters, defaults
+        to the current precision.
         """
     def add(self, *cells: Cell, replace_pre_existing: bool = False) -> None:
         """Add cells to the library.
@@ -831,26 +1420,157 @@ class Library:
 
         :param bool deep: If True, a deep copy is returned, defaults to False.
         """
+    def diff(self, other: Library, xor: bool = False) -> LibraryDiff:
+        """Return the differences from this library to another.
+
+        Cells only in the other library are added and cells only in this library
+        are removed, unless their contents match, in which case they are renamed.
+        Elements of cells in both libraries are matched regardless of order and of
+        where polygon outlines start.
+
+        The report is truthy when the libraries differ and ``str`` gives a text
+        summary.
+
+        :param Library other: Library to compare against.
+        :param bool xor: If True, also report the XOR area on each layer of
+        changed cells and the cells that reference them, after flattening,
+        computed on the finer database unit of the two libraries, defaults to
+        False
+        """
     def to_gds(
         self,
         file_name: PathLike | None = None,
-        units: float = 1e-6,
-        precision: float = 1e-10,
+        units: float | None = None,
+        precision: float | None = None,
+        max_points_mode: MaxPointsMode = "fracture",
+        timestamp: Timestamp | None = None,
     ) -> str:
         """Write the Library to a GDS file.
 
+        Each cell, including cells only reachable through references, is written
+        once, after the cells it references. A ValueError is raised when different
+        cells share a name or the references form a cycle.
+
         :param PathLike file_name: Output GDS file name.
-        :param float units: GDS file units in meters, defaults to 1e-6.
-        :param float precision: GDS file precision, defaults to 1e-10.
+        :param float units: GDS file units in meters, defaults to the library units.
+        :param float precision: GDS file precision, defaults to the library
+        precision.
+        :param MaxPointsMode max_points_mode: How to write polygons and paths with
+        more than 8191 points, ``"fracture"`` splits them into several elements,
+        ``"error"`` raises a ValueError and ``"extended"`` writes consecutive XY
+        records which not all readers support, defaults to ``"fracture"``.
+        :param Timestamp timestamp: Dates written to the BGNLIB and BGNSTR records,
+        a fixed datetime, ``"now"``, ``"preserve"`` to reuse the dates read by
+        ``from_gds`` or ``"epoch"``, defaults to the current time.
         :return: GDS file path
         """
     @staticmethod
-    def from_gds(file_name: PathLike) -> Library:
+    def from_gds(
+        file_name: PathLike,
+        cells: list[str] | None = None,
+        layers: list[LayerDataType] | None = None,
+    ) -> Library:
         """Read a Library from a GDS file.
 
+        Structures and elements that do not match the filters are skipped while
+        parsing, so they are never created in memory. References to cells that are
+        not read are dropped.
+
         :param PathLike file_name: Input GDS file name.
+        :param list[str] cells: Names of the cells to read, defaults to all cells.
+        :param list[LayerDataType] layers: Layer and data type pairs of the polygons,
+        paths and texts to read, defaults to all layers. References are always read.
+        :return: Library
+        """
+    def to_oasis(
+        self,
+        file_name: PathLike | None = None,
+        units: float | None = None,
+        precision: float | None = None,
+    ) -> str:
+        """Write the Library to an OASIS file.
+
+        Polygons, rectangles, paths, texts, nodes and references are written along
+        with their properties. Reference grids and runs of identical elements are
+        written as OASIS repetitions and each cell is compressed in a CBLOCK. Boxes
+        are written as polygons and nodes as XGEOMETRY records. Paths with round
+        ends or with a width that is not an even number of database units cannot
+        be written and raise a ValueError.
+
+        :param PathLike file_name: Output OASIS file name.
+        :param float units: Library units in meters, defaults to the library units.
+        :param float precision: Database precision in meters, defaults to the
+        library precision.
+        :return: OASIS file path
+        """
+    @staticmethod
+    def from_oasis(file_name: PathLike) -> Library:
+        """Read a Library from an OASIS file.
+
+        Coordinates are read in micrometers, so the library units are 1e-6 and the
+        precision follows the file's database unit. Circles are approximated by
+        polygons and trapezoids are read as polygons. Nodes written by ``to_oasis``
+        are read back as nodes.
+
+        :param PathLike file_name: Input OASIS file name.
         :return: Library
         """
+    def export_lyrdb(
+        self, violations: list[DrcViolation], file_name: PathLike | None = None
+    ) -> str:
+        """Write DRC violations to a KLayout report database.
+
+        Each rule becomes a category and each violation an item tied to its cell,
+        with the marker polygon, violating edges and measured value as item values.
+        Coordinates are written in the library's user units. Violations without a
+        cell are tied to the top cell, which needs the library to have exactly
+        one. A ValueError is raised for violations whose cell is not in the library.
+
+        :param list[DrcViolation] violations: Violations to write.
+        :param PathLike file_name: Output lyrdb file name.
+        :return: lyrdb file path
+        """
+    def import_lyrdb(self, file_name: PathLike) -> list[DrcViolation]:
+        """Read DRC violations from a KLayout report database.
+
+        Polygon and box values become the marker, edge and edge pair values the
+        violating edges, and float values the measured value. Items with only
+        edges are marked by the bounding box of their edges. Markers are read on
+        layer 0 and a ValueError is raised for cells not in the library.
+
+        :param PathLike file_name: Input lyrdb file name.
+        :return: The violations, in file order.
+        """
+    def export_rdb(
+        self, violations: list[DrcViolation], file_name: PathLike | None = None
+    ) -> str:
+        """Write DRC violations to a Calibre ASCII results database.
+
+        Each rule becomes a check with a polygon result per violation, followed by
+        an edge result with the same ordinal for its violating edges. Results are
+        tied to their cell and the measured value is written as a ``VALUE``
+        property line after the polygon vertices. The header precision is the
+        number of database units per user unit rounded to an integer, and
+        coordinates are written with that precision. Cells follow the same rules as
+        :meth:`export_lyrdb`.
+
+        :param list[DrcViolation] violations: Violations to write.
+        :param PathLike file_name: Output RDB file name.
+        :return: RDB file path
+        """
+    def import_rdb(self, file_name: PathLike) -> list[DrcViolation]:
+        """Read DRC violations from a Calibre ASCII results database.
+
+        Coordinates are converted to user units with the precision in the file
+        header. Edge results following a polygon result with the same ordinal are
+        read as its violating edges, other edge results are marked by the bounding
+        box of their edges. Values are read from ``VALUE`` property lines and are 0
+        for results without one, markers are read on layer 0 and a ValueError is
+        raised for cells not in the library.
+
+        :param PathLike file_name: Input RDB file name.
+        :return: The violations, in file order.
+        """
     def __add__(self, other: Cell) -> Self:
         """Add a cell to the library.
 
@@ -883,8 +1603,256 @@ class Library:
     def __repr__(self) -> str:
         """Return a string representation of the library."""
 
+class DrcRule:
+    """A design rule, created with one of the static constructors.
+
+    Distances are measured between edges of the merged geometry of a layer, so
+    rules are checked against shapes rather than individual polygons.
+    """
+    @property
+    def name(self) -> str:
+        """Return the name of the rule, used to label violations."""
+    @property
+    def check(self) -> Literal["width", "spacing", "enclosure", "area", "notch"]:
+        """Return the kind of check the rule performs."""
+    @property
+    def layer(self) -> LayerDataType:
+        """Return the layer and data type the rule checks."""
+    @property
+    def other_layer(self) -> LayerDataType | None:
+        """Return the second layer and data type of two layer rules."""
+    @property
+    def value(self) -> float:
+        """Return the limit of the rule."""
+    @staticmethod
+    def width(
+        layer: LayerDataType, min_width: float, name: str | None = None
+    ) -> DrcRule:
+        """Create a minimum width rule.
+
+        Opposite edges of a shape closer than ``min_width`` are violations.
+
+        :param LayerDataType layer: Layer and data type to check.
+        :param float min_width: Minimum width.
+        :param str | None name: Name of the rule, defaults to one describing it.
+        """
+    @staticmethod
+    def spacing(
+        layer: LayerDataType,
+        min_spacing: float,
+        other_layer: LayerDataType | None = None,
+        name: str | None = None,
+    ) -> DrcRule:
+        """Create a minimum spacing rule.
+
+        Without ``other_layer`` edges of different shapes on ``layer`` closer than
+        ``min_spacing`` are violations, otherwise edges of ``layer`` closer than
+        ``min_spacing`` to edges of ``other_layer`` are.
+
+        :param LayerDataType layer: Layer and data type to check.
+        :param float min_spacing: Minimum spacing.
+        :param LayerDataType | None other_layer: Layer and data type to measure
+        against, defaults to ``layer``.
+        :param str | None name: Name of the rule, defaults to one describing it.
+        """
+    @staticmethod
+    def enclosure(
+        inner_layer: LayerDataType,
+        outer_layer: LayerDataType,
+        min_enclosure: float,
+        name: str | None = None,
+    ) -> DrcRule:
+        """Create a minimum enclosure rule.
+
+        Edges of ``inner_layer`` closer than ``min_enclosure`` to the edges of
+        ``outer_layer`` around them are violations, as are parts of
+        ``inner_layer`` outside ``outer_layer``, which have a value of 0.
+
+        :param LayerDataType inner_layer: Layer and data type to be enclosed.
+        :param LayerDataType outer_layer: Layer and data type enclosing it.
+        :param float min_enclosure: Minimum enclosure.
+        :param str | None name: Name of the rule, defaults to one describing it.
+        """
+    @staticmethod
+    def area(layer: LayerDataType, min_area: float, name: str | None = None) -> DrcRule:
+        """Create a minimum area rule.
+
+        Shapes with an area below ``min_area`` are violations.
+
+        :param LayerDataType layer: Layer and data type to check.
+        :param float min_area: Minimum area.
+        :param str | None name: Name of the rule, defaults to one describing it.
+        """
+    @staticmethod
+    def notch(
+        layer: LayerDataType, min_notch: float, name: str | None = None
+    ) -> DrcRule:
+        """Create a minimum notch rule.
+
+        Edges of the same shape facing each other across empty space closer than
+        ``min_notch`` are violations, such as the sides of a narrow slot.
+
+        :param LayerDataType layer: Layer and data type to check.
+        :param float min_notch: Minimum notch.
+        :param str | None name: Name of the rule, defaults to one describing it.
+        """
+    def __eq__(self, value: object) -> bool:
+        """Return True if the rules are equal."""
+    def __str__(self) -> str:
+        """Return a string representation of the rule."""
+    def __repr__(self) -> str:
+        """Return a string representation of the rule."""
+
+class DrcViolation:
+    """A single violation of a design rule."""
+    @property
+    def rule(self) -> str:
+        """Return the name of the violated rule."""
+    @property
+    def cell(self) -> str | None:
+        """Return the name of the checked cell, if any."""
+    @property
+    def value(self) -> float:
+        """Return the measured distance or area."""
+    @property
+    def marker(self) -> Polygon:
+        """Return a polygon marking the violation, on the layer of the rule."""
+    @property
+    def edges(self) -> list[tuple[Point, Point]]:
+        """Return the violating edges."""
+    def __init__(
+        self,
+        rule: str,
+        marker: Polygon,
+        value: float = 0.0,
+        edges: list[tuple[Point, Point]] = [],
+        cell: str | None = None,
+    ) -> None:
+        """Initialize the violation.
+
+        :param str rule: Name of the violated rule.
+        :param Polygon marker: Polygon marking the violation.
+        :param float value: Measured distance or area, defaults to 0.0
+        :param list[tuple[Point, Point]] edges: Violating edges, defaults to []
+        :param str | None cell: Name of the checked cell, defaults to None
+        """
+    def __eq__(self, value: object) -> bool:
+        """Return True if the violations are equal."""
+    def __str__(self) -> str:
+        """Return a string representation of the violation."""
+    def __repr__(self) -> str:
+        """Return a string representation of the violation."""
+
+class CellDiff:
+    """Differences between a cell of two libraries."""
+    @property
+    def name(self) -> str:
+        """Return the name of the cell in the first library."""
+    @property
+    def other_name(self) -> str:
+        """Return the name of the cell in the other library."""
+    @property
+    def added(self) -> list[Element]:
+        """Return the elements only in the other library."""
+    @property
+    def removed(self) -> list[Element]:
+        """Return the elements only in the first library."""
+    @property
+    def changes(
+        self,
+    ) -> dict[tuple[str, LayerDataType | None], tuple[int, int]]:
+        """Return the number of added and removed elements.
+
+        Keys are the kind of element, such as "polygon" or "reference", and its
+        layer and data type, which is None for references.
+        """
+    @property
+    def xor_areas(self) -> dict[LayerDataType, float]:
+        """Return the XOR area on each layer that differs, when compared with xor."""
+    def __str__(self) -> str:
+        """Return a text report of the differences."""
+    def __repr__(self) -> str:
+        """Return a string representation of the differences."""
+
+class LibraryDiff:
+    """Differences between two libraries, returned by Library.diff."""
+    @property
+    def added_cells(self) -> list[str]:
+        """Return the names of the cells only in the other library."""
+    @property
+    def removed_cells(self) -> list[str]:
+        """Return the names of the cells only in the first library."""
+    @property
+    def renamed_cells(self) -> list[tuple[str, str]]:
+        """Return the old and new names of renamed cells."""
+    @property
+    def cells(self) -> list[CellDiff]:
+        """Return the differences of the changed cells."""
+    def __bool__(self) -> bool:
+        """Return True if the libraries differ."""
+    def __str__(self) -> str:
+        """Return a text report of the differences."""
+    def __repr__(self) -> str:
+        """Return a string representation of the differences."""
+
+class GdsCellIterator(Iterator[Cell]):
+    """Iterate over the cells of a GDS file one at a time.
+
+    Only the cell currently being read is held in memory, which makes it possible
+    to process files that are too large to load with ``Library.from_gds``.
+
+    ```python
+
+    import gdsr
+
+    for cell in gdsr.GdsCellIterator("layout.gds", layers=[(1, 0)]):
+        print(cell.name, len(cell.polygons))
+    ```
+    """
+    def __init__(
+        self,
+        file_name: PathLike,
+        cells: list[str] | None = None,
+        layers: list[LayerDataType] | None = None,
+    ) -> None:
+        """Open a GDS file for iterating over its cells.
+
+        :param PathLike file_name: Input GDS file name.
+        :param list[str] cells: Names of the cells to yield, defaults to all cells.
+        :param list[LayerDataType] layers: Layer and data type pairs of the polygons,
+        paths and texts to read, defaults to all layers. References are always read.
+        """
+    def __iter__(self) -> Self: ...
+    def __next__(self) -> Cell: ...
+
+class GdsElementIterator(Iterator[tuple[str, Element]]):
+    """Iterate over the elements of a GDS file one at a time.
+
+    Each item is a tuple of the name of the cell containing the element and the
+    element itself. No cells are created.
+    """
+    def __init__(
+        self,
+        file_name: PathLike,
+        cells: list[str] | None = None,
+        layers: list[LayerDataType] | None = None,
+    ) -> None:
+        """Open a GDS file for iterating over its elements.
+
+        :param PathLike file_name: Input GDS file name.
+        :param list[str] cells: Names of the cells to read elements from, defaults
+        to all cells.
+        :param list[LayerDataType] layers: Layer and data type pairs of the polygons,
+        paths and texts to yield, defaults to all layers. References are always
+        yielded.
+        """
+    def __iter__(self) -> Self: ...
+    def __next__(self) -> tuple[str, Element]: ...
+
 __all__ = [
     "Cell",
+    "GdsCellIterator",
+    "GdsElementIterator",
     "Grid",
     "HorizontalPresentation",
     "Library",
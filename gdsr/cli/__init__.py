@@ -0,0 +1,5 @@
+"""Command line tools for GDSR."""
+
+from .diff import main as diff
+
+__all__ = ["diff"]
@@ -0,0 +1,43 @@
+"""Compare two layout files from the command line."""
+
+import argparse
+from collections.abc import Sequence
+from pathlib import Path
+
+from .._gdsr import Library
+
+OASIS_SUFFIXES = (".oas", ".oasis")
+
+
+def read_library(file_name: str) -> Library:
+    """Read a GDSII or OASIS file, chosen by the file extension."""
+    if Path(file_name).suffix.lower() in OASIS_SUFFIXES:
+        return Library.from_oasis(file_name)
+    return Library.from_gds(file_name)
+
+
+def main(argv: Sequence[str] | None = None) -> int:
+    """Print the differences between two layout files.
+
+    Returns 0 when the libraries are the same and 1 when they differ.
+    """
+    parser = argparse.ArgumentParser(
+        prog="gdsr-diff",
+        description="Compare the cells and elements of two GDSII or OASIS files.",
+    )
+    parser.add_argument("old", help="the original file")
+    parser.add_argument("new", help="the changed file")
+    parser.add_argument(
+        "--xor",
+        action="store_true",
+        help="also report the XOR area of changed cells on each layer after flattening",
+    )
+    args = parser.parse_args(argv)
+
+    difference = read_library(args.old).diff(read_library(args.new), xor=args.xor)
+    print(difference, end="")
+    return 1 if difference else 0
+
+
+if __name__ == "__main__":
+    raise SystemExit(main())
@@ -1,5 +1,6 @@
 """Type hints for the gdsr package."""
 
+from datetime import datetime
 from pathlib import Path
 from typing import Iterable, Literal, Protocol, runtime_checkable
 
@@ -112,3 +113,24 @@ DataType = int
 PathLike = Path | str
 LayerDataType = tuple[Layer, DataType]
 """A tuple containing a layer and a data type."""
+Properties = dict[int, str]
+"""GDSII element properties, mapping a property attribute to its value."""
+
+BooleanOperation = Literal[
+    "union", "or", "intersection", "and", "difference", "not", "xor"
+]
+"""A boolean operation that can be performed between polygons."""
+
+OffsetJoin = Literal["miter", "round", "bevel"]
+"""The style of the corners created when offsetting polygons."""
+
+MaxPointsMode = Literal["fracture", "error", "extended"]
+"""How elements with more points than a GDSII XY record allows are written."""
+
+Timestamp = datetime | Literal["now", "preserve", "epoch"]
+"""The dates written to the BGNLIB and BGNSTR records of a GDSII file.
+
+A datetime is written to every record, ``"now"`` uses the current time,
+``"preserve"`` reuses the dates stored on the library and cells, falling back to the
+current time, and ``"epoch"`` writes 1970-01-01 00:00:00.
+"""
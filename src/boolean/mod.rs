@@ -0,0 +1,83 @@
+use i_overlay::core::overlay_rule::OverlayRule;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    config::DEFAULT_GRID,
+    polygon::Polygon,
+    utils::overlay::OverlayGrid,
+    validation::input::{check_data_type_valid, check_grid_valid, check_layer_valid},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BooleanOperation {
+    Union,
+    Intersection,
+    Difference,
+    Xor,
+}
+
+impl BooleanOperation {
+    pub fn new(value: &str) -> PyResult<Self> {
+        match value.to_lowercase().as_str() {
+            "union" | "or" => Ok(BooleanOperation::Union),
+            "intersection" | "and" => Ok(BooleanOperation::Intersection),
+            "difference" | "not" => Ok(BooleanOperation::Difference),
+            "xor" => Ok(BooleanOperation::Xor),
+            _ => Err(PyValueError::new_err(format!(
+                "Invalid boolean operation: {}, expected one of 'union', 'intersection', 'difference' or 'xor'",
+                value
+            ))),
+        }
+    }
+
+    fn overlay_rule(&self) -> OverlayRule {
+        match self {
+            BooleanOperation::Union => OverlayRule::Union,
+            BooleanOperation::Intersection => OverlayRule::Intersect,
+            BooleanOperation::Difference => OverlayRule::Difference,
+            BooleanOperation::Xor => OverlayRule::Xor,
+        }
+    }
+}
+
+pub fn boolean_polygons(
+    a: &[Polygon],
+    b: &[Polygon],
+    operation: BooleanOperation,
+    layer: i32,
+    data_type: i32,
+    grid: f64,
+) -> PyResult<Vec<Polygon>> {
+    let grid = OverlayGrid::fit(a.iter().chain(b), 0.0, grid)?;
+    let shapes = grid.overlay(
+        &grid.contours(a),
+        &grid.contours(b),
+        operation.overlay_rule(),
+    );
+
+    grid.shapes_to_polygons(shapes, layer, data_type)
+}
+
+#[pyfunction]
+#[pyo3(signature = (a, b, operation, layer=0, data_type=0, grid=DEFAULT_GRID))]
+pub fn boolean(
+    a: Vec<Polygon>,
+    b: Vec<Polygon>,
+    operation: &str,
+    layer: i32,
+    data_type: i32,
+    grid: f64,
+) -> PyResult<Vec<Polygon>> {
+    check_layer_valid(layer)?;
+    check_data_type_valid(data_type)?;
+    check_grid_valid(grid)?;
+
+    boolean_polygons(
+        &a,
+        &b,
+        BooleanOperation::new(operation)?,
+        layer,
+        data_type,
+        grid,
+    )
+}
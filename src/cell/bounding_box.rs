@@ -0,0 +1,335 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    config::FLOATING_POINT_INACCURACY_ROUND_DECIMALS,
+    element::Element,
+    grid::Grid,
+    path::utils::path_outline_points,
+    point::Point,
+    reference::{Instance, Reference},
+    traits::{Dimensions, LayerDataTypeMatches},
+};
+
+use super::Cell;
+
+pub type BoundingBox = (Point, Point);
+
+pub fn empty_bounding_box() -> BoundingBox {
+    (
+        Point::new(f64::INFINITY, f64::INFINITY),
+        Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+    )
+}
+
+pub(crate) fn is_empty(bounding_box: &BoundingBox) -> bool {
+    bounding_box.0.x > bounding_box.1.x || bounding_box.0.y > bounding_box.1.y
+}
+
+fn union(a: BoundingBox, b: BoundingBox) -> BoundingBox {
+    (
+        Point::new(a.0.x.min(b.0.x), a.0.y.min(b.0.y)),
+        Point::new(a.1.x.max(b.1.x), a.1.y.max(b.1.y)),
+    )
+}
+
+pub(crate) fn points_bounding_box(points: impl IntoIterator<Item = Point>) -> BoundingBox {
+    points
+        .into_iter()
+        .fold(empty_bounding_box(), |bounding_box, point| {
+            union(bounding_box, (point, point))
+        })
+}
+
+/// Layer filter used as a cache key, `None` means every layer.
+pub type LayerFilter = Option<Vec<(i32, i32)>>;
+
+pub fn layer_filter(layer_data_types: Option<Vec<(i32, i32)>>) -> LayerFilter {
+    let mut layer_data_types = layer_data_types.filter(|layers| !layers.is_empty())?;
+    layer_data_types.sort_unstable();
+    layer_data_types.dedup();
+    Some(layer_data_types)
+}
+
+fn layer_slice(layers: &LayerFilter) -> &[(i32, i32)] {
+    layers.as_deref().unwrap_or_default()
+}
+
+#[derive(Default)]
+pub struct BoundingBoxCache {
+    entries: Mutex<HashMap<LayerFilter, (u64, BoundingBox)>>,
+    absolute_transforms: Mutex<Option<(u64, bool)>>,
+}
+
+// Copies start with an empty cache so they never share entries with the original
+impl Clone for BoundingBoxCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl BoundingBoxCache {
+    fn get(&self, layers: &LayerFilter, revision: u64) -> Option<BoundingBox> {
+        let entries = self.entries.lock().unwrap();
+        match entries.get(layers) {
+            Some((entry_revision, bounding_box)) if *entry_revision == revision => {
+                Some(*bounding_box)
+            }
+            _ => None,
+        }
+    }
+
+    fn insert(&self, layers: &LayerFilter, revision: u64, bounding_box: BoundingBox) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (entry_revision, _)| *entry_revision == revision);
+        entries.insert(layers.clone(), (revision, bounding_box));
+    }
+
+    fn get_absolute_transforms(&self, revision: u64) -> Option<bool> {
+        match *self.absolute_transforms.lock().unwrap() {
+            Some((entry_revision, absolute_transforms)) if entry_revision == revision => {
+                Some(absolute_transforms)
+            }
+            _ => None,
+        }
+    }
+
+    fn insert_absolute_transforms(&self, revision: u64, absolute_transforms: bool) {
+        *self.absolute_transforms.lock().unwrap() = Some((revision, absolute_transforms));
+    }
+}
+
+/// A single instance transform: x reflection, then magnification and rotation, then translation.
+#[derive(Clone, Copy)]
+pub(crate) struct Placement {
+    origin: Point,
+    angle: f64,
+    magnification: f64,
+    x_reflection: bool,
+}
+
+impl Placement {
+    const IDENTITY: Placement = Placement {
+        origin: Point { x: 0.0, y: 0.0 },
+        angle: 0.0,
+        magnification: 1.0,
+        x_reflection: false,
+    };
+
+    pub(crate) fn from_grid(grid: &Grid, origin: Point) -> Self {
+        Placement {
+            origin,
+            angle: grid.angle,
+            magnification: grid.magnification,
+            x_reflection: grid.x_reflection,
+        }
+    }
+
+    fn as_grid(&self) -> Grid {
+        Grid {
+            angle: self.angle,
+            magnification: self.magnification,
+            x_reflection: self.x_reflection,
+            ..Grid::default()
+        }
+    }
+
+    fn apply(&self, point: Point) -> Point {
+        let point = if self.x_reflection {
+            Point::new(point.x, -point.y)
+        } else {
+            point
+        };
+        ((point * self.magnification).rotate(self.angle, Point::default()) + self.origin)
+            .round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS)
+    }
+
+    /// Bounding box in the placed frame of everything this placement maps into `bounding_box`.
+    pub(crate) fn invert_bounding_box(&self, bounding_box: BoundingBox) -> BoundingBox {
+        if self.magnification == 0.0 {
+            return (
+                Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+                Point::new(f64::INFINITY, f64::INFINITY),
+            );
+        }
+        let (min, max) = bounding_box;
+        points_bounding_box(
+            [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)]
+                .into_iter()
+                .map(|corner| {
+                    let point = (corner - self.origin).rotate(-self.angle, Point::default())
+                        * (1.0 / self.magnification);
+                    let point = if self.x_reflection {
+                        Point::new(point.x, -point.y)
+                    } else {
+                        point
+                    };
+                    point.round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS)
+                }),
+        )
+    }
+
+    fn is_manhattan(&self) -> bool {
+        self.angle.rem_euclid(90.0) == 0.0
+    }
+
+    pub(crate) fn apply_to_bounding_box(&self, bounding_box: BoundingBox) -> BoundingBox {
+        if is_empty(&bounding_box) {
+            return bounding_box;
+        }
+        let (min, max) = bounding_box;
+        points_bounding_box(
+            [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)]
+                .into_iter()
+                .map(|corner| self.apply(corner)),
+        )
+    }
+}
+
+pub fn cell_bounding_box(cell: &Cell, layers: &LayerFilter) -> BoundingBox {
+    let revision = cell.revision.get();
+    if let Some(bounding_box) = cell.bounding_box_cache.get(layers, revision) {
+        return bounding_box;
+    }
+
+    let bounding_box = walk_cell(cell, &Placement::IDENTITY, layers);
+    cell.bounding_box_cache
+        .insert(layers, revision, bounding_box);
+    bounding_box
+}
+
+pub fn reference_bounding_box(reference: &Reference, layers: &LayerFilter) -> BoundingBox {
+    placed_reference_bounding_box(reference, &Placement::IDENTITY, layers)
+}
+
+fn reference_has_absolute_transforms(reference: &Reference) -> bool {
+    let grid = reference.grid.read();
+    if grid.absolute_magnification || grid.absolute_angle {
+        return true;
+    }
+    match &reference.instance {
+        Instance::Cell(cell) => has_absolute_transforms(&cell.read()),
+        Instance::Element(Element::Reference(reference)) => {
+            reference_has_absolute_transforms(&reference.read())
+        }
+        Instance::Element(_) => false,
+    }
+}
+
+/// Whether any reference below `cell` ignores the magnification or angle it is placed with.
+fn has_absolute_transforms(cell: &Cell) -> bool {
+    let revision = cell.revision.get();
+    if let Some(absolute_transforms) = cell.bounding_box_cache.get_absolute_transforms(revision) {
+        return absolute_transforms;
+    }
+
+    let absolute_transforms = cell
+        .references
+        .iter()
+        .any(|reference| reference_has_absolute_transforms(&reference.read()));
+    cell.bounding_box_cache
+        .insert_absolute_transforms(revision, absolute_transforms);
+    absolute_transforms
+}
+
+fn placed_cell_bounding_box(
+    cell: &Cell,
+    placement: &Placement,
+    layers: &LayerFilter,
+) -> BoundingBox {
+    // Rotating a box by a multiple of 90 degrees is exact, any other angle needs the geometry,
+    // as do absolute transforms below the cell since the cached box was placed without them
+    if placement.is_manhattan() && !has_absolute_transforms(cell) {
+        placement.apply_to_bounding_box(cell_bounding_box(cell, layers))
+    } else {
+        walk_cell(cell, placement, layers)
+    }
+}
+
+fn walk_cell(cell: &Cell, placement: &Placement, layers: &LayerFilter) -> BoundingBox {
+    let mut bounding_box = empty_bounding_box();
+
+    for element in cell.direct_elements(layer_slice(layers)) {
+        bounding_box = union(
+            bounding_box,
+            placed_element_bounding_box(&element, placement, layers),
+        );
+    }
+
+    for reference in &cell.references {
+        bounding_box = union(
+            bounding_box,
+            placed_reference_bounding_box(&reference.read(), placement, layers),
+        );
+    }
+
+    bounding_box
+}
+
+fn placed_element_bounding_box(
+    element: &Element,
+    placement: &Placement,
+    layers: &LayerFilter,
+) -> BoundingBox {
+    let points = match element {
+        Element::Reference(reference) => {
+            return placed_reference_bounding_box(&reference.read(), placement, layers);
+        }
+        Element::Polygon(polygon) => polygon.read().points.clone(),
+        Element::Box(gds_box) => gds_box.read().points.clone(),
+        Element::Node(node) => node.read().points.clone(),
+        Element::Path(path) => path_outline_points(&path.read()),
+        Element::Text(text) => {
+            let (min, max) = text.read().bounding_box();
+            vec![min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)]
+        }
+    };
+
+    points_bounding_box(points.into_iter().map(|point| placement.apply(point)))
+}
+
+fn placed_reference_bounding_box(
+    reference: &Reference,
+    placement: &Placement,
+    layers: &LayerFilter,
+) -> BoundingBox {
+    let grid = reference
+        .grid
+        .read()
+        .placed_in(&placement.as_grid(), placement.origin);
+
+    if grid.columns == 0 || grid.rows == 0 {
+        return empty_bounding_box();
+    }
+
+    // Every instance is a translated copy, so the corner instances bound the whole array
+    let corner_grid = Grid {
+        columns: grid.columns.min(2),
+        rows: grid.rows.min(2),
+        spacing_x: grid.spacing_x * (grid.columns - 1).max(1) as f64,
+        spacing_y: grid.spacing_y * (grid.rows - 1).max(1) as f64,
+        ..grid.clone()
+    };
+
+    corner_grid
+        .instance_origins()
+        .into_iter()
+        .map(|origin| {
+            let instance_placement = Placement::from_grid(&grid, origin);
+            match &reference.instance {
+                Instance::Cell(cell) => {
+                    placed_cell_bounding_box(&cell.read(), &instance_placement, layers)
+                }
+                Instance::Element(element @ Element::Reference(_)) => {
+                    placed_element_bounding_box(element, &instance_placement, layers)
+                }
+                Instance::Element(element) => {
+                    if element.is_on(layer_slice(layers).to_vec()) {
+                        placed_element_bounding_box(element, &instance_placement, layers)
+                    } else {
+                        empty_bounding_box()
+                    }
+                }
+            }
+        })
+        .fold(empty_bounding_box(), union)
+}
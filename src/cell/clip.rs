@@ -0,0 +1,348 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    slice,
+};
+
+use pyo3::prelude::*;
+
+use crate::{
+    boolean::{boolean_polygons, BooleanOperation},
+    element::Element,
+    grid::Grid,
+    point::Point,
+    polygon::Polygon,
+    reference::{Instance, Reference},
+    shared::Shared,
+    utils::geometry::{area, bounding_box, is_point_inside, rectangle, AREA_TOLERANCE},
+};
+
+use super::{
+    bounding_box::{is_empty, BoundingBox},
+    spatial_index::{instance_extent, overlapping_instances, overlaps, query_reference},
+    Cell,
+};
+
+pub struct ClipRegion {
+    polygon: Polygon,
+    bounding_box: BoundingBox,
+    is_rectangle: bool,
+    grid: f64,
+}
+
+enum Overlap {
+    Inside,
+    Outside,
+    Partial,
+}
+
+fn total_area(polygons: &[Polygon]) -> f64 {
+    polygons
+        .iter()
+        .map(|polygon| area(&polygon.points).unwrap_or(0.0))
+        .sum()
+}
+
+impl ClipRegion {
+    pub fn from_corners(a: Point, b: Point, grid: f64) -> Self {
+        let min = Point::new(a.x.min(b.x), a.y.min(b.y));
+        let max = Point::new(a.x.max(b.x), a.y.max(b.y));
+        ClipRegion {
+            polygon: rectangle(min, max, 0, 0),
+            bounding_box: (min, max),
+            is_rectangle: true,
+            grid,
+        }
+    }
+
+    pub fn from_polygon(polygon: Polygon, grid: f64) -> Self {
+        let bounding_box = bounding_box(&polygon.points);
+        let (min, max) = bounding_box;
+        let bounding_box_area = (max.x - min.x) * (max.y - min.y);
+        let is_rectangle = (total_area(slice::from_ref(&polygon)) - bounding_box_area).abs()
+            <= bounding_box_area * AREA_TOLERANCE;
+        ClipRegion {
+            polygon,
+            bounding_box,
+            is_rectangle,
+            grid,
+        }
+    }
+
+    fn contains_point(&self, point: &Point) -> bool {
+        is_point_inside(point, &self.polygon.points)
+    }
+
+    fn contains_points(&self, points: &[Point]) -> bool {
+        points.iter().all(|point| self.contains_point(point))
+    }
+
+    fn classify(&self, bounding_box: &BoundingBox) -> Overlap {
+        if !overlaps(bounding_box, &self.bounding_box) {
+            return Overlap::Outside;
+        }
+        let (min, max) = bounding_box;
+        let within_bounds = min.x >= self.bounding_box.0.x
+            && min.y >= self.bounding_box.0.y
+            && max.x <= self.bounding_box.1.x
+            && max.y <= self.bounding_box.1.y;
+        if within_bounds && self.is_rectangle {
+            Overlap::Inside
+        } else {
+            Overlap::Partial
+        }
+    }
+
+    fn contains_bounding_box(&self, bounding_box: &BoundingBox) -> PyResult<bool> {
+        match self.classify(bounding_box) {
+            Overlap::Inside => Ok(true),
+            Overlap::Outside => Ok(false),
+            Overlap::Partial => {
+                let (min, max) = *bounding_box;
+                let bounding_box_area = (max.x - min.x) * (max.y - min.y);
+                if bounding_box_area == 0.0 {
+                    return Ok(self.contains_points(&[min, max]));
+                }
+                let inside = self.intersect(&[rectangle(min, max, 0, 0)], 0, 0)?;
+                Ok((total_area(&inside) - bounding_box_area).abs()
+                    <= bounding_box_area * AREA_TOLERANCE)
+            }
+        }
+    }
+
+    fn intersect(
+        &self,
+        polygons: &[Polygon],
+        layer: i32,
+        data_type: i32,
+    ) -> PyResult<Vec<Polygon>> {
+        boolean_polygons(
+            polygons,
+            slice::from_ref(&self.polygon),
+            BooleanOperation::Intersection,
+            layer,
+            data_type,
+            self.grid,
+        )
+    }
+
+    /// Cuts filled geometry, `None` means it is already entirely inside the region.
+    fn cut(
+        &self,
+        outline: &[Polygon],
+        layer: i32,
+        data_type: i32,
+    ) -> PyResult<Option<Vec<Polygon>>> {
+        let points = outline
+            .iter()
+            .flat_map(|polygon| polygon.points.clone())
+            .collect();
+        Ok(match self.classify(&bounding_box(&points)) {
+            Overlap::Inside => None,
+            Overlap::Outside => Some(Vec::new()),
+            Overlap::Partial => {
+                let pieces = self.intersect(outline, layer, data_type)?;
+                let original_area = total_area(outline);
+                let unchanged = pieces.len() == outline.len()
+                    && (total_area(&pieces) - original_area).abs()
+                        <= original_area * AREA_TOLERANCE;
+                if unchanged {
+                    None
+                } else {
+                    Some(pieces)
+                }
+            }
+        })
+    }
+}
+
+fn polygon_elements(pieces: Vec<Polygon>, properties: &HashMap<i32, String>) -> Vec<Element> {
+    pieces
+        .into_iter()
+        .map(|piece| {
+            Element::Polygon(Shared::new(Polygon {
+                properties: properties.clone(),
+                ..piece
+            }))
+        })
+        .collect()
+}
+
+fn clip_element(element: &Element, region: &ClipRegion) -> PyResult<Vec<Element>> {
+    Ok(match element {
+        Element::Polygon(polygon) => {
+            let polygon = polygon.read();
+            match region.cut(slice::from_ref(&*polygon), polygon.layer, polygon.data_type)? {
+                None => vec![element.copy()],
+                Some(pieces) => polygon_elements(pieces, &polygon.properties),
+            }
+        }
+        Element::Path(path) => {
+            let path = path.read();
+            let outline = path.to_polygons(region.grid)?;
+            if outline.is_empty() {
+                return Ok(if region.contains_points(&path.points) {
+                    vec![element.copy()]
+                } else {
+                    Vec::new()
+                });
+            }
+            match region.cut(&outline, path.layer, path.data_type)? {
+                None => vec![element.copy()],
+                Some(pieces) => polygon_elements(pieces, &path.properties),
+            }
+        }
+        Element::Text(text) => {
+            if region.contains_point(&text.read().origin) {
+                vec![element.copy()]
+            } else {
+                Vec::new()
+            }
+        }
+        Element::Node(node) => {
+            if region.contains_points(&node.read().points) {
+                vec![element.copy()]
+            } else {
+                Vec::new()
+            }
+        }
+        Element::Box(gds_box) => {
+            if region.contains_points(&gds_box.read().points) {
+                vec![element.copy()]
+            } else {
+                Vec::new()
+            }
+        }
+        Element::Reference(reference) => clip_reference(&reference.read(), region)?,
+    })
+}
+
+fn copy_instance(instance: &Instance) -> Instance {
+    match instance {
+        Instance::Cell(cell) => Instance::Cell(cell.clone()),
+        Instance::Element(element) => Instance::Element(element.copy()),
+    }
+}
+
+fn sub_reference(
+    reference: &Reference,
+    grid: &Grid,
+    origin: Point,
+    columns: u32,
+    rows: u32,
+) -> Element {
+    let grid = Grid {
+        origin,
+        columns,
+        rows,
+        ..grid.clone()
+    };
+    Element::Reference(Shared::new(Reference {
+        instance: copy_instance(&reference.instance),
+        grid: Shared::new(grid),
+        properties: reference.properties.clone(),
+    }))
+}
+
+fn clip_reference(reference: &Reference, region: &ClipRegion) -> PyResult<Vec<Element>> {
+    let grid = reference.grid.read().clone();
+    let extent = instance_extent(reference, &grid, &None);
+    if is_empty(&extent) {
+        return Ok(Vec::new());
+    }
+
+    let mut elements = Vec::new();
+    // Instances fully inside the region stay as references, keyed by column then row
+    let mut kept: BTreeMap<u32, BTreeMap<u32, Point>> = BTreeMap::new();
+
+    for (column, row, origin) in overlapping_instances(&grid, extent, region.bounding_box) {
+        let instance_box = (extent.0 + origin, extent.1 + origin);
+        if region.contains_bounding_box(&instance_box)? {
+            kept.entry(column).or_default().insert(row, origin);
+            continue;
+        }
+
+        let single = Reference {
+            instance: copy_instance(&reference.instance),
+            grid: Shared::new(Grid {
+                origin,
+                columns: 1,
+                rows: 1,
+                ..grid.clone()
+            }),
+            properties: reference.properties.clone(),
+        };
+        let mut placed = Vec::new();
+        query_reference(&single, region.bounding_box, &None, usize::MAX, &mut placed);
+        for element in placed {
+            elements.extend(clip_element(&element, region)?);
+        }
+    }
+
+    let kept_count: usize = kept.values().map(|rows| rows.len()).sum();
+    let first_column = kept.keys().next().copied();
+    let last_column = kept.keys().next_back().copied();
+    let first_row = kept.values().flat_map(|rows| rows.keys()).min().copied();
+    let last_row = kept.values().flat_map(|rows| rows.keys()).max().copied();
+
+    if let (Some(first_column), Some(last_column), Some(first_row), Some(last_row)) =
+        (first_column, last_column, first_row, last_row)
+    {
+        let columns = last_column - first_column + 1;
+        let rows = last_row - first_row + 1;
+        if kept_count == (columns * rows) as usize {
+            let origin = kept[&first_column][&first_row];
+            elements.push(sub_reference(reference, &grid, origin, columns, rows));
+            return Ok(elements);
+        }
+    }
+
+    // Irregular selections become one reference per contiguous run within a column
+    for rows in kept.values() {
+        let mut run: Option<(u32, u32, Point)> = None;
+        for (&row, &origin) in rows {
+            run = match run {
+                Some((start, end, start_origin)) if row == end + 1 => {
+                    Some((start, row, start_origin))
+                }
+                Some((start, end, start_origin)) => {
+                    elements.push(sub_reference(
+                        reference,
+                        &grid,
+                        start_origin,
+                        1,
+                        end - start + 1,
+                    ));
+                    Some((row, row, origin))
+                }
+                None => Some((row, row, origin)),
+            };
+        }
+        if let Some((start, end, start_origin)) = run {
+            elements.push(sub_reference(
+                reference,
+                &grid,
+                start_origin,
+                1,
+                end - start + 1,
+            ));
+        }
+    }
+
+    Ok(elements)
+}
+
+/// Returns a copy of `cell` holding only the geometry inside `region`.
+pub fn clip_cell(cell: &Cell, region: &ClipRegion) -> PyResult<Cell> {
+    let mut clipped = Cell::new(cell.name.clone());
+
+    let mut elements = Vec::new();
+    for element in cell.direct_elements(&[]) {
+        elements.extend(clip_element(&element, region)?);
+    }
+    for reference in &cell.references {
+        elements.extend(clip_reference(&reference.read(), region)?);
+    }
+
+    clipped.add(elements);
+    Ok(clipped)
+}
@@ -0,0 +1,387 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    slice,
+};
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+use rstar::{
+    primitives::{GeomWithData, Rectangle},
+    RTree, AABB,
+};
+
+use crate::{
+    boolean::{boolean_polygons, BooleanOperation},
+    element::Element,
+    grid::Grid,
+    offset::{offset_polygons, OffsetJoin},
+    point::Point,
+    polygon::Polygon,
+    reference::{Instance, Reference},
+    shared::Shared,
+    utils::geometry::{area, bounding_box, rectangle, AREA_TOLERANCE},
+    validation::input::{check_data_type_valid, check_grid_valid, check_layer_valid},
+};
+
+use super::{bounding_box::BoundingBox, Cell};
+
+type IndexEntry = GeomWithData<Rectangle<[f64; 2]>, usize>;
+
+pub struct FillSettings {
+    pub name: String,
+    pub fill_layer: (i32, i32),
+    pub tile: Polygon,
+    pub pitch: Point,
+    pub target_density: f64,
+    pub spacing: f64,
+    pub window_size: Option<f64>,
+    pub grid: f64,
+}
+
+impl FillSettings {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        name: String,
+        fill_layer: (i32, i32),
+        tile: Polygon,
+        pitch: Point,
+        target_density: f64,
+        spacing: f64,
+        window_size: Option<f64>,
+        grid: f64,
+    ) -> PyResult<Self> {
+        check_layer_valid(fill_layer.0)?;
+        check_data_type_valid(fill_layer.1)?;
+        check_grid_valid(grid)?;
+        if polygon_area(&tile) <= 0.0 {
+            return Err(PyValueError::new_err("Fill tile must have a positive area"));
+        }
+        let (tile_min, tile_max) = bounding_box(&tile.points);
+        let tile_size = tile_max - tile_min;
+        if pitch.x < tile_size.x || pitch.y < tile_size.y {
+            return Err(PyValueError::new_err(
+                "Fill pitch must be at least the tile size",
+            ));
+        }
+        if target_density.is_nan() || target_density <= 0.0 || target_density > 1.0 {
+            return Err(PyValueError::new_err(
+                "Target density must be greater than 0 and at most 1",
+            ));
+        }
+        if spacing.is_nan() || spacing < 0.0 {
+            return Err(PyValueError::new_err("Fill spacing must not be negative"));
+        }
+        if window_size.is_some_and(|window_size| window_size.is_nan() || window_size <= 0.0) {
+            return Err(PyValueError::new_err("Window size must be positive"));
+        }
+        Ok(FillSettings {
+            name,
+            fill_layer,
+            tile,
+            pitch,
+            target_density,
+            spacing,
+            window_size,
+            grid,
+        })
+    }
+}
+
+fn polygon_area(polygon: &Polygon) -> f64 {
+    area(&polygon.points).unwrap_or(0.0).abs()
+}
+
+/// Polygons with an R-tree over their bounding boxes.
+struct PolygonIndex {
+    polygons: Vec<Polygon>,
+    bounding_boxes: Vec<BoundingBox>,
+    tree: RTree<IndexEntry>,
+    grid: f64,
+}
+
+impl PolygonIndex {
+    fn new(polygons: Vec<Polygon>, grid: f64) -> Self {
+        let bounding_boxes: Vec<BoundingBox> = polygons
+            .iter()
+            .map(|polygon| bounding_box(&polygon.points))
+            .collect();
+        let tree = RTree::bulk_load(
+            bounding_boxes
+                .iter()
+                .enumerate()
+                .map(|(index, (min, max))| {
+                    IndexEntry::new(
+                        Rectangle::from_corners([min.x, min.y], [max.x, max.y]),
+                        index,
+                    )
+                })
+                .collect(),
+        );
+        PolygonIndex {
+            polygons,
+            bounding_boxes,
+            tree,
+            grid,
+        }
+    }
+
+    /// Polygons whose bounding box overlaps `region` with a positive area.
+    fn overlapping(&self, region: BoundingBox) -> impl Iterator<Item = usize> + '_ {
+        let (min, max) = region;
+        self.tree
+            .locate_in_envelope_intersecting(&AABB::from_corners([min.x, min.y], [max.x, max.y]))
+            .map(|entry| entry.data)
+            .filter(move |&index| {
+                let (other_min, other_max) = self.bounding_boxes[index];
+                max.x.min(other_max.x) - min.x.max(other_min.x) > AREA_TOLERANCE
+                    && max.y.min(other_max.y) - min.y.max(other_min.y) > AREA_TOLERANCE
+            })
+    }
+
+    fn area_within(&self, region: BoundingBox) -> PyResult<f64> {
+        let polygons: Vec<Polygon> = self
+            .overlapping(region)
+            .map(|index| self.polygons[index].clone())
+            .collect();
+        if polygons.is_empty() {
+            return Ok(0.0);
+        }
+        Ok(boolean_polygons(
+            &polygons,
+            slice::from_ref(&rectangle(region.0, region.1, 0, 0)),
+            BooleanOperation::Intersection,
+            0,
+            0,
+            self.grid,
+        )?
+        .iter()
+        .map(polygon_area)
+        .sum())
+    }
+
+    fn is_blocking(&self, index: usize, placed: &Polygon, placed_area: f64) -> PyResult<bool> {
+        let blocker = &self.polygons[index];
+        let (min, max) = self.bounding_boxes[index];
+        // Rectangular blockers overlap exactly where their bounding boxes do
+        if (polygon_area(blocker) - (max.x - min.x) * (max.y - min.y)).abs()
+            <= polygon_area(blocker) * AREA_TOLERANCE
+        {
+            return Ok(true);
+        }
+        Ok(boolean_polygons(
+            slice::from_ref(placed),
+            slice::from_ref(blocker),
+            BooleanOperation::Intersection,
+            0,
+            0,
+            self.grid,
+        )?
+        .iter()
+        .map(polygon_area)
+        .sum::<f64>()
+            > placed_area * AREA_TOLERANCE)
+    }
+}
+
+/// Order of `count` rows that spreads the first picks evenly over the window.
+fn spread_order(count: usize) -> Vec<usize> {
+    let bits = usize::BITS - count.saturating_sub(1).leading_zeros();
+    let mut order: Vec<usize> = (0..count).collect();
+    if bits > 0 {
+        order.sort_by_key(|row| row.reverse_bits() >> (usize::BITS - bits));
+    }
+    order
+}
+
+/// Returns a cell of `Reference` arrays to a single tile cell, filling each density
+/// window of `region` up to the target density while keeping `spacing` away from the
+/// layer and keep-out polygons.
+pub fn fill_cell(
+    region: BoundingBox,
+    layer_polygons: Vec<Polygon>,
+    keep_out_polygons: Vec<Polygon>,
+    settings: &FillSettings,
+) -> PyResult<Cell> {
+    let mut fill = Cell::new(settings.name.clone());
+
+    let (tile_min, tile_max) = bounding_box(&settings.tile.points);
+    let tile_size = tile_max - tile_min;
+    let tile_area = polygon_area(&settings.tile);
+    let (region_min, region_max) = region;
+    let region_size = region_max - region_min;
+    if region_size.x < tile_size.x || region_size.y < tile_size.y || tile_area <= 0.0 {
+        return Ok(fill);
+    }
+
+    let mut blockers = layer_polygons.clone();
+    blockers.extend(keep_out_polygons);
+    let blocked = PolygonIndex::new(
+        offset_polygons(
+            &blockers,
+            settings.spacing,
+            OffsetJoin::Miter(2.0),
+            0,
+            0,
+            settings.grid,
+        )?,
+        settings.grid,
+    );
+    let existing = PolygonIndex::new(layer_polygons, settings.grid);
+
+    let pitch = settings.pitch;
+    let site_origin = |column: i64, row: i64| {
+        region_min - tile_min + Point::new(column as f64 * pitch.x, row as f64 * pitch.y)
+    };
+    let is_free = |column: i64, row: i64| {
+        let origin = site_origin(column, row);
+        let site = (origin + tile_min, origin + tile_max);
+        let mut overlapping = blocked.overlapping(site).peekable();
+        if overlapping.peek().is_none() {
+            return Ok(true);
+        }
+        let placed = Polygon {
+            points: settings
+                .tile
+                .points
+                .iter()
+                .map(|point| *point + origin)
+                .collect(),
+            ..Polygon::default()
+        };
+        for index in overlapping {
+            if blocked.is_blocking(index, &placed, tile_area)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    };
+
+    let window_size = settings
+        .window_size
+        .unwrap_or(region_size.x.max(region_size.y));
+    let windows_x = ((region_size.x / window_size) - AREA_TOLERANCE)
+        .ceil()
+        .max(1.0) as i64;
+    let windows_y = ((region_size.y / window_size) - AREA_TOLERANCE)
+        .ceil()
+        .max(1.0) as i64;
+
+    // Runs of tiles along each row of the site lattice, as start column and length
+    let mut runs: BTreeMap<i64, Vec<(i64, i64)>> = BTreeMap::new();
+
+    for window_x in 0..windows_x {
+        for window_y in 0..windows_y {
+            let window_min =
+                region_min + Point::new(window_x as f64, window_y as f64) * window_size;
+            let window_max = Point::new(
+                (window_min.x + window_size).min(region_max.x),
+                (window_min.y + window_size).min(region_max.y),
+            );
+            let window_area = (window_max.x - window_min.x) * (window_max.y - window_min.y);
+            let needed = settings.target_density * window_area
+                - existing.area_within((window_min, window_max))?;
+            if needed <= 0.0 {
+                continue;
+            }
+            let mut remaining = (needed / tile_area - AREA_TOLERANCE).ceil() as i64;
+
+            // Only tiles entirely inside the window count towards its density
+            let first_column =
+                ((window_min.x - region_min.x) / pitch.x - AREA_TOLERANCE).ceil() as i64;
+            let last_column = ((window_max.x - tile_size.x - region_min.x) / pitch.x
+                + AREA_TOLERANCE)
+                .floor() as i64;
+            let first_row =
+                ((window_min.y - region_min.y) / pitch.y - AREA_TOLERANCE).ceil() as i64;
+            let last_row = ((window_max.y - tile_size.y - region_min.y) / pitch.y + AREA_TOLERANCE)
+                .floor() as i64;
+            if last_column < first_column || last_row < first_row {
+                continue;
+            }
+
+            for offset in spread_order((last_row - first_row + 1) as usize) {
+                let row = first_row + offset as i64;
+                let free: Vec<bool> = (first_column..=last_column)
+                    .map(|column| is_free(column, row))
+                    .collect::<PyResult<_>>()?;
+                let mut column = 0;
+                while column < free.len() && remaining > 0 {
+                    if !free[column] {
+                        column += 1;
+                        continue;
+                    }
+                    let start = column;
+                    while column < free.len()
+                        && free[column]
+                        && ((column - start) as i64) < remaining
+                    {
+                        column += 1;
+                    }
+                    let length = (column - start) as i64;
+                    runs.entry(row)
+                        .or_default()
+                        .push((first_column + start as i64, length));
+                    remaining -= length;
+                }
+                if remaining == 0 {
+                    break;
+                }
+            }
+        }
+    }
+
+    // Runs continuing into the next window are joined, then identical runs on
+    // consecutive rows are stacked into a single array
+    let mut stacks: BTreeMap<(i64, i64), Vec<i64>> = BTreeMap::new();
+    for (row, mut row_runs) in runs {
+        row_runs.sort();
+        let mut joined: Vec<(i64, i64)> = Vec::new();
+        for (start, length) in row_runs {
+            match joined.last_mut() {
+                Some((last_start, last_length)) if *last_start + *last_length == start => {
+                    *last_length += length;
+                }
+                _ => joined.push((start, length)),
+            }
+        }
+        for run in joined {
+            stacks.entry(run).or_default().push(row);
+        }
+    }
+
+    let mut tile_cell = Cell::new(format!("{}_TILE", settings.name));
+    tile_cell.add(vec![Element::Polygon(Shared::new(Polygon {
+        layer: settings.fill_layer.0,
+        data_type: settings.fill_layer.1,
+        properties: HashMap::new(),
+        ..settings.tile.clone()
+    }))]);
+    let tile_cell = Shared::new(tile_cell);
+
+    let mut references = Vec::new();
+    for ((start, columns), rows) in stacks {
+        let mut index = 0;
+        while index < rows.len() {
+            let mut count = 1;
+            while index + count < rows.len() && rows[index + count] == rows[index] + count as i64 {
+                count += 1;
+            }
+            let grid = Grid {
+                origin: site_origin(start, rows[index]),
+                columns: columns as u32,
+                rows: count as u32,
+                spacing_x: Point::new(pitch.x, 0.0),
+                spacing_y: Point::new(0.0, pitch.y),
+                ..Grid::default()
+            };
+            references.push(Element::Reference(Shared::new(Reference {
+                instance: Instance::Cell(tile_cell.clone()),
+                grid: Shared::new(grid),
+                properties: HashMap::new(),
+            })));
+            index += count;
+        }
+    }
+
+    fill.add(references);
+    Ok(fill)
+}
@@ -1,222 +1,468 @@
-use std::ops::DerefMut;
+use std::sync::Arc;
 
-use pyo3::prelude::*;
+use chrono::NaiveDateTime;
+use pyo3::{exceptions::PyTypeError, prelude::*};
 
 use crate::{
+    config::DEFAULT_GRID,
+    drc::{general::group_by_layer, run_rules, DrcRule, DrcViolation},
     element::Element,
+    gds_box::GdsBox,
+    node::Node,
+    path::Path,
     point::Point,
-    traits::{Dimensions, LayerDataTypeMatches, Movable, Rotatable, Scalable},
+    polygon::Polygon,
+    reference::Reference,
+    shared::{revision::Revision, Shared},
+    text::Text,
+    traits::{LayerDataTypeMatches, Movable, Rotatable, Scalable},
     utils::transformations::py_any_to_point,
+    validation::input::check_grid_valid,
 };
 
-use super::Cell;
+use super::{
+    bounding_box::{cell_bounding_box, layer_filter, BoundingBoxCache},
+    clip::{clip_cell, ClipRegion},
+    fill::{fill_cell, FillSettings},
+    spatial_index::{query_cell, SpatialIndexCache},
+    xor::{check_tolerance_valid, xor_by_layer, LayerXor},
+    Cell, PyCell,
+};
 
 #[pymethods]
-impl Cell {
+impl PyCell {
     #[new]
     pub fn new(name: String) -> Self {
-        Cell {
-            name,
-            polygons: Vec::new(),
-            paths: Vec::new(),
-            references: Vec::new(),
-            texts: Vec::new(),
-        }
+        PyCell(Shared::new(Cell::new(name)))
     }
 
     #[getter]
-    fn bounding_box(&self) -> (Point, Point) {
-        Dimensions::bounding_box(self)
+    fn name(&self) -> String {
+        self.0.read().name.clone()
     }
 
-    #[pyo3(signature = (*elements))]
-    pub fn add(&mut self, elements: Vec<Element>) {
-        Python::with_gil(|py| {
-            for element in elements {
-                match element {
-                    Element::Polygon(polygon) => {
-                        self.polygons.push(polygon.clone_ref(py));
-                    }
-                    Element::Path(path) => {
-                        self.paths.push(path.clone_ref(py));
-                    }
-                    Element::Reference(reference) => {
-                        self.references.push(reference.clone_ref(py));
-                    }
-                    Element::Text(text) => {
-                        self.texts.push(text.clone_ref(py));
-                    }
+    #[setter]
+    fn set_name(&self, name: String) {
+        self.0.write().name = name;
+    }
+
+    #[getter]
+    fn polygons(&self) -> Vec<Shared<Polygon>> {
+        self.0.read().polygons.clone()
+    }
+
+    #[getter]
+    fn paths(&self) -> Vec<Shared<Path>> {
+        self.0.read().paths.clone()
+    }
+
+    #[getter]
+    fn references(&self) -> Vec<Shared<Reference>> {
+        self.0.read().references.clone()
+    }
+
+    #[getter]
+    fn texts(&self) -> Vec<Shared<Text>> {
+        self.0.read().texts.clone()
+    }
+
+    #[getter]
+    fn nodes(&self) -> Vec<Shared<Node>> {
+        self.0.read().nodes.clone()
+    }
+
+    #[getter]
+    fn boxes(&self) -> Vec<Shared<GdsBox>> {
+        self.0.read().boxes.clone()
+    }
+
+    #[getter]
+    fn modification_date(&self) -> Option<NaiveDateTime> {
+        self.0.read().modification_date
+    }
+
+    #[setter]
+    fn set_modification_date(&self, modification_date: Option<NaiveDateTime>) {
+        self.0.write().modification_date = modification_date;
+    }
+
+    #[getter]
+    fn access_date(&self) -> Option<NaiveDateTime> {
+        self.0.read().access_date
+    }
+
+    #[setter]
+    fn set_access_date(&self, access_date: Option<NaiveDateTime>) {
+        self.0.write().access_date = access_date;
+    }
+
+    #[pyo3(signature = (layers=None))]
+    fn bounding_box(&self, layers: Option<Vec<(i32, i32)>>) -> (Point, Point) {
+        cell_bounding_box(&self.0.read(), &layer_filter(layers))
+    }
+
+    #[pyo3(signature = (region_min, region_max, layers=None, depth=None))]
+    fn query(
+        &self,
+        #[pyo3(from_py_with = "py_any_to_point")] region_min: Point,
+        #[pyo3(from_py_with = "py_any_to_point")] region_max: Point,
+        layers: Option<Vec<(i32, i32)>>,
+        depth: Option<usize>,
+    ) -> Vec<Element> {
+        let region = (
+            Point::new(
+                region_min.x.min(region_max.x),
+                region_min.y.min(region_max.y),
+            ),
+            Point::new(
+                region_min.x.max(region_max.x),
+                region_min.y.max(region_max.y),
+            ),
+        );
+        query_cell(
+            &self.0.read(),
+            region,
+            &layer_filter(layers),
+            depth.unwrap_or(usize::MAX),
+        )
+    }
+
+    #[pyo3(signature = (region, region_max=None, grid=DEFAULT_GRID))]
+    fn clip(
+        &self,
+        region: &Bound<'_, PyAny>,
+        region_max: Option<&Bound<'_, PyAny>>,
+        grid: f64,
+    ) -> PyResult<Cell> {
+        check_grid_valid(grid)?;
+        let region = match region_max {
+            Some(region_max) => ClipRegion::from_corners(
+                py_any_to_point(region)?,
+                py_any_to_point(region_max)?,
+                grid,
+            ),
+            None => match region.extract::<Polygon>() {
+                Ok(polygon) => ClipRegion::from_polygon(polygon, grid),
+                Err(_) => {
+                    return Err(PyTypeError::new_err(
+                        "Clip region must be a Polygon or two corner points",
+                    ))
                 }
-            }
-        });
+            },
+        };
+        clip_cell(&self.0.read(), &region)
     }
 
-    #[pyo3(signature=(*elements))]
-    pub fn remove(&mut self, elements: Vec<Element>) {
-        Python::with_gil(|py| {
-            for element in elements {
-                match element {
-                    Element::Polygon(polygon) => {
-                        self.polygons
-                            .retain(|x| !x.borrow(py).eq(&polygon.borrow(py)));
-                    }
-                    Element::Path(path) => {
-                        self.paths.retain(|x| !x.borrow(py).eq(&path.borrow(py)));
-                    }
-                    Element::Reference(reference) => {
-                        self.references
-                            .retain(|x| !x.borrow(py).eq(&reference.borrow(py)));
-                    }
-                    Element::Text(text) => {
-                        self.texts.retain(|x| !x.borrow(py).eq(&text.borrow(py)));
-                    }
+    #[pyo3(signature = (rules, grid=DEFAULT_GRID))]
+    fn drc(&self, rules: Vec<DrcRule>, grid: f64) -> PyResult<Vec<DrcViolation>> {
+        check_grid_valid(grid)?;
+        let mut layers = Vec::new();
+        for rule in &rules {
+            layers.push(rule.layer);
+            layers.extend(rule.other_layer);
+        }
+        layers.sort();
+        layers.dedup();
+
+        let cell = self.0.read();
+        let polygons = cell.layer_polygons(layers, grid)?;
+        run_rules(&group_by_layer(polygons), &rules, Some(&cell.name), grid)
+    }
+
+    #[pyo3(signature = (other, layers=None, tolerance=0.0, grid=DEFAULT_GRID))]
+    fn xor(
+        &self,
+        other: Shared<Cell>,
+        layers: Option<Vec<(i32, i32)>>,
+        tolerance: f64,
+        grid: f64,
+    ) -> PyResult<LayerXor> {
+        check_tolerance_valid(tolerance)?;
+        check_grid_valid(grid)?;
+        if self.0.ptr_eq(&other) {
+            return Ok(LayerXor::new());
+        }
+        let layers = layer_filter(layers).unwrap_or_default();
+        let polygons = self.0.read().layer_polygons(layers.clone(), grid)?;
+        let other_polygons = other.read().layer_polygons(layers, grid)?;
+        xor_by_layer(polygons, other_polygons, tolerance, grid)
+    }
+
+    #[pyo3(signature = (layer, tile, pitch, target_density, keep_out_layers=Vec::new(), spacing=0.0, window_size=None, fill_layer=None, name=None, grid=DEFAULT_GRID))]
+    #[allow(clippy::too_many_arguments)]
+    fn fill(
+        &self,
+        layer: (i32, i32),
+        tile: &Bound<'_, PyAny>,
+        #[pyo3(from_py_with = "py_any_to_point")] pitch: Point,
+        target_density: f64,
+        keep_out_layers: Vec<(i32, i32)>,
+        spacing: f64,
+        window_size: Option<f64>,
+        fill_layer: Option<(i32, i32)>,
+        name: Option<String>,
+        grid: f64,
+    ) -> PyResult<Cell> {
+        let tile = match tile.extract::<Polygon>() {
+            Ok(polygon) => polygon,
+            Err(_) => match py_any_to_point(tile) {
+                Ok(size) => Polygon {
+                    points: vec![
+                        Point::new(0.0, 0.0),
+                        Point::new(size.x, 0.0),
+                        size,
+                        Point::new(0.0, size.y),
+                        Point::new(0.0, 0.0),
+                    ],
+                    ..Polygon::default()
+                },
+                Err(_) => {
+                    return Err(PyTypeError::new_err(
+                        "Fill tile must be a Polygon or a width and height",
+                    ))
                 }
-            }
-        });
+            },
+        };
+        let cell = self.0.read();
+        let settings = FillSettings::new(
+            name.unwrap_or_else(|| format!("{}_FILL", cell.name)),
+            fill_layer.unwrap_or(layer),
+            tile,
+            pitch,
+            target_density,
+            spacing,
+            window_size,
+            grid,
+        )?;
+
+        let region = cell_bounding_box(&cell, &None);
+        let layer_polygons = cell.layer_polygons(vec![layer], grid)?;
+        let keep_out_polygons = if keep_out_layers.is_empty() {
+            Vec::new()
+        } else {
+            cell.layer_polygons(keep_out_layers, grid)?
+        };
+        fill_cell(region, layer_polygons, keep_out_polygons, &settings)
+    }
+
+    #[pyo3(signature = (*elements))]
+    pub fn add(&self, elements: Vec<Element>) {
+        self.0.write().add(elements);
+    }
+
+    #[pyo3(signature=(*elements))]
+    pub fn remove(&self, elements: Vec<Element>) {
+        self.0.write().remove(elements);
     }
 
     pub fn contains(&self, element: Element) -> bool {
-        Python::with_gil(|py| match element {
-            Element::Polygon(polygon) => {
-                for p in &self.polygons {
-                    if p.borrow(py).eq(&polygon.borrow(py)) {
-                        return true;
-                    }
-                }
-                false
-            }
-            Element::Path(path) => {
-                for p in &self.paths {
-                    if p.borrow(py).eq(&path.borrow(py)) {
-                        return true;
-                    }
-                }
-                false
-            }
-            Element::Reference(reference) => {
-                for r in &self.references {
-                    if r.borrow(py).eq(&reference.borrow(py)) {
-                        return true;
-                    }
-                }
-                false
-            }
-            Element::Text(text) => {
-                for t in &self.texts {
-                    if t.borrow(py).eq(&text.borrow(py)) {
-                        return true;
-                    }
-                }
-                false
-            }
-        })
+        self.0.read().contains(&element)
     }
 
     pub fn is_empty(&self) -> bool {
-        self.polygons.is_empty()
-            && self.paths.is_empty()
-            && self.references.is_empty()
-            && self.texts.is_empty()
+        self.0.read().is_empty()
     }
 
     fn move_to(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] point: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_to(slf.deref_mut(), point);
+    ) -> PyRef<'_, Self> {
+        Movable::move_to(&mut *slf.0.write(), point);
         slf
     }
 
     fn move_by(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] vector: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_by(slf.deref_mut(), vector);
+    ) -> PyRef<'_, Self> {
+        Movable::move_by(&mut *slf.0.write(), vector);
         slf
     }
 
     #[pyo3(signature = (angle, centre=Point::default()))]
     fn rotate(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         angle: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Rotatable::rotate(slf.deref_mut(), angle, centre);
+    ) -> PyRef<'_, Self> {
+        Rotatable::rotate(&mut *slf.0.write(), angle, centre);
         slf
     }
 
     #[pyo3(signature = (factor, centre=Point::default()))]
     fn scale(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         factor: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Scalable::scale(slf.deref_mut(), factor, centre);
+    ) -> PyRef<'_, Self> {
+        Scalable::scale(&mut *slf.0.write(), factor, centre);
         slf
     }
 
     #[pyo3(signature = (*layer_data_types, depth=None))]
-    pub fn flatten<'a>(
-        mut slf: PyRefMut<'a, Self>,
+    pub fn flatten(
+        slf: PyRef<'_, Self>,
+        layer_data_types: Vec<(i32, i32)>,
+        depth: Option<usize>,
+    ) -> PyRef<'_, Self> {
+        slf.0.write().flatten(layer_data_types, depth);
+        slf
+    }
+
+    #[pyo3(signature = (*layer_data_types, depth=None))]
+    pub fn get_elements(
+        &self,
+        layer_data_types: Vec<(i32, i32)>,
+        depth: Option<usize>,
+    ) -> Vec<Element> {
+        self.0.read().get_elements(layer_data_types, depth)
+    }
+
+    pub fn copy(&self) -> Self {
+        PyCell(self.0.copy())
+    }
+
+    #[pyo3(signature = (*layer_data_types))]
+    pub fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
+        LayerDataTypeMatches::is_on(&*self.0.read(), layer_data_types)
+    }
+
+    fn __contains__(&self, element: Element) -> bool {
+        self.contains(element)
+    }
+
+    fn __str__(&self) -> PyResult<String> {
+        Ok(format!("{}", self.0))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{:?}", self.0))
+    }
+}
+
+impl Cell {
+    pub fn new(name: String) -> Self {
+        Cell {
+            name,
+            polygons: Vec::new(),
+            paths: Vec::new(),
+            references: Vec::new(),
+            texts: Vec::new(),
+            nodes: Vec::new(),
+            boxes: Vec::new(),
+            modification_date: None,
+            access_date: None,
+            revision: Arc::default(),
+            bounding_box_cache: BoundingBoxCache::default(),
+            spatial_index_cache: SpatialIndexCache::default(),
+        }
+    }
+
+    pub fn add(&mut self, elements: Vec<Element>) {
+        self.revision.invalidate();
+        for element in elements {
+            element.add_dependent(&self.revision);
+            match element {
+                Element::Polygon(polygon) => self.polygons.push(polygon),
+                Element::Path(path) => self.paths.push(path),
+                Element::Reference(reference) => self.references.push(reference),
+                Element::Text(text) => self.texts.push(text),
+                Element::Node(node) => self.nodes.push(node),
+                Element::Box(gds_box) => self.boxes.push(gds_box),
+            }
+        }
+    }
+
+    pub fn remove(&mut self, elements: Vec<Element>) {
+        self.revision.invalidate();
+        let revision = &self.revision;
+        for element in elements {
+            match element {
+                Element::Polygon(polygon) => {
+                    remove_matching(&mut self.polygons, &polygon, Element::Polygon, revision)
+                }
+                Element::Path(path) => {
+                    remove_matching(&mut self.paths, &path, Element::Path, revision)
+                }
+                Element::Reference(reference) => remove_matching(
+                    &mut self.references,
+                    &reference,
+                    Element::Reference,
+                    revision,
+                ),
+                Element::Text(text) => {
+                    remove_matching(&mut self.texts, &text, Element::Text, revision)
+                }
+                Element::Node(node) => {
+                    remove_matching(&mut self.nodes, &node, Element::Node, revision)
+                }
+                Element::Box(gds_box) => {
+                    remove_matching(&mut self.boxes, &gds_box, Element::Box, revision)
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, element: &Element) -> bool {
+        match element {
+            Element::Polygon(polygon) => self.polygons.contains(polygon),
+            Element::Path(path) => self.paths.contains(path),
+            Element::Reference(reference) => self.references.contains(reference),
+            Element::Text(text) => self.texts.contains(text),
+            Element::Node(node) => self.nodes.contains(node),
+            Element::Box(gds_box) => self.boxes.contains(gds_box),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+            && self.paths.is_empty()
+            && self.references.is_empty()
+            && self.texts.is_empty()
+            && self.nodes.is_empty()
+            && self.boxes.is_empty()
+    }
+
+    pub fn flatten(
+        &mut self,
         layer_data_types: Vec<(i32, i32)>,
         depth: Option<usize>,
-        py: Python<'a>,
-    ) -> PyRefMut<'a, Self> {
+    ) -> &mut Self {
         let depth = depth.unwrap_or(usize::MAX);
         if depth == 0 {
-            return slf;
+            return self;
         }
 
         let mut new_elements: Vec<Element> = Vec::new();
 
-        for reference in &slf.references {
-            let reference_elements =
-                reference
-                    .borrow_mut(py)
-                    .flatten(layer_data_types.clone(), Some(depth), py);
+        for reference in &self.references {
+            let reference_elements = reference
+                .read()
+                .flatten(layer_data_types.clone(), Some(depth));
             new_elements.extend(reference_elements);
         }
 
-        slf.references.clear();
+        for reference in std::mem::take(&mut self.references) {
+            Element::Reference(reference).remove_dependent(&self.revision);
+        }
 
-        slf.add(new_elements);
+        self.add(new_elements);
 
-        slf
+        self
     }
 
-    #[pyo3(signature = (*layer_data_types, depth=None))]
     pub fn get_elements(
-        &mut self,
+        &self,
         layer_data_types: Vec<(i32, i32)>,
         depth: Option<usize>,
-        py: Python,
     ) -> Vec<Element> {
         let depth = depth.unwrap_or(usize::MAX);
-        let mut elements: Vec<Element> = Vec::new();
-
-        for polygon in &self.polygons {
-            if polygon.borrow(py).is_on(layer_data_types.clone()) {
-                elements.push(Element::Polygon(polygon.clone()));
-            }
-        }
-
-        for path in &self.paths {
-            if path.borrow(py).is_on(layer_data_types.clone()) {
-                elements.push(Element::Path(path.clone()));
-            }
-        }
-
-        for text in &self.texts {
-            if text.borrow(py).is_on(layer_data_types.clone()) {
-                elements.push(Element::Text(text.clone()));
-            }
-        }
+        let mut elements = self.direct_elements(&layer_data_types);
 
         for reference in &self.references {
-            let reference_elements =
-                reference
-                    .borrow_mut(py)
-                    .flatten(layer_data_types.clone(), Some(depth), py);
+            let reference_elements = reference
+                .read()
+                .flatten(layer_data_types.clone(), Some(depth));
             for referenced_element in reference_elements {
                 if referenced_element.is_on(layer_data_types.clone()) {
                     elements.push(referenced_element);
@@ -226,25 +472,19 @@ impl Cell {
 
         elements
     }
+}
 
-    pub fn copy(&self) -> Self {
-        self.clone()
-    }
-
-    #[pyo3(signature = (*layer_data_types))]
-    pub fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
-        LayerDataTypeMatches::is_on(self, layer_data_types)
-    }
-
-    fn __contains__(&self, element: Element) -> bool {
-        self.contains(element)
-    }
-
-    fn __str__(&self) -> PyResult<String> {
-        Ok(format!("{}", self))
-    }
-
-    fn __repr__(&self) -> PyResult<String> {
-        Ok(format!("{:?}", self))
-    }
+fn remove_matching<T: PartialEq>(
+    items: &mut Vec<Shared<T>>,
+    item: &Shared<T>,
+    element: fn(Shared<T>) -> Element,
+    revision: &Arc<Revision>,
+) {
+    items.retain(|existing| {
+        let matches = existing == item;
+        if matches {
+            element(existing.clone()).remove_dependent(revision);
+        }
+        !matches
+    });
 }
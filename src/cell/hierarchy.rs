@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    element::Element,
+    reference::{Instance, Reference},
+    shared::Shared,
+};
+
+use super::Cell;
+
+enum VisitState {
+    InProgress,
+    Done,
+}
+
+/// Orders a cell hierarchy so that every cell comes after the cells it references.
+///
+/// Each cell is returned once, even when it is reachable from several parents.
+/// Different cells sharing a name and reference cycles are errors.
+pub struct CellHierarchy {
+    cells_by_name: HashMap<String, Shared<Cell>>,
+    states: HashMap<String, VisitState>,
+    path: Vec<String>,
+    ordered: Vec<Shared<Cell>>,
+}
+
+impl CellHierarchy {
+    pub fn order(roots: &[Shared<Cell>]) -> PyResult<Vec<Shared<Cell>>> {
+        let mut hierarchy = CellHierarchy {
+            cells_by_name: HashMap::new(),
+            states: HashMap::new(),
+            path: Vec::new(),
+            ordered: Vec::new(),
+        };
+
+        for root in roots {
+            hierarchy.visit_cell(root)?;
+        }
+
+        Ok(hierarchy.ordered)
+    }
+
+    fn check_unique_name(&mut self, cell: &Shared<Cell>, name: &str) -> PyResult<()> {
+        match self.cells_by_name.get(name) {
+            Some(existing) => {
+                if existing.ptr_eq(cell) || *existing.read() == *cell.read() {
+                    Ok(())
+                } else {
+                    Err(PyValueError::new_err(format!(
+                        "Multiple different cells are named '{}'",
+                        name
+                    )))
+                }
+            }
+            None => {
+                self.cells_by_name.insert(name.to_string(), cell.clone());
+                Ok(())
+            }
+        }
+    }
+
+    fn visit_cell(&mut self, cell: &Shared<Cell>) -> PyResult<()> {
+        let name = cell.read().name.clone();
+
+        self.check_unique_name(cell, &name)?;
+
+        match self.states.get(&name) {
+            Some(VisitState::Done) => return Ok(()),
+            Some(VisitState::InProgress) => {
+                let start = self.path.iter().position(|n| *n == name).unwrap_or(0);
+                let mut cycle = self.path[start..].to_vec();
+                cycle.push(name);
+                return Err(PyValueError::new_err(format!(
+                    "Cell reference cycle detected: {}",
+                    cycle.join(" -> ")
+                )));
+            }
+            None => {}
+        }
+
+        self.states.insert(name.clone(), VisitState::InProgress);
+        self.path.push(name.clone());
+
+        let references = cell.read().references.to_vec();
+
+        for reference in &references {
+            self.visit_reference(reference)?;
+        }
+
+        self.path.pop();
+        self.states.insert(name.clone(), VisitState::Done);
+        self.ordered.push(self.cells_by_name[&name].clone());
+
+        Ok(())
+    }
+
+    fn visit_reference(&mut self, reference: &Shared<Reference>) -> PyResult<()> {
+        let instance = reference.read().instance.clone();
+
+        match instance {
+            Instance::Cell(cell) => self.visit_cell(&cell),
+            Instance::Element(Element::Reference(reference)) => self.visit_reference(&reference),
+            Instance::Element(_) => Ok(()),
+        }
+    }
+}
@@ -1,30 +1,103 @@
+use std::sync::Arc;
+
+use chrono::NaiveDateTime;
 use pyo3::prelude::*;
 
+use bounding_box::{cell_bounding_box, BoundingBoxCache};
+use spatial_index::SpatialIndexCache;
+
 use crate::{
+    element::Element,
+    gds_box::GdsBox,
+    node::Node,
     path::Path,
     point::Point,
     polygon::Polygon,
     reference::Reference,
+    shared::{
+        revision::{Revision, Tracked},
+        wrapper::{extract_value, value_into_py, Wrapped, Wrapper},
+        Shared,
+    },
     text::Text,
     traits::{Dimensions, LayerDataTypeMatches, Movable, Rotatable, Scalable},
 };
 
+pub mod bounding_box;
+mod clip;
+mod fill;
 mod general;
+pub mod hierarchy;
 mod io;
+pub mod spatial_index;
+pub mod xor;
 
-#[pyclass(eq)]
-#[derive(Clone, Default)]
+#[derive(Default)]
 pub struct Cell {
-    #[pyo3(get, set)]
     pub name: String,
-    #[pyo3(get)]
-    pub polygons: Vec<Py<Polygon>>,
-    #[pyo3(get)]
-    pub paths: Vec<Py<Path>>,
-    #[pyo3(get)]
-    pub references: Vec<Py<Reference>>,
-    #[pyo3(get)]
-    pub texts: Vec<Py<Text>>,
+    pub polygons: Vec<Shared<Polygon>>,
+    pub paths: Vec<Shared<Path>>,
+    pub references: Vec<Shared<Reference>>,
+    pub texts: Vec<Shared<Text>>,
+    pub nodes: Vec<Shared<Node>>,
+    pub boxes: Vec<Shared<GdsBox>>,
+    pub modification_date: Option<NaiveDateTime>,
+    pub access_date: Option<NaiveDateTime>,
+    pub revision: Arc<Revision>,
+    pub bounding_box_cache: BoundingBoxCache,
+    pub spatial_index_cache: SpatialIndexCache,
+}
+
+#[pyclass(name = "Cell", eq, frozen, weakref)]
+#[derive(PartialEq)]
+pub struct PyCell(pub Shared<Cell>);
+
+impl Wrapper for PyCell {
+    type Inner = Cell;
+
+    fn wrap(shared: Shared<Cell>) -> Self {
+        PyCell(shared)
+    }
+
+    fn shared(&self) -> &Shared<Cell> {
+        &self.0
+    }
+}
+
+impl Wrapped for Cell {
+    type Wrapper = PyCell;
+}
+
+// Handles to a cell share the revision its elements record themselves against
+impl Tracked for Cell {
+    fn revision(&self) -> Arc<Revision> {
+        self.revision.clone()
+    }
+}
+
+// Copies get their own revision, so the elements they share with the original are linked again
+impl Clone for Cell {
+    fn clone(&self) -> Self {
+        let mut cell = Cell {
+            modification_date: self.modification_date,
+            access_date: self.access_date,
+            ..Cell::new(self.name.clone())
+        };
+        cell.add(self.elements());
+        cell
+    }
+}
+
+impl IntoPy<PyObject> for Cell {
+    fn into_py(self, py: Python) -> PyObject {
+        value_into_py(self, py)
+    }
+}
+
+impl FromPyObject<'_> for Cell {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        extract_value(ob)
+    }
 }
 
 impl std::fmt::Display for Cell {
@@ -49,221 +122,241 @@ impl std::fmt::Debug for Cell {
 
 impl PartialEq for Cell {
     fn eq(&self, other: &Self) -> bool {
-        Python::with_gil(|py| {
-            if self.name != other.name {
-                return false;
-            }
+        self.name == other.name
+            && self.polygons == other.polygons
+            && self.paths == other.paths
+            && self.references == other.references
+            && self.texts == other.texts
+            && self.nodes == other.nodes
+            && self.boxes == other.boxes
+    }
+}
 
-            if self.polygons.len() != other.polygons.len() {
-                return false;
-            }
+impl Cell {
+    /// Returns every element of this cell, references included.
+    pub fn elements(&self) -> Vec<Element> {
+        let mut elements = self.direct_elements(&[]);
+        elements.extend(self.references.iter().cloned().map(Element::Reference));
+        elements
+    }
 
-            for (self_polygon, other_polygon) in self.polygons.iter().zip(other.polygons.iter()) {
-                if !self_polygon.borrow(py).eq(&other_polygon.borrow(py)) {
-                    return false;
-                }
-            }
+    /// Returns the non-reference elements of this cell on the given layers.
+    pub fn direct_elements(&self, layer_data_types: &[(i32, i32)]) -> Vec<Element> {
+        let mut elements: Vec<Element> = Vec::new();
 
-            if self.paths.len() != other.paths.len() {
-                return false;
+        for polygon in &self.polygons {
+            if polygon.read().is_on(layer_data_types.to_vec()) {
+                elements.push(Element::Polygon(polygon.clone()));
             }
+        }
 
-            for (self_path, other_path) in self.paths.iter().zip(other.paths.iter()) {
-                if !self_path.borrow(py).eq(&other_path.borrow(py)) {
-                    return false;
-                }
+        for path in &self.paths {
+            if path.read().is_on(layer_data_types.to_vec()) {
+                elements.push(Element::Path(path.clone()));
             }
+        }
 
-            if self.references.len() != other.references.len() {
-                return false;
+        for text in &self.texts {
+            if text.read().is_on(layer_data_types.to_vec()) {
+                elements.push(Element::Text(text.clone()));
             }
+        }
 
-            for (self_reference, other_reference) in
-                self.references.iter().zip(other.references.iter())
-            {
-                if !self_reference.borrow(py).eq(&other_reference.borrow(py)) {
-                    return false;
-                }
+        for node in &self.nodes {
+            if node.read().is_on(layer_data_types.to_vec()) {
+                elements.push(Element::Node(node.clone()));
             }
+        }
 
-            if self.texts.len() != other.texts.len() {
-                return false;
+        for gds_box in &self.boxes {
+            if gds_box.read().is_on(layer_data_types.to_vec()) {
+                elements.push(Element::Box(gds_box.clone()));
             }
+        }
 
-            for (self_text, other_text) in self.texts.iter().zip(other.texts.iter()) {
-                if !self_text.borrow(py).eq(&other_text.borrow(py)) {
-                    return false;
-                }
-            }
-            true
-        })
+        elements
+    }
+
+    /// Returns the polygons, path outlines and boxes on the given layers through every reference.
+    pub fn layer_polygons(
+        &self,
+        layer_data_types: Vec<(i32, i32)>,
+        grid: f64,
+    ) -> PyResult<Vec<Polygon>> {
+        let mut polygons = Vec::new();
+        for element in self.get_elements(layer_data_types, None) {
+            match element {
+                Element::Polygon(polygon) => polygons.push(polygon.read().clone()),
+                Element::Path(path) => polygons.extend(path.read().to_polygons(grid)?),
+                Element::Box(gds_box) => polygons.push(gds_box.read().to_polygon()),
+                _ => {}
+            }
+        }
+        Ok(polygons)
     }
 }
 
 impl Movable for Cell {
     fn move_to(&mut self, point: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            for polygon in &mut self.polygons {
-                polygon.borrow_mut(py).move_to(point);
-            }
+        for polygon in &mut self.polygons {
+            polygon.write().move_to(point);
+        }
 
-            for path in &mut self.paths {
-                path.borrow_mut(py).move_to(point);
-            }
+        for path in &mut self.paths {
+            path.write().move_to(point);
+        }
 
-            for reference in &mut self.references {
-                reference.borrow_mut(py).move_to(point);
-            }
+        for reference in &mut self.references {
+            reference.write().move_to(point);
+        }
 
-            for text in &mut self.texts {
-                text.borrow_mut(py).move_to(point);
-            }
+        for text in &mut self.texts {
+            text.write().move_to(point);
+        }
+
+        for node in &mut self.nodes {
+            node.write().move_to(point);
+        }
+
+        for gds_box in &mut self.boxes {
+            gds_box.write().move_to(point);
+        }
 
-            self
-        })
+        self
     }
 
     fn move_by(&mut self, vector: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            for polygon in &mut self.polygons {
-                polygon.borrow_mut(py).move_by(vector);
-            }
+        for polygon in &mut self.polygons {
+            polygon.write().move_by(vector);
+        }
 
-            for path in &mut self.paths {
-                path.borrow_mut(py).move_by(vector);
-            }
+        for path in &mut self.paths {
+            path.write().move_by(vector);
+        }
 
-            for reference in &mut self.references {
-                reference.borrow_mut(py).move_by(vector);
-            }
+        for reference in &mut self.references {
+            reference.write().move_by(vector);
+        }
 
-            for text in &mut self.texts {
-                text.borrow_mut(py).move_by(vector);
-            }
+        for text in &mut self.texts {
+            text.write().move_by(vector);
+        }
+
+        for node in &mut self.nodes {
+            node.write().move_by(vector);
+        }
 
-            self
-        })
+        for gds_box in &mut self.boxes {
+            gds_box.write().move_by(vector);
+        }
+
+        self
     }
 }
 
 impl Rotatable for Cell {
     fn rotate(&mut self, angle: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            for polygon in &mut self.polygons {
-                polygon.borrow_mut(py).rotate(angle, centre);
-            }
+        for polygon in &mut self.polygons {
+            polygon.write().rotate(angle, centre);
+        }
 
-            for path in &mut self.paths {
-                path.borrow_mut(py).rotate(angle, centre);
-            }
+        for path in &mut self.paths {
+            path.write().rotate(angle, centre);
+        }
 
-            for reference in &mut self.references {
-                reference.borrow_mut(py).rotate(angle, centre);
-            }
+        for reference in &mut self.references {
+            reference.write().rotate(angle, centre);
+        }
 
-            for text in &mut self.texts {
-                text.borrow_mut(py).rotate(angle, centre);
-            }
+        for text in &mut self.texts {
+            text.write().rotate(angle, centre);
+        }
+
+        for node in &mut self.nodes {
+            node.write().rotate(angle, centre);
+        }
+
+        for gds_box in &mut self.boxes {
+            gds_box.write().rotate(angle, centre);
+        }
 
-            self
-        })
+        self
     }
 }
 
 impl Scalable for Cell {
     fn scale(&mut self, factor: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            for polygon in &mut self.polygons {
-                polygon.borrow_mut(py).scale(factor, centre);
-            }
+        for polygon in &mut self.polygons {
+            polygon.write().scale(factor, centre);
+        }
 
-            for path in &mut self.paths {
-                path.borrow_mut(py).scale(factor, centre);
-            }
+        for path in &mut self.paths {
+            path.write().scale(factor, centre);
+        }
 
-            for reference in &mut self.references {
-                reference.borrow_mut(py).scale(factor, centre);
-            }
+        for reference in &mut self.references {
+            reference.write().scale(factor, centre);
+        }
 
-            for text in &mut self.texts {
-                text.borrow_mut(py).scale(factor, centre);
-            }
+        for text in &mut self.texts {
+            text.write().scale(factor, centre);
+        }
+
+        for node in &mut self.nodes {
+            node.write().scale(factor, centre);
+        }
 
-            self
-        })
+        for gds_box in &mut self.boxes {
+            gds_box.write().scale(factor, centre);
+        }
+
+        self
     }
 }
 
 impl Dimensions for Cell {
     fn bounding_box(&self) -> (Point, Point) {
-        Python::with_gil(|py| {
-            let mut min = Point::new(f64::INFINITY, f64::INFINITY);
-            let mut max = Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY);
-
-            for polygon in &self.polygons {
-                let (polygon_min, polygon_max) = polygon.borrow_mut(py).bounding_box();
-                min.x = min.x.min(polygon_min.x);
-                min.y = min.y.min(polygon_min.y);
-                max.x = max.x.max(polygon_max.x);
-                max.y = max.y.max(polygon_max.y);
-            }
-
-            for path in &self.paths {
-                let (path_min, path_max) = path.borrow_mut(py).bounding_box();
-                min.x = min.x.min(path_min.x);
-                min.y = min.y.min(path_min.y);
-                max.x = max.x.max(path_max.x);
-                max.y = max.y.max(path_max.y);
-            }
-
-            for reference in &self.references {
-                let (reference_min, reference_max) = reference.borrow_mut(py).bounding_box();
-                min.x = min.x.min(reference_min.x);
-                min.y = min.y.min(reference_min.y);
-                max.x = max.x.max(reference_max.x);
-                max.y = max.y.max(reference_max.y);
-            }
-
-            for text in &self.texts {
-                let (text_min, text_max) = text.borrow_mut(py).bounding_box();
-                min.x = min.x.min(text_min.x);
-                min.y = min.y.min(text_min.y);
-                max.x = max.x.max(text_max.x);
-                max.y = max.y.max(text_max.y);
-            }
-
-            (min, max)
-        })
+        cell_bounding_box(self, &None)
     }
 }
 
 impl LayerDataTypeMatches for Cell {
     fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
-        Python::with_gil(|py| {
-            for polygon in &self.polygons {
-                if !polygon.borrow_mut(py).is_on(layer_data_types.clone()) {
-                    return false;
-                }
+        for polygon in &self.polygons {
+            if !polygon.read().is_on(layer_data_types.clone()) {
+                return false;
             }
+        }
 
-            for path in &self.paths {
-                if !path.borrow_mut(py).is_on(layer_data_types.clone()) {
-                    return false;
-                }
+        for path in &self.paths {
+            if !path.read().is_on(layer_data_types.clone()) {
+                return false;
+            }
+        }
+        for reference in &self.references {
+            if !reference.read().is_on(layer_data_types.clone()) {
+                return false;
             }
-            for reference in &self.references {
-                if !reference.borrow_mut(py).is_on(layer_data_types.clone()) {
-                    return false;
-                }
+        }
+
+        for text in &self.texts {
+            if !text.read().is_on(layer_data_types.clone()) {
+                return false;
             }
+        }
 
-            for text in &self.texts {
-                if !text.borrow_mut(py).is_on(layer_data_types.clone()) {
-                    return false;
-                }
+        for node in &self.nodes {
+            if !node.read().is_on(layer_data_types.clone()) {
+                return false;
+            }
+        }
+
+        for gds_box in &self.boxes {
+            if !gds_box.read().is_on(layer_data_types.clone()) {
+                return false;
             }
+        }
 
-            true
-        })
+        true
     }
 }
@@ -0,0 +1,304 @@
+use std::sync::{Arc, Mutex};
+
+use rstar::{
+    primitives::{GeomWithData, Rectangle},
+    RTree, AABB,
+};
+
+use crate::{
+    config::FLOATING_POINT_INACCURACY_ROUND_DECIMALS,
+    element::Element,
+    grid::Grid,
+    point::Point,
+    reference::{Instance, Reference},
+    shared::Shared,
+    traits::{Dimensions, LayerDataTypeMatches},
+};
+
+use super::{
+    bounding_box::{
+        cell_bounding_box, is_empty, reference_bounding_box, BoundingBox, LayerFilter, Placement,
+    },
+    Cell,
+};
+
+type IndexEntry = GeomWithData<Rectangle<[f64; 2]>, usize>;
+
+/// R-tree over the direct elements and reference extents of a single cell.
+struct SpatialIndex {
+    elements: Vec<Element>,
+    tree: RTree<IndexEntry>,
+}
+
+impl SpatialIndex {
+    fn build(cell: &Cell) -> Self {
+        let mut elements = cell.direct_elements(&[]);
+        elements.extend(
+            cell.references
+                .iter()
+                .map(|reference| Element::Reference(reference.clone())),
+        );
+
+        let entries = elements
+            .iter()
+            .enumerate()
+            .filter_map(|(index, element)| {
+                let bounding_box = element_bounding_box(element, &None);
+                if is_empty(&bounding_box) {
+                    return None;
+                }
+                let (min, max) = bounding_box;
+                Some(IndexEntry::new(
+                    Rectangle::from_corners([min.x, min.y], [max.x, max.y]),
+                    index,
+                ))
+            })
+            .collect();
+
+        SpatialIndex {
+            elements,
+            tree: RTree::bulk_load(entries),
+        }
+    }
+
+    fn locate(&self, region: BoundingBox) -> impl Iterator<Item = &Element> {
+        let (min, max) = region;
+        self.tree
+            .locate_in_envelope_intersecting(&AABB::from_corners([min.x, min.y], [max.x, max.y]))
+            .map(|entry| &self.elements[entry.data])
+    }
+}
+
+#[derive(Default)]
+pub struct SpatialIndexCache {
+    index: Mutex<Option<(u64, Arc<SpatialIndex>)>>,
+}
+
+// Copies start without an index so they never share entries with the original
+impl Clone for SpatialIndexCache {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl SpatialIndexCache {
+    fn get_or_build(&self, cell: &Cell) -> Arc<SpatialIndex> {
+        let mut index = self.index.lock().unwrap();
+        let revision = cell.revision.get();
+        match index.as_ref() {
+            Some((index_revision, spatial_index)) if *index_revision == revision => {
+                spatial_index.clone()
+            }
+            _ => {
+                let spatial_index = Arc::new(SpatialIndex::build(cell));
+                *index = Some((revision, spatial_index.clone()));
+                spatial_index
+            }
+        }
+    }
+}
+
+fn element_bounding_box(element: &Element, layers: &LayerFilter) -> BoundingBox {
+    match element {
+        Element::Reference(reference) => reference_bounding_box(&reference.read(), layers),
+        element => element.bounding_box(),
+    }
+}
+
+/// Bounding box of a single instance of `reference`, relative to the instance origin.
+pub(crate) fn instance_extent(
+    reference: &Reference,
+    grid: &Grid,
+    layers: &LayerFilter,
+) -> BoundingBox {
+    let contents = match &reference.instance {
+        Instance::Cell(cell) => cell_bounding_box(&cell.read(), layers),
+        Instance::Element(element) => element_bounding_box(element, layers),
+    };
+    Placement::from_grid(grid, Point::default()).apply_to_bounding_box(contents)
+}
+
+pub(crate) fn overlaps(a: &BoundingBox, b: &BoundingBox) -> bool {
+    !is_empty(a) && a.0.x <= b.1.x && b.0.x <= a.1.x && a.0.y <= b.1.y && b.0.y <= a.1.y
+}
+
+fn is_on_layers(element: &Element, layers: &LayerFilter) -> bool {
+    match layers {
+        Some(layers) => element.is_on(layers.clone()),
+        None => true,
+    }
+}
+
+/// Column, row and origin of the instances of `grid` whose `extent`, relative to the
+/// instance origin, overlaps `region`, without walking every instance of large arrays.
+pub(crate) fn overlapping_instances(
+    grid: &Grid,
+    extent: BoundingBox,
+    region: BoundingBox,
+) -> Vec<(u32, u32, Point)> {
+    if is_empty(&extent) || grid.columns == 0 || grid.rows == 0 {
+        return Vec::new();
+    }
+
+    // An instance overlaps the region exactly when its origin falls inside this box
+    let origin_region = (region.0 - extent.1, region.1 - extent.0);
+    let contains = |(_, _, origin): &(u32, u32, Point)| {
+        origin.x >= origin_region.0.x
+            && origin.x <= origin_region.1.x
+            && origin.y >= origin_region.0.y
+            && origin.y <= origin_region.1.y
+    };
+
+    // Unused spacing vectors are replaced so the lattice can always be solved
+    let perpendicular = |vector: Point| {
+        if vector.x == 0.0 && vector.y == 0.0 {
+            Point::new(1.0, 0.0)
+        } else {
+            Point::new(-vector.y, vector.x)
+        }
+    };
+    let spacing_x = if grid.columns == 1 {
+        perpendicular(grid.spacing_y)
+    } else {
+        grid.spacing_x
+    };
+    let spacing_y = if grid.rows == 1 {
+        perpendicular(spacing_x)
+    } else {
+        grid.spacing_y
+    };
+
+    let determinant = spacing_x.x * spacing_y.y - spacing_x.y * spacing_y.x;
+    if determinant == 0.0 {
+        return grid
+            .instance_origins()
+            .into_iter()
+            .enumerate()
+            .map(|(index, origin)| {
+                let index = index as u32;
+                (index / grid.rows, index % grid.rows, origin)
+            })
+            .filter(contains)
+            .collect();
+    }
+
+    let (mut min_column, mut max_column) = (f64::INFINITY, f64::NEG_INFINITY);
+    let (mut min_row, mut max_row) = (f64::INFINITY, f64::NEG_INFINITY);
+    let (min, max) = origin_region;
+    for corner in [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)] {
+        let offset = corner.rotate(-grid.angle, grid.origin) - grid.origin;
+        let column = (offset.x * spacing_y.y - offset.y * spacing_y.x) / determinant;
+        let row = (spacing_x.x * offset.y - spacing_x.y * offset.x) / determinant;
+        min_column = min_column.min(column);
+        max_column = max_column.max(column);
+        min_row = min_row.min(row);
+        max_row = max_row.max(row);
+    }
+
+    let clamp = |value: f64, count: u32| value.clamp(0.0, (count - 1) as f64) as u32;
+    let columns = clamp(min_column.floor(), grid.columns)..=clamp(max_column.ceil(), grid.columns);
+    let rows = clamp(min_row.floor(), grid.rows)..=clamp(max_row.ceil(), grid.rows);
+    if max_column < 0.0 || max_row < 0.0 {
+        return Vec::new();
+    }
+
+    columns
+        .flat_map(|column| rows.clone().map(move |row| (column, row)))
+        .map(|(column, row)| {
+            let origin =
+                (grid.origin + grid.spacing_x * column as f64 + grid.spacing_y * row as f64)
+                    .rotate(grid.angle, grid.origin)
+                    .round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS);
+            (column, row, origin)
+        })
+        .filter(contains)
+        .collect()
+}
+
+/// Elements of `cell` whose bounding box overlaps `region`, expanding references `depth` levels deep.
+pub fn query_cell(
+    cell: &Cell,
+    region: BoundingBox,
+    layers: &LayerFilter,
+    depth: usize,
+) -> Vec<Element> {
+    let mut elements = Vec::new();
+    let index = cell.spatial_index_cache.get_or_build(cell);
+
+    for element in index.locate(region) {
+        match element {
+            Element::Reference(reference) => {
+                query_reference(&reference.read(), region, layers, depth, &mut elements);
+            }
+            element => {
+                if is_on_layers(element, layers) {
+                    elements.push(element.clone());
+                }
+            }
+        }
+    }
+
+    elements
+}
+
+pub(crate) fn query_reference(
+    reference: &Reference,
+    region: BoundingBox,
+    layers: &LayerFilter,
+    depth: usize,
+    elements: &mut Vec<Element>,
+) {
+    if depth == 0 {
+        if overlaps(&reference_bounding_box(reference, layers), &region) {
+            elements.push(Element::Reference(Shared::new(reference.copy())));
+        }
+        return;
+    }
+
+    let grid = reference.grid.read().clone();
+
+    let extent = instance_extent(reference, &grid, layers);
+
+    for (_, _, origin) in overlapping_instances(&grid, extent, region) {
+        match &reference.instance {
+            Instance::Cell(cell) => {
+                let cell = cell.read();
+                let index = cell.spatial_index_cache.get_or_build(&cell);
+                let local_region = Placement::from_grid(&grid, origin).invert_bounding_box(region);
+
+                for element in index.locate(local_region) {
+                    query_placed_element(element, &grid, origin, region, layers, depth, elements);
+                }
+            }
+            Instance::Element(element) => {
+                query_placed_element(element, &grid, origin, region, layers, depth, elements);
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn query_placed_element(
+    element: &Element,
+    grid: &Grid,
+    origin: Point,
+    region: BoundingBox,
+    layers: &LayerFilter,
+    depth: usize,
+    elements: &mut Vec<Element>,
+) {
+    if !matches!(element, Element::Reference(_)) && !is_on_layers(element, layers) {
+        return;
+    }
+
+    match Reference::place_element(grid, element, origin) {
+        Element::Reference(placed) => {
+            query_reference(&placed.read(), region, layers, depth - 1, elements);
+        }
+        placed => {
+            if overlaps(&placed.bounding_box(), &region) {
+                elements.push(placed);
+            }
+        }
+    }
+}
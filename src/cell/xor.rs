@@ -0,0 +1,69 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    boolean::{boolean_polygons, BooleanOperation},
+    drc::general::group_by_layer,
+    offset::{offset_polygons, OffsetJoin},
+    polygon::Polygon,
+    utils::geometry::{area, AREA_TOLERANCE},
+};
+
+/// Differing polygons and their total area on each layer.
+pub type LayerXor = BTreeMap<(i32, i32), (Vec<Polygon>, f64)>;
+
+pub fn check_tolerance_valid(tolerance: f64) -> PyResult<()> {
+    if tolerance.is_nan() || tolerance < 0.0 {
+        return Err(PyValueError::new_err("XOR tolerance must not be negative"));
+    }
+    Ok(())
+}
+
+/// Returns the exclusive or of two sets of polygons on `grid` and its area on each layer
+/// that differs. Differences narrower than `tolerance` are removed by shrinking and
+/// growing the result by half of it.
+pub fn xor_by_layer(
+    polygons: Vec<Polygon>,
+    other_polygons: Vec<Polygon>,
+    tolerance: f64,
+    grid: f64,
+) -> PyResult<LayerXor> {
+    let polygons = group_by_layer(polygons);
+    let other_polygons = group_by_layer(other_polygons);
+    let layers: BTreeSet<(i32, i32)> = polygons
+        .keys()
+        .chain(other_polygons.keys())
+        .copied()
+        .collect();
+
+    let mut differences = LayerXor::new();
+    for layer in layers {
+        let mut difference = boolean_polygons(
+            polygons.get(&layer).map(Vec::as_slice).unwrap_or_default(),
+            other_polygons
+                .get(&layer)
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+            BooleanOperation::Xor,
+            layer.0,
+            layer.1,
+            grid,
+        )?;
+        if tolerance > 0.0 && !difference.is_empty() {
+            let join = OffsetJoin::Miter(2.0);
+            let shrunk =
+                offset_polygons(&difference, -tolerance / 2.0, join, layer.0, layer.1, grid)?;
+            difference = offset_polygons(&shrunk, tolerance / 2.0, join, layer.0, layer.1, grid)?;
+        }
+
+        let xor_area: f64 = difference
+            .iter()
+            .map(|polygon| area(&polygon.points).unwrap_or(0.0).abs())
+            .sum();
+        if xor_area > AREA_TOLERANCE {
+            differences.insert(layer, (difference, xor_area));
+        }
+    }
+    Ok(differences)
+}
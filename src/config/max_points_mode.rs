@@ -0,0 +1,25 @@
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+pub const MAX_POINTS: usize = 8191;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MaxPointsMode {
+    #[default]
+    Fracture,
+    Error,
+    Extended,
+}
+
+impl MaxPointsMode {
+    pub fn new(value: &str) -> PyResult<Self> {
+        match value.to_lowercase().as_str() {
+            "fracture" => Ok(MaxPointsMode::Fracture),
+            "error" => Ok(MaxPointsMode::Error),
+            "extended" => Ok(MaxPointsMode::Extended),
+            _ => Err(PyValueError::new_err(format!(
+                "Invalid max points mode: {}, expected one of 'fracture', 'error' or 'extended'",
+                value
+            ))),
+        }
+    }
+}
@@ -3,9 +3,13 @@ use pyo3::prelude::*;
 use crate::utils::geometry::round_to_decimals;
 
 pub mod gds_file_types;
+pub mod max_points_mode;
+pub mod timestamp;
 
 pub const FLOATING_POINT_INACCURACY_ROUND_DECIMALS: u32 = 10;
 pub static mut EPSILON: f64 = 1e-4;
+// The database unit of a library with the default units and precision
+pub const DEFAULT_GRID: f64 = 1e-4;
 
 #[pyfunction]
 pub fn set_epsilon(epsilon: f64) {
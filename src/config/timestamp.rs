@@ -0,0 +1,105 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, Timelike};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Timestamp {
+    #[default]
+    Now,
+    Fixed(NaiveDateTime),
+    Preserve,
+    Epoch,
+}
+
+impl Timestamp {
+    pub fn new(value: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let Some(value) = value else {
+            return Ok(Timestamp::Now);
+        };
+
+        if let Ok(mode) = value.extract::<String>() {
+            return match mode.to_lowercase().as_str() {
+                "now" => Ok(Timestamp::Now),
+                "preserve" => Ok(Timestamp::Preserve),
+                "epoch" => Ok(Timestamp::Epoch),
+                _ => Err(PyValueError::new_err(format!(
+                    "Invalid timestamp: {}, expected a datetime or one of 'now', 'preserve' or 'epoch'",
+                    mode
+                ))),
+            };
+        }
+
+        if let Ok(datetime) = value.extract::<NaiveDateTime>() {
+            return Ok(Timestamp::Fixed(datetime));
+        }
+
+        if let Ok(datetime) = value.extract::<DateTime<FixedOffset>>() {
+            return Ok(Timestamp::Fixed(datetime.naive_utc()));
+        }
+
+        Err(PyValueError::new_err(
+            "Invalid timestamp, expected a datetime or one of 'now', 'preserve' or 'epoch'",
+        ))
+    }
+
+    /// Returns the modification and access dates to write, where `now` is taken once per
+    /// file so every record of it gets the same date
+    pub fn dates(
+        &self,
+        now: NaiveDateTime,
+        modification_date: Option<NaiveDateTime>,
+        access_date: Option<NaiveDateTime>,
+    ) -> (NaiveDateTime, NaiveDateTime) {
+        match self {
+            Timestamp::Now => (now, now),
+            Timestamp::Fixed(datetime) => (*datetime, *datetime),
+            Timestamp::Preserve => (
+                modification_date.unwrap_or(now),
+                access_date.or(modification_date).unwrap_or(now),
+            ),
+            Timestamp::Epoch => (
+                DateTime::UNIX_EPOCH.naive_utc(),
+                DateTime::UNIX_EPOCH.naive_utc(),
+            ),
+        }
+    }
+}
+
+pub fn dates_to_gds_fields(
+    modification_date: NaiveDateTime,
+    access_date: NaiveDateTime,
+) -> [u16; 12] {
+    let mut fields = [0; 12];
+    for (chunk, date) in fields.chunks_mut(6).zip([modification_date, access_date]) {
+        chunk.copy_from_slice(&[
+            date.year() as u16,
+            date.month() as u16,
+            date.day() as u16,
+            date.hour() as u16,
+            date.minute() as u16,
+            date.second() as u16,
+        ]);
+    }
+    fields
+}
+
+pub fn gds_fields_to_dates(fields: &[i16]) -> (Option<NaiveDateTime>, Option<NaiveDateTime>) {
+    let date = |fields: &[i16]| -> Option<NaiveDateTime> {
+        let [year, month, day, hour, minute, second] = fields.try_into().ok()?;
+        // Some writers store the year as an offset from 1900
+        let year = if year < 1900 {
+            year as i32 + 1900
+        } else {
+            year as i32
+        };
+        NaiveDate::from_ymd_opt(year, month as u32, day as u32)?.and_hms_opt(
+            hour as u32,
+            minute as u32,
+            second as u32,
+        )
+    };
+
+    (
+        fields.get(0..6).and_then(date),
+        fields.get(6..12).and_then(date),
+    )
+}
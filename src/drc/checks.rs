@@ -0,0 +1,396 @@
+use i_overlay::core::overlay_rule::OverlayRule;
+use pyo3::prelude::*;
+use rstar::{
+    primitives::{GeomWithData, Rectangle},
+    RTree, AABB,
+};
+
+use crate::{
+    boolean::{boolean_polygons, BooleanOperation},
+    config::FLOATING_POINT_INACCURACY_ROUND_DECIMALS,
+    point::Point,
+    polygon::Polygon,
+    utils::{
+        geometry::{bounding_box, keyhole, round_to_decimals, signed_area},
+        overlay::OverlayGrid,
+    },
+};
+
+const SIDE_TOLERANCE: f64 = 1e-9;
+
+/// A merged shape: the outer contour counter-clockwise followed by clockwise holes, all open.
+pub type Shape = Vec<Vec<Point>>;
+
+/// Geometry of a single violation: the measured value, marker outline and violating edges.
+pub struct Finding {
+    pub value: f64,
+    pub marker: Vec<Point>,
+    pub edges: Vec<(Point, Point)>,
+}
+
+pub fn merge(polygons: &[Polygon], grid: f64) -> PyResult<Vec<Shape>> {
+    let grid = OverlayGrid::fit(polygons, 0.0, grid)?;
+
+    Ok(grid
+        .overlay(&grid.contours(polygons), &[], OverlayRule::Subject)
+        .into_iter()
+        .map(|shape| {
+            shape
+                .iter()
+                .enumerate()
+                .map(|(index, contour)| {
+                    let mut points = grid.contour_points(contour);
+                    // Material is always on the left of an edge
+                    if (signed_area(&points) > 0.0) != (index == 0) {
+                        points.reverse();
+                    }
+                    points
+                })
+                .filter(|contour| contour.len() >= 3)
+                .collect::<Shape>()
+        })
+        .filter(|shape| !shape.is_empty())
+        .collect())
+}
+
+#[derive(Clone, Copy)]
+struct Edge {
+    start: Point,
+    end: Point,
+    shape: usize,
+    contour: usize,
+    position: usize,
+    contour_length: usize,
+}
+
+impl Edge {
+    fn direction(&self) -> Point {
+        self.end - self.start
+    }
+
+    fn length(&self) -> f64 {
+        let direction = self.direction();
+        direction.x.hypot(direction.y)
+    }
+
+    /// Signed distance of `point` from the edge line, positive on the material side.
+    fn side(&self, point: Point) -> f64 {
+        cross(self.direction(), point - self.start) / self.length()
+    }
+
+    fn is_adjacent(&self, other: &Edge) -> bool {
+        if self.shape != other.shape || self.contour != other.contour {
+            return false;
+        }
+        let next = (self.position + 1) % self.contour_length;
+        let previous = (other.position + 1) % other.contour_length;
+        next == other.position || previous == self.position
+    }
+
+    /// The part of this edge covered by projecting `other` onto it.
+    fn projection_of(&self, other: &Edge) -> (Point, Point) {
+        let direction = self.direction();
+        let squared_length = dot(direction, direction);
+        let parameter =
+            |point: Point| (dot(point - self.start, direction) / squared_length).clamp(0.0, 1.0);
+        let (a, b) = (parameter(other.start), parameter(other.end));
+        (
+            self.start + direction * a.min(b),
+            self.start + direction * a.max(b),
+        )
+    }
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+fn point_segment_distance(point: Point, start: Point, end: Point) -> f64 {
+    let direction = end - start;
+    let squared_length = dot(direction, direction);
+    let closest = if squared_length == 0.0 {
+        start
+    } else {
+        let parameter = (dot(point - start, direction) / squared_length).clamp(0.0, 1.0);
+        start + direction * parameter
+    };
+    let offset = point - closest;
+    offset.x.hypot(offset.y)
+}
+
+fn segments_intersect(a: &Edge, b: &Edge) -> bool {
+    let d1 = cross(a.direction(), b.start - a.start);
+    let d2 = cross(a.direction(), b.end - a.start);
+    let d3 = cross(b.direction(), a.start - b.start);
+    let d4 = cross(b.direction(), a.end - b.start);
+    d1 * d2 <= 0.0 && d3 * d4 <= 0.0 && !(d1 == 0.0 && d2 == 0.0)
+}
+
+fn segment_distance(a: &Edge, b: &Edge) -> f64 {
+    if segments_intersect(a, b) {
+        return 0.0;
+    }
+    point_segment_distance(a.start, b.start, b.end)
+        .min(point_segment_distance(a.end, b.start, b.end))
+        .min(point_segment_distance(b.start, a.start, a.end))
+        .min(point_segment_distance(b.end, a.start, a.end))
+}
+
+fn shape_edges(shapes: &[Shape]) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for (shape_index, shape) in shapes.iter().enumerate() {
+        for (contour_index, contour) in shape.iter().enumerate() {
+            for position in 0..contour.len() {
+                edges.push(Edge {
+                    start: contour[position],
+                    end: contour[(position + 1) % contour.len()],
+                    shape: shape_index,
+                    contour: contour_index,
+                    position,
+                    contour_length: contour.len(),
+                });
+            }
+        }
+    }
+    edges
+}
+
+fn edge_index(edges: &[Edge]) -> RTree<GeomWithData<Rectangle<[f64; 2]>, usize>> {
+    RTree::bulk_load(
+        edges
+            .iter()
+            .enumerate()
+            .map(|(index, edge)| {
+                GeomWithData::new(
+                    Rectangle::from_corners([edge.start.x, edge.start.y], [edge.end.x, edge.end.y]),
+                    index,
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Whether `other` lies on the material side of `edge` (or the outside when `inside` is false).
+fn is_facing(edge: &Edge, other: &Edge, inside: bool) -> bool {
+    let sign = if inside { 1.0 } else { -1.0 };
+    sign * edge.side(other.start) > SIDE_TOLERANCE || sign * edge.side(other.end) > SIDE_TOLERANCE
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Pairing {
+    // Anti-parallel edges with material between them
+    Width,
+    // Anti-parallel edges with empty space between them
+    Space,
+    // An inner edge with a parallel outer edge on its outside
+    Enclosure,
+}
+
+fn is_pair(a: &Edge, b: &Edge, pairing: Pairing) -> bool {
+    match pairing {
+        Pairing::Width => {
+            dot(a.direction(), b.direction()) < 0.0
+                && is_facing(a, b, true)
+                && is_facing(b, a, true)
+        }
+        Pairing::Space => {
+            dot(a.direction(), b.direction()) < 0.0
+                && is_facing(a, b, false)
+                && is_facing(b, a, false)
+        }
+        Pairing::Enclosure => {
+            dot(a.direction(), b.direction()) > 0.0
+                && is_facing(a, b, false)
+                && is_facing(b, a, true)
+        }
+    }
+}
+
+fn pair_finding(a: &Edge, b: &Edge, distance: f64, limit: f64, pairing: Pairing) -> Finding {
+    let round = |(start, end): (Point, Point)| {
+        (
+            start.round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS),
+            end.round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS),
+        )
+    };
+    let first = round(a.projection_of(b));
+    let second = round(b.projection_of(a));
+    let mut marker = match pairing {
+        Pairing::Enclosure => vec![first.0, first.1, second.1, second.0],
+        _ => vec![first.0, first.1, second.0, second.1],
+    };
+    marker.dedup_by(|x, y| x.epsilon_is_close(*y));
+
+    // Edges that only meet at a corner leave no area, so mark the shortfall around them
+    if marker.len() < 3 || signed_area(&marker).abs() <= SIDE_TOLERANCE {
+        let (min, max) = bounding_box(&marker);
+        let padding = Point::new((limit - distance) / 2.0, (limit - distance) / 2.0);
+        let (min, max) = round((min - padding, max + padding));
+        marker = vec![min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+    }
+    if signed_area(&marker) < 0.0 {
+        marker.reverse();
+    }
+
+    Finding {
+        value: distance,
+        marker,
+        edges: vec![first, second],
+    }
+}
+
+fn check_pairs(
+    edges: &[Edge],
+    others: Option<&[Edge]>,
+    limit: f64,
+    pairing: Pairing,
+    accept: impl Fn(&Edge, &Edge) -> bool,
+) -> Vec<Finding> {
+    let candidates = others.unwrap_or(edges);
+    let index = edge_index(candidates);
+    let mut findings = Vec::new();
+
+    for (edge_position, edge) in edges.iter().enumerate() {
+        let (min, max) = bounding_box(&vec![edge.start, edge.end]);
+        let envelope = AABB::from_corners(
+            [min.x - limit, min.y - limit],
+            [max.x + limit, max.y + limit],
+        );
+        for entry in index.locate_in_envelope_intersecting(&envelope) {
+            // Pairs within a single layer are only visited once
+            if others.is_none() && entry.data <= edge_position {
+                continue;
+            }
+            let other = &candidates[entry.data];
+            if !accept(edge, other) || !is_pair(edge, other, pairing) {
+                continue;
+            }
+            // Merged shapes are on the grid, so this only drops floating point noise
+            let distance = round_to_decimals(
+                segment_distance(edge, other),
+                FLOATING_POINT_INACCURACY_ROUND_DECIMALS,
+            );
+            if distance < limit {
+                findings.push(pair_finding(edge, other, distance, limit, pairing));
+            }
+        }
+    }
+
+    findings
+}
+
+pub fn check_width(shapes: &[Shape], min_width: f64) -> Vec<Finding> {
+    let edges = shape_edges(shapes);
+    check_pairs(&edges, None, min_width, Pairing::Width, |a, b| {
+        a.shape == b.shape && !a.is_adjacent(b)
+    })
+}
+
+pub fn check_spacing(shapes: &[Shape], min_spacing: f64) -> Vec<Finding> {
+    let edges = shape_edges(shapes);
+    check_pairs(&edges, None, min_spacing, Pairing::Space, |a, b| {
+        a.shape != b.shape
+    })
+}
+
+pub fn check_separation(shapes: &[Shape], others: &[Shape], min_spacing: f64) -> Vec<Finding> {
+    let edges = shape_edges(shapes);
+    let other_edges = shape_edges(others);
+    check_pairs(
+        &edges,
+        Some(&other_edges),
+        min_spacing,
+        Pairing::Space,
+        |_, _| true,
+    )
+}
+
+pub fn check_notch(shapes: &[Shape], min_notch: f64) -> Vec<Finding> {
+    let edges = shape_edges(shapes);
+    check_pairs(&edges, None, min_notch, Pairing::Space, |a, b| {
+        a.shape == b.shape && !a.is_adjacent(b)
+    })
+}
+
+pub fn check_enclosure(
+    inner: &[Shape],
+    outer: &[Shape],
+    min_enclosure: f64,
+    grid: f64,
+) -> PyResult<Vec<Finding>> {
+    let inner_edges = shape_edges(inner);
+    let outer_edges = shape_edges(outer);
+    let mut findings = check_pairs(
+        &inner_edges,
+        Some(&outer_edges),
+        min_enclosure,
+        Pairing::Enclosure,
+        |_, _| true,
+    );
+
+    // Anything sticking out of the enclosing layer is not enclosed at all
+    let uncovered = boolean_polygons(
+        &shapes_to_polygons(inner)?,
+        &shapes_to_polygons(outer)?,
+        BooleanOperation::Difference,
+        0,
+        0,
+        grid,
+    )?;
+    for polygon in uncovered {
+        let mut marker = polygon.points;
+        marker.pop();
+        findings.push(Finding {
+            value: 0.0,
+            edges: contour_edges(&marker),
+            marker,
+        });
+    }
+
+    Ok(findings)
+}
+
+pub fn check_area(shapes: &[Shape], min_area: f64) -> PyResult<Vec<Finding>> {
+    let mut findings = Vec::new();
+    for shape in shapes {
+        let area = round_to_decimals(
+            shape
+                .iter()
+                .map(|contour| signed_area(contour))
+                .sum::<f64>(),
+            FLOATING_POINT_INACCURACY_ROUND_DECIMALS,
+        );
+        if area < min_area {
+            findings.push(Finding {
+                value: area,
+                marker: keyhole(&shape[0], &shape[1..])?,
+                edges: contour_edges(&shape[0]),
+            });
+        }
+    }
+    Ok(findings)
+}
+
+fn contour_edges(contour: &[Point]) -> Vec<(Point, Point)> {
+    (0..contour.len())
+        .map(|index| (contour[index], contour[(index + 1) % contour.len()]))
+        .collect()
+}
+
+fn shapes_to_polygons(shapes: &[Shape]) -> PyResult<Vec<Polygon>> {
+    shapes
+        .iter()
+        .map(|shape| {
+            let mut points = keyhole(&shape[0], &shape[1..])?;
+            points.push(points[0]);
+            Ok(Polygon {
+                points,
+                ..Polygon::default()
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    config::DEFAULT_GRID,
+    point::Point,
+    polygon::Polygon,
+    validation::input::{check_data_type_valid, check_grid_valid, check_layer_valid},
+};
+
+use super::{run_rules, DrcCheck, DrcRule, DrcViolation};
+
+fn check_layer_data_type_valid(layer: (i32, i32)) -> PyResult<()> {
+    check_layer_valid(layer.0)?;
+    check_data_type_valid(layer.1)
+}
+
+impl DrcRule {
+    fn new(
+        check: DrcCheck,
+        layer: (i32, i32),
+        other_layer: Option<(i32, i32)>,
+        value: f64,
+        name: Option<String>,
+    ) -> PyResult<Self> {
+        check_layer_data_type_valid(layer)?;
+        if let Some(other_layer) = other_layer {
+            check_layer_data_type_valid(other_layer)?;
+        }
+        if value.is_nan() || value <= 0.0 {
+            return Err(PyValueError::new_err("DRC rule value must be positive"));
+        }
+
+        let name = name.unwrap_or_else(|| match other_layer {
+            Some(other_layer) => format!(
+                "{} {}/{} {}/{} {}",
+                check.name(),
+                layer.0,
+                layer.1,
+                other_layer.0,
+                other_layer.1,
+                value
+            ),
+            None => format!("{} {}/{} {}", check.name(), layer.0, layer.1, value),
+        });
+
+        Ok(DrcRule {
+            name,
+            check,
+            layer,
+            other_layer,
+            value,
+        })
+    }
+}
+
+#[pymethods]
+impl DrcRule {
+    #[staticmethod]
+    #[pyo3(signature = (layer, min_width, name=None))]
+    fn width(layer: (i32, i32), min_width: f64, name: Option<String>) -> PyResult<Self> {
+        DrcRule::new(DrcCheck::Width, layer, None, min_width, name)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (layer, min_spacing, other_layer=None, name=None))]
+    fn spacing(
+        layer: (i32, i32),
+        min_spacing: f64,
+        other_layer: Option<(i32, i32)>,
+        name: Option<String>,
+    ) -> PyResult<Self> {
+        DrcRule::new(DrcCheck::Spacing, layer, other_layer, min_spacing, name)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (inner_layer, outer_layer, min_enclosure, name=None))]
+    fn enclosure(
+        inner_layer: (i32, i32),
+        outer_layer: (i32, i32),
+        min_enclosure: f64,
+        name: Option<String>,
+    ) -> PyResult<Self> {
+        DrcRule::new(
+            DrcCheck::Enclosure,
+            inner_layer,
+            Some(outer_layer),
+            min_enclosure,
+            name,
+        )
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (layer, min_area, name=None))]
+    fn area(layer: (i32, i32), min_area: f64, name: Option<String>) -> PyResult<Self> {
+        DrcRule::new(DrcCheck::Area, layer, None, min_area, name)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (layer, min_notch, name=None))]
+    fn notch(layer: (i32, i32), min_notch: f64, name: Option<String>) -> PyResult<Self> {
+        DrcRule::new(DrcCheck::Notch, layer, None, min_notch, name)
+    }
+
+    #[getter]
+    fn check(&self) -> &'static str {
+        self.check.name()
+    }
+
+    fn __str__(&self) -> PyResult<String> {
+        Ok(format!("{}", self))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{:?}", self))
+    }
+}
+
+#[pymethods]
+impl DrcViolation {
+    #[new]
+    #[pyo3(signature = (rule, marker, value=0.0, edges=Vec::new(), cell=None))]
+    pub fn new(
+        rule: String,
+        marker: Polygon,
+        value: f64,
+        edges: Vec<(Point, Point)>,
+        cell: Option<String>,
+    ) -> Self {
+        DrcViolation {
+            rule,
+            cell,
+            value,
+            marker,
+            edges,
+        }
+    }
+
+    fn __str__(&self) -> PyResult<String> {
+        Ok(format!("{}", self))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{:?}", self))
+    }
+}
+
+pub fn group_by_layer(polygons: Vec<Polygon>) -> HashMap<(i32, i32), Vec<Polygon>> {
+    let mut grouped: HashMap<(i32, i32), Vec<Polygon>> = HashMap::new();
+    for polygon in polygons {
+        grouped
+            .entry((polygon.layer, polygon.data_type))
+            .or_default()
+            .push(polygon);
+    }
+    grouped
+}
+
+#[pyfunction]
+#[pyo3(signature = (polygons, rules, grid=DEFAULT_GRID))]
+pub fn drc(polygons: Vec<Polygon>, rules: Vec<DrcRule>, grid: f64) -> PyResult<Vec<DrcViolation>> {
+    check_grid_valid(grid)?;
+    run_rules(&group_by_layer(polygons), &rules, None, grid)
+}
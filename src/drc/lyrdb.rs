@@ -0,0 +1,284 @@
+use std::fs;
+
+use pyo3::{
+    exceptions::{PyIOError, PyValueError},
+    prelude::*,
+};
+use quick_xml::{escape::partial_escape, events::Event, Reader};
+
+use crate::{point::Point, utils::geometry::keyhole};
+
+use super::{marker_from_edges, marker_polygon, DrcViolation};
+
+fn invalid_lyrdb(message: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(format!("Invalid lyrdb file: {}", message))
+}
+
+fn is_word(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|character| {
+            character.is_ascii_alphanumeric() || character == '_' || character == '$'
+        })
+}
+
+/// Category and cell names in items are paths, so anything but a plain word is quoted.
+fn quote_name(name: &str) -> String {
+    if is_word(name) {
+        return name.to_string();
+    }
+    format!("'{}'", name.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn unquote_name(name: &str) -> String {
+    let name = name.trim();
+    let Some(quoted) = name
+        .strip_prefix('\'')
+        .and_then(|name| name.strip_suffix('\''))
+    else {
+        return name.to_string();
+    };
+    let mut unquoted = String::new();
+    let mut characters = quoted.chars();
+    while let Some(character) = characters.next() {
+        if character == '\\' {
+            unquoted.extend(characters.next());
+        } else {
+            unquoted.push(character);
+        }
+    }
+    unquoted
+}
+
+/// Strips the variant, which follows the possibly quoted cell name after a colon.
+fn cell_name(text: &str) -> String {
+    let text = text.trim();
+    let end = if text.starts_with('\'') {
+        let mut escaped = false;
+        text.char_indices()
+            .skip(1)
+            .find(|&(_, character)| {
+                let closing = character == '\'' && !escaped;
+                escaped = character == '\\' && !escaped;
+                closing
+            })
+            .map_or(text.len(), |(index, _)| index + 1)
+    } else {
+        text.find(':').unwrap_or(text.len())
+    };
+    unquote_name(&text[..end])
+}
+
+fn format_points(points: &[Point]) -> String {
+    points
+        .iter()
+        .map(|point| format!("{},{}", point.x, point.y))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn parse_points(text: &str) -> PyResult<Vec<Point>> {
+    text.split(';')
+        .map(|pair| {
+            let (x, y) = pair
+                .split_once(',')
+                .ok_or_else(|| invalid_lyrdb(format!("bad point '{}'", pair)))?;
+            let parse = |value: &str| {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| invalid_lyrdb(format!("bad coordinate '{}'", value)))
+            };
+            Ok(Point::new(parse(x)?, parse(y)?))
+        })
+        .collect()
+}
+
+fn parse_edge(text: &str) -> PyResult<(Point, Point)> {
+    match parse_points(text.trim().trim_start_matches('(').trim_end_matches(')'))?[..] {
+        [start, end] => Ok((start, end)),
+        _ => Err(invalid_lyrdb(format!("bad edge '{}'", text))),
+    }
+}
+
+#[derive(Default)]
+struct Item {
+    category: Option<String>,
+    cell: Option<String>,
+    value: f64,
+    marker: Option<Vec<Point>>,
+    edges: Vec<(Point, Point)>,
+}
+
+impl Item {
+    fn add_value(&mut self, text: &str) -> PyResult<()> {
+        let Some((kind, body)) = text.split_once(':') else {
+            return Ok(());
+        };
+        let body = body.trim();
+        match kind.trim() {
+            "polygon" => {
+                let body = body.trim_start_matches('(').trim_end_matches(')');
+                let mut contours = body.split('/').map(parse_points);
+                let outer = contours
+                    .next()
+                    .ok_or_else(|| invalid_lyrdb("empty polygon"))??;
+                let holes = contours.collect::<PyResult<Vec<_>>>()?;
+                self.marker = Some(keyhole(&outer, &holes)?);
+            }
+            "box" => {
+                let (a, b) = parse_edge(body)?;
+                self.marker = Some(vec![a, Point::new(b.x, a.y), b, Point::new(a.x, b.y)]);
+            }
+            "edge" => self.edges.push(parse_edge(body)?),
+            "edge-pair" => {
+                for edge in body.split('/') {
+                    self.edges.push(parse_edge(edge)?);
+                }
+            }
+            "float" => {
+                self.value = body
+                    .parse()
+                    .map_err(|_| invalid_lyrdb(format!("bad value '{}'", body)))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn into_violation(self) -> PyResult<DrcViolation> {
+        let marker = match self.marker {
+            Some(points) => marker_polygon(points),
+            None if !self.edges.is_empty() => marker_from_edges(&self.edges),
+            None => return Err(invalid_lyrdb("item without a marker")),
+        };
+        Ok(DrcViolation {
+            rule: self
+                .category
+                .ok_or_else(|| invalid_lyrdb("item without a category"))?,
+            cell: self.cell,
+            value: self.value,
+            marker,
+            edges: self.edges,
+        })
+    }
+}
+
+/// Writes violations as a KLayout report database, coordinates in user units.
+pub fn write_lyrdb(
+    file_name: String,
+    top_cell: &str,
+    violations: &[DrcViolation],
+) -> PyResult<String> {
+    let mut categories: Vec<&str> = Vec::new();
+    let mut cells: Vec<&str> = Vec::new();
+    for violation in violations {
+        if !categories.contains(&violation.rule.as_str()) {
+            categories.push(&violation.rule);
+        }
+        let cell = violation.cell.as_deref().unwrap_or(top_cell);
+        if !cells.contains(&cell) {
+            cells.push(cell);
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<report-database>\n");
+    xml.push_str(" <description>DRC results</description>\n");
+    xml.push_str(" <original-file/>\n <generator>gdsr</generator>\n");
+    xml.push_str(&format!(
+        " <top-cell>{}</top-cell>\n",
+        partial_escape(top_cell)
+    ));
+    xml.push_str(" <tags/>\n <categories>\n");
+    for category in &categories {
+        xml.push_str(&format!(
+            "  <category>\n   <name>{}</name>\n   <description/>\n   <categories/>\n  </category>\n",
+            partial_escape(*category)
+        ));
+    }
+    xml.push_str(" </categories>\n <cells>\n");
+    for cell in &cells {
+        xml.push_str(&format!(
+            "  <cell>\n   <name>{}</name>\n   <variant/>\n   <references/>\n  </cell>\n",
+            partial_escape(*cell)
+        ));
+    }
+    xml.push_str(" </cells>\n <items>\n");
+    for violation in violations {
+        let mut points = violation.marker.points.clone();
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+
+        xml.push_str("  <item>\n   <tags/>\n");
+        xml.push_str(&format!(
+            "   <category>{}</category>\n",
+            partial_escape(quote_name(&violation.rule))
+        ));
+        xml.push_str(&format!(
+            "   <cell>{}</cell>\n",
+            partial_escape(quote_name(violation.cell.as_deref().unwrap_or(top_cell)))
+        ));
+        xml.push_str("   <visited>false</visited>\n   <multiplicity>1</multiplicity>\n");
+        xml.push_str("   <values>\n");
+        xml.push_str(&format!(
+            "    <value>polygon: ({})</value>\n",
+            format_points(&points)
+        ));
+        for (start, end) in &violation.edges {
+            xml.push_str(&format!(
+                "    <value>edge: ({})</value>\n",
+                format_points(&[*start, *end])
+            ));
+        }
+        xml.push_str(&format!("    <value>float: {}</value>\n", violation.value));
+        xml.push_str("   </values>\n  </item>\n");
+    }
+    xml.push_str(" </items>\n</report-database>\n");
+
+    fs::write(&file_name, xml)
+        .map_err(|_| PyIOError::new_err("Could not open file for writing"))?;
+    Ok(file_name)
+}
+
+/// Reads the items of a KLayout report database as violations.
+pub fn read_lyrdb(file_name: String) -> PyResult<Vec<DrcViolation>> {
+    let xml = fs::read_to_string(file_name)?;
+    let mut reader = Reader::from_str(&xml);
+
+    let mut violations = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut item: Option<Item> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event().map_err(invalid_lyrdb)? {
+            Event::Start(start) => {
+                let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+                if name == "item" && path.last().map(String::as_str) == Some("items") {
+                    item = Some(Item::default());
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Text(content) => text.push_str(&content.unescape().map_err(invalid_lyrdb)?),
+            Event::CData(content) => text.push_str(&String::from_utf8_lossy(&content)),
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                if let Some(current) = item.as_mut() {
+                    match (name.as_str(), path.last().map(String::as_str)) {
+                        ("category", Some("item")) => current.category = Some(unquote_name(&text)),
+                        ("cell", Some("item")) => current.cell = Some(cell_name(&text)),
+                        ("value", Some("values")) => current.add_value(text.trim())?,
+                        ("item", _) => violations.push(item.take().unwrap().into_violation()?),
+                        _ => {}
+                    }
+                }
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(violations)
+}
@@ -0,0 +1,209 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use pyo3::prelude::*;
+
+use crate::{point::Point, polygon::Polygon, utils::geometry::bounding_box};
+
+use checks::{
+    check_area, check_enclosure, check_notch, check_separation, check_spacing, check_width, merge,
+    Finding, Shape,
+};
+
+mod checks;
+pub mod general;
+pub mod lyrdb;
+pub mod rdb;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrcCheck {
+    Width,
+    Spacing,
+    Enclosure,
+    Area,
+    Notch,
+}
+
+impl DrcCheck {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DrcCheck::Width => "width",
+            DrcCheck::Spacing => "spacing",
+            DrcCheck::Enclosure => "enclosure",
+            DrcCheck::Area => "area",
+            DrcCheck::Notch => "notch",
+        }
+    }
+}
+
+#[pyclass(eq)]
+#[derive(Clone, PartialEq)]
+pub struct DrcRule {
+    #[pyo3(get)]
+    pub name: String,
+    pub check: DrcCheck,
+    #[pyo3(get)]
+    pub layer: (i32, i32),
+    #[pyo3(get)]
+    pub other_layer: Option<(i32, i32)>,
+    #[pyo3(get)]
+    pub value: f64,
+}
+
+impl std::fmt::Display for DrcRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.other_layer {
+            Some(other_layer) => write!(
+                f,
+                "DRC rule '{}': {} of {:?} to {:?} at least {}",
+                self.name,
+                self.check.name(),
+                self.layer,
+                other_layer,
+                self.value
+            ),
+            None => write!(
+                f,
+                "DRC rule '{}': {} of {:?} at least {}",
+                self.name,
+                self.check.name(),
+                self.layer,
+                self.value
+            ),
+        }
+    }
+}
+
+impl std::fmt::Debug for DrcRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "DrcRule({}, {}, {:?}, {:?}, {})",
+            self.name,
+            self.check.name(),
+            self.layer,
+            self.other_layer,
+            self.value
+        )
+    }
+}
+
+#[pyclass(eq)]
+#[derive(Clone, PartialEq)]
+pub struct DrcViolation {
+    #[pyo3(get)]
+    pub rule: String,
+    #[pyo3(get)]
+    pub cell: Option<String>,
+    #[pyo3(get)]
+    pub value: f64,
+    #[pyo3(get)]
+    pub marker: Polygon,
+    #[pyo3(get)]
+    pub edges: Vec<(Point, Point)>,
+}
+
+impl std::fmt::Display for DrcViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.cell {
+            Some(cell) => write!(
+                f,
+                "DRC violation of '{}' in cell {} with value {}",
+                self.rule, cell, self.value
+            ),
+            None => write!(
+                f,
+                "DRC violation of '{}' with value {}",
+                self.rule, self.value
+            ),
+        }
+    }
+}
+
+impl std::fmt::Debug for DrcViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "DrcViolation({}, {:?}, {}, {:?})",
+            self.rule, self.cell, self.value, self.marker.points
+        )
+    }
+}
+
+/// Closes an open outline into a marker polygon.
+pub(crate) fn marker_polygon(mut points: Vec<Point>) -> Polygon {
+    if let Some(&first) = points.first() {
+        points.push(first);
+    }
+    Polygon {
+        points,
+        ..Polygon::default()
+    }
+}
+
+/// Marker covering the bounding box of edges, for results that only report edges.
+pub(crate) fn marker_from_edges(edges: &[(Point, Point)]) -> Polygon {
+    let points = edges
+        .iter()
+        .flat_map(|&(start, end)| [start, end])
+        .collect::<Vec<_>>();
+    let (min, max) = bounding_box(&points);
+    marker_polygon(vec![
+        min,
+        Point::new(max.x, min.y),
+        max,
+        Point::new(min.x, max.y),
+    ])
+}
+
+/// Runs every rule over polygons grouped by layer and data type, merged on `grid`.
+pub fn run_rules(
+    polygons: &HashMap<(i32, i32), Vec<Polygon>>,
+    rules: &[DrcRule],
+    cell: Option<&str>,
+    grid: f64,
+) -> PyResult<Vec<DrcViolation>> {
+    let mut merged: HashMap<(i32, i32), Vec<Shape>> = HashMap::new();
+    let mut shapes = |layer: (i32, i32)| -> PyResult<Vec<Shape>> {
+        Ok(match merged.entry(layer) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => entry
+                .insert(merge(
+                    polygons.get(&layer).map(Vec::as_slice).unwrap_or(&[]),
+                    grid,
+                )?)
+                .clone(),
+        })
+    };
+
+    let mut violations = Vec::new();
+    for rule in rules {
+        let layer_shapes = shapes(rule.layer)?;
+        let findings: Vec<Finding> = match (rule.check, rule.other_layer) {
+            (DrcCheck::Width, _) => check_width(&layer_shapes, rule.value),
+            (DrcCheck::Spacing, None) => check_spacing(&layer_shapes, rule.value),
+            (DrcCheck::Spacing, Some(other_layer)) => {
+                check_separation(&layer_shapes, &shapes(other_layer)?, rule.value)
+            }
+            (DrcCheck::Enclosure, Some(other_layer)) => {
+                check_enclosure(&layer_shapes, &shapes(other_layer)?, rule.value, grid)?
+            }
+            (DrcCheck::Enclosure, None) => Vec::new(),
+            (DrcCheck::Area, _) => check_area(&layer_shapes, rule.value)?,
+            (DrcCheck::Notch, _) => check_notch(&layer_shapes, rule.value),
+        };
+
+        violations.extend(findings.into_iter().map(|finding| DrcViolation {
+            rule: rule.name.clone(),
+            cell: cell.map(String::from),
+            value: finding.value,
+            marker: Polygon {
+                layer: rule.layer.0,
+                data_type: rule.layer.1,
+                ..marker_polygon(finding.marker)
+            },
+            edges: finding.edges,
+        }));
+    }
+
+    Ok(violations)
+}
@@ -0,0 +1,236 @@
+use std::fs;
+
+use chrono::Local;
+use pyo3::{
+    exceptions::{PyIOError, PyValueError},
+    prelude::*,
+};
+
+use crate::point::Point;
+
+use super::{marker_from_edges, marker_polygon, DrcViolation};
+
+const VALUE_PROPERTY: &str = "VALUE";
+
+fn invalid_rdb(message: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(format!("Invalid RDB file: {}", message))
+}
+
+fn parse_numbers(line: &str) -> PyResult<Vec<f64>> {
+    line.split_whitespace()
+        .map(|value| {
+            value
+                .parse::<f64>()
+                .map_err(|_| invalid_rdb(format!("bad number '{}'", value)))
+        })
+        .collect()
+}
+
+fn next_line<'a>(lines: &mut impl Iterator<Item = &'a str>) -> PyResult<&'a str> {
+    lines
+        .next()
+        .ok_or_else(|| invalid_rdb("unexpected end of file"))
+}
+
+/// Writes violations as a Calibre ASCII results database, with one check per rule and
+/// coordinates in database units.
+pub fn write_rdb(
+    file_name: String,
+    top_cell: &str,
+    database_units_per_unit: f64,
+    violations: &[DrcViolation],
+) -> PyResult<String> {
+    // The header precision is an integer, coordinates are scaled by the same value
+    let database_units_per_unit = database_units_per_unit.round();
+    let to_database_units = |value: f64| (value * database_units_per_unit).round() as i64;
+    let date = Local::now().format("%b %d %H:%M:%S %Y");
+
+    let mut rules: Vec<&str> = Vec::new();
+    for violation in violations {
+        if !rules.contains(&violation.rule.as_str()) {
+            rules.push(&violation.rule);
+        }
+    }
+
+    let mut rdb = format!("{} {}\n", top_cell, database_units_per_unit.round());
+    for rule in rules {
+        let rule_violations: Vec<&DrcViolation> = violations
+            .iter()
+            .filter(|violation| violation.rule == rule)
+            .collect();
+        let results = rule_violations.len()
+            + rule_violations
+                .iter()
+                .filter(|violation| !violation.edges.is_empty())
+                .count();
+
+        rdb.push_str(&format!("{}\n{} {} 0 {}\n", rule, results, results, date));
+        for (ordinal, violation) in rule_violations.into_iter().enumerate() {
+            let cell = violation.cell.as_deref().unwrap_or(top_cell);
+            let mut points = violation.marker.points.clone();
+            if points.len() > 1 && points.first() == points.last() {
+                points.pop();
+            }
+
+            rdb.push_str(&format!("p {} {}\n", ordinal + 1, points.len()));
+            rdb.push_str(&format!("CN {} c 1 0 0 1 0 0\n", cell));
+            for point in points {
+                rdb.push_str(&format!(
+                    "{} {}\n",
+                    to_database_units(point.x),
+                    to_database_units(point.y)
+                ));
+            }
+            rdb.push_str(&format!("{} {}\n", VALUE_PROPERTY, violation.value));
+
+            // Edges share the ordinal of their marker so they can be read back together
+            if !violation.edges.is_empty() {
+                rdb.push_str(&format!("e {} {}\n", ordinal + 1, violation.edges.len()));
+                rdb.push_str(&format!("CN {} c 1 0 0 1 0 0\n", cell));
+                for (start, end) in &violation.edges {
+                    rdb.push_str(&format!(
+                        "{} {} {} {}\n",
+                        to_database_units(start.x),
+                        to_database_units(start.y),
+                        to_database_units(end.x),
+                        to_database_units(end.y)
+                    ));
+                }
+            }
+        }
+    }
+
+    fs::write(&file_name, rdb)
+        .map_err(|_| PyIOError::new_err("Could not open file for writing"))?;
+    Ok(file_name)
+}
+
+/// Reads the results of a Calibre ASCII results database as violations.
+pub fn read_rdb(file_name: String) -> PyResult<Vec<DrcViolation>> {
+    let content = fs::read_to_string(file_name)?;
+    let mut lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .peekable();
+
+    let header = next_line(&mut lines)?;
+    let (top_cell, database_units_per_unit) = header
+        .rsplit_once(char::is_whitespace)
+        .ok_or_else(|| invalid_rdb("missing precision"))?;
+    let top_cell = top_cell.trim().to_string();
+    let database_units_per_unit: f64 = database_units_per_unit
+        .parse()
+        .map_err(|_| invalid_rdb("bad precision"))?;
+    let to_units = |value: f64| value / database_units_per_unit;
+
+    let mut violations: Vec<DrcViolation> = Vec::new();
+    while let Some(rule) = lines.next() {
+        let counts = next_line(&mut lines)?;
+        let counts: Vec<&str> = counts.split_whitespace().collect();
+        let count = |index: usize| -> PyResult<usize> {
+            counts
+                .get(index)
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| invalid_rdb(format!("bad counts for check '{}'", rule)))
+        };
+        let (results, text_lines) = (count(0)?, count(2)?);
+        for _ in 0..text_lines {
+            next_line(&mut lines)?;
+        }
+
+        let mut previous_ordinal = None;
+        for _ in 0..results {
+            let result = next_line(&mut lines)?;
+            let fields: Vec<&str> = result.split_whitespace().collect();
+            let (kind, ordinal, size) = match fields[..] {
+                [kind, ordinal, size, ..] => (
+                    kind,
+                    ordinal.to_string(),
+                    size.parse::<usize>()
+                        .map_err(|_| invalid_rdb(format!("bad result '{}'", result)))?,
+                ),
+                _ => return Err(invalid_rdb(format!("bad result '{}'", result))),
+            };
+
+            let mut line = next_line(&mut lines)?;
+            let mut cell = top_cell.clone();
+            if let Some(name) = line.strip_prefix("CN ") {
+                cell = name
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or(&top_cell)
+                    .to_string();
+                line = next_line(&mut lines)?;
+            }
+
+            let mut rows = Vec::with_capacity(size);
+            for index in 0..size {
+                if index > 0 {
+                    line = next_line(&mut lines)?;
+                }
+                rows.push(parse_numbers(line)?);
+            }
+
+            let mut value = 0.0;
+            if let Some(property) = lines
+                .peek()
+                .and_then(|line| line.strip_prefix(VALUE_PROPERTY))
+                .and_then(|property| property.trim().parse::<f64>().ok())
+            {
+                value = property;
+                lines.next();
+            }
+
+            match kind {
+                "p" => {
+                    let points = rows
+                        .iter()
+                        .map(|row| match row[..] {
+                            [x, y] => Ok(Point::new(to_units(x), to_units(y))),
+                            _ => Err(invalid_rdb(format!("bad vertex in check '{}'", rule))),
+                        })
+                        .collect::<PyResult<Vec<_>>>()?;
+                    violations.push(DrcViolation {
+                        rule: rule.to_string(),
+                        cell: Some(cell),
+                        value,
+                        marker: marker_polygon(points),
+                        edges: Vec::new(),
+                    });
+                }
+                "e" => {
+                    let edges = rows
+                        .iter()
+                        .map(|row| match row[..] {
+                            [x1, y1, x2, y2] => Ok((
+                                Point::new(to_units(x1), to_units(y1)),
+                                Point::new(to_units(x2), to_units(y2)),
+                            )),
+                            _ => Err(invalid_rdb(format!("bad edge in check '{}'", rule))),
+                        })
+                        .collect::<PyResult<Vec<_>>>()?;
+                    match violations.last_mut() {
+                        Some(violation)
+                            if previous_ordinal.as_ref() == Some(&ordinal)
+                                && violation.edges.is_empty() =>
+                        {
+                            violation.edges = edges;
+                        }
+                        _ => violations.push(DrcViolation {
+                            rule: rule.to_string(),
+                            cell: Some(cell),
+                            value,
+                            marker: marker_from_edges(&edges),
+                            edges,
+                        }),
+                    }
+                }
+                _ => return Err(invalid_rdb(format!("unsupported result type '{}'", kind))),
+            }
+            previous_ordinal = Some(ordinal);
+        }
+    }
+
+    Ok(violations)
+}
@@ -1,12 +1,16 @@
-use std::fs::File;
+use std::{fs::File, sync::Arc};
 
 use pyo3::{exceptions::PyTypeError, prelude::*};
 
 use crate::{
+    config::max_points_mode::MaxPointsMode,
+    gds_box::GdsBox,
+    node::Node,
     path::Path,
     point::Point,
     polygon::Polygon,
     reference::Reference,
+    shared::{revision::Revision, Shared},
     text::Text,
     traits::{Dimensions, LayerDataTypeMatches, Movable, Reflect, Rotatable, Scalable, ToGds},
 };
@@ -14,37 +18,45 @@ use crate::{
 #[derive(Clone)]
 #[allow(clippy::enum_variant_names)]
 pub enum Element {
-    Path(Py<Path>),
-    Polygon(Py<Polygon>),
-    Reference(Py<Reference>),
-    Text(Py<Text>),
+    Box(Shared<GdsBox>),
+    Node(Shared<Node>),
+    Path(Shared<Path>),
+    Polygon(Shared<Polygon>),
+    Reference(Shared<Reference>),
+    Text(Shared<Text>),
 }
 
 impl PartialEq for Element {
     fn eq(&self, other: &Self) -> bool {
-        Python::with_gil(|py| match (self, other) {
-            (Element::Path(a), Element::Path(b)) => a.borrow(py).eq(&b.borrow(py)),
-            (Element::Polygon(a), Element::Polygon(b)) => a.borrow(py).eq(&b.borrow(py)),
-            (Element::Reference(a), Element::Reference(b)) => a.borrow(py).eq(&b.borrow(py)),
-            (Element::Text(a), Element::Text(b)) => a.borrow(py).eq(&b.borrow(py)),
+        match (self, other) {
+            (Element::Box(a), Element::Box(b)) => a == b,
+            (Element::Node(a), Element::Node(b)) => a == b,
+            (Element::Path(a), Element::Path(b)) => a == b,
+            (Element::Polygon(a), Element::Polygon(b)) => a == b,
+            (Element::Reference(a), Element::Reference(b)) => a == b,
+            (Element::Text(a), Element::Text(b)) => a == b,
             _ => false,
-        })
+        }
     }
 }
 
 impl FromPyObject<'_> for Element {
     fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(element) = ob.extract::<Py<Path>>() {
+        if let Ok(element) = ob.extract::<Shared<Path>>() {
             Ok(Element::Path(element))
-        } else if let Ok(element) = ob.extract::<Py<Polygon>>() {
+        } else if let Ok(element) = ob.extract::<Shared<Polygon>>() {
             Ok(Element::Polygon(element))
-        } else if let Ok(element) = ob.extract::<Py<Reference>>() {
+        } else if let Ok(element) = ob.extract::<Shared<Reference>>() {
             Ok(Element::Reference(element))
-        } else if let Ok(element) = ob.extract::<Py<Text>>() {
+        } else if let Ok(element) = ob.extract::<Shared<Text>>() {
             Ok(Element::Text(element))
+        } else if let Ok(element) = ob.extract::<Shared<GdsBox>>() {
+            Ok(Element::Box(element))
+        } else if let Ok(element) = ob.extract::<Shared<Node>>() {
+            Ok(Element::Node(element))
         } else {
             Err(PyTypeError::new_err(
-                "Element must be a Path, Polygon, Reference or Text",
+                "Element must be a Box, Node, Path, Polygon, Reference or Text",
             ))
         }
     }
@@ -53,6 +65,8 @@ impl FromPyObject<'_> for Element {
 impl IntoPy<PyObject> for Element {
     fn into_py(self, py: Python) -> PyObject {
         match self {
+            Element::Box(gds_box) => gds_box.into_py(py),
+            Element::Node(node) => node.into_py(py),
             Element::Path(path) => path.into_py(py),
             Element::Polygon(polygon) => polygon.into_py(py),
             Element::Reference(reference) => reference.into_py(py),
@@ -61,173 +75,233 @@ impl IntoPy<PyObject> for Element {
     }
 }
 impl ToGds for Element {
-    fn _to_gds(&self, file: File, scale: f64) -> PyResult<File> {
-        Python::with_gil(|py| match self {
-            Element::Path(element) => element.borrow(py)._to_gds(file, scale),
-            Element::Polygon(element) => element.borrow(py)._to_gds(file, scale),
-            Element::Reference(element) => element.borrow(py)._to_gds(file, scale),
-            Element::Text(element) => element.borrow(py)._to_gds(file, scale),
-        })
+    fn _to_gds(&self, file: File, scale: f64, max_points_mode: MaxPointsMode) -> PyResult<File> {
+        match self {
+            Element::Box(element) => element.read()._to_gds(file, scale, max_points_mode),
+            Element::Node(element) => element.read()._to_gds(file, scale, max_points_mode),
+            Element::Path(element) => element.read()._to_gds(file, scale, max_points_mode),
+            Element::Polygon(element) => element.read()._to_gds(file, scale, max_points_mode),
+            Element::Reference(element) => element.read()._to_gds(file, scale, max_points_mode),
+            Element::Text(element) => element.read()._to_gds(file, scale, max_points_mode),
+        }
     }
 }
 
 impl Movable for Element {
     fn move_to(&mut self, point: Point) -> &mut Self {
-        Python::with_gil(|py| match self {
+        match self {
+            Element::Box(element) => {
+                element.write().move_to(point);
+            }
+            Element::Node(element) => {
+                element.write().move_to(point);
+            }
             Element::Path(element) => {
-                element.borrow_mut(py).move_to(point);
+                element.write().move_to(point);
             }
             Element::Polygon(element) => {
-                element.borrow_mut(py).move_to(point);
+                element.write().move_to(point);
             }
             Element::Reference(element) => {
-                element.borrow_mut(py).move_to(point);
+                element.write().move_to(point);
             }
             Element::Text(element) => {
-                element.borrow_mut(py).move_to(point);
+                element.write().move_to(point);
             }
-        });
+        }
         self
     }
 
     fn move_by(&mut self, vector: Point) -> &mut Self {
-        Python::with_gil(|py| match self {
+        match self {
+            Element::Box(element) => {
+                element.write().move_by(vector);
+            }
+            Element::Node(element) => {
+                element.write().move_by(vector);
+            }
             Element::Path(element) => {
-                element.borrow_mut(py).move_by(vector);
+                element.write().move_by(vector);
             }
             Element::Polygon(element) => {
-                element.borrow_mut(py).move_by(vector);
+                element.write().move_by(vector);
             }
             Element::Reference(element) => {
-                element.borrow_mut(py).move_by(vector);
+                element.write().move_by(vector);
             }
             Element::Text(element) => {
-                element.borrow_mut(py).move_by(vector);
+                element.write().move_by(vector);
             }
-        });
+        }
         self
     }
 }
 
 impl Element {
     pub fn copy(&self) -> Self {
-        Python::with_gil(|py| match self {
-            Element::Path(element) => {
-                Element::Path(Py::new(py, element.borrow(py).copy()).unwrap())
-            }
-            Element::Polygon(element) => {
-                Element::Polygon(Py::new(py, element.borrow(py).copy()).unwrap())
-            }
-            Element::Reference(element) => {
-                Element::Reference(Py::new(py, element.borrow(py).copy()).unwrap())
-            }
-            Element::Text(element) => {
-                Element::Text(Py::new(py, element.borrow(py).copy()).unwrap())
-            }
-        })
+        match self {
+            Element::Box(element) => Element::Box(element.copy()),
+            Element::Node(element) => Element::Node(element.copy()),
+            Element::Path(element) => Element::Path(element.copy()),
+            Element::Polygon(element) => Element::Polygon(element.copy()),
+            Element::Reference(element) => Element::Reference(Shared::new(element.read().copy())),
+            Element::Text(element) => Element::Text(element.copy()),
+        }
+    }
+
+    pub fn revision(&self) -> &Arc<Revision> {
+        match self {
+            Element::Box(element) => element.revision(),
+            Element::Node(element) => element.revision(),
+            Element::Path(element) => element.revision(),
+            Element::Polygon(element) => element.revision(),
+            Element::Reference(element) => element.revision(),
+            Element::Text(element) => element.revision(),
+        }
+    }
+
+    /// Records `revision` as depending on the element and, for a reference, on what it places.
+    pub fn add_dependent(&self, revision: &Arc<Revision>) {
+        self.revision().add_dependent(revision);
+        if let Element::Reference(reference) = self {
+            reference.read().add_dependent(revision);
+        }
+    }
+
+    pub fn remove_dependent(&self, revision: &Arc<Revision>) {
+        self.revision().remove_dependent(revision);
+        if let Element::Reference(reference) = self {
+            reference.read().remove_dependent(revision);
+        }
     }
 }
 
 impl Rotatable for Element {
     fn rotate(&mut self, angle: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| match self {
+        match self {
+            Element::Box(element) => {
+                element.write().rotate(angle, centre);
+            }
+            Element::Node(element) => {
+                element.write().rotate(angle, centre);
+            }
             Element::Path(element) => {
-                element.borrow_mut(py).rotate(angle, centre);
+                element.write().rotate(angle, centre);
             }
             Element::Polygon(element) => {
-                element.borrow_mut(py).rotate(angle, centre);
+                element.write().rotate(angle, centre);
             }
             Element::Reference(element) => {
-                element.borrow_mut(py).rotate(angle, centre);
+                element.write().rotate(angle, centre);
             }
             Element::Text(element) => {
-                element.borrow_mut(py).rotate(angle, centre);
+                element.write().rotate(angle, centre);
             }
-        });
+        }
         self
     }
 }
 
 impl Scalable for Element {
     fn scale(&mut self, factor: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| match self {
+        match self {
+            Element::Box(element) => {
+                element.write().scale(factor, centre);
+            }
+            Element::Node(element) => {
+                element.write().scale(factor, centre);
+            }
             Element::Path(element) => {
-                element.borrow_mut(py).scale(factor, centre);
+                element.write().scale(factor, centre);
             }
             Element::Polygon(element) => {
-                element.borrow_mut(py).scale(factor, centre);
+                element.write().scale(factor, centre);
             }
             Element::Reference(element) => {
-                element.borrow_mut(py).scale(factor, centre);
+                element.write().scale(factor, centre);
             }
             Element::Text(element) => {
-                element.borrow_mut(py).scale(factor, centre);
+                element.write().scale(factor, centre);
             }
-        });
+        }
         self
     }
 }
 
 impl Dimensions for Element {
     fn bounding_box(&self) -> (Point, Point) {
-        Python::with_gil(|py| match self {
-            Element::Path(element) => element.borrow(py).bounding_box(),
-            Element::Polygon(element) => element.borrow(py).bounding_box(),
-            Element::Reference(element) => element.borrow(py).bounding_box(),
-            Element::Text(element) => element.borrow(py).bounding_box(),
-        })
+        match self {
+            Element::Box(element) => element.read().bounding_box(),
+            Element::Node(element) => element.read().bounding_box(),
+            Element::Path(element) => element.read().bounding_box(),
+            Element::Polygon(element) => element.read().bounding_box(),
+            Element::Reference(element) => element.read().bounding_box(),
+            Element::Text(element) => element.read().bounding_box(),
+        }
     }
 }
 
 impl Reflect for Element {
     fn reflect(&mut self, angle: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| match self {
+        match self {
+            Element::Box(element) => {
+                element.write().reflect(angle, centre);
+            }
+            Element::Node(element) => {
+                element.write().reflect(angle, centre);
+            }
             Element::Path(element) => {
-                element.borrow_mut(py).reflect(angle, centre);
+                element.write().reflect(angle, centre);
             }
             Element::Polygon(element) => {
-                element.borrow_mut(py).reflect(angle, centre);
+                element.write().reflect(angle, centre);
             }
             Element::Reference(element) => {
-                element.borrow_mut(py).reflect(angle, centre);
+                element.write().reflect(angle, centre);
             }
             Element::Text(element) => {
-                element.borrow_mut(py).reflect(angle, centre);
+                element.write().reflect(angle, centre);
             }
-        });
+        }
         self
     }
 }
 
 impl LayerDataTypeMatches for Element {
     fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
-        Python::with_gil(|py| match self {
-            Element::Path(element) => element.borrow(py).is_on(layer_data_types),
-            Element::Polygon(element) => element.borrow(py).is_on(layer_data_types),
-            Element::Reference(element) => element.borrow(py).is_on(layer_data_types),
-            Element::Text(element) => element.borrow(py).is_on(layer_data_types),
-        })
+        match self {
+            Element::Box(element) => element.read().is_on(layer_data_types),
+            Element::Node(element) => element.read().is_on(layer_data_types),
+            Element::Path(element) => element.read().is_on(layer_data_types),
+            Element::Polygon(element) => element.read().is_on(layer_data_types),
+            Element::Reference(element) => element.read().is_on(layer_data_types),
+            Element::Text(element) => element.read().is_on(layer_data_types),
+        }
     }
 }
 
 impl std::fmt::Display for Element {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        Python::with_gil(|py| match self {
-            Element::Path(element) => write!(f, "{}", element.borrow(py).clone()),
-            Element::Polygon(element) => write!(f, "{}", element.borrow(py).clone()),
-            Element::Reference(element) => write!(f, "{}", element.borrow(py).clone()),
-            Element::Text(element) => write!(f, "{}", element.borrow(py).clone()),
-        })
+        match self {
+            Element::Box(element) => write!(f, "{}", element),
+            Element::Node(element) => write!(f, "{}", element),
+            Element::Path(element) => write!(f, "{}", element),
+            Element::Polygon(element) => write!(f, "{}", element),
+            Element::Reference(element) => write!(f, "{}", element),
+            Element::Text(element) => write!(f, "{}", element),
+        }
     }
 }
 
 impl std::fmt::Debug for Element {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        Python::with_gil(|py| match self {
-            Element::Path(element) => write!(f, "{:?}", element.borrow(py).clone()),
-            Element::Polygon(element) => write!(f, "{:?}", element.borrow(py).clone()),
-            Element::Reference(element) => write!(f, "{:?}", element.borrow(py).clone()),
-            Element::Text(element) => write!(f, "{:?}", element.borrow(py).clone()),
-        })
+        match self {
+            Element::Box(element) => write!(f, "{:?}", element),
+            Element::Node(element) => write!(f, "{:?}", element),
+            Element::Path(element) => write!(f, "{:?}", element),
+            Element::Polygon(element) => write!(f, "{:?}", element),
+            Element::Reference(element) => write!(f, "{:?}", element),
+            Element::Text(element) => write!(f, "{:?}", element),
+        }
     }
 }
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+
+use crate::{
+    point::Point,
+    polygon::utils::get_correct_polygon_points_format,
+    shared::Shared,
+    traits::{Dimensions, LayerDataTypeMatches, Movable, Rotatable, Scalable},
+    utils::transformations::{py_any_to_point, py_any_to_points_vec},
+    validation::input::{
+        check_box_points_valid, check_data_type_valid, check_layer_valid, check_properties_valid,
+    },
+};
+
+use super::{GdsBox, PyGdsBox};
+
+impl GdsBox {
+    pub fn new(
+        points: Vec<Point>,
+        layer: i32,
+        box_type: i32,
+        properties: Option<HashMap<i32, String>>,
+    ) -> PyResult<Self> {
+        check_box_points_valid(&points)?;
+        let points = get_correct_polygon_points_format(points);
+        check_layer_valid(layer)?;
+        check_data_type_valid(box_type)?;
+        let properties = properties.unwrap_or_default();
+        check_properties_valid(&properties)?;
+
+        Ok(Self {
+            points,
+            layer,
+            box_type,
+            properties,
+        })
+    }
+}
+
+#[pymethods]
+impl PyGdsBox {
+    #[new]
+    #[pyo3(signature = (points, layer=0, box_type=0, properties=None))]
+    fn new(
+        #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
+        layer: i32,
+        box_type: i32,
+        properties: Option<HashMap<i32, String>>,
+    ) -> PyResult<Self> {
+        GdsBox::new(points, layer, box_type, properties).map(|value| PyGdsBox(Shared::new(value)))
+    }
+
+    #[getter]
+    fn points(&self) -> Vec<Point> {
+        self.0.read().points.clone()
+    }
+
+    #[getter]
+    fn layer(&self) -> i32 {
+        self.0.read().layer
+    }
+
+    #[getter]
+    fn box_type(&self) -> i32 {
+        self.0.read().box_type
+    }
+
+    #[getter]
+    fn properties(&self) -> HashMap<i32, String> {
+        self.0.read().properties.clone()
+    }
+
+    #[setter(points)]
+    fn setter_points(
+        &self,
+        #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
+    ) -> PyResult<()> {
+        check_box_points_valid(&points)?;
+        let points = get_correct_polygon_points_format(points);
+        self.0.write().points = points;
+        Ok(())
+    }
+
+    fn set_points(
+        slf: PyRef<'_, Self>,
+        #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
+    ) -> PyResult<PyRef<'_, Self>> {
+        slf.setter_points(points)?;
+        Ok(slf)
+    }
+
+    #[setter(layer)]
+    fn setter_layer(&self, layer: i32) -> PyResult<()> {
+        check_layer_valid(layer)?;
+        self.0.write().layer = layer;
+        Ok(())
+    }
+
+    fn set_layer(slf: PyRef<'_, Self>, layer: i32) -> PyRef<'_, Self> {
+        slf.setter_layer(layer).unwrap();
+        slf
+    }
+
+    #[setter(box_type)]
+    fn setter_box_type(&self, box_type: i32) -> PyResult<()> {
+        check_data_type_valid(box_type)?;
+        self.0.write().box_type = box_type;
+        Ok(())
+    }
+
+    fn set_box_type(slf: PyRef<'_, Self>, box_type: i32) -> PyRef<'_, Self> {
+        slf.setter_box_type(box_type).unwrap();
+        slf
+    }
+
+    #[setter(properties)]
+    fn setter_properties(&self, properties: HashMap<i32, String>) -> PyResult<()> {
+        check_properties_valid(&properties)?;
+        self.0.write().properties = properties;
+        Ok(())
+    }
+
+    fn set_properties(
+        slf: PyRef<'_, Self>,
+        properties: HashMap<i32, String>,
+    ) -> PyResult<PyRef<'_, Self>> {
+        slf.setter_properties(properties)?;
+        Ok(slf)
+    }
+
+    #[getter]
+    fn bounding_box(&self) -> (Point, Point) {
+        Dimensions::bounding_box(&*self.0.read())
+    }
+
+    pub fn copy(&self) -> Self {
+        Self(self.0.copy())
+    }
+
+    fn move_to(
+        slf: PyRef<'_, Self>,
+        #[pyo3(from_py_with = "py_any_to_point")] point: Point,
+    ) -> PyRef<'_, Self> {
+        Movable::move_to(&mut *slf.0.write(), point);
+        slf
+    }
+
+    fn move_by(
+        slf: PyRef<'_, Self>,
+        #[pyo3(from_py_with = "py_any_to_point")] vector: Point,
+    ) -> PyRef<'_, Self> {
+        Movable::move_by(&mut *slf.0.write(), vector);
+        slf
+    }
+
+    #[pyo3(signature = (angle, centre=Point::default()))]
+    fn rotate(
+        slf: PyRef<'_, Self>,
+        angle: f64,
+        #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
+    ) -> PyRef<'_, Self> {
+        Rotatable::rotate(&mut *slf.0.write(), angle, centre);
+        slf
+    }
+
+    #[pyo3(signature = (factor, centre=Point::default()))]
+    fn scale(
+        slf: PyRef<'_, Self>,
+        factor: f64,
+        #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
+    ) -> PyRef<'_, Self> {
+        Scalable::scale(&mut *slf.0.write(), factor, centre);
+        slf
+    }
+
+    #[pyo3(signature = (*layer_data_types))]
+    pub fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
+        LayerDataTypeMatches::is_on(&*self.0.read(), layer_data_types)
+    }
+
+    fn __str__(&self) -> PyResult<String> {
+        Ok(format!("{}", self.0))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{:?}", self.0))
+    }
+}
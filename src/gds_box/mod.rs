@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+
+use crate::{
+    point::Point,
+    polygon::Polygon,
+    shared::{
+        revision::Tracked,
+        wrapper::{extract_value, value_into_py, Wrapped, Wrapper},
+        Shared,
+    },
+    traits::{Dimensions, LayerDataTypeMatches, Movable, Reflect, Rotatable, Scalable},
+    utils::geometry::bounding_box,
+};
+
+mod general;
+mod io;
+
+#[derive(Clone, Default)]
+pub struct GdsBox {
+    pub points: Vec<Point>,
+    pub layer: i32,
+    pub box_type: i32,
+    pub properties: HashMap<i32, String>,
+}
+
+#[pyclass(name = "Box", eq, frozen, weakref)]
+#[derive(PartialEq)]
+pub struct PyGdsBox(pub Shared<GdsBox>);
+
+impl Wrapper for PyGdsBox {
+    type Inner = GdsBox;
+
+    fn wrap(shared: Shared<GdsBox>) -> Self {
+        PyGdsBox(shared)
+    }
+
+    fn shared(&self) -> &Shared<GdsBox> {
+        &self.0
+    }
+}
+
+impl Wrapped for GdsBox {
+    type Wrapper = PyGdsBox;
+}
+
+impl Tracked for GdsBox {}
+
+impl IntoPy<PyObject> for GdsBox {
+    fn into_py(self, py: Python) -> PyObject {
+        value_into_py(self, py)
+    }
+}
+
+impl FromPyObject<'_> for GdsBox {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        extract_value(ob)
+    }
+}
+
+impl PartialEq for GdsBox {
+    fn eq(&self, other: &Self) -> bool {
+        self.points.len() == other.points.len()
+            && self
+                .points
+                .iter()
+                .zip(other.points.iter())
+                .all(|(self_point, other_point)| self_point.epsilon_is_close(*other_point))
+            && self.layer == other.layer
+            && self.box_type == other.box_type
+            && self.properties == other.properties
+    }
+}
+
+impl std::fmt::Display for GdsBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Box with {} point(s) on layer {}, box type {}",
+            self.points.len(),
+            self.layer,
+            self.box_type
+        )
+    }
+}
+
+impl std::fmt::Debug for GdsBox {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Box({:?}, {}, {})",
+            self.points, self.layer, self.box_type
+        )
+    }
+}
+
+impl GdsBox {
+    /// The box outline as a polygon with the box type as its data type
+    pub fn to_polygon(&self) -> Polygon {
+        Polygon {
+            points: self.points.clone(),
+            layer: self.layer,
+            data_type: self.box_type,
+            properties: self.properties.clone(),
+        }
+    }
+}
+
+impl Movable for GdsBox {
+    fn move_by(&mut self, delta: Point) -> &mut Self {
+        for point in &mut self.points {
+            *point += delta;
+        }
+        self
+    }
+
+    fn move_to(&mut self, target: Point) -> &mut Self {
+        let delta = target - self.points[0];
+        self.move_by(delta)
+    }
+}
+
+impl Rotatable for GdsBox {
+    fn rotate(&mut self, angle: f64, centre: Point) -> &mut Self {
+        for point in &mut self.points {
+            *point = point.rotate(angle, centre);
+        }
+        self
+    }
+}
+
+impl Scalable for GdsBox {
+    fn scale(&mut self, factor: f64, centre: Point) -> &mut Self {
+        for point in &mut self.points {
+            *point = point.scale(factor, centre);
+        }
+        self
+    }
+}
+
+impl Dimensions for GdsBox {
+    fn bounding_box(&self) -> (Point, Point) {
+        bounding_box(&self.points)
+    }
+}
+
+impl Reflect for GdsBox {
+    fn reflect(&mut self, angle: f64, centre: Point) -> &mut Self {
+        for point in &mut self.points {
+            *point = point.reflect(angle, centre);
+        }
+        self
+    }
+}
+
+impl LayerDataTypeMatches for GdsBox {
+    fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
+        layer_data_types.contains(&(self.layer, self.box_type)) || layer_data_types.is_empty()
+    }
+}
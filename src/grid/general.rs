@@ -1,16 +1,44 @@
-use std::ops::DerefMut;
-
 use pyo3::prelude::*;
 
-use super::Grid;
+use super::{Grid, PyGrid};
 use crate::utils::transformations::py_any_to_point;
 use crate::{
     point::Point,
+    shared::Shared,
     traits::{Movable, Rotatable, Scalable},
 };
 
-#[pymethods]
 impl Grid {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        origin: Point,
+        columns: u32,
+        rows: u32,
+        spacing_x: Point,
+        spacing_y: Point,
+        magnification: f64,
+        angle: f64,
+        x_reflection: bool,
+        absolute_magnification: bool,
+        absolute_angle: bool,
+    ) -> Self {
+        Grid {
+            origin,
+            columns,
+            rows,
+            spacing_x,
+            spacing_y,
+            magnification,
+            angle,
+            x_reflection,
+            absolute_magnification,
+            absolute_angle,
+        }
+    }
+}
+
+#[pymethods]
+impl PyGrid {
     #[new]
     #[pyo3(signature=(
         origin=Point::default(),
@@ -20,10 +48,12 @@ impl Grid {
         spacing_y=Point::default(),
         magnification=1.0,
         angle=0.0,
-        x_reflection=false
+        x_reflection=false,
+        absolute_magnification=false,
+        absolute_angle=false
     ))]
     #[allow(clippy::too_many_arguments)]
-    pub fn new(
+    fn new(
         #[pyo3(from_py_with = "py_any_to_point")] origin: Point,
         columns: u32,
         rows: u32,
@@ -32,8 +62,10 @@ impl Grid {
         magnification: f64,
         angle: f64,
         x_reflection: bool,
+        absolute_magnification: bool,
+        absolute_angle: bool,
     ) -> Self {
-        Grid {
+        PyGrid(Shared::new(Grid::new(
             origin,
             columns,
             rows,
@@ -42,69 +74,156 @@ impl Grid {
             magnification,
             angle,
             x_reflection,
-        }
+            absolute_magnification,
+            absolute_angle,
+        )))
+    }
+
+    #[getter]
+    fn origin(&self) -> Point {
+        self.0.read().origin
+    }
+
+    #[getter]
+    fn columns(&self) -> u32 {
+        self.0.read().columns
+    }
+
+    #[getter]
+    fn rows(&self) -> u32 {
+        self.0.read().rows
+    }
+
+    #[getter]
+    fn spacing_x(&self) -> Point {
+        self.0.read().spacing_x
+    }
+
+    #[getter]
+    fn spacing_y(&self) -> Point {
+        self.0.read().spacing_y
+    }
+
+    #[getter]
+    fn magnification(&self) -> f64 {
+        self.0.read().magnification
+    }
+
+    #[getter]
+    fn angle(&self) -> f64 {
+        self.0.read().angle
+    }
+
+    #[getter]
+    fn x_reflection(&self) -> bool {
+        self.0.read().x_reflection
+    }
+
+    #[getter]
+    fn absolute_magnification(&self) -> bool {
+        self.0.read().absolute_magnification
+    }
+
+    #[getter]
+    fn absolute_angle(&self) -> bool {
+        self.0.read().absolute_angle
+    }
+
+    #[setter]
+    pub fn set_origin(&self, #[pyo3(from_py_with = "py_any_to_point")] origin: Point) {
+        self.0.write().origin = origin;
+    }
+
+    #[setter]
+    pub fn set_columns(&self, columns: u32) {
+        self.0.write().columns = columns;
+    }
+
+    #[setter]
+    pub fn set_rows(&self, rows: u32) {
+        self.0.write().rows = rows;
+    }
+
+    #[setter]
+    pub fn set_spacing_x(&self, #[pyo3(from_py_with = "py_any_to_point")] spacing_x: Point) {
+        self.0.write().spacing_x = spacing_x;
+    }
+
+    #[setter]
+    pub fn set_spacing_y(&self, #[pyo3(from_py_with = "py_any_to_point")] spacing_y: Point) {
+        self.0.write().spacing_y = spacing_y;
+    }
+
+    #[setter]
+    pub fn set_magnification(&self, magnification: f64) {
+        self.0.write().magnification = magnification;
+    }
+
+    #[setter]
+    pub fn set_angle(&self, angle: f64) {
+        self.0.write().angle = angle;
     }
 
     #[setter]
-    pub fn set_origin(&mut self, #[pyo3(from_py_with = "py_any_to_point")] origin: Point) {
-        self.origin = origin;
+    pub fn set_x_reflection(&self, x_reflection: bool) {
+        self.0.write().x_reflection = x_reflection;
     }
 
     #[setter]
-    pub fn set_spacing_x(&mut self, #[pyo3(from_py_with = "py_any_to_point")] spacing_x: Point) {
-        self.spacing_x = spacing_x;
+    pub fn set_absolute_magnification(&self, absolute_magnification: bool) {
+        self.0.write().absolute_magnification = absolute_magnification;
     }
 
     #[setter]
-    pub fn set_spacing_y(&mut self, #[pyo3(from_py_with = "py_any_to_point")] spacing_y: Point) {
-        self.spacing_y = spacing_y;
+    pub fn set_absolute_angle(&self, absolute_angle: bool) {
+        self.0.write().absolute_angle = absolute_angle;
     }
 
     pub fn copy(&self) -> Self {
-        self.clone()
+        Self(self.0.copy())
     }
 
     fn move_to(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] point: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_to(slf.deref_mut(), point);
+    ) -> PyRef<'_, Self> {
+        Movable::move_to(&mut *slf.0.write(), point);
         slf
     }
 
     fn move_by(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] vector: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_by(slf.deref_mut(), vector);
+    ) -> PyRef<'_, Self> {
+        Movable::move_by(&mut *slf.0.write(), vector);
         slf
     }
 
     #[pyo3(signature = (angle, centre=Point::default()))]
     fn rotate(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         angle: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Rotatable::rotate(slf.deref_mut(), angle, centre);
+    ) -> PyRef<'_, Self> {
+        Rotatable::rotate(&mut *slf.0.write(), angle, centre);
         slf
     }
 
     #[pyo3(signature = (factor, centre=Point::default()))]
     fn scale(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         factor: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Scalable::scale(slf.deref_mut(), factor, centre);
+    ) -> PyRef<'_, Self> {
+        Scalable::scale(&mut *slf.0.write(), factor, centre);
         slf
     }
 
     fn __str__(&self) -> PyResult<String> {
-        Ok(format!("{}", self))
+        Ok(format!("{}", self.0))
     }
 
     fn __repr__(&self) -> PyResult<String> {
-        Ok(format!("{:?}", self))
+        Ok(format!("{:?}", self.0))
     }
 }
@@ -1,31 +1,64 @@
 use pyo3::prelude::*;
 
 use crate::{
+    config::FLOATING_POINT_INACCURACY_ROUND_DECIMALS,
     point::Point,
+    shared::{
+        revision::Tracked,
+        wrapper::{extract_value, value_into_py, Wrapped, Wrapper},
+        Shared,
+    },
     traits::{Movable, Reflect, Rotatable, Scalable},
 };
 
 mod general;
 
-#[pyclass(eq)]
 #[derive(Clone)]
 pub struct Grid {
-    #[pyo3(get)]
     pub origin: Point,
-    #[pyo3(get, set)]
     pub columns: u32,
-    #[pyo3(get, set)]
     pub rows: u32,
-    #[pyo3(get)]
     pub spacing_x: Point,
-    #[pyo3(get)]
     pub spacing_y: Point,
-    #[pyo3(get, set)]
     pub magnification: f64,
-    #[pyo3(get, set)]
     pub angle: f64,
-    #[pyo3(get, set)]
     pub x_reflection: bool,
+    pub absolute_magnification: bool,
+    pub absolute_angle: bool,
+}
+
+#[pyclass(name = "Grid", eq, frozen, weakref)]
+#[derive(PartialEq)]
+pub struct PyGrid(pub Shared<Grid>);
+
+impl Wrapper for PyGrid {
+    type Inner = Grid;
+
+    fn wrap(shared: Shared<Grid>) -> Self {
+        PyGrid(shared)
+    }
+
+    fn shared(&self) -> &Shared<Grid> {
+        &self.0
+    }
+}
+
+impl Wrapped for Grid {
+    type Wrapper = PyGrid;
+}
+
+impl Tracked for Grid {}
+
+impl IntoPy<PyObject> for Grid {
+    fn into_py(self, py: Python) -> PyObject {
+        value_into_py(self, py)
+    }
+}
+
+impl FromPyObject<'_> for Grid {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        extract_value(ob)
+    }
 }
 
 impl Default for Grid {
@@ -39,6 +72,8 @@ impl Default for Grid {
             magnification: 1.0,
             angle: 0.0,
             x_reflection: false,
+            absolute_magnification: false,
+            absolute_angle: false,
         }
     }
 }
@@ -53,6 +88,8 @@ impl PartialEq for Grid {
             && self.magnification == other.magnification
             && self.angle == other.angle
             && self.x_reflection == other.x_reflection
+            && self.absolute_magnification == other.absolute_magnification
+            && self.absolute_angle == other.absolute_angle
     }
 }
 
@@ -60,8 +97,8 @@ impl std::fmt::Display for Grid {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "Grid at {:?} with {} columns and {} rows, spacing ({:?}, {:?}), magnification {:?}, angle {:?}, x_reflection {}",
-            self.origin, self.columns, self.rows, self.spacing_x, self.spacing_y, self.magnification, self.angle, self.x_reflection,
+            "Grid at {:?} with {} columns and {} rows, spacing ({:?}, {:?}), magnification {:?}, angle {:?}, x_reflection {}, absolute_magnification {}, absolute_angle {}",
+            self.origin, self.columns, self.rows, self.spacing_x, self.spacing_y, self.magnification, self.angle, self.x_reflection, self.absolute_magnification, self.absolute_angle,
         )
     }
 }
@@ -70,7 +107,7 @@ impl std::fmt::Debug for Grid {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "Grid({:?}, {}, {}, {:?}, {:?}, {:?}, {:?}, {})",
+            "Grid({:?}, {}, {}, {:?}, {:?}, {:?}, {:?}, {}, {}, {})",
             self.origin,
             self.columns,
             self.rows,
@@ -79,10 +116,76 @@ impl std::fmt::Debug for Grid {
             self.magnification,
             self.angle,
             self.x_reflection,
+            self.absolute_magnification,
+            self.absolute_angle,
         )
     }
 }
 
+impl Grid {
+    pub fn instance_origins(&self) -> Vec<Point> {
+        let mut origins = Vec::with_capacity((self.columns * self.rows) as usize);
+        for c in 0..self.columns {
+            for r in 0..self.rows {
+                let origin = self.origin + self.spacing_x * c as f64 + self.spacing_y * r as f64;
+                origins.push(
+                    origin
+                        .rotate(self.angle, self.origin)
+                        .round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS),
+                );
+            }
+        }
+        origins
+    }
+
+    /// Returns this grid as placed by one instance of `parent` at `origin`.
+    ///
+    /// Absolute magnification and angle are kept as they are instead of
+    /// being composed with the parent.
+    pub fn placed_in(&self, parent: &Grid, origin: Point) -> Grid {
+        let transform_vector = |vector: Point| {
+            let vector = if parent.x_reflection {
+                Point::new(vector.x, -vector.y)
+            } else {
+                vector
+            };
+            vector.rotate(parent.angle, Point::default()) * parent.magnification
+        };
+
+        let angle = if self.absolute_angle {
+            self.angle
+        } else if parent.x_reflection {
+            parent.angle - self.angle
+        } else {
+            parent.angle + self.angle
+        }
+        .rem_euclid(360.0);
+
+        let magnification = if self.absolute_magnification {
+            self.magnification
+        } else {
+            parent.magnification * self.magnification
+        };
+
+        let transform_spacing = |spacing: Point| {
+            transform_vector(spacing.rotate(self.angle, Point::default()))
+                .rotate(-angle, Point::default())
+                .round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS)
+        };
+
+        Grid {
+            origin: (origin + transform_vector(self.origin))
+                .round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS),
+            spacing_x: transform_spacing(self.spacing_x),
+            spacing_y: transform_spacing(self.spacing_y),
+            magnification,
+            angle,
+            x_reflection: self.x_reflection != parent.x_reflection,
+            ..self.clone()
+        }
+    }
+}
+
 impl Movable for Grid {
     fn move_to(&mut self, point: Point) -> &mut Self {
         self.origin = point;
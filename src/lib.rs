@@ -1,49 +1,80 @@
+// PyO3 0.22 converts PyErr into itself in the wrappers it generates for every method
+// returning PyResult. Attributes on the methods do not reach those wrappers, and PyO3
+// 0.23, which silences the lint itself, is not supported by pyo3-log 0.11.
+#![allow(clippy::useless_conversion)]
+
 use config::{get_epsilon, set_epsilon};
 use pyo3::prelude::*;
 
-mod utils;
+pub mod utils;
 
-mod cell;
-mod config;
-mod element;
-mod grid;
-mod library;
-mod path;
-mod point;
-mod polygon;
-mod reference;
-mod text;
-mod traits;
-mod validation;
+pub mod boolean;
+pub mod cell;
+pub mod config;
+pub mod drc;
+pub mod element;
+pub mod gds_box;
+pub mod grid;
+pub mod library;
+pub mod node;
+pub mod oasis;
+pub mod offset;
+pub mod path;
+pub mod point;
+pub mod polygon;
+pub mod reader;
+pub mod reference;
+pub mod shared;
+pub mod text;
+pub mod traits;
+pub mod validation;
 
-use cell::Cell;
-use grid::Grid;
-use library::Library;
-use path::{path_type::PathType, Path};
+use cell::PyCell;
+use drc::{DrcRule, DrcViolation};
+use gds_box::PyGdsBox;
+use grid::PyGrid;
+use library::{
+    diff::{CellDiff, LibraryDiff},
+    Library,
+};
+use node::PyNode;
+use path::{path_type::PathType, PyPath};
 use point::{Point, PointIterator};
-use polygon::Polygon;
-use reference::Reference;
-use text::{presentation::HorizontalPresentation, presentation::VerticalPresentation, Text};
+use polygon::PyPolygon;
+use reader::{GdsCellIterator, GdsElementIterator};
+use reference::PyReference;
+use text::{presentation::HorizontalPresentation, presentation::VerticalPresentation, PyText};
 
 #[pymodule]
 #[pyo3(name = "_gdsr")]
 fn gdsr(m: &Bound<'_, PyModule>) -> PyResult<()> {
     pyo3_log::init();
-    m.add_class::<Cell>()?;
-    m.add_class::<Polygon>()?;
-    m.add_class::<Path>()?;
-    m.add_class::<Reference>()?;
-    m.add_class::<Text>()?;
+    m.add_class::<PyCell>()?;
+    m.add_class::<PyPolygon>()?;
+    m.add_class::<PyPath>()?;
+    m.add_class::<PyReference>()?;
+    m.add_class::<PyText>()?;
+    m.add_class::<PyGdsBox>()?;
+    m.add_class::<PyNode>()?;
     m.add_class::<Point>()?;
     m.add_class::<PointIterator>()?;
     m.add_class::<Library>()?;
-    m.add_class::<Grid>()?;
+    m.add_class::<LibraryDiff>()?;
+    m.add_class::<CellDiff>()?;
+    m.add_class::<PyGrid>()?;
     m.add_class::<VerticalPresentation>()?;
     m.add_class::<HorizontalPresentation>()?;
     m.add_class::<PathType>()?;
+    m.add_class::<GdsCellIterator>()?;
+    m.add_class::<GdsElementIterator>()?;
+    m.add_class::<DrcRule>()?;
+    m.add_class::<DrcViolation>()?;
 
     let _ = m.add_function(wrap_pyfunction!(set_epsilon, m)?);
     let _ = m.add_function(wrap_pyfunction!(get_epsilon, m)?);
+    let _ = m.add_function(wrap_pyfunction!(boolean::boolean, m)?);
+    let _ = m.add_function(wrap_pyfunction!(offset::offset, m)?);
+    let _ = m.add_function(wrap_pyfunction!(drc::general::drc, m)?);
 
     Ok(())
 }
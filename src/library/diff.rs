@@ -0,0 +1,539 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use pyo3::prelude::*;
+
+use crate::{
+    cell::{xor::xor_by_layer, Cell},
+    config::FLOATING_POINT_INACCURACY_ROUND_DECIMALS,
+    element::Element,
+    grid::Grid,
+    point::Point,
+    reference::Instance,
+    shared::Shared,
+    utils::geometry::{round_to_decimals, signed_area},
+};
+
+use super::Library;
+
+/// Kind of element and the layer and data type it is on, references have no layer.
+pub type ChangeKey = (String, Option<(i32, i32)>);
+
+#[pyclass]
+pub struct CellDiff {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub other_name: String,
+    #[pyo3(get)]
+    pub added: Vec<Element>,
+    #[pyo3(get)]
+    pub removed: Vec<Element>,
+    #[pyo3(get)]
+    pub xor_areas: HashMap<(i32, i32), f64>,
+}
+
+#[pyclass]
+pub struct LibraryDiff {
+    #[pyo3(get)]
+    pub added_cells: Vec<String>,
+    #[pyo3(get)]
+    pub removed_cells: Vec<String>,
+    #[pyo3(get)]
+    pub renamed_cells: Vec<(String, String)>,
+    #[pyo3(get)]
+    pub cells: Vec<Py<CellDiff>>,
+}
+
+fn element_kind(element: &Element) -> ChangeKey {
+    match element {
+        Element::Polygon(polygon) => {
+            let polygon = polygon.read();
+            ("polygon".into(), Some((polygon.layer, polygon.data_type)))
+        }
+        Element::Path(path) => {
+            let path = path.read();
+            ("path".into(), Some((path.layer, path.data_type)))
+        }
+        Element::Text(text) => {
+            let text = text.read();
+            ("text".into(), Some((text.layer, text.text_type)))
+        }
+        Element::Box(gds_box) => {
+            let gds_box = gds_box.read();
+            ("box".into(), Some((gds_box.layer, gds_box.box_type)))
+        }
+        Element::Node(node) => {
+            let node = node.read();
+            ("node".into(), Some((node.layer, node.node_type)))
+        }
+        Element::Reference(_) => ("reference".into(), None),
+    }
+}
+
+impl CellDiff {
+    /// Number of added and removed elements by kind, layer and data type.
+    pub fn change_counts(&self) -> BTreeMap<ChangeKey, (usize, usize)> {
+        let mut counts: BTreeMap<ChangeKey, (usize, usize)> = BTreeMap::new();
+        for element in &self.added {
+            counts.entry(element_kind(element)).or_default().0 += 1;
+        }
+        for element in &self.removed {
+            counts.entry(element_kind(element)).or_default().1 += 1;
+        }
+        counts
+    }
+
+    fn report(&self) -> String {
+        let mut report = if self.name == self.other_name {
+            format!("Cell '{}':\n", self.name)
+        } else {
+            format!("Cell '{}' -> '{}':\n", self.name, self.other_name)
+        };
+        for ((kind, layer), (added, removed)) in self.change_counts() {
+            match layer {
+                Some((layer, data_type)) => {
+                    let _ = write!(report, "  {} {}/{}:", kind, layer, data_type);
+                }
+                None => {
+                    let _ = write!(report, "  {}:", kind);
+                }
+            }
+            if added > 0 {
+                let _ = write!(report, " +{}", added);
+            }
+            if removed > 0 {
+                let _ = write!(report, " -{}", removed);
+            }
+            report.push('\n');
+        }
+        let mut xor_areas: Vec<_> = self.xor_areas.iter().collect();
+        xor_areas.sort_by(|a, b| a.0.cmp(b.0));
+        for ((layer, data_type), xor_area) in xor_areas {
+            let _ = writeln!(report, "  XOR area {}/{}: {}", layer, data_type, xor_area);
+        }
+        report
+    }
+}
+
+#[pymethods]
+impl CellDiff {
+    #[getter]
+    fn changes(&self) -> BTreeMap<ChangeKey, (usize, usize)> {
+        self.change_counts()
+    }
+
+    fn __str__(&self) -> String {
+        self.report()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CellDiff({}, {}, +{}, -{})",
+            self.name,
+            self.other_name,
+            self.added.len(),
+            self.removed.len()
+        )
+    }
+}
+
+impl LibraryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_cells.is_empty()
+            && self.removed_cells.is_empty()
+            && self.renamed_cells.is_empty()
+            && self.cells.is_empty()
+    }
+
+    pub fn report(&self, py: Python) -> String {
+        if self.is_empty() {
+            return String::from("Libraries are identical\n");
+        }
+        let mut report = String::new();
+        if !self.added_cells.is_empty() {
+            let _ = writeln!(report, "Added cells: {}", self.added_cells.join(", "));
+        }
+        if !self.removed_cells.is_empty() {
+            let _ = writeln!(report, "Removed cells: {}", self.removed_cells.join(", "));
+        }
+        for (old_name, new_name) in &self.renamed_cells {
+            let _ = writeln!(report, "Renamed cell: {} -> {}", old_name, new_name);
+        }
+        for cell in &self.cells {
+            report.push_str(&cell.borrow(py).report());
+        }
+        report
+    }
+}
+
+#[pymethods]
+impl LibraryDiff {
+    fn __bool__(&self) -> bool {
+        !self.is_empty()
+    }
+
+    fn __str__(&self, py: Python) -> String {
+        self.report(py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "LibraryDiff(+{}, -{}, renamed {}, changed {})",
+            self.added_cells.len(),
+            self.removed_cells.len(),
+            self.renamed_cells.len(),
+            self.cells.len()
+        )
+    }
+}
+
+fn push_number(signature: &mut String, value: f64) {
+    let value = round_to_decimals(value, FLOATING_POINT_INACCURACY_ROUND_DECIMALS);
+    // Adding zero turns negative zero into zero
+    let _ = write!(signature, "{} ", value + 0.0);
+}
+
+fn push_points(signature: &mut String, points: &[Point]) {
+    for point in points {
+        push_number(signature, point.x);
+        push_number(signature, point.y);
+    }
+    signature.push('|');
+}
+
+fn push_properties(signature: &mut String, properties: &HashMap<i32, String>) {
+    let properties: BTreeMap<_, _> = properties.iter().collect();
+    let _ = write!(signature, "{:?}", properties);
+}
+
+/// Polygon outline independent of its start point and orientation.
+fn normalised_outline(points: &[Point]) -> Vec<Point> {
+    let mut points = points.to_vec();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if signed_area(&points) < 0.0 {
+        points.reverse();
+    }
+    let rounded: Vec<Point> = points
+        .iter()
+        .map(|point| point.round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS))
+        .collect();
+    let start = (0..rounded.len())
+        .min_by(|&a, &b| {
+            (rounded[a].x, rounded[a].y)
+                .partial_cmp(&(rounded[b].x, rounded[b].y))
+                .unwrap()
+        })
+        .unwrap_or(0);
+    rounded[start..]
+        .iter()
+        .chain(&rounded[..start])
+        .copied()
+        .collect()
+}
+
+fn push_grid(signature: &mut String, grid: &Grid) {
+    push_points(signature, &[grid.origin, grid.spacing_x, grid.spacing_y]);
+    let _ = write!(signature, "{} {} ", grid.columns, grid.rows);
+    push_number(signature, grid.magnification);
+    push_number(signature, grid.angle);
+    let _ = write!(
+        signature,
+        "{} {} {}",
+        grid.x_reflection, grid.absolute_magnification, grid.absolute_angle
+    );
+}
+
+/// Text identifying an element, equal for elements that are the same. Cells referenced
+/// by name are looked up in `renames` first.
+fn element_signature(element: &Element, renames: &HashMap<String, String>) -> String {
+    let mut signature = String::new();
+    match element {
+        Element::Polygon(polygon) => {
+            let polygon = polygon.read();
+            let _ = write!(
+                signature,
+                "polygon {} {} ",
+                polygon.layer, polygon.data_type
+            );
+            push_points(&mut signature, &normalised_outline(&polygon.points));
+            push_properties(&mut signature, &polygon.properties);
+        }
+        Element::Path(path) => {
+            let path = path.read();
+            let _ = write!(
+                signature,
+                "path {} {} {:?} ",
+                path.layer, path.data_type, path.path_type
+            );
+            push_points(&mut signature, &path.points);
+            push_number(&mut signature, path.width.unwrap_or_default());
+            push_number(&mut signature, path.begin_extension);
+            push_number(&mut signature, path.end_extension);
+            push_properties(&mut signature, &path.properties);
+        }
+        Element::Text(text) => {
+            let text = text.read();
+            let _ = write!(
+                signature,
+                "text {} {} {:?} {:?} {:?} {} {} {} ",
+                text.layer,
+                text.text_type,
+                text.text,
+                text.vertical_presentation,
+                text.horizontal_presentation,
+                text.x_reflection,
+                text.absolute_magnification,
+                text.absolute_angle
+            );
+            push_points(&mut signature, &[text.origin]);
+            push_number(&mut signature, text.magnification);
+            push_number(&mut signature, text.angle);
+            push_properties(&mut signature, &text.properties);
+        }
+        Element::Box(gds_box) => {
+            let gds_box = gds_box.read();
+            let _ = write!(signature, "box {} {} ", gds_box.layer, gds_box.box_type);
+            push_points(&mut signature, &gds_box.points);
+            push_properties(&mut signature, &gds_box.properties);
+        }
+        Element::Node(node) => {
+            let node = node.read();
+            let _ = write!(signature, "node {} {} ", node.layer, node.node_type);
+            push_points(&mut signature, &node.points);
+            push_properties(&mut signature, &node.properties);
+        }
+        Element::Reference(reference) => {
+            let reference = reference.read();
+            match &reference.instance {
+                Instance::Cell(cell) => {
+                    let name = cell.read().name.clone();
+                    let name = renames.get(&name).unwrap_or(&name);
+                    let _ = write!(signature, "reference cell {:?} ", name);
+                }
+                Instance::Element(element) => {
+                    let _ = write!(
+                        signature,
+                        "reference element {{{}}} ",
+                        element_signature(element, renames)
+                    );
+                }
+            }
+            push_grid(&mut signature, &reference.grid.read());
+            push_properties(&mut signature, &reference.properties);
+        }
+    }
+    signature
+}
+
+fn cell_elements(cell: &Cell) -> Vec<Element> {
+    let mut elements = cell.direct_elements(&[]);
+    elements.extend(
+        cell.references
+            .iter()
+            .map(|reference| Element::Reference(reference.clone())),
+    );
+    elements
+}
+
+fn content_signature(cell: &Cell, renames: &HashMap<String, String>) -> Vec<String> {
+    let mut signatures: Vec<String> = cell_elements(cell)
+        .iter()
+        .map(|element| element_signature(element, renames))
+        .collect();
+    signatures.sort();
+    signatures
+}
+
+/// Elements only in `cell` and elements only in `other`, matched by signature.
+fn element_changes(
+    cell: &Cell,
+    other: &Cell,
+    renames: &HashMap<String, String>,
+) -> (Vec<Element>, Vec<Element>) {
+    let mut unmatched: HashMap<String, Vec<Element>> = HashMap::new();
+    for element in cell_elements(cell) {
+        unmatched
+            .entry(element_signature(&element, renames))
+            .or_default()
+            .push(element);
+    }
+
+    let no_renames = HashMap::new();
+    let mut added = Vec::new();
+    for element in cell_elements(other) {
+        let signature = element_signature(&element, &no_renames);
+        if unmatched.get_mut(&signature).and_then(Vec::pop).is_none() {
+            added.push(element);
+        }
+    }
+
+    let mut removed: Vec<Element> = unmatched.into_values().flatten().collect();
+    removed.sort_by_cached_key(|element| element_signature(element, renames));
+    (added, removed)
+}
+
+fn xor_areas(
+    cell: &Shared<Cell>,
+    other: &Shared<Cell>,
+    grid: f64,
+) -> PyResult<HashMap<(i32, i32), f64>> {
+    let polygons = cell.read().layer_polygons(Vec::new(), grid)?;
+    let other_polygons = other.read().layer_polygons(Vec::new(), grid)?;
+    Ok(xor_by_layer(polygons, other_polygons, 0.0, grid)?
+        .into_iter()
+        .map(|(layer, (_, xor_area))| (layer, xor_area))
+        .collect())
+}
+
+/// Names of the cells of `library` that reference any of `names`, directly or not.
+fn ancestors(library: &Library, names: &HashSet<String>) -> HashSet<String> {
+    let mut parents: HashMap<String, Vec<String>> = HashMap::new();
+    for (name, cell) in &library.cells {
+        for reference in &cell.read().references {
+            if let Instance::Cell(child) = &reference.read().instance {
+                parents
+                    .entry(child.read().name.clone())
+                    .or_default()
+                    .push(name.clone());
+            }
+        }
+    }
+
+    let mut found = HashSet::new();
+    let mut stack: Vec<String> = names.iter().cloned().collect();
+    while let Some(name) = stack.pop() {
+        for parent in parents.get(&name).into_iter().flatten() {
+            if found.insert(parent.clone()) {
+                stack.push(parent.clone());
+            }
+        }
+    }
+    found
+}
+
+pub fn diff_libraries(
+    library: &Library,
+    other: &Library,
+    xor: bool,
+    py: Python,
+) -> PyResult<LibraryDiff> {
+    let mut removed: BTreeSet<String> = library
+        .cells
+        .keys()
+        .filter(|name| !other.cells.contains_key(*name))
+        .cloned()
+        .collect();
+    let mut added: BTreeSet<String> = other
+        .cells
+        .keys()
+        .filter(|name| !library.cells.contains_key(*name))
+        .cloned()
+        .collect();
+
+    // A renamed child changes the references of its parents, so renames are found
+    // until no more cells match
+    let mut renames: HashMap<String, String> = HashMap::new();
+    loop {
+        let mut added_by_content: HashMap<Vec<String>, Vec<String>> = HashMap::new();
+        for name in &added {
+            added_by_content
+                .entry(content_signature(
+                    &other.cells[name].read(),
+                    &HashMap::new(),
+                ))
+                .or_default()
+                .push(name.clone());
+        }
+
+        let mut found = Vec::new();
+        for name in &removed {
+            let signature = content_signature(&library.cells[name].read(), &renames);
+            if let Some(new_name) = added_by_content.get_mut(&signature).and_then(|names| {
+                names.sort();
+                (!names.is_empty()).then(|| names.remove(0))
+            }) {
+                found.push((name.clone(), new_name));
+            }
+        }
+        if found.is_empty() {
+            break;
+        }
+        for (old_name, new_name) in found {
+            removed.remove(&old_name);
+            added.remove(&new_name);
+            renames.insert(old_name, new_name);
+        }
+    }
+
+    let mut pairs: Vec<(String, String)> = library
+        .cells
+        .keys()
+        .filter(|name| other.cells.contains_key(*name))
+        .map(|name| (name.clone(), name.clone()))
+        .collect();
+    pairs.sort();
+
+    let mut cells = Vec::new();
+    let mut changed = HashSet::new();
+    let mut changes = HashMap::new();
+    for (name, other_name) in &pairs {
+        let (added_elements, removed_elements) = element_changes(
+            &library.cells[name].read(),
+            &other.cells[other_name].read(),
+            &renames,
+        );
+        if !added_elements.is_empty() || !removed_elements.is_empty() {
+            changed.insert(name.clone());
+            changes.insert(name.clone(), (added_elements, removed_elements));
+        }
+    }
+
+    // Geometry is compared on the finer database unit of the two libraries
+    let grid = (library.precision / library.units).min(other.precision / other.units);
+    let mut xor_candidates = changed.clone();
+    if xor {
+        let changed_or_gone: HashSet<String> = changed.iter().chain(&removed).cloned().collect();
+        xor_candidates.extend(ancestors(library, &changed_or_gone));
+    }
+
+    for (name, other_name) in pairs {
+        if !xor_candidates.contains(&name) {
+            continue;
+        }
+        let (added_elements, removed_elements) = changes.remove(&name).unwrap_or_default();
+        let xor_areas = if xor {
+            xor_areas(&library.cells[&name], &other.cells[&other_name], grid)?
+        } else {
+            HashMap::new()
+        };
+        if added_elements.is_empty() && removed_elements.is_empty() && xor_areas.is_empty() {
+            continue;
+        }
+        cells.push(
+            Py::new(
+                py,
+                CellDiff {
+                    name,
+                    other_name,
+                    added: added_elements,
+                    removed: removed_elements,
+                    xor_areas,
+                },
+            )
+            .unwrap(),
+        );
+    }
+
+    let mut renamed_cells: Vec<(String, String)> = renames.into_iter().collect();
+    renamed_cells.sort();
+
+    Ok(LibraryDiff {
+        added_cells: added.into_iter().collect(),
+        removed_cells: removed.into_iter().collect(),
+        renamed_cells,
+        cells,
+    })
+}
@@ -2,91 +2,114 @@ use std::collections::HashMap;
 
 use pyo3::{exceptions::PyValueError, prelude::*};
 
-use crate::cell::Cell;
+use crate::{cell::Cell, shared::Shared, validation::input::check_units_valid};
 
-use super::Library;
+use super::{
+    diff::{diff_libraries, LibraryDiff},
+    utils::UnitRescaler,
+    Library,
+};
 
 #[pymethods]
 impl Library {
     #[new]
-    #[pyo3(signature = (name=String::from("Library")))]
-    pub fn new(name: String) -> Self {
-        Library {
+    #[pyo3(signature = (name=String::from("Library"), units=1e-6, precision=1e-10))]
+    pub fn new(name: String, units: f64, precision: f64) -> PyResult<Self> {
+        check_units_valid(units, precision)?;
+        Ok(Library {
             name,
-            cells: HashMap::new(),
+            units,
+            precision,
+            ..Library::default()
+        })
+    }
+
+    #[pyo3(signature = (units, precision=None))]
+    pub fn set_units(&mut self, units: f64, precision: Option<f64>) -> PyResult<()> {
+        let precision = precision.unwrap_or(self.precision);
+        check_units_valid(units, precision)?;
+
+        let mut rescaler = UnitRescaler::new(self.units / units, units / precision);
+        for cell in self.cells.values() {
+            rescaler.rescale_cell(cell);
         }
+
+        self.units = units;
+        self.precision = precision;
+
+        Ok(())
     }
 
     #[pyo3(signature = (*cells, replace_pre_existing=false))]
-    pub fn add(&mut self, cells: Vec<Py<Cell>>, replace_pre_existing: bool) -> PyResult<()> {
-        Python::with_gil(|py| {
-            for cell in cells {
-                if !replace_pre_existing && self.cells.contains_key(&cell.borrow(py).name) {
-                    return Err(PyValueError::new_err(format!(
-                        "Cell with name {} already exists in library",
-                        cell.borrow(py).name
-                    )));
-                }
-                self.cells
-                    .insert(cell.borrow(py).name.clone(), cell.clone_ref(py));
+    pub fn add(&mut self, cells: Vec<Shared<Cell>>, replace_pre_existing: bool) -> PyResult<()> {
+        for cell in cells {
+            if !replace_pre_existing && self.cells.contains_key(&cell.read().name) {
+                return Err(PyValueError::new_err(format!(
+                    "Cell with name {} already exists in library",
+                    cell.read().name
+                )));
             }
-            Ok(())
-        })
+            self.cells.insert(cell.read().name.clone(), cell.clone());
+        }
+        Ok(())
     }
 
     #[pyo3(signature = (*cells))]
-    pub fn remove(&mut self, cells: Vec<Py<Cell>>, py: Python) -> PyResult<()> {
+    pub fn remove(&mut self, cells: Vec<Shared<Cell>>) -> PyResult<()> {
         for cell in cells {
-            self.cells.remove(&cell.borrow(py).name);
+            self.cells.remove(&cell.read().name);
         }
         Ok(())
     }
 
-    pub fn contains(&self, cell: Py<Cell>, py: Python) -> bool {
-        let cell = cell.borrow(py);
-        for c in self.cells.values() {
-            if c.borrow(py).eq(&cell) {
-                return true;
-            }
-        }
-        false
+    pub fn contains(&self, cell: Shared<Cell>) -> bool {
+        self.cells.values().any(|c| *c == cell)
     }
 
     #[pyo3(signature = (deep=false))]
-    pub fn copy(&self, deep: bool, py: Python) -> PyResult<Self> {
-        let mut cells: HashMap<String, Py<Cell>> = HashMap::new();
+    pub fn copy(&self, deep: bool) -> PyResult<Self> {
+        let mut cells: HashMap<String, Shared<Cell>> = HashMap::new();
         for (key, value) in &self.cells {
             if deep {
-                cells.insert(key.clone(), Py::new(py, value.borrow(py).clone())?);
+                cells.insert(key.clone(), value.copy());
             } else {
-                cells.insert(key.clone(), value.clone_ref(py));
+                cells.insert(key.clone(), value.clone());
             }
         }
         Ok(Library {
             name: self.name.clone(),
             cells,
+            units: self.units,
+            precision: self.precision,
+            modification_date: self.modification_date,
+            access_date: self.access_date,
         })
     }
 
-    fn __add__(mut slf: PyRefMut<'_, Self>, cell: Py<Cell>) -> PyRefMut<'_, Self> {
+    #[pyo3(signature = (other, xor=false))]
+    pub fn diff(&self, other: &Self, xor: bool, py: Python) -> PyResult<LibraryDiff> {
+        diff_libraries(self, other, xor, py)
+    }
+
+    fn __add__(mut slf: PyRefMut<'_, Self>, cell: Shared<Cell>) -> PyRefMut<'_, Self> {
         let _ = slf.add([cell].to_vec(), true);
         slf
     }
 
-    pub fn __contains__(&self, cell: Py<Cell>, py: Python) -> bool {
-        self.contains(cell, py)
+    pub fn __contains__(&self, cell: Shared<Cell>) -> bool {
+        self.contains(cell)
     }
 
-    pub fn __eq__(&self, other: &Self, py: Python) -> bool {
-        if (self.name != other.name) || (self.cells.len() != other.cells.len()) {
+    pub fn __eq__(&self, other: &Self) -> bool {
+        if (self.name != other.name)
+            || (self.units != other.units)
+            || (self.precision != other.precision)
+            || (self.cells.len() != other.cells.len())
+        {
             return false;
         }
         for (key, value) in &self.cells {
-            if !other.cells.contains_key(key)
-                || !value
-                    .borrow(py)
-                    .eq(&other.cells.get(key).unwrap().borrow(py))
-            {
+            if other.cells.get(key) != Some(value) {
                 return false;
             }
         }
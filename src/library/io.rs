@@ -1,39 +1,170 @@
-use pyo3::prelude::*;
+use pyo3::{exceptions::PyValueError, prelude::*};
 
+use crate::config::{max_points_mode::MaxPointsMode, timestamp::Timestamp};
+use crate::drc::{
+    lyrdb::{read_lyrdb, write_lyrdb},
+    rdb::{read_rdb, write_rdb},
+    DrcViolation,
+};
+use crate::oasis::{from_oasis, write_oasis};
+use crate::reader::GdsFilter;
+use crate::reference::Instance;
 use crate::utils::{
-    io::{create_temp_file, from_gds, write_gds},
+    io::{create_temp_file, create_temp_file_with_suffix, from_gds, write_gds},
     transformations::{py_any_path_to_string, py_any_path_to_string_or_temp_name},
 };
 
 use super::Library;
 
+impl Library {
+    /// The only cell not referenced by another cell of the library, if there is one.
+    fn top_cell_name(&self) -> Option<String> {
+        let mut referenced = Vec::new();
+        for cell in self.cells.values() {
+            for reference in &cell.read().references {
+                if let Instance::Cell(instance) = &reference.read().instance {
+                    referenced.push(instance.read().name.clone());
+                }
+            }
+        }
+        let mut top_cells = self.cells.keys().filter(|name| !referenced.contains(name));
+        match (top_cells.next(), top_cells.next()) {
+            (Some(name), None) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    fn check_violation_cells(&self, violations: &[DrcViolation]) -> PyResult<()> {
+        for violation in violations {
+            if let Some(cell) = &violation.cell {
+                if !self.cells.contains_key(cell) {
+                    return Err(PyValueError::new_err(format!(
+                        "Cell '{}' is not in the library",
+                        cell
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Top cell of a results database, which violations without a cell are tied to.
+    fn results_top_cell(&self, violations: &[DrcViolation]) -> PyResult<String> {
+        self.check_violation_cells(violations)?;
+        if let Some(top_cell) = self.top_cell_name() {
+            return Ok(top_cell);
+        }
+        if violations.iter().any(|violation| violation.cell.is_none()) {
+            return Err(PyValueError::new_err(
+                "DRC violations without a cell need a library with a single top cell",
+            ));
+        }
+        Ok(violations
+            .first()
+            .and_then(|violation| violation.cell.clone())
+            .unwrap_or_else(|| self.name.clone()))
+    }
+}
+
 #[pymethods]
 impl Library {
-    #[pyo3(signature=(file_name=None, units=1e-6, precision=1e-10))]
+    #[pyo3(signature=(file_name=None, units=None, precision=None, max_points_mode="fracture", timestamp=None))]
     pub fn to_gds(
         &self,
         #[pyo3(from_py_with = "py_any_path_to_string_or_temp_name")] file_name: Option<String>,
-        units: f64,
-        precision: f64,
-        py: Python,
+        units: Option<f64>,
+        precision: Option<f64>,
+        max_points_mode: &str,
+        timestamp: Option<&Bound<'_, PyAny>>,
     ) -> PyResult<String> {
         write_gds(
             file_name.unwrap_or(create_temp_file()?),
             &self.name,
-            units,
-            precision,
-            self.cells
-                .values()
-                .map(|cell| cell.borrow(py).clone())
-                .collect(),
+            units.unwrap_or(self.units),
+            precision.unwrap_or(self.precision),
+            MaxPointsMode::new(max_points_mode)?,
+            Timestamp::new(timestamp)?,
+            (self.modification_date, self.access_date),
+            self.sorted_cells(),
         )
     }
 
     #[staticmethod]
+    #[pyo3(signature=(file_name, cells=None, layers=None))]
     pub fn from_gds(
         #[pyo3(from_py_with = "py_any_path_to_string")] file_name: String,
-        py: Python,
+        cells: Option<Vec<String>>,
+        layers: Option<Vec<(i32, i32)>>,
+    ) -> PyResult<Library> {
+        from_gds(file_name, GdsFilter::new(cells, layers))
+    }
+
+    #[pyo3(signature=(file_name=None, units=None, precision=None))]
+    pub fn to_oasis(
+        &self,
+        #[pyo3(from_py_with = "py_any_path_to_string_or_temp_name")] file_name: Option<String>,
+        units: Option<f64>,
+        precision: Option<f64>,
+    ) -> PyResult<String> {
+        write_oasis(
+            file_name.unwrap_or(create_temp_file_with_suffix(".oas")?),
+            units.unwrap_or(self.units),
+            precision.unwrap_or(self.precision),
+            self.sorted_cells(),
+        )
+    }
+
+    #[staticmethod]
+    pub fn from_oasis(
+        #[pyo3(from_py_with = "py_any_path_to_string")] file_name: String,
     ) -> PyResult<Library> {
-        from_gds(py, file_name)
+        from_oasis(file_name)
+    }
+
+    #[pyo3(signature=(violations, file_name=None))]
+    pub fn export_lyrdb(
+        &self,
+        violations: Vec<DrcViolation>,
+        #[pyo3(from_py_with = "py_any_path_to_string_or_temp_name")] file_name: Option<String>,
+    ) -> PyResult<String> {
+        let top_cell = self.results_top_cell(&violations)?;
+        write_lyrdb(
+            file_name.unwrap_or(create_temp_file_with_suffix(".lyrdb")?),
+            &top_cell,
+            &violations,
+        )
+    }
+
+    pub fn import_lyrdb(
+        &self,
+        #[pyo3(from_py_with = "py_any_path_to_string")] file_name: String,
+    ) -> PyResult<Vec<DrcViolation>> {
+        let violations = read_lyrdb(file_name)?;
+        self.check_violation_cells(&violations)?;
+        Ok(violations)
+    }
+
+    #[pyo3(signature=(violations, file_name=None))]
+    pub fn export_rdb(
+        &self,
+        violations: Vec<DrcViolation>,
+        #[pyo3(from_py_with = "py_any_path_to_string_or_temp_name")] file_name: Option<String>,
+    ) -> PyResult<String> {
+        let top_cell = self.results_top_cell(&violations)?;
+        write_rdb(
+            file_name.unwrap_or(create_temp_file_with_suffix(".rdb")?),
+            &top_cell,
+            self.units / self.precision,
+            &violations,
+        )
+    }
+
+    pub fn import_rdb(
+        &self,
+        #[pyo3(from_py_with = "py_any_path_to_string")] file_name: String,
+    ) -> PyResult<Vec<DrcViolation>> {
+        let violations = read_rdb(file_name)?;
+        self.check_violation_cells(&violations)?;
+        Ok(violations)
     }
 }
@@ -1,19 +1,43 @@
 use std::collections::HashMap;
 
+use chrono::NaiveDateTime;
 use pyo3::prelude::*;
 
 use crate::cell::Cell;
+use crate::shared::Shared;
 
+pub mod diff;
 mod general;
 mod io;
+mod utils;
 
 #[pyclass]
-#[derive(Default)]
 pub struct Library {
     #[pyo3(get, set)]
     pub name: String,
     #[pyo3(get)]
-    pub cells: HashMap<String, Py<Cell>>,
+    pub cells: HashMap<String, Shared<Cell>>,
+    #[pyo3(get)]
+    pub units: f64,
+    #[pyo3(get)]
+    pub precision: f64,
+    #[pyo3(get, set)]
+    pub modification_date: Option<NaiveDateTime>,
+    #[pyo3(get, set)]
+    pub access_date: Option<NaiveDateTime>,
+}
+
+impl Default for Library {
+    fn default() -> Self {
+        Library {
+            name: String::from("Library"),
+            cells: HashMap::new(),
+            units: 1e-6,
+            precision: 1e-10,
+            modification_date: None,
+            access_date: None,
+        }
+    }
 }
 
 impl std::fmt::Display for Library {
@@ -27,3 +51,15 @@ impl std::fmt::Debug for Library {
         write!(f, "Library({})", self.name)
     }
 }
+
+impl Library {
+    /// Library cells ordered by name so written files do not depend on hash order
+    pub fn sorted_cells(&self) -> Vec<Shared<Cell>> {
+        let mut names: Vec<&String> = self.cells.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| self.cells[name].clone())
+            .collect()
+    }
+}
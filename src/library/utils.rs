@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+
+use crate::{
+    cell::Cell,
+    element::Element,
+    point::Point,
+    reference::{Instance, Reference},
+    shared::Shared,
+};
+
+/// Rescales geometry for a change of library units.
+///
+/// Every cell, element and grid is visited once, even when it is shared between
+/// several cells or references, so nothing is scaled twice. Magnifications are left
+/// untouched because the referenced geometry is rescaled as well.
+pub struct UnitRescaler {
+    factor: f64,
+    rounding_digits: u32,
+    visited: HashSet<usize>,
+}
+
+impl UnitRescaler {
+    pub fn new(factor: f64, database_units_per_unit: f64) -> Self {
+        UnitRescaler {
+            factor,
+            rounding_digits: database_units_per_unit.log10().ceil().max(0.0) as u32,
+            visited: HashSet::new(),
+        }
+    }
+
+    fn first_visit<T>(&mut self, object: &Shared<T>) -> bool {
+        self.visited.insert(object.id())
+    }
+
+    fn rescale_point(&self, point: Point) -> Point {
+        (point * self.factor).round(self.rounding_digits)
+    }
+
+    fn rescale_length(&self, length: f64) -> f64 {
+        self.rescale_point(Point::new(length, 0.0)).x
+    }
+
+    pub fn rescale_cell(&mut self, cell: &Shared<Cell>) {
+        if !self.first_visit(cell) {
+            return;
+        }
+
+        let cell = cell.read();
+
+        for polygon in &cell.polygons {
+            self.rescale_element(&Element::Polygon(polygon.clone()));
+        }
+
+        for path in &cell.paths {
+            self.rescale_element(&Element::Path(path.clone()));
+        }
+
+        for text in &cell.texts {
+            self.rescale_element(&Element::Text(text.clone()));
+        }
+
+        for node in &cell.nodes {
+            self.rescale_element(&Element::Node(node.clone()));
+        }
+
+        for gds_box in &cell.boxes {
+            self.rescale_element(&Element::Box(gds_box.clone()));
+        }
+
+        for reference in &cell.references {
+            self.rescale_reference(reference);
+        }
+    }
+
+    fn rescale_reference(&mut self, reference: &Shared<Reference>) {
+        if !self.first_visit(reference) {
+            return;
+        }
+
+        let reference = reference.read();
+
+        if self.first_visit(&reference.grid) {
+            let mut grid = reference.grid.write();
+            grid.origin = self.rescale_point(grid.origin);
+            grid.spacing_x = self.rescale_point(grid.spacing_x);
+            grid.spacing_y = self.rescale_point(grid.spacing_y);
+        }
+
+        match &reference.instance {
+            Instance::Cell(cell) => self.rescale_cell(cell),
+            Instance::Element(element) => self.rescale_element(element),
+        }
+    }
+
+    fn rescale_element(&mut self, element: &Element) {
+        match element {
+            Element::Polygon(polygon) => {
+                if self.first_visit(polygon) {
+                    let mut polygon = polygon.write();
+                    polygon.points = polygon
+                        .points
+                        .iter()
+                        .map(|&point| self.rescale_point(point))
+                        .collect();
+                }
+            }
+            Element::Path(path) => {
+                if self.first_visit(path) {
+                    let mut path = path.write();
+                    path.points = path
+                        .points
+                        .iter()
+                        .map(|&point| self.rescale_point(point))
+                        .collect();
+                    path.width = path.width.map(|width| self.rescale_length(width));
+                    path.begin_extension = self.rescale_length(path.begin_extension);
+                    path.end_extension = self.rescale_length(path.end_extension);
+                }
+            }
+            Element::Text(text) => {
+                if self.first_visit(text) {
+                    let mut text = text.write();
+                    text.origin = self.rescale_point(text.origin);
+                }
+            }
+            Element::Node(node) => {
+                if self.first_visit(node) {
+                    let mut node = node.write();
+                    node.points = node
+                        .points
+                        .iter()
+                        .map(|&point| self.rescale_point(point))
+                        .collect();
+                }
+            }
+            Element::Box(gds_box) => {
+                if self.first_visit(gds_box) {
+                    let mut gds_box = gds_box.write();
+                    gds_box.points = gds_box
+                        .points
+                        .iter()
+                        .map(|&point| self.rescale_point(point))
+                        .collect();
+                }
+            }
+            Element::Reference(reference) => self.rescale_reference(reference),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use pyo3::prelude::*;
+use std::fs::File;
+
+use crate::{
+    config::{
+        gds_file_types::{combine_record_and_data_type, GDSDataType, GDSRecord},
+        max_points_mode::MaxPointsMode,
+    },
+    traits::ToGds,
+    utils::io::{
+        write_element_tail_to_file, write_points_to_file, write_properties_to_file,
+        write_u16_array_to_file,
+    },
+};
+
+use super::Node;
+
+impl ToGds for Node {
+    fn _to_gds(
+        &self,
+        mut file: File,
+        scale: f64,
+        _max_points_mode: MaxPointsMode,
+    ) -> PyResult<File> {
+        let mut node_head = [
+            4,
+            combine_record_and_data_type(GDSRecord::Node, GDSDataType::NoData),
+            6,
+            combine_record_and_data_type(GDSRecord::Layer, GDSDataType::TwoByteSignedInteger),
+            self.layer as u16,
+            6,
+            combine_record_and_data_type(GDSRecord::NodeType, GDSDataType::TwoByteSignedInteger),
+            self.node_type as u16,
+        ];
+
+        file = write_u16_array_to_file(file, &mut node_head)?;
+
+        file = write_points_to_file(file, &self.points, scale)?;
+
+        file = write_properties_to_file(file, &self.properties)?;
+
+        file = write_element_tail_to_file(file)?;
+
+        Ok(file)
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+
+use crate::{
+    point::Point,
+    shared::{
+        revision::Tracked,
+        wrapper::{extract_value, value_into_py, Wrapped, Wrapper},
+        Shared,
+    },
+    traits::{Dimensions, LayerDataTypeMatches, Movable, Reflect, Rotatable, Scalable},
+    utils::geometry::bounding_box,
+};
+
+mod general;
+mod io;
+
+#[derive(Clone, Default)]
+pub struct Node {
+    pub points: Vec<Point>,
+    pub layer: i32,
+    pub node_type: i32,
+    pub properties: HashMap<i32, String>,
+}
+
+#[pyclass(name = "Node", eq, frozen, weakref)]
+#[derive(PartialEq)]
+pub struct PyNode(pub Shared<Node>);
+
+impl Wrapper for PyNode {
+    type Inner = Node;
+
+    fn wrap(shared: Shared<Node>) -> Self {
+        PyNode(shared)
+    }
+
+    fn shared(&self) -> &Shared<Node> {
+        &self.0
+    }
+}
+
+impl Wrapped for Node {
+    type Wrapper = PyNode;
+}
+
+impl Tracked for Node {}
+
+impl IntoPy<PyObject> for Node {
+    fn into_py(self, py: Python) -> PyObject {
+        value_into_py(self, py)
+    }
+}
+
+impl FromPyObject<'_> for Node {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        extract_value(ob)
+    }
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        self.points.len() == other.points.len()
+            && self
+                .points
+                .iter()
+                .zip(other.points.iter())
+                .all(|(self_point, other_point)| self_point.epsilon_is_close(*other_point))
+            && self.layer == other.layer
+            && self.node_type == other.node_type
+            && self.properties == other.properties
+    }
+}
+
+impl std::fmt::Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Node with {} point(s) on layer {}, node type {}",
+            self.points.len(),
+            self.layer,
+            self.node_type
+        )
+    }
+}
+
+impl std::fmt::Debug for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Node({:?}, {}, {})",
+            self.points, self.layer, self.node_type
+        )
+    }
+}
+
+impl Movable for Node {
+    fn move_by(&mut self, delta: Point) -> &mut Self {
+        for point in &mut self.points {
+            *point += delta;
+        }
+        self
+    }
+
+    fn move_to(&mut self, target: Point) -> &mut Self {
+        let delta = target - self.points[0];
+        self.move_by(delta)
+    }
+}
+
+impl Rotatable for Node {
+    fn rotate(&mut self, angle: f64, centre: Point) -> &mut Self {
+        for point in &mut self.points {
+            *point = point.rotate(angle, centre);
+        }
+        self
+    }
+}
+
+impl Scalable for Node {
+    fn scale(&mut self, factor: f64, centre: Point) -> &mut Self {
+        for point in &mut self.points {
+            *point = point.scale(factor, centre);
+        }
+        self
+    }
+}
+
+impl Dimensions for Node {
+    fn bounding_box(&self) -> (Point, Point) {
+        bounding_box(&self.points)
+    }
+}
+
+impl Reflect for Node {
+    fn reflect(&mut self, angle: f64, centre: Point) -> &mut Self {
+        for point in &mut self.points {
+            *point = point.reflect(angle, centre);
+        }
+        self
+    }
+}
+
+impl LayerDataTypeMatches for Node {
+    fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
+        layer_data_types.contains(&(self.layer, self.node_type)) || layer_data_types.is_empty()
+    }
+}
@@ -0,0 +1,98 @@
+mod reader;
+mod writer;
+
+pub use reader::from_oasis;
+pub use writer::write_oasis;
+
+pub const MAGIC: &[u8] = b"%SEMI-OASIS\r\n";
+pub const VERSION: &str = "1.0";
+pub const GDS_PROPERTY_NAME: &str = "S_GDS_PROPERTY";
+pub const GDS_NODE_NAME: &str = "S_GDS_NODE";
+pub const END_RECORD_LENGTH: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OasisRecord {
+    Pad = 0,
+    Start = 1,
+    End = 2,
+    CellName = 3,
+    CellNameExplicit = 4,
+    TextString = 5,
+    TextStringExplicit = 6,
+    PropName = 7,
+    PropNameExplicit = 8,
+    PropString = 9,
+    PropStringExplicit = 10,
+    LayerName = 11,
+    TextLayerName = 12,
+    CellReference = 13,
+    Cell = 14,
+    XYAbsolute = 15,
+    XYRelative = 16,
+    Placement = 17,
+    PlacementTransform = 18,
+    Text = 19,
+    Rectangle = 20,
+    Polygon = 21,
+    Path = 22,
+    Trapezoid = 23,
+    TrapezoidA = 24,
+    TrapezoidB = 25,
+    CTrapezoid = 26,
+    Circle = 27,
+    Property = 28,
+    PropertyRepeat = 29,
+    XName = 30,
+    XNameExplicit = 31,
+    XElement = 32,
+    XGeometry = 33,
+    CBlock = 34,
+}
+
+impl TryFrom<u64> for OasisRecord {
+    type Error = u64;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        use OasisRecord::*;
+
+        const RECORDS: [OasisRecord; 35] = [
+            Pad,
+            Start,
+            End,
+            CellName,
+            CellNameExplicit,
+            TextString,
+            TextStringExplicit,
+            PropName,
+            PropNameExplicit,
+            PropString,
+            PropStringExplicit,
+            LayerName,
+            TextLayerName,
+            CellReference,
+            Cell,
+            XYAbsolute,
+            XYRelative,
+            Placement,
+            PlacementTransform,
+            Text,
+            Rectangle,
+            Polygon,
+            Path,
+            Trapezoid,
+            TrapezoidA,
+            TrapezoidB,
+            CTrapezoid,
+            Circle,
+            Property,
+            PropertyRepeat,
+            XName,
+            XNameExplicit,
+            XElement,
+            XGeometry,
+            CBlock,
+        ];
+
+        RECORDS.get(value as usize).copied().ok_or(value)
+    }
+}
@@ -0,0 +1,1368 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fs;
+use std::ops::Range;
+
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    cell::Cell,
+    element::Element,
+    grid::Grid,
+    library::Library,
+    node::Node,
+    path::{path_type::PathType, Path},
+    point::Point,
+    polygon::{utils::get_correct_polygon_points_format, Polygon},
+    reference::{Instance, Reference},
+    shared::Shared,
+    text::Text,
+    utils::geometry::round_to_significant_digits,
+};
+
+use super::{OasisRecord, GDS_NODE_NAME, GDS_PROPERTY_NAME, MAGIC, VERSION};
+
+const CIRCLE_SIDES: usize = 64;
+
+fn invalid_oasis(message: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(format!("Invalid OASIS file: {}", message))
+}
+
+fn modal<T: Clone>(value: &Option<T>, name: &str) -> PyResult<T> {
+    value
+        .clone()
+        .ok_or_else(|| invalid_oasis(format!("modal variable {} is undefined", name)))
+}
+
+#[derive(Clone, Debug)]
+enum NameReference {
+    Name(String),
+    Number(u64),
+}
+
+#[derive(Clone, Debug)]
+enum PropertyValue {
+    Real(f64),
+    Unsigned(u64),
+    Signed(i64),
+    String(String),
+    Reference(u64),
+}
+
+#[derive(Clone, Debug)]
+enum Repetition {
+    Regular {
+        columns: u64,
+        rows: u64,
+        column_step: (i64, i64),
+        row_step: (i64, i64),
+    },
+    Irregular(Vec<(i64, i64)>),
+}
+
+impl Repetition {
+    fn offsets(&self) -> Vec<(i64, i64)> {
+        match self {
+            Repetition::Regular {
+                columns,
+                rows,
+                column_step,
+                row_step,
+            } => {
+                let mut offsets = Vec::with_capacity((columns * rows) as usize);
+                for column in 0..*columns as i64 {
+                    for row in 0..*rows as i64 {
+                        offsets.push((
+                            column * column_step.0 + row * row_step.0,
+                            column * column_step.1 + row * row_step.1,
+                        ));
+                    }
+                }
+                offsets
+            }
+            Repetition::Irregular(offsets) => offsets.clone(),
+        }
+    }
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        ByteReader { data, position: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    fn read_bytes(&mut self, length: usize) -> PyResult<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| invalid_oasis("unexpected end of data"))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_byte(&mut self) -> PyResult<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_unsigned(&mut self) -> PyResult<u64> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_byte()?;
+            if shift > 63 {
+                return Err(invalid_oasis("unsigned integer is too large"));
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_signed(&mut self) -> PyResult<i64> {
+        let value = self.read_unsigned()?;
+        let magnitude = (value >> 1) as i64;
+        Ok(if value & 1 == 1 {
+            -magnitude
+        } else {
+            magnitude
+        })
+    }
+
+    fn read_real_with_type(&mut self, real_type: u64) -> PyResult<f64> {
+        Ok(match real_type {
+            0 => self.read_unsigned()? as f64,
+            1 => -(self.read_unsigned()? as f64),
+            2 => 1.0 / self.read_unsigned()? as f64,
+            3 => -1.0 / self.read_unsigned()? as f64,
+            4 => self.read_unsigned()? as f64 / self.read_unsigned()? as f64,
+            5 => -(self.read_unsigned()? as f64 / self.read_unsigned()? as f64),
+            6 => f32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()) as f64,
+            7 => f64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()),
+            _ => return Err(invalid_oasis(format!("unknown real type {}", real_type))),
+        })
+    }
+
+    fn read_real(&mut self) -> PyResult<f64> {
+        let real_type = self.read_unsigned()?;
+        self.read_real_with_type(real_type)
+    }
+
+    fn read_string(&mut self) -> PyResult<String> {
+        Ok(String::from_utf8_lossy(self.read_byte_string()?).into_owned())
+    }
+
+    fn read_byte_string(&mut self) -> PyResult<&'a [u8]> {
+        let length = self.read_unsigned()? as usize;
+        self.read_bytes(length)
+    }
+
+    fn read_g_delta(&mut self) -> PyResult<(i64, i64)> {
+        let value = self.read_unsigned()?;
+        if value & 1 == 0 {
+            let magnitude = (value >> 4) as i64;
+            Ok(octangular_delta((value >> 1) & 0x07, magnitude))
+        } else {
+            let x = (value >> 2) as i64;
+            let x = if value & 0x02 != 0 { -x } else { x };
+            Ok((x, self.read_signed()?))
+        }
+    }
+
+    fn read_interval(&mut self) -> PyResult<()> {
+        match self.read_unsigned()? {
+            0 => {}
+            1..=3 => {
+                self.read_unsigned()?;
+            }
+            4 => {
+                self.read_unsigned()?;
+                self.read_unsigned()?;
+            }
+            interval_type => {
+                return Err(invalid_oasis(format!(
+                    "unknown interval type {}",
+                    interval_type
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+fn octangular_delta(direction: u64, magnitude: i64) -> (i64, i64) {
+    match direction {
+        0 => (magnitude, 0),
+        1 => (0, magnitude),
+        2 => (-magnitude, 0),
+        3 => (0, -magnitude),
+        4 => (magnitude, magnitude),
+        5 => (-magnitude, magnitude),
+        6 => (-magnitude, -magnitude),
+        _ => (magnitude, -magnitude),
+    }
+}
+
+#[derive(Clone, Default)]
+struct Modals {
+    repetition: Option<Repetition>,
+    placement_x: i64,
+    placement_y: i64,
+    placement_cell: Option<NameReference>,
+    layer: Option<u64>,
+    data_type: Option<u64>,
+    text_layer: Option<u64>,
+    text_type: Option<u64>,
+    text_x: i64,
+    text_y: i64,
+    text_string: Option<NameReference>,
+    geometry_x: i64,
+    geometry_y: i64,
+    xy_relative: bool,
+    geometry_width: Option<u64>,
+    geometry_height: Option<u64>,
+    polygon_points: Option<Vec<(i64, i64)>>,
+    path_half_width: Option<u64>,
+    path_points: Option<Vec<(i64, i64)>>,
+    path_start_extension: Option<i64>,
+    path_end_extension: Option<i64>,
+    circle_radius: Option<u64>,
+    ctrapezoid_type: Option<u64>,
+    property_name: Option<NameReference>,
+    property_values: Option<Vec<PropertyValue>>,
+}
+
+enum PendingKind {
+    Polygon(Polygon),
+    Path(Path),
+    Node(Node),
+    Text(Text, NameReference),
+    Placement(Grid, NameReference),
+}
+
+struct PendingElement {
+    kind: PendingKind,
+    properties: Vec<(NameReference, Vec<PropertyValue>)>,
+}
+
+struct PendingCell {
+    name: NameReference,
+    elements: Vec<PendingElement>,
+}
+
+#[derive(Default)]
+struct NameTable {
+    names: HashMap<u64, String>,
+    next_implicit: u64,
+}
+
+impl NameTable {
+    fn insert(&mut self, name: String, reference_number: Option<u64>) {
+        let reference_number = reference_number.unwrap_or_else(|| {
+            self.next_implicit += 1;
+            self.next_implicit - 1
+        });
+        self.names.insert(reference_number, name);
+    }
+
+    fn resolve(&self, name: &NameReference, kind: &str) -> PyResult<String> {
+        match name {
+            NameReference::Name(name) => Ok(name.clone()),
+            NameReference::Number(number) => self.names.get(number).cloned().ok_or_else(|| {
+                invalid_oasis(format!("undefined {} reference number {}", kind, number))
+            }),
+        }
+    }
+}
+
+#[derive(Default)]
+struct OasisReader {
+    unit: f64,
+    rounding_digits: u32,
+    modals: Modals,
+    cell_names: NameTable,
+    text_strings: NameTable,
+    property_names: NameTable,
+    property_strings: NameTable,
+    cells: Vec<PendingCell>,
+    last_elements: Option<Range<usize>>,
+    node_attribute: Option<u64>,
+}
+
+impl OasisReader {
+    fn to_point(&self, point: (i64, i64)) -> Point {
+        Point::new(point.0 as f64 / self.unit, point.1 as f64 / self.unit)
+            .round(self.rounding_digits)
+    }
+
+    fn to_length(&self, value: f64) -> f64 {
+        Point::new(value / self.unit, 0.0)
+            .round(self.rounding_digits)
+            .x
+    }
+
+    fn read_coordinate(
+        &mut self,
+        reader: &mut ByteReader,
+        modal: fn(&mut Modals) -> &mut i64,
+    ) -> PyResult<i64> {
+        let value = reader.read_signed()?;
+        let relative = self.modals.xy_relative;
+        let current = modal(&mut self.modals);
+        *current = if relative { *current + value } else { value };
+        Ok(*current)
+    }
+
+    fn read_repetition(&mut self, reader: &mut ByteReader) -> PyResult<Repetition> {
+        let repetition_type = reader.read_unsigned()?;
+
+        let repetition = match repetition_type {
+            0 => return modal(&self.modals.repetition, "repetition"),
+            1 => {
+                let columns = reader.read_unsigned()? + 2;
+                let rows = reader.read_unsigned()? + 2;
+                let column_space = reader.read_unsigned()? as i64;
+                let row_space = reader.read_unsigned()? as i64;
+                Repetition::Regular {
+                    columns,
+                    rows,
+                    column_step: (column_space, 0),
+                    row_step: (0, row_space),
+                }
+            }
+            2 => {
+                let columns = reader.read_unsigned()? + 2;
+                let column_space = reader.read_unsigned()? as i64;
+                Repetition::Regular {
+                    columns,
+                    rows: 1,
+                    column_step: (column_space, 0),
+                    row_step: (0, 0),
+                }
+            }
+            3 => {
+                let rows = reader.read_unsigned()? + 2;
+                let row_space = reader.read_unsigned()? as i64;
+                Repetition::Regular {
+                    columns: 1,
+                    rows,
+                    column_step: (0, 0),
+                    row_step: (0, row_space),
+                }
+            }
+            4..=7 => {
+                let count = reader.read_unsigned()? + 1;
+                let grid = if repetition_type == 5 || repetition_type == 7 {
+                    reader.read_unsigned()? as i64
+                } else {
+                    1
+                };
+                let mut offsets = vec![(0, 0)];
+                let mut position = 0;
+                for _ in 0..count {
+                    position += reader.read_unsigned()? as i64 * grid;
+                    if repetition_type <= 5 {
+                        offsets.push((position, 0));
+                    } else {
+                        offsets.push((0, position));
+                    }
+                }
+                Repetition::Irregular(offsets)
+            }
+            8 => {
+                let columns = reader.read_unsigned()? + 2;
+                let rows = reader.read_unsigned()? + 2;
+                let column_step = reader.read_g_delta()?;
+                let row_step = reader.read_g_delta()?;
+                Repetition::Regular {
+                    columns,
+                    rows,
+                    column_step,
+                    row_step,
+                }
+            }
+            9 => {
+                let columns = reader.read_unsigned()? + 2;
+                let column_step = reader.read_g_delta()?;
+                Repetition::Regular {
+                    columns,
+                    rows: 1,
+                    column_step,
+                    row_step: (0, 0),
+                }
+            }
+            10 | 11 => {
+                let count = reader.read_unsigned()? + 1;
+                let grid = if repetition_type == 11 {
+                    reader.read_unsigned()? as i64
+                } else {
+                    1
+                };
+                let mut offsets = vec![(0, 0)];
+                let mut position = (0, 0);
+                for _ in 0..count {
+                    let delta = reader.read_g_delta()?;
+                    position = (position.0 + delta.0 * grid, position.1 + delta.1 * grid);
+                    offsets.push(position);
+                }
+                Repetition::Irregular(offsets)
+            }
+            _ => {
+                return Err(invalid_oasis(format!(
+                    "unknown repetition type {}",
+                    repetition_type
+                )))
+            }
+        };
+
+        self.modals.repetition = Some(repetition.clone());
+        Ok(repetition)
+    }
+
+    fn read_point_list(
+        &mut self,
+        reader: &mut ByteReader,
+        is_polygon: bool,
+    ) -> PyResult<Vec<(i64, i64)>> {
+        let list_type = reader.read_unsigned()?;
+        let count = reader.read_unsigned()? as usize;
+
+        let mut points = Vec::with_capacity(count + 2);
+        let mut position = (0i64, 0i64);
+        points.push(position);
+
+        match list_type {
+            0 | 1 => {
+                for index in 0..count {
+                    let delta = reader.read_signed()?;
+                    if (index % 2 == 0) == (list_type == 0) {
+                        position.0 += delta;
+                    } else {
+                        position.1 += delta;
+                    }
+                    points.push(position);
+                }
+                if is_polygon {
+                    if list_type == 0 {
+                        points.push((0, position.1));
+                    } else {
+                        points.push((position.0, 0));
+                    }
+                }
+            }
+            2 | 3 => {
+                let direction_bits = if list_type == 2 { 2 } else { 3 };
+                for _ in 0..count {
+                    let value = reader.read_unsigned()?;
+                    let direction = value & ((1 << direction_bits) - 1);
+                    let delta = octangular_delta(direction, (value >> direction_bits) as i64);
+                    position = (position.0 + delta.0, position.1 + delta.1);
+                    points.push(position);
+                }
+            }
+            4 => {
+                for _ in 0..count {
+                    let delta = reader.read_g_delta()?;
+                    position = (position.0 + delta.0, position.1 + delta.1);
+                    points.push(position);
+                }
+            }
+            5 => {
+                let mut delta = (0, 0);
+                for _ in 0..count {
+                    let second_delta = reader.read_g_delta()?;
+                    delta = (delta.0 + second_delta.0, delta.1 + second_delta.1);
+                    position = (position.0 + delta.0, position.1 + delta.1);
+                    points.push(position);
+                }
+            }
+            _ => {
+                return Err(invalid_oasis(format!(
+                    "unknown point list type {}",
+                    list_type
+                )))
+            }
+        }
+
+        Ok(points)
+    }
+
+    fn read_layer_and_data_type(
+        &mut self,
+        reader: &mut ByteReader,
+        info: u8,
+    ) -> PyResult<(i32, i32)> {
+        if info & 0x01 != 0 {
+            self.modals.layer = Some(reader.read_unsigned()?);
+        }
+        if info & 0x02 != 0 {
+            self.modals.data_type = Some(reader.read_unsigned()?);
+        }
+        Ok((
+            modal(&self.modals.layer, "layer")? as i32,
+            modal(&self.modals.data_type, "datatype")? as i32,
+        ))
+    }
+
+    fn read_geometry_position(
+        &mut self,
+        reader: &mut ByteReader,
+        info: u8,
+    ) -> PyResult<Vec<(i64, i64)>> {
+        if info & 0x10 != 0 {
+            self.read_coordinate(reader, |modals| &mut modals.geometry_x)?;
+        }
+        if info & 0x08 != 0 {
+            self.read_coordinate(reader, |modals| &mut modals.geometry_y)?;
+        }
+        let origin = (self.modals.geometry_x, self.modals.geometry_y);
+        self.positions(reader, origin, info & 0x04 != 0)
+    }
+
+    fn positions(
+        &mut self,
+        reader: &mut ByteReader,
+        origin: (i64, i64),
+        has_repetition: bool,
+    ) -> PyResult<Vec<(i64, i64)>> {
+        if !has_repetition {
+            return Ok(vec![origin]);
+        }
+        Ok(self
+            .read_repetition(reader)?
+            .offsets()
+            .into_iter()
+            .map(|offset| (origin.0 + offset.0, origin.1 + offset.1))
+            .collect())
+    }
+
+    fn push_elements(&mut self, kinds: Vec<PendingKind>) -> PyResult<()> {
+        let cell = self
+            .cells
+            .last_mut()
+            .ok_or_else(|| invalid_oasis("element record outside of a cell"))?;
+
+        let start = cell.elements.len();
+        cell.elements
+            .extend(kinds.into_iter().map(|kind| PendingElement {
+                kind,
+                properties: Vec::new(),
+            }));
+        self.last_elements = Some(start..cell.elements.len());
+
+        Ok(())
+    }
+
+    fn polygon_from_points(
+        &self,
+        points: &[(i64, i64)],
+        position: (i64, i64),
+        layer: i32,
+        data_type: i32,
+    ) -> PendingKind {
+        PendingKind::Polygon(Polygon {
+            points: get_correct_polygon_points_format(
+                points
+                    .iter()
+                    .map(|point| self.to_point((point.0 + position.0, point.1 + position.1)))
+                    .collect(),
+            ),
+            layer,
+            data_type,
+            properties: HashMap::new(),
+        })
+    }
+
+    fn read_placement(&mut self, reader: &mut ByteReader, record: OasisRecord) -> PyResult<()> {
+        let info = reader.read_byte()?;
+
+        if info & 0x80 != 0 {
+            self.modals.placement_cell = Some(if info & 0x40 != 0 {
+                NameReference::Number(reader.read_unsigned()?)
+            } else {
+                NameReference::Name(reader.read_string()?)
+            });
+        }
+        let cell = modal(&self.modals.placement_cell, "placement-cell")?;
+
+        let (magnification, angle) = if record == OasisRecord::PlacementTransform {
+            let magnification = if info & 0x04 != 0 {
+                reader.read_real()?
+            } else {
+                1.0
+            };
+            let angle = if info & 0x02 != 0 {
+                reader.read_real()?
+            } else {
+                0.0
+            };
+            (magnification, angle)
+        } else {
+            (1.0, 90.0 * ((info >> 1) & 0x03) as f64)
+        };
+
+        if info & 0x20 != 0 {
+            self.read_coordinate(reader, |modals| &mut modals.placement_x)?;
+        }
+        if info & 0x10 != 0 {
+            self.read_coordinate(reader, |modals| &mut modals.placement_y)?;
+        }
+        let origin = (self.modals.placement_x, self.modals.placement_y);
+
+        let base_grid = Grid {
+            origin: self.to_point(origin),
+            magnification,
+            angle,
+            x_reflection: info & 0x01 != 0,
+            ..Grid::default()
+        };
+
+        let grids = if info & 0x08 == 0 {
+            vec![base_grid]
+        } else {
+            match self.read_repetition(reader)? {
+                Repetition::Regular {
+                    columns,
+                    rows,
+                    column_step,
+                    row_step,
+                } => vec![Grid {
+                    columns: columns as u32,
+                    rows: rows as u32,
+                    spacing_x: self
+                        .to_point(column_step)
+                        .rotate(-angle, Point::default())
+                        .round(self.rounding_digits),
+                    spacing_y: self
+                        .to_point(row_step)
+                        .rotate(-angle, Point::default())
+                        .round(self.rounding_digits),
+                    ..base_grid
+                }],
+                Repetition::Irregular(offsets) => offsets
+                    .into_iter()
+                    .map(|offset| Grid {
+                        origin: self.to_point((origin.0 + offset.0, origin.1 + offset.1)),
+                        ..base_grid.clone()
+                    })
+                    .collect(),
+            }
+        };
+
+        self.push_elements(
+            grids
+                .into_iter()
+                .map(|grid| PendingKind::Placement(grid, cell.clone()))
+                .collect(),
+        )
+    }
+
+    fn read_text(&mut self, reader: &mut ByteReader) -> PyResult<()> {
+        let info = reader.read_byte()?;
+
+        if info & 0x40 != 0 {
+            self.modals.text_string = Some(if info & 0x20 != 0 {
+                NameReference::Number(reader.read_unsigned()?)
+            } else {
+                NameReference::Name(reader.read_string()?)
+            });
+        }
+        let string = modal(&self.modals.text_string, "text-string")?;
+
+        if info & 0x01 != 0 {
+            self.modals.text_layer = Some(reader.read_unsigned()?);
+        }
+        if info & 0x02 != 0 {
+            self.modals.text_type = Some(reader.read_unsigned()?);
+        }
+        let layer = modal(&self.modals.text_layer, "textlayer")? as i32;
+        let text_type = modal(&self.modals.text_type, "texttype")? as i32;
+
+        if info & 0x10 != 0 {
+            self.read_coordinate(reader, |modals| &mut modals.text_x)?;
+        }
+        if info & 0x08 != 0 {
+            self.read_coordinate(reader, |modals| &mut modals.text_y)?;
+        }
+        let origin = (self.modals.text_x, self.modals.text_y);
+        let positions = self.positions(reader, origin, info & 0x04 != 0)?;
+
+        let texts = positions
+            .into_iter()
+            .map(|position| {
+                PendingKind::Text(
+                    Text {
+                        origin: self.to_point(position),
+                        layer,
+                        text_type,
+                        ..Text::default()
+                    },
+                    string.clone(),
+                )
+            })
+            .collect();
+
+        self.push_elements(texts)
+    }
+
+    fn read_rectangle(&mut self, reader: &mut ByteReader) -> PyResult<()> {
+        let info = reader.read_byte()?;
+        let (layer, data_type) = self.read_layer_and_data_type(reader, info)?;
+
+        if info & 0x40 != 0 {
+            self.modals.geometry_width = Some(reader.read_unsigned()?);
+        }
+        let width = modal(&self.modals.geometry_width, "geometry-w")? as i64;
+
+        if info & 0x80 != 0 {
+            self.modals.geometry_height = Some(width as u64);
+        } else if info & 0x20 != 0 {
+            self.modals.geometry_height = Some(reader.read_unsigned()?);
+        }
+        let height = modal(&self.modals.geometry_height, "geometry-h")? as i64;
+
+        let positions = self.read_geometry_position(reader, info)?;
+        let points = [(0, 0), (width, 0), (width, height), (0, height)];
+
+        let polygons = positions
+            .into_iter()
+            .map(|position| self.polygon_from_points(&points, position, layer, data_type))
+            .collect();
+
+        self.push_elements(polygons)
+    }
+
+    fn read_polygon(&mut self, reader: &mut ByteReader) -> PyResult<()> {
+        let info = reader.read_byte()?;
+        let (layer, data_type) = self.read_layer_and_data_type(reader, info)?;
+
+        if info & 0x20 != 0 {
+            self.modals.polygon_points = Some(self.read_point_list(reader, true)?);
+        }
+        let points = modal(&self.modals.polygon_points, "polygon-point-list")?;
+
+        let positions = self.read_geometry_position(reader, info)?;
+
+        let polygons = positions
+            .into_iter()
+            .map(|position| self.polygon_from_points(&points, position, layer, data_type))
+            .collect();
+
+        self.push_elements(polygons)
+    }
+
+    fn read_path_extension(
+        &mut self,
+        reader: &mut ByteReader,
+        scheme: u64,
+        half_width: i64,
+    ) -> PyResult<Option<i64>> {
+        Ok(match scheme {
+            1 => Some(0),
+            2 => Some(half_width),
+            3 => Some(reader.read_signed()?),
+            _ => None,
+        })
+    }
+
+    fn read_path(&mut self, reader: &mut ByteReader) -> PyResult<()> {
+        let info = reader.read_byte()?;
+        let (layer, data_type) = self.read_layer_and_data_type(reader, info)?;
+
+        if info & 0x40 != 0 {
+            self.modals.path_half_width = Some(reader.read_unsigned()?);
+        }
+        let half_width = modal(&self.modals.path_half_width, "path-halfwidth")? as i64;
+
+        if info & 0x80 != 0 {
+            let scheme = reader.read_unsigned()?;
+            if let Some(extension) =
+                self.read_path_extension(reader, (scheme >> 2) & 0x03, half_width)?
+            {
+                self.modals.path_start_extension = Some(extension);
+            }
+            if let Some(extension) = self.read_path_extension(reader, scheme & 0x03, half_width)? {
+                self.modals.path_end_extension = Some(extension);
+            }
+        }
+        let start_extension = modal(&self.modals.path_start_extension, "path-start-extension")?;
+        let end_extension = modal(&self.modals.path_end_extension, "path-end-extension")?;
+
+        if info & 0x20 != 0 {
+            self.modals.path_points = Some(self.read_point_list(reader, false)?);
+        }
+        let points = modal(&self.modals.path_points, "path-point-list")?;
+
+        let positions = self.read_geometry_position(reader, info)?;
+
+        let (path_type, begin_extension, end_extension) =
+            if start_extension == 0 && end_extension == 0 {
+                (PathType::Square, 0.0, 0.0)
+            } else if start_extension == half_width && end_extension == half_width {
+                (PathType::Overlap, 0.0, 0.0)
+            } else {
+                (
+                    PathType::Custom,
+                    self.to_length(start_extension as f64),
+                    self.to_length(end_extension as f64),
+                )
+            };
+        let width = self.to_length(2.0 * half_width as f64);
+
+        let paths = positions
+            .into_iter()
+            .map(|position| {
+                PendingKind::Path(Path {
+                    points: points
+                        .iter()
+                        .map(|point| self.to_point((point.0 + position.0, point.1 + position.1)))
+                        .collect(),
+                    layer,
+                    data_type,
+                    path_type: Some(path_type),
+                    width: Some(width),
+                    properties: HashMap::new(),
+                    begin_extension,
+                    end_extension,
+                })
+            })
+            .collect();
+
+        self.push_elements(paths)
+    }
+
+    fn read_circle(&mut self, reader: &mut ByteReader) -> PyResult<()> {
+        let info = reader.read_byte()?;
+        let (layer, data_type) = self.read_layer_and_data_type(reader, info)?;
+
+        if info & 0x20 != 0 {
+            self.modals.circle_radius = Some(reader.read_unsigned()?);
+        }
+        let radius = modal(&self.modals.circle_radius, "circle-radius")? as f64;
+
+        let positions = self.read_geometry_position(reader, info)?;
+
+        let polygons = positions
+            .into_iter()
+            .map(|position| {
+                let centre = self.to_point(position);
+                let points = (0..CIRCLE_SIDES)
+                    .map(|index| {
+                        let angle = 2.0 * PI * index as f64 / CIRCLE_SIDES as f64;
+                        Point::new(
+                            centre.x + self.to_length(radius) * angle.cos(),
+                            centre.y + self.to_length(radius) * angle.sin(),
+                        )
+                        .round(self.rounding_digits)
+                    })
+                    .collect();
+                PendingKind::Polygon(Polygon {
+                    points: get_correct_polygon_points_format(points),
+                    layer,
+                    data_type,
+                    properties: HashMap::new(),
+                })
+            })
+            .collect();
+
+        self.push_elements(polygons)
+    }
+
+    fn read_trapezoid(&mut self, reader: &mut ByteReader, record: OasisRecord) -> PyResult<()> {
+        let info = reader.read_byte()?;
+        let (layer, data_type) = self.read_layer_and_data_type(reader, info)?;
+
+        if info & 0x40 != 0 {
+            self.modals.geometry_width = Some(reader.read_unsigned()?);
+        }
+        if info & 0x20 != 0 {
+            self.modals.geometry_height = Some(reader.read_unsigned()?);
+        }
+        let width = modal(&self.modals.geometry_width, "geometry-w")? as i64;
+        let height = modal(&self.modals.geometry_height, "geometry-h")? as i64;
+
+        let delta_a = if record == OasisRecord::TrapezoidB {
+            0
+        } else {
+            reader.read_signed()?
+        };
+        let delta_b = if record == OasisRecord::TrapezoidA {
+            0
+        } else {
+            reader.read_signed()?
+        };
+
+        let mut points = if info & 0x80 != 0 {
+            vec![
+                (0, delta_a.max(0)),
+                (0, height + delta_b.min(0)),
+                (width, height - delta_b.max(0)),
+                (width, -delta_a.min(0)),
+            ]
+        } else {
+            vec![
+                (delta_a.max(0), height),
+                (width + delta_b.min(0), height),
+                (width - delta_b.max(0), 0),
+                (-delta_a.min(0), 0),
+            ]
+        };
+        // Trapezoids with a zero length edge are triangles
+        points.dedup();
+
+        let positions = self.read_geometry_position(reader, info)?;
+
+        let polygons = positions
+            .into_iter()
+            .map(|position| self.polygon_from_points(&points, position, layer, data_type))
+            .collect();
+
+        self.push_elements(polygons)
+    }
+
+    fn read_ctrapezoid(&mut self, reader: &mut ByteReader) -> PyResult<()> {
+        let info = reader.read_byte()?;
+        let (layer, data_type) = self.read_layer_and_data_type(reader, info)?;
+
+        if info & 0x80 != 0 {
+            self.modals.ctrapezoid_type = Some(reader.read_unsigned()?);
+        }
+        let ctrapezoid_type = modal(&self.modals.ctrapezoid_type, "ctrapezoid-type")?;
+
+        if info & 0x40 != 0 {
+            self.modals.geometry_width = Some(reader.read_unsigned()?);
+        }
+        if info & 0x20 != 0 {
+            self.modals.geometry_height = Some(reader.read_unsigned()?);
+        }
+        // Some types only use one of the width and height, the other is implied
+        let (w, h) = match ctrapezoid_type {
+            16..=19 | 22 | 23 | 25 => (modal(&self.modals.geometry_width, "geometry-w")? as i64, 0),
+            20 | 21 => (0, modal(&self.modals.geometry_height, "geometry-h")? as i64),
+            _ => (
+                modal(&self.modals.geometry_width, "geometry-w")? as i64,
+                modal(&self.modals.geometry_height, "geometry-h")? as i64,
+            ),
+        };
+
+        let mut points = match ctrapezoid_type {
+            0 => vec![(0, 0), (0, h), (w - h, h), (w, 0)],
+            1 => vec![(0, 0), (0, h), (w, h), (w - h, 0)],
+            2 => vec![(0, 0), (h, h), (w, h), (w, 0)],
+            3 => vec![(h, 0), (0, h), (w, h), (w, 0)],
+            4 => vec![(0, 0), (h, h), (w - h, h), (w, 0)],
+            5 => vec![(h, 0), (0, h), (w, h), (w - h, 0)],
+            6 => vec![(0, 0), (h, h), (w, h), (w - h, 0)],
+            7 => vec![(h, 0), (0, h), (w - h, h), (w, 0)],
+            8 => vec![(0, 0), (0, h), (w, h - w), (w, 0)],
+            9 => vec![(0, 0), (0, h - w), (w, h), (w, 0)],
+            10 => vec![(0, 0), (0, h), (w, h), (w, w)],
+            11 => vec![(0, w), (0, h), (w, h), (w, 0)],
+            12 => vec![(0, 0), (0, h), (w, h - w), (w, w)],
+            13 => vec![(0, w), (0, h - w), (w, h), (w, 0)],
+            14 => vec![(0, 0), (0, h - w), (w, h), (w, w)],
+            15 => vec![(0, w), (0, h), (w, h - w), (w, 0)],
+            16 => vec![(0, 0), (0, w), (w, 0)],
+            17 => vec![(0, 0), (0, w), (w, w)],
+            18 => vec![(0, 0), (w, w), (w, 0)],
+            19 => vec![(0, w), (w, w), (w, 0)],
+            20 => vec![(0, 0), (h, h), (2 * h, 0)],
+            21 => vec![(0, h), (2 * h, h), (h, 0)],
+            22 => vec![(0, 0), (0, 2 * w), (w, w)],
+            23 => vec![(w, 0), (0, w), (w, 2 * w)],
+            24 => vec![(0, 0), (0, h), (w, h), (w, 0)],
+            25 => vec![(0, 0), (0, w), (w, w), (w, 0)],
+            _ => {
+                return Err(invalid_oasis(format!(
+                    "unknown ctrapezoid type {}",
+                    ctrapezoid_type
+                )))
+            }
+        };
+        points.dedup();
+
+        let positions = self.read_geometry_position(reader, info)?;
+
+        let polygons = positions
+            .into_iter()
+            .map(|position| self.polygon_from_points(&points, position, layer, data_type))
+            .collect();
+
+        self.push_elements(polygons)
+    }
+
+    fn read_xgeometry(&mut self, reader: &mut ByteReader) -> PyResult<()> {
+        let info = reader.read_byte()?;
+        let attribute = reader.read_unsigned()?;
+        let (layer, node_type) = self.read_layer_and_data_type(reader, info)?;
+        let data = reader.read_byte_string()?;
+        let positions = self.read_geometry_position(reader, info)?;
+
+        // Only nodes written by gdsr are understood, other extension geometry is skipped
+        if self.node_attribute != Some(attribute) {
+            return Ok(());
+        }
+
+        let points = self.read_point_list(&mut ByteReader::new(data), false)?;
+        let nodes = positions
+            .into_iter()
+            .map(|position| {
+                PendingKind::Node(Node {
+                    points: points
+                        .iter()
+                        .map(|point| self.to_point((point.0 + position.0, point.1 + position.1)))
+                        .collect(),
+                    layer,
+                    node_type,
+                    properties: HashMap::new(),
+                })
+            })
+            .collect();
+
+        self.push_elements(nodes)
+    }
+
+    fn read_property_value(&mut self, reader: &mut ByteReader) -> PyResult<PropertyValue> {
+        let value_type = reader.read_unsigned()?;
+        Ok(match value_type {
+            0..=7 => PropertyValue::Real(reader.read_real_with_type(value_type)?),
+            8 => PropertyValue::Unsigned(reader.read_unsigned()?),
+            9 => PropertyValue::Signed(reader.read_signed()?),
+            10..=12 => PropertyValue::String(reader.read_string()?),
+            13..=15 => PropertyValue::Reference(reader.read_unsigned()?),
+            _ => {
+                return Err(invalid_oasis(format!(
+                    "unknown property value type {}",
+                    value_type
+                )))
+            }
+        })
+    }
+
+    fn read_property(&mut self, reader: &mut ByteReader) -> PyResult<()> {
+        let info = reader.read_byte()?;
+
+        if info & 0x04 != 0 {
+            self.modals.property_name = Some(if info & 0x02 != 0 {
+                NameReference::Number(reader.read_unsigned()?)
+            } else {
+                NameReference::Name(reader.read_string()?)
+            });
+        }
+        let name = modal(&self.modals.property_name, "last-property-name")?;
+
+        if info & 0x08 == 0 {
+            let mut count = (info >> 4) as u64;
+            if count == 15 {
+                count = reader.read_unsigned()?;
+            }
+            let values = (0..count)
+                .map(|_| self.read_property_value(reader))
+                .collect::<PyResult<Vec<PropertyValue>>>()?;
+            self.modals.property_values = Some(values);
+        }
+        let values = modal(&self.modals.property_values, "last-value-list")?;
+
+        self.add_property(name, values);
+
+        Ok(())
+    }
+
+    fn add_property(&mut self, name: NameReference, values: Vec<PropertyValue>) {
+        if let (Some(range), Some(cell)) = (self.last_elements.clone(), self.cells.last_mut()) {
+            for element in &mut cell.elements[range] {
+                element.properties.push((name.clone(), values.clone()));
+            }
+        }
+    }
+
+    fn read_cblock(&mut self, reader: &mut ByteReader) -> PyResult<bool> {
+        let compression_type = reader.read_unsigned()?;
+        if compression_type != 0 {
+            return Err(invalid_oasis(format!(
+                "unknown CBLOCK compression type {}",
+                compression_type
+            )));
+        }
+        let uncompressed_length = reader.read_unsigned()? as usize;
+        let compressed_length = reader.read_unsigned()? as usize;
+        let compressed = reader.read_bytes(compressed_length)?;
+
+        let data = decompress_to_vec_with_limit(compressed, uncompressed_length)
+            .map_err(|error| invalid_oasis(format!("invalid CBLOCK data: {}", error)))?;
+        self.read_records(&mut ByteReader::new(&data))
+    }
+
+    fn read_records(&mut self, reader: &mut ByteReader) -> PyResult<bool> {
+        while !reader.is_empty() {
+            let record_id = reader.read_unsigned()?;
+            let record = OasisRecord::try_from(record_id)
+                .map_err(|id| invalid_oasis(format!("unknown record type {}", id)))?;
+
+            if !matches!(
+                record,
+                OasisRecord::Pad
+                    | OasisRecord::Property
+                    | OasisRecord::PropertyRepeat
+                    | OasisRecord::XYAbsolute
+                    | OasisRecord::XYRelative
+                    | OasisRecord::CBlock
+            ) {
+                self.last_elements = None;
+            }
+
+            match record {
+                OasisRecord::Pad => {}
+                OasisRecord::Start => {
+                    let version = reader.read_string()?;
+                    if version != VERSION {
+                        return Err(invalid_oasis(format!("unsupported version {}", version)));
+                    }
+                    self.unit = reader.read_real()?;
+                    if self.unit <= 0.0 {
+                        return Err(invalid_oasis("unit must be positive"));
+                    }
+                    self.rounding_digits = self.unit.log10().ceil().max(0.0) as u32;
+                    if reader.read_unsigned()? == 0 {
+                        for _ in 0..12 {
+                            reader.read_unsigned()?;
+                        }
+                    }
+                }
+                OasisRecord::End => return Ok(true),
+                OasisRecord::CellName | OasisRecord::CellNameExplicit => {
+                    let name = reader.read_string()?;
+                    let reference_number = if record == OasisRecord::CellNameExplicit {
+                        Some(reader.read_unsigned()?)
+                    } else {
+                        None
+                    };
+                    self.cell_names.insert(name, reference_number);
+                }
+                OasisRecord::TextString | OasisRecord::TextStringExplicit => {
+                    let name = reader.read_string()?;
+                    let reference_number = if record == OasisRecord::TextStringExplicit {
+                        Some(reader.read_unsigned()?)
+                    } else {
+                        None
+                    };
+                    self.text_strings.insert(name, reference_number);
+                }
+                OasisRecord::PropName | OasisRecord::PropNameExplicit => {
+                    let name = reader.read_string()?;
+                    let reference_number = if record == OasisRecord::PropNameExplicit {
+                        Some(reader.read_unsigned()?)
+                    } else {
+                        None
+                    };
+                    self.property_names.insert(name, reference_number);
+                }
+                OasisRecord::PropString | OasisRecord::PropStringExplicit => {
+                    let name = reader.read_string()?;
+                    let reference_number = if record == OasisRecord::PropStringExplicit {
+                        Some(reader.read_unsigned()?)
+                    } else {
+                        None
+                    };
+                    self.property_strings.insert(name, reference_number);
+                }
+                OasisRecord::LayerName | OasisRecord::TextLayerName => {
+                    reader.read_string()?;
+                    reader.read_interval()?;
+                    reader.read_interval()?;
+                }
+                OasisRecord::CellReference | OasisRecord::Cell => {
+                    if self.unit <= 0.0 {
+                        return Err(invalid_oasis("missing START record"));
+                    }
+                    let name = if record == OasisRecord::CellReference {
+                        NameReference::Number(reader.read_unsigned()?)
+                    } else {
+                        NameReference::Name(reader.read_string()?)
+                    };
+                    self.cells.push(PendingCell {
+                        name,
+                        elements: Vec::new(),
+                    });
+                    self.modals = Modals::default();
+                }
+                OasisRecord::XYAbsolute => self.modals.xy_relative = false,
+                OasisRecord::XYRelative => self.modals.xy_relative = true,
+                OasisRecord::Placement | OasisRecord::PlacementTransform => {
+                    self.read_placement(reader, record)?
+                }
+                OasisRecord::Text => self.read_text(reader)?,
+                OasisRecord::Rectangle => self.read_rectangle(reader)?,
+                OasisRecord::Polygon => self.read_polygon(reader)?,
+                OasisRecord::Path => self.read_path(reader)?,
+                OasisRecord::Circle => self.read_circle(reader)?,
+                OasisRecord::Trapezoid | OasisRecord::TrapezoidA | OasisRecord::TrapezoidB => {
+                    self.read_trapezoid(reader, record)?
+                }
+                OasisRecord::CTrapezoid => self.read_ctrapezoid(reader)?,
+                OasisRecord::Property => self.read_property(reader)?,
+                OasisRecord::PropertyRepeat => {
+                    let name = modal(&self.modals.property_name, "last-property-name")?;
+                    let values = modal(&self.modals.property_values, "last-value-list")?;
+                    self.add_property(name, values);
+                }
+                OasisRecord::XName | OasisRecord::XNameExplicit => {
+                    let attribute = reader.read_unsigned()?;
+                    if reader.read_string()? == GDS_NODE_NAME {
+                        self.node_attribute = Some(attribute);
+                    }
+                    if record == OasisRecord::XNameExplicit {
+                        reader.read_unsigned()?;
+                    }
+                }
+                OasisRecord::XElement => {
+                    reader.read_unsigned()?;
+                    reader.read_string()?;
+                }
+                OasisRecord::XGeometry => self.read_xgeometry(reader)?,
+                OasisRecord::CBlock => {
+                    if self.read_cblock(reader)? {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn resolve_properties(
+        &self,
+        properties: &[(NameReference, Vec<PropertyValue>)],
+    ) -> PyResult<HashMap<i32, String>> {
+        let mut resolved = HashMap::new();
+
+        for (name, values) in properties {
+            if self.property_names.resolve(name, "property name")? != GDS_PROPERTY_NAME {
+                continue;
+            }
+
+            let attribute = match values.first() {
+                Some(PropertyValue::Unsigned(attribute)) => *attribute as i32,
+                Some(PropertyValue::Signed(attribute)) => *attribute as i32,
+                Some(PropertyValue::Real(attribute)) => *attribute as i32,
+                _ => continue,
+            };
+
+            let value = match values.get(1) {
+                Some(PropertyValue::String(value)) => value.clone(),
+                Some(PropertyValue::Reference(number)) => self
+                    .property_strings
+                    .resolve(&NameReference::Number(*number), "property string")?,
+                _ => continue,
+            };
+
+            resolved.insert(attribute, value);
+        }
+
+        Ok(resolved)
+    }
+
+    fn into_library(self) -> PyResult<Library> {
+        let mut library = Library {
+            precision: round_to_significant_digits(1e-6 / self.unit),
+            ..Library::default()
+        };
+
+        let cell_names = self
+            .cells
+            .iter()
+            .map(|cell| self.cell_names.resolve(&cell.name, "cell name"))
+            .collect::<PyResult<Vec<String>>>()?;
+
+        for name in &cell_names {
+            library
+                .cells
+                .insert(name.clone(), Shared::new(Cell::new(name.clone())));
+        }
+
+        for (pending_cell, name) in self.cells.iter().zip(cell_names) {
+            let mut cell = library.cells[&name].write();
+
+            for element in &pending_cell.elements {
+                let properties = self.resolve_properties(&element.properties)?;
+
+                match &element.kind {
+                    PendingKind::Polygon(polygon) => {
+                        let polygon = Polygon {
+                            properties,
+                            ..polygon.clone()
+                        };
+                        cell.add(vec![Element::Polygon(Shared::new(polygon))]);
+                    }
+                    PendingKind::Path(path) => {
+                        let path = Path {
+                            properties,
+                            ..path.clone()
+                        };
+                        cell.add(vec![Element::Path(Shared::new(path))]);
+                    }
+                    PendingKind::Node(node) => {
+                        let node = Node {
+                            properties,
+                            ..node.clone()
+                        };
+                        cell.add(vec![Element::Node(Shared::new(node))]);
+                    }
+                    PendingKind::Text(text, string) => {
+                        let text = Text {
+                            text: self.text_strings.resolve(string, "text string")?,
+                            properties,
+                            ..text.clone()
+                        };
+                        cell.add(vec![Element::Text(Shared::new(text))]);
+                    }
+                    PendingKind::Placement(grid, cell_name) => {
+                        let cell_name = self.cell_names.resolve(cell_name, "cell name")?;
+                        let instance = match library.cells.get(&cell_name) {
+                            Some(referenced_cell) => referenced_cell.clone(),
+                            None => Shared::new(Cell::new(cell_name)),
+                        };
+                        let reference = Reference {
+                            instance: Instance::Cell(instance),
+                            grid: Shared::new(grid.clone()),
+                            properties,
+                        };
+                        cell.add(vec![Element::Reference(Shared::new(reference))]);
+                    }
+                }
+            }
+        }
+
+        Ok(library)
+    }
+}
+
+pub fn from_oasis(file_name: String) -> PyResult<Library> {
+    let data = fs::read(file_name)?;
+
+    if !data.starts_with(MAGIC) {
+        return Err(invalid_oasis("missing magic bytes"));
+    }
+
+    let mut reader = OasisReader::default();
+    let mut byte_reader = ByteReader::new(&data[MAGIC.len()..]);
+
+    if !reader.read_records(&mut byte_reader)? {
+        return Err(invalid_oasis("missing END record"));
+    }
+
+    reader.into_library()
+}
@@ -0,0 +1,796 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+
+use miniz_oxide::deflate::compress_to_vec;
+use pyo3::{
+    exceptions::{PyIOError, PyValueError},
+    prelude::*,
+};
+
+use crate::{
+    cell::{hierarchy::CellHierarchy, Cell},
+    element::Element,
+    grid::Grid,
+    node::Node,
+    path::{path_type::PathType, Path},
+    point::Point,
+    polygon::Polygon,
+    reference::{Instance, Reference},
+    shared::Shared,
+    text::Text,
+};
+
+use super::{OasisRecord, END_RECORD_LENGTH, GDS_NODE_NAME, GDS_PROPERTY_NAME, MAGIC, VERSION};
+
+const GDS_PROPERTY_REFERENCE: u64 = 0;
+const GDS_NODE_ATTRIBUTE: u64 = 0;
+const CBLOCK_COMPRESSION_LEVEL: u8 = 6;
+const WIDTH_TOLERANCE: f64 = 1e-6;
+
+fn is_manhattan(delta: (i64, i64)) -> bool {
+    delta.0 == 0 || delta.1 == 0
+}
+
+fn is_octangular(delta: (i64, i64)) -> bool {
+    is_manhattan(delta) || delta.0.abs() == delta.1.abs()
+}
+
+fn octangular_direction(delta: (i64, i64)) -> (u64, u64) {
+    let magnitude = delta.0.unsigned_abs().max(delta.1.unsigned_abs());
+    let direction = match (delta.0.signum(), delta.1.signum()) {
+        (1, 0) | (0, 0) => 0,
+        (0, 1) => 1,
+        (-1, 0) => 2,
+        (0, -1) => 3,
+        (1, 1) => 4,
+        (-1, 1) => 5,
+        (-1, -1) => 6,
+        _ => 7,
+    };
+    (direction, magnitude)
+}
+
+struct OasisWriter {
+    buffer: Vec<u8>,
+    scale: f64,
+    layer: Option<i32>,
+    data_type: Option<i32>,
+    text_layer: Option<i32>,
+    text_type: Option<i32>,
+}
+
+impl OasisWriter {
+    fn new(scale: f64) -> Self {
+        OasisWriter {
+            buffer: Vec::new(),
+            scale,
+            layer: None,
+            data_type: None,
+            text_layer: None,
+            text_type: None,
+        }
+    }
+
+    fn to_database(&self, point: Point) -> (i64, i64) {
+        (
+            (point.x * self.scale).round() as i64,
+            (point.y * self.scale).round() as i64,
+        )
+    }
+
+    fn write_record(&mut self, record: OasisRecord) {
+        self.write_unsigned(record as u64);
+    }
+
+    fn write_byte(&mut self, byte: u8) {
+        self.buffer.push(byte);
+    }
+
+    fn write_unsigned(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.buffer.push(byte);
+                break;
+            }
+            self.buffer.push(byte | 0x80);
+        }
+    }
+
+    fn write_signed(&mut self, value: i64) {
+        let sign = if value < 0 { 1 } else { 0 };
+        self.write_unsigned((value.unsigned_abs() << 1) | sign);
+    }
+
+    fn write_real(&mut self, value: f64) {
+        if value.fract() == 0.0 && value.abs() < u32::MAX as f64 {
+            self.write_unsigned(if value < 0.0 { 1 } else { 0 });
+            self.write_unsigned(value.abs() as u64);
+        } else {
+            self.write_unsigned(7);
+            self.buffer.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fn write_string(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    fn write_bytes(&mut self, value: &[u8]) {
+        self.write_unsigned(value.len() as u64);
+        self.buffer.extend_from_slice(value);
+    }
+
+    fn write_g_delta(&mut self, delta: (i64, i64)) {
+        if is_octangular(delta) {
+            let (direction, magnitude) = octangular_direction(delta);
+            self.write_unsigned((magnitude << 4) | (direction << 1));
+        } else {
+            let sign = if delta.0 < 0 { 1 } else { 0 };
+            self.write_unsigned((delta.0.unsigned_abs() << 2) | (sign << 1) | 1);
+            self.write_signed(delta.1);
+        }
+    }
+
+    fn write_point_list(&mut self, points: &[(i64, i64)], closed: bool) {
+        let mut deltas = points
+            .windows(2)
+            .map(|window| (window[1].0 - window[0].0, window[1].1 - window[0].1))
+            .collect::<Vec<(i64, i64)>>();
+
+        let mut all_deltas = deltas.clone();
+        if closed {
+            if let (Some(first), Some(last)) = (points.first(), points.last()) {
+                all_deltas.push((first.0 - last.0, first.1 - last.1));
+            }
+        }
+
+        if all_deltas.iter().all(|&delta| is_manhattan(delta)) {
+            self.write_unsigned(2);
+            self.write_unsigned(deltas.len() as u64);
+            for delta in deltas.drain(..) {
+                let (direction, magnitude) = octangular_direction(delta);
+                self.write_unsigned((magnitude << 2) | direction);
+            }
+        } else if all_deltas.iter().all(|&delta| is_octangular(delta)) {
+            self.write_unsigned(3);
+            self.write_unsigned(deltas.len() as u64);
+            for delta in deltas.drain(..) {
+                let (direction, magnitude) = octangular_direction(delta);
+                self.write_unsigned((magnitude << 3) | direction);
+            }
+        } else {
+            self.write_unsigned(4);
+            self.write_unsigned(deltas.len() as u64);
+            for delta in deltas.drain(..) {
+                self.write_g_delta(delta);
+            }
+        }
+    }
+
+    fn layer_info_bits(&mut self, layer: i32, data_type: i32) -> u8 {
+        let mut info = 0;
+        if self.data_type != Some(data_type) {
+            info |= 0x02;
+        }
+        if self.layer != Some(layer) {
+            info |= 0x01;
+        }
+        info
+    }
+
+    fn write_layer_fields(&mut self, info: u8, layer: i32, data_type: i32) {
+        if info & 0x01 != 0 {
+            self.write_unsigned(layer as u64);
+            self.layer = Some(layer);
+        }
+        if info & 0x02 != 0 {
+            self.write_unsigned(data_type as u64);
+            self.data_type = Some(data_type);
+        }
+    }
+
+    fn write_start(&mut self, unit: f64) {
+        self.buffer.extend_from_slice(MAGIC);
+        self.write_record(OasisRecord::Start);
+        self.write_string(VERSION);
+        self.write_real(unit);
+        // Table offsets are stored in the START record, all tables are marked absent
+        self.write_unsigned(0);
+        for _ in 0..12 {
+            self.write_unsigned(0);
+        }
+    }
+
+    fn write_end(&mut self) {
+        let start = self.buffer.len();
+        self.write_record(OasisRecord::End);
+        let padding_length = END_RECORD_LENGTH - 4;
+        self.write_unsigned(padding_length as u64);
+        self.buffer.extend(std::iter::repeat_n(0, padding_length));
+        self.write_unsigned(0);
+        debug_assert_eq!(self.buffer.len() - start, END_RECORD_LENGTH);
+    }
+
+    fn write_properties(&mut self, properties: &HashMap<i32, String>) {
+        let mut attributes = properties.keys().collect::<Vec<&i32>>();
+        attributes.sort();
+
+        for attribute in attributes {
+            self.write_record(OasisRecord::Property);
+            self.write_byte(0x27);
+            self.write_unsigned(GDS_PROPERTY_REFERENCE);
+            self.write_unsigned(8);
+            self.write_unsigned(*attribute as u64);
+            self.write_unsigned(11);
+            self.write_string(&properties[attribute]);
+        }
+    }
+
+    fn polygon_geometry(&self, polygon: &Polygon) -> Option<PlacedGeometry> {
+        let mut points = polygon
+            .points
+            .iter()
+            .map(|&point| self.to_database(point))
+            .collect::<Vec<(i64, i64)>>();
+        points.dedup();
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        let first = *points.first()?;
+
+        let (geometry, position) = match rectangle_bounds(&points) {
+            Some((min, max)) => (
+                Geometry::Rectangle {
+                    width: max.0 - min.0,
+                    height: max.1 - min.1,
+                },
+                min,
+            ),
+            None => (Geometry::Polygon(relative_points(&points)), first),
+        };
+
+        Some(PlacedGeometry {
+            geometry,
+            layer: polygon.layer,
+            data_type: polygon.data_type,
+            properties: polygon.properties.clone(),
+            position,
+        })
+    }
+
+    fn path_geometry(&self, path: &Path) -> PyResult<Option<PlacedGeometry>> {
+        let mut points = path
+            .points
+            .iter()
+            .map(|&point| self.to_database(point))
+            .collect::<Vec<(i64, i64)>>();
+        points.dedup();
+        let Some(&first) = points.first() else {
+            return Ok(None);
+        };
+
+        // OASIS stores half of the width, so only even widths can be written
+        let width = path.width.unwrap_or(0.0).abs() * self.scale;
+        let half_width = (width / 2.0).round();
+        if (2.0 * half_width - width).abs() > WIDTH_TOLERANCE {
+            return Err(PyValueError::new_err(format!(
+                "Path width {} is not an even number of database units, OASIS stores half of the path width",
+                path.width.unwrap_or(0.0)
+            )));
+        }
+
+        let (extension_scheme, extensions) = match path.path_type.unwrap_or_default() {
+            PathType::Square => (0b0101, None),
+            PathType::Overlap => (0b1010, None),
+            PathType::Custom => (
+                0b1111,
+                Some((
+                    (path.begin_extension * self.scale).round() as i64,
+                    (path.end_extension * self.scale).round() as i64,
+                )),
+            ),
+            PathType::Round => {
+                return Err(PyValueError::new_err(format!(
+                    "OASIS has no round path ends, the path on layer {} and data type {} cannot be written",
+                    path.layer, path.data_type
+                )))
+            }
+        };
+
+        Ok(Some(PlacedGeometry {
+            geometry: Geometry::Path {
+                half_width: half_width as u64,
+                extension_scheme,
+                extensions,
+                points: relative_points(&points),
+            },
+            layer: path.layer,
+            data_type: path.data_type,
+            properties: path.properties.clone(),
+            position: first,
+        }))
+    }
+
+    fn text_geometry(&self, text: &Text) -> PlacedGeometry {
+        PlacedGeometry {
+            geometry: Geometry::Text(text.text.clone()),
+            layer: text.layer,
+            data_type: text.text_type,
+            properties: text.properties.clone(),
+            position: self.to_database(text.origin),
+        }
+    }
+
+    fn node_geometry(&self, node: &Node) -> Option<PlacedGeometry> {
+        let points = node
+            .points
+            .iter()
+            .map(|&point| self.to_database(point))
+            .collect::<Vec<(i64, i64)>>();
+        let first = *points.first()?;
+
+        Some(PlacedGeometry {
+            geometry: Geometry::Node(relative_points(&points)),
+            layer: node.layer,
+            data_type: node.node_type,
+            properties: node.properties.clone(),
+            position: first,
+        })
+    }
+
+    fn write_geometry(&mut self, placed: &PlacedGeometry, offsets: &[(i64, i64)]) {
+        let (layer, data_type) = (placed.layer, placed.data_type);
+        let (x, y) = placed.position;
+        let repetition = if offsets.len() > 1 { 0x04 } else { 0 };
+
+        match &placed.geometry {
+            Geometry::Rectangle { width, height } => {
+                let info = self.layer_info_bits(layer, data_type) | repetition;
+                self.write_record(OasisRecord::Rectangle);
+                if width == height {
+                    self.write_byte(0x80 | 0x40 | 0x10 | 0x08 | info);
+                } else {
+                    self.write_byte(0x40 | 0x20 | 0x10 | 0x08 | info);
+                }
+                self.write_layer_fields(info, layer, data_type);
+                self.write_unsigned(*width as u64);
+                if width != height {
+                    self.write_unsigned(*height as u64);
+                }
+            }
+            Geometry::Polygon(points) => {
+                let info = self.layer_info_bits(layer, data_type) | repetition;
+                self.write_record(OasisRecord::Polygon);
+                self.write_byte(0x20 | 0x10 | 0x08 | info);
+                self.write_layer_fields(info, layer, data_type);
+                self.write_point_list(points, true);
+            }
+            Geometry::Path {
+                half_width,
+                extension_scheme,
+                extensions,
+                points,
+            } => {
+                let info = self.layer_info_bits(layer, data_type) | repetition;
+                self.write_record(OasisRecord::Path);
+                self.write_byte(0x80 | 0x40 | 0x20 | 0x10 | 0x08 | info);
+                self.write_layer_fields(info, layer, data_type);
+                self.write_unsigned(*half_width);
+                self.write_unsigned(*extension_scheme);
+                if let Some((begin_extension, end_extension)) = extensions {
+                    self.write_signed(*begin_extension);
+                    self.write_signed(*end_extension);
+                }
+                self.write_point_list(points, false);
+            }
+            Geometry::Text(string) => {
+                let mut info = 0x40 | 0x10 | 0x08 | repetition;
+                if self.text_type != Some(data_type) {
+                    info |= 0x02;
+                }
+                if self.text_layer != Some(layer) {
+                    info |= 0x01;
+                }
+
+                self.write_record(OasisRecord::Text);
+                self.write_byte(info);
+                self.write_string(string);
+                if info & 0x01 != 0 {
+                    self.write_unsigned(layer as u64);
+                    self.text_layer = Some(layer);
+                }
+                if info & 0x02 != 0 {
+                    self.write_unsigned(data_type as u64);
+                    self.text_type = Some(data_type);
+                }
+            }
+            Geometry::Node(points) => {
+                let info = self.layer_info_bits(layer, data_type) | repetition;
+                let mut encoded = OasisWriter::new(self.scale);
+                encoded.write_point_list(points, false);
+
+                self.write_record(OasisRecord::XGeometry);
+                self.write_byte(0x10 | 0x08 | info);
+                self.write_unsigned(GDS_NODE_ATTRIBUTE);
+                self.write_layer_fields(info, layer, data_type);
+                self.write_bytes(&encoded.buffer);
+            }
+        }
+
+        self.write_signed(x);
+        self.write_signed(y);
+        if offsets.len() > 1 {
+            self.write_offsets(offsets);
+        }
+
+        self.write_properties(&placed.properties);
+    }
+
+    // Consecutive copies of the same geometry are written as one record with a repetition
+    fn write_geometries(&mut self, geometries: &[PlacedGeometry]) {
+        let mut start = 0;
+        while start < geometries.len() {
+            let first = &geometries[start];
+            let end = geometries[start..]
+                .iter()
+                .position(|placed| !placed.repeats(first))
+                .map_or(geometries.len(), |length| start + length);
+            let offsets = geometries[start..end]
+                .iter()
+                .map(|placed| {
+                    (
+                        placed.position.0 - first.position.0,
+                        placed.position.1 - first.position.1,
+                    )
+                })
+                .collect::<Vec<(i64, i64)>>();
+            self.write_geometry(first, &offsets);
+            start = end;
+        }
+    }
+
+    fn write_offsets(&mut self, offsets: &[(i64, i64)]) {
+        let count = offsets.len() as u64;
+        let row_step = offsets[1];
+        let on_line = |index: usize, step: (i64, i64)| {
+            offsets[index] == (step.0 * index as i64, step.1 * index as i64)
+        };
+
+        let rows = (0..offsets.len())
+            .find(|&index| !on_line(index, row_step))
+            .unwrap_or(offsets.len());
+        if rows == offsets.len() {
+            match row_step {
+                (x, 0) if x > 0 => {
+                    self.write_unsigned(2);
+                    self.write_unsigned(count - 2);
+                    self.write_unsigned(x as u64);
+                }
+                (0, y) if y > 0 => {
+                    self.write_unsigned(3);
+                    self.write_unsigned(count - 2);
+                    self.write_unsigned(y as u64);
+                }
+                _ => {
+                    self.write_unsigned(9);
+                    self.write_unsigned(count - 2);
+                    self.write_g_delta(row_step);
+                }
+            }
+            return;
+        }
+
+        // Regular repetitions are read back column by column
+        let column_step = offsets[rows];
+        let is_grid = offsets.len().is_multiple_of(rows)
+            && offsets.iter().enumerate().all(|(index, &offset)| {
+                let (column, row) = ((index / rows) as i64, (index % rows) as i64);
+                offset
+                    == (
+                        column * column_step.0 + row * row_step.0,
+                        column * column_step.1 + row * row_step.1,
+                    )
+            });
+        if is_grid {
+            let (columns, rows) = (count / rows as u64, rows as u64);
+            if column_step.1 == 0 && column_step.0 > 0 && row_step.0 == 0 && row_step.1 > 0 {
+                self.write_unsigned(1);
+                self.write_unsigned(columns - 2);
+                self.write_unsigned(rows - 2);
+                self.write_unsigned(column_step.0 as u64);
+                self.write_unsigned(row_step.1 as u64);
+            } else {
+                self.write_unsigned(8);
+                self.write_unsigned(columns - 2);
+                self.write_unsigned(rows - 2);
+                self.write_g_delta(column_step);
+                self.write_g_delta(row_step);
+            }
+            return;
+        }
+
+        self.write_unsigned(10);
+        self.write_unsigned(count - 2);
+        for window in offsets.windows(2) {
+            self.write_g_delta((window[1].0 - window[0].0, window[1].1 - window[0].1));
+        }
+    }
+
+    fn write_repetition(&mut self, grid: &Grid) {
+        let column_step = self.to_database(grid.spacing_x.rotate(grid.angle, Point::default()));
+        let row_step = self.to_database(grid.spacing_y.rotate(grid.angle, Point::default()));
+        let (columns, rows) = (grid.columns as u64, grid.rows as u64);
+
+        let is_orthogonal = (columns == 1 || (column_step.1 == 0 && column_step.0 > 0))
+            && (rows == 1 || (row_step.0 == 0 && row_step.1 > 0));
+
+        match (columns > 1, rows > 1, is_orthogonal) {
+            (true, true, true) => {
+                self.write_unsigned(1);
+                self.write_unsigned(columns - 2);
+                self.write_unsigned(rows - 2);
+                self.write_unsigned(column_step.0 as u64);
+                self.write_unsigned(row_step.1 as u64);
+            }
+            (true, false, true) => {
+                self.write_unsigned(2);
+                self.write_unsigned(columns - 2);
+                self.write_unsigned(column_step.0 as u64);
+            }
+            (false, true, true) => {
+                self.write_unsigned(3);
+                self.write_unsigned(rows - 2);
+                self.write_unsigned(row_step.1 as u64);
+            }
+            (true, true, false) => {
+                self.write_unsigned(8);
+                self.write_unsigned(columns - 2);
+                self.write_unsigned(rows - 2);
+                self.write_g_delta(column_step);
+                self.write_g_delta(row_step);
+            }
+            (true, false, false) => {
+                self.write_unsigned(9);
+                self.write_unsigned(columns - 2);
+                self.write_g_delta(column_step);
+            }
+            (false, true, false) => {
+                self.write_unsigned(9);
+                self.write_unsigned(rows - 2);
+                self.write_g_delta(row_step);
+            }
+            (false, false, _) => unreachable!(),
+        }
+    }
+
+    fn write_placement(&mut self, reference: &Reference, cell_number: u64, grid: &Grid) {
+        let (x, y) = self.to_database(grid.origin);
+        let angle = grid.angle.rem_euclid(360.0);
+        let has_repetition = grid.columns > 1 || grid.rows > 1;
+        let is_right_angle = (angle / 90.0).fract() == 0.0;
+
+        let mut info = 0x80 | 0x40 | 0x20 | 0x10;
+        if has_repetition {
+            info |= 0x08;
+        }
+        if grid.x_reflection {
+            info |= 0x01;
+        }
+
+        if grid.magnification == 1.0 && is_right_angle {
+            info |= ((angle / 90.0) as u8 & 0x03) << 1;
+            self.write_record(OasisRecord::Placement);
+            self.write_byte(info);
+            self.write_unsigned(cell_number);
+        } else {
+            info |= 0x04 | 0x02;
+            self.write_record(OasisRecord::PlacementTransform);
+            self.write_byte(info);
+            self.write_unsigned(cell_number);
+            self.write_real(grid.magnification);
+            self.write_real(angle);
+        }
+
+        self.write_signed(x);
+        self.write_signed(y);
+
+        if has_repetition {
+            self.write_repetition(grid);
+        }
+
+        self.write_properties(&reference.properties);
+    }
+
+    fn write_reference(
+        &mut self,
+        reference: &Reference,
+        cell_numbers: &HashMap<String, u64>,
+    ) -> PyResult<()> {
+        match &reference.instance {
+            Instance::Cell(cell) => {
+                let grid = reference.grid.read().clone();
+                let cell_number = cell_numbers[&cell.read().name];
+                self.write_placement(reference, cell_number, &grid);
+                Ok(())
+            }
+            Instance::Element(element) => self.write_elements(
+                reference._get_elements_in_grid(element.copy()),
+                cell_numbers,
+            ),
+        }
+    }
+
+    fn write_elements(
+        &mut self,
+        elements: Vec<Element>,
+        cell_numbers: &HashMap<String, u64>,
+    ) -> PyResult<()> {
+        let mut geometries = Vec::new();
+        let mut references = Vec::new();
+        for element in elements {
+            match element {
+                Element::Polygon(polygon) => {
+                    geometries.extend(self.polygon_geometry(&polygon.read()))
+                }
+                Element::Box(gds_box) => {
+                    geometries.extend(self.polygon_geometry(&gds_box.read().to_polygon()))
+                }
+                Element::Path(path) => geometries.extend(self.path_geometry(&path.read())?),
+                Element::Text(text) => geometries.push(self.text_geometry(&text.read())),
+                Element::Node(node) => geometries.extend(self.node_geometry(&node.read())),
+                Element::Reference(reference) => references.push(reference),
+            }
+        }
+
+        self.write_geometries(&geometries);
+
+        for reference in references {
+            self.write_reference(&reference.read(), cell_numbers)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_cell(&mut self, cell: &Cell, cell_numbers: &HashMap<String, u64>) -> PyResult<()> {
+        self.write_record(OasisRecord::CellReference);
+        self.write_unsigned(cell_numbers[&cell.name]);
+
+        // Modal variables are reset at the start of every cell, so each cell body is
+        // written on its own and compressed into a CBLOCK
+        let mut body = OasisWriter::new(self.scale);
+        let mut elements = cell.direct_elements(&[]);
+        elements.extend(cell.references.iter().cloned().map(Element::Reference));
+        body.write_elements(elements, cell_numbers)?;
+        self.write_cblock(&body.buffer);
+
+        Ok(())
+    }
+
+    fn write_cblock(&mut self, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        let compressed = compress_to_vec(data, CBLOCK_COMPRESSION_LEVEL);
+        self.write_record(OasisRecord::CBlock);
+        self.write_unsigned(0);
+        self.write_unsigned(data.len() as u64);
+        self.write_unsigned(compressed.len() as u64);
+        self.buffer.extend_from_slice(&compressed);
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum Geometry {
+    Rectangle {
+        width: i64,
+        height: i64,
+    },
+    Polygon(Vec<(i64, i64)>),
+    Path {
+        half_width: u64,
+        extension_scheme: u64,
+        extensions: Option<(i64, i64)>,
+        points: Vec<(i64, i64)>,
+    },
+    Text(String),
+    Node(Vec<(i64, i64)>),
+}
+
+/// An element converted to database units, with its points relative to `position`.
+struct PlacedGeometry {
+    geometry: Geometry,
+    layer: i32,
+    data_type: i32,
+    properties: HashMap<i32, String>,
+    position: (i64, i64),
+}
+
+impl PlacedGeometry {
+    fn repeats(&self, other: &PlacedGeometry) -> bool {
+        self.geometry == other.geometry
+            && self.layer == other.layer
+            && self.data_type == other.data_type
+            && self.properties == other.properties
+    }
+}
+
+fn relative_points(points: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let first = points[0];
+    points
+        .iter()
+        .map(|point| (point.0 - first.0, point.1 - first.1))
+        .collect()
+}
+
+// Rectangles are only used for counter-clockwise points starting at the lower left
+// corner, as that is the order they are read back in
+fn rectangle_bounds(points: &[(i64, i64)]) -> Option<((i64, i64), (i64, i64))> {
+    if points.len() != 4 {
+        return None;
+    }
+
+    let (min, max) = (points[0], points[2]);
+
+    if min.0 >= max.0 || min.1 >= max.1 {
+        return None;
+    }
+
+    if points[1] != (max.0, min.1) || points[3] != (min.0, max.1) {
+        return None;
+    }
+
+    Some((min, max))
+}
+
+pub fn write_oasis(
+    file_name: String,
+    units: f64,
+    precision: f64,
+    cells: Vec<Shared<Cell>>,
+) -> PyResult<String> {
+    let mut file = File::create(file_name.clone())
+        .map_err(|_| PyIOError::new_err("Could not open file for writing"))?;
+
+    let all_cells: Vec<Cell> = CellHierarchy::order(&cells)?
+        .iter()
+        .map(|cell| cell.read().clone())
+        .collect();
+
+    let unit = 1e-6 / precision;
+    let unit = if (unit - unit.round()).abs() < 1e-6 {
+        unit.round()
+    } else {
+        unit
+    };
+
+    let mut writer = OasisWriter::new(units / precision);
+    writer.write_start(unit);
+
+    writer.write_record(OasisRecord::PropName);
+    writer.write_string(GDS_PROPERTY_NAME);
+
+    let mut cell_numbers = HashMap::new();
+    for (number, cell) in all_cells.iter().enumerate() {
+        writer.write_record(OasisRecord::CellName);
+        writer.write_string(&cell.name);
+        cell_numbers.insert(cell.name.clone(), number as u64);
+    }
+
+    writer.write_record(OasisRecord::XName);
+    writer.write_unsigned(GDS_NODE_ATTRIBUTE);
+    writer.write_string(GDS_NODE_NAME);
+
+    for cell in &all_cells {
+        writer.write_cell(cell, &cell_numbers)?;
+    }
+
+    writer.write_end();
+
+    file.write_all(&writer.buffer)?;
+    file.flush()?;
+
+    Ok(file_name)
+}
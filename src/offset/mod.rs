@@ -0,0 +1,99 @@
+use i_overlay::core::overlay_rule::OverlayRule;
+use i_overlay::mesh::{
+    outline::offset::OutlineOffset,
+    style::{LineJoin, OutlineStyle},
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    config::DEFAULT_GRID,
+    polygon::Polygon,
+    utils::overlay::OverlayGrid,
+    validation::input::{check_data_type_valid, check_grid_valid, check_layer_valid},
+};
+
+const ROUND_JOIN_SEGMENT_RATIO: f64 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OffsetJoin {
+    Miter(f64),
+    Round,
+    Bevel,
+}
+
+impl OffsetJoin {
+    pub fn new(value: &str, miter_limit: f64) -> PyResult<Self> {
+        match value.to_lowercase().as_str() {
+            "miter" => {
+                if miter_limit < 1.0 {
+                    return Err(PyValueError::new_err(
+                        "Miter limit must be greater than or equal to 1",
+                    ));
+                }
+                Ok(OffsetJoin::Miter(miter_limit))
+            }
+            "round" => Ok(OffsetJoin::Round),
+            "bevel" => Ok(OffsetJoin::Bevel),
+            _ => Err(PyValueError::new_err(format!(
+                "Invalid join: {}, expected one of 'miter', 'round' or 'bevel'",
+                value
+            ))),
+        }
+    }
+
+    pub fn line_join(&self) -> LineJoin<f64> {
+        match self {
+            // A miter limit is the ratio of the miter length to the offset distance,
+            // whereas i_overlay expects the sharpest corner angle that is still mitered.
+            OffsetJoin::Miter(miter_limit) => LineJoin::Miter(2.0 * (1.0 / miter_limit).asin()),
+            OffsetJoin::Round => LineJoin::Round(ROUND_JOIN_SEGMENT_RATIO),
+            OffsetJoin::Bevel => LineJoin::Bevel,
+        }
+    }
+}
+
+pub fn offset_polygons(
+    polygons: &[Polygon],
+    distance: f64,
+    join: OffsetJoin,
+    layer: i32,
+    data_type: i32,
+    grid: f64,
+) -> PyResult<Vec<Polygon>> {
+    let grid = OverlayGrid::fit(polygons, distance, grid)?;
+    let shapes = grid.overlay(&grid.contours(polygons), &[], OverlayRule::Subject);
+
+    if distance == 0.0 {
+        return grid.shapes_to_polygons(shapes, layer, data_type);
+    }
+
+    let style = OutlineStyle::new(distance).line_join(join.line_join());
+    let outline = grid.to_relative_shapes(&shapes).outline(&style);
+
+    grid.shapes_to_polygons(grid.snap_relative_shapes(outline), layer, data_type)
+}
+
+#[pyfunction]
+#[pyo3(signature = (polygons, distance, join="miter", miter_limit=2.0, layer=0, data_type=0, grid=DEFAULT_GRID))]
+pub fn offset(
+    polygons: Vec<Polygon>,
+    distance: f64,
+    join: &str,
+    miter_limit: f64,
+    layer: i32,
+    data_type: i32,
+    grid: f64,
+) -> PyResult<Vec<Polygon>> {
+    check_layer_valid(layer)?;
+    check_data_type_valid(data_type)?;
+    check_grid_valid(grid)?;
+
+    offset_polygons(
+        &polygons,
+        distance,
+        OffsetJoin::new(join, miter_limit)?,
+        layer,
+        data_type,
+        grid,
+    )
+}
@@ -1,35 +1,42 @@
-use std::ops::DerefMut;
+use std::collections::HashMap;
 
-use pyo3::prelude::*;
+use pyo3::{exceptions::PyValueError, prelude::*};
 
 use crate::{
+    config::DEFAULT_GRID,
     point::Point,
+    polygon::Polygon,
+    shared::Shared,
     traits::{Dimensions, LayerDataTypeMatches, Movable, Rotatable, Scalable},
     utils::{
         geometry::perimeter,
         transformations::{py_any_to_point, py_any_to_points_vec},
     },
     validation::input::{
-        check_data_type_valid, check_layer_valid, check_points_vec_has_at_least_two_points,
+        check_data_type_valid, check_grid_valid, check_layer_valid,
+        check_points_vec_has_at_least_two_points, check_properties_valid,
     },
 };
 
-use super::{path_type::PathType, Path};
+use super::{path_type::PathType, utils::path_to_polygons, Path, PyPath};
 
-#[pymethods]
 impl Path {
-    #[new]
-    #[pyo3(signature = (points, layer=0, data_type=0, path_type=None, width=None))]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
+        points: Vec<Point>,
         layer: i32,
         data_type: i32,
         path_type: Option<PathType>,
         width: Option<f64>,
+        properties: Option<HashMap<i32, String>>,
+        begin_extension: f64,
+        end_extension: f64,
     ) -> PyResult<Self> {
         check_points_vec_has_at_least_two_points(&points)?;
         check_layer_valid(layer)?;
         check_data_type_valid(data_type)?;
+        let properties = properties.unwrap_or_default();
+        check_properties_valid(&properties)?;
 
         Ok(Self {
             points,
@@ -37,133 +44,275 @@ impl Path {
             data_type,
             path_type,
             width,
+            properties,
+            begin_extension,
+            end_extension,
         })
     }
 
+    pub fn to_polygons(&self, grid: f64) -> PyResult<Vec<Polygon>> {
+        path_to_polygons(self, grid)
+    }
+
+    pub fn to_polygon(&self, grid: f64) -> PyResult<Polygon> {
+        let mut polygons = self.to_polygons(grid)?;
+        match polygons.len() {
+            0 => Err(PyValueError::new_err("Path has no area, it needs a width")),
+            1 => Ok(polygons.remove(0)),
+            count => Err(PyValueError::new_err(format!(
+                "Path outline is made of {} polygons, use to_polygons instead",
+                count
+            ))),
+        }
+    }
+}
+
+#[pymethods]
+impl PyPath {
+    #[new]
+    #[pyo3(signature = (
+        points,
+        layer=0,
+        data_type=0,
+        path_type=None,
+        width=None,
+        properties=None,
+        begin_extension=0.0,
+        end_extension=0.0
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
+        layer: i32,
+        data_type: i32,
+        path_type: Option<PathType>,
+        width: Option<f64>,
+        properties: Option<HashMap<i32, String>>,
+        begin_extension: f64,
+        end_extension: f64,
+    ) -> PyResult<Self> {
+        Path::new(
+            points,
+            layer,
+            data_type,
+            path_type,
+            width,
+            properties,
+            begin_extension,
+            end_extension,
+        )
+        .map(|value| PyPath(Shared::new(value)))
+    }
+
+    #[getter]
+    fn points(&self) -> Vec<Point> {
+        self.0.read().points.clone()
+    }
+
+    #[getter]
+    fn layer(&self) -> i32 {
+        self.0.read().layer
+    }
+
+    #[getter]
+    fn data_type(&self) -> i32 {
+        self.0.read().data_type
+    }
+
+    #[getter]
+    fn path_type(&self) -> Option<PathType> {
+        self.0.read().path_type
+    }
+
+    #[getter]
+    fn width(&self) -> Option<f64> {
+        self.0.read().width
+    }
+
+    #[getter]
+    fn properties(&self) -> HashMap<i32, String> {
+        self.0.read().properties.clone()
+    }
+
+    #[getter]
+    fn begin_extension(&self) -> f64 {
+        self.0.read().begin_extension
+    }
+
+    #[getter]
+    fn end_extension(&self) -> f64 {
+        self.0.read().end_extension
+    }
+
     #[setter(points)]
-    fn setter_points(&mut self, #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>) {
+    fn setter_points(&self, #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>) {
         check_points_vec_has_at_least_two_points(&points).unwrap();
-        self.points = points;
+        self.0.write().points = points;
     }
 
     fn set_points(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
-    ) -> PyRefMut<'_, Self> {
+    ) -> PyRef<'_, Self> {
         slf.setter_points(points);
         slf
     }
 
     #[setter(layer)]
-    fn setter_layer(&mut self, layer: i32) -> PyResult<()> {
+    fn setter_layer(&self, layer: i32) -> PyResult<()> {
         check_layer_valid(layer)?;
-        self.layer = layer;
+        self.0.write().layer = layer;
         Ok(())
     }
 
-    fn set_layer(mut slf: PyRefMut<'_, Self>, layer: i32) -> PyRefMut<'_, Self> {
+    fn set_layer(slf: PyRef<'_, Self>, layer: i32) -> PyRef<'_, Self> {
         slf.setter_layer(layer).unwrap();
         slf
     }
 
     #[setter(data_type)]
-    fn setter_data_type(&mut self, data_type: i32) -> PyResult<()> {
+    fn setter_data_type(&self, data_type: i32) -> PyResult<()> {
         check_data_type_valid(data_type)?;
-        self.data_type = data_type;
+        self.0.write().data_type = data_type;
         Ok(())
     }
 
-    fn set_data_type(mut slf: PyRefMut<'_, Self>, data_type: i32) -> PyRefMut<'_, Self> {
+    fn set_data_type(slf: PyRef<'_, Self>, data_type: i32) -> PyRef<'_, Self> {
         slf.setter_data_type(data_type).unwrap();
         slf
     }
 
     #[setter(path_type)]
-    fn setter_path_type(&mut self, path_type: Option<PathType>) {
-        self.path_type = path_type;
+    fn setter_path_type(&self, path_type: Option<PathType>) {
+        self.0.write().path_type = path_type;
     }
 
     #[pyo3(signature = (path_type=None))]
-    fn set_path_type(
-        mut slf: PyRefMut<'_, Self>,
-        path_type: Option<PathType>,
-    ) -> PyRefMut<'_, Self> {
+    fn set_path_type(slf: PyRef<'_, Self>, path_type: Option<PathType>) -> PyRef<'_, Self> {
         slf.setter_path_type(path_type);
         slf
     }
 
     #[setter(width)]
-    fn setter_width(&mut self, width: Option<f64>) {
-        self.width = width;
+    fn setter_width(&self, width: Option<f64>) {
+        self.0.write().width = width;
     }
 
     #[pyo3(signature = (width=None))]
-    fn set_width(mut slf: PyRefMut<'_, Self>, width: Option<f64>) -> PyRefMut<'_, Self> {
+    fn set_width(slf: PyRef<'_, Self>, width: Option<f64>) -> PyRef<'_, Self> {
         slf.setter_width(width);
         slf
     }
 
+    #[setter(begin_extension)]
+    fn setter_begin_extension(&self, begin_extension: f64) {
+        self.0.write().begin_extension = begin_extension;
+    }
+
+    fn set_begin_extension(slf: PyRef<'_, Self>, begin_extension: f64) -> PyRef<'_, Self> {
+        slf.setter_begin_extension(begin_extension);
+        slf
+    }
+
+    #[setter(end_extension)]
+    fn setter_end_extension(&self, end_extension: f64) {
+        self.0.write().end_extension = end_extension;
+    }
+
+    fn set_end_extension(slf: PyRef<'_, Self>, end_extension: f64) -> PyRef<'_, Self> {
+        slf.setter_end_extension(end_extension);
+        slf
+    }
+
+    #[setter(properties)]
+    fn setter_properties(&self, properties: HashMap<i32, String>) -> PyResult<()> {
+        check_properties_valid(&properties)?;
+        self.0.write().properties = properties;
+        Ok(())
+    }
+
+    fn set_properties(
+        slf: PyRef<'_, Self>,
+        properties: HashMap<i32, String>,
+    ) -> PyResult<PyRef<'_, Self>> {
+        slf.setter_properties(properties)?;
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (grid=DEFAULT_GRID))]
+    pub fn to_polygons(&self, grid: f64) -> PyResult<Vec<Polygon>> {
+        check_grid_valid(grid)?;
+        self.0.read().to_polygons(grid)
+    }
+
+    #[pyo3(signature = (grid=DEFAULT_GRID))]
+    pub fn to_polygon(&self, grid: f64) -> PyResult<Polygon> {
+        check_grid_valid(grid)?;
+        self.0.read().to_polygon(grid)
+    }
+
     #[getter]
     fn length(&self) -> PyResult<f64> {
-        perimeter(&self.points)
+        let path = self.0.read();
+        perimeter(&path.points)
     }
 
     #[getter]
     fn bounding_box(&self) -> (Point, Point) {
-        Dimensions::bounding_box(self)
+        Dimensions::bounding_box(&*self.0.read())
     }
 
     pub fn copy(&self) -> Self {
-        self.clone()
+        Self(self.0.copy())
     }
 
     fn move_to(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] point: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_to(slf.deref_mut(), point);
+    ) -> PyRef<'_, Self> {
+        Movable::move_to(&mut *slf.0.write(), point);
 
         slf
     }
 
     fn move_by(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] vector: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_by(slf.deref_mut(), vector);
+    ) -> PyRef<'_, Self> {
+        Movable::move_by(&mut *slf.0.write(), vector);
         slf
     }
 
     #[pyo3(signature = (angle, centre=Point::default()))]
     fn rotate(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         angle: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Rotatable::rotate(slf.deref_mut(), angle, centre);
+    ) -> PyRef<'_, Self> {
+        Rotatable::rotate(&mut *slf.0.write(), angle, centre);
         slf
     }
 
     #[pyo3(signature = (factor, centre=Point::default()))]
     fn scale(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         factor: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Scalable::scale(slf.deref_mut(), factor, centre);
+    ) -> PyRef<'_, Self> {
+        Scalable::scale(&mut *slf.0.write(), factor, centre);
         slf
     }
 
     #[pyo3(signature = (*layer_data_types))]
     pub fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
-        LayerDataTypeMatches::is_on(self, layer_data_types)
+        LayerDataTypeMatches::is_on(&*self.0.read(), layer_data_types)
     }
 
     fn __str__(&self) -> PyResult<String> {
-        Ok(format!("{}", self))
+        Ok(format!("{}", self.0))
     }
 
     fn __repr__(&self) -> PyResult<String> {
-        Ok(format!("{:?}", self))
+        Ok(format!("{:?}", self.0))
     }
 }
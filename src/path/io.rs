@@ -2,19 +2,45 @@ use pyo3::{exceptions::PyValueError, prelude::*};
 use std::{fs::File, io::Write};
 
 use crate::{
-    config::gds_file_types::{combine_record_and_data_type, GDSDataType, GDSRecord},
+    config::{
+        gds_file_types::{combine_record_and_data_type, GDSDataType, GDSRecord},
+        max_points_mode::{MaxPointsMode, MAX_POINTS},
+    },
     traits::ToGds,
-    utils::io::{write_element_tail_to_file, write_points_to_file, write_u16_array_to_file},
+    utils::io::{
+        write_element_tail_to_file, write_points_to_file, write_properties_to_file,
+        write_u16_array_to_file,
+    },
 };
 
-use super::{path_type::PathType, Path};
+use super::{path_type::PathType, utils::split_path, Path};
 
 impl ToGds for Path {
-    fn _to_gds(&self, mut file: File, scale: f64) -> PyResult<File> {
+    fn _to_gds(
+        &self,
+        mut file: File,
+        scale: f64,
+        max_points_mode: MaxPointsMode,
+    ) -> PyResult<File> {
         if self.points.len() < 2 {
             return Err(PyValueError::new_err("Path must have at least 2 points"));
         }
 
+        if self.points.len() > MAX_POINTS {
+            match max_points_mode {
+                MaxPointsMode::Error => Err(PyValueError::new_err(
+                    "A path can only have a maximum of 8191 points",
+                ))?,
+                MaxPointsMode::Fracture => {
+                    for path in split_path(self) {
+                        file = path._to_gds(file, scale, MaxPointsMode::Error)?;
+                    }
+                    return Ok(file);
+                }
+                MaxPointsMode::Extended => {}
+            }
+        }
+
         let mut path_head = [
             4,
             combine_record_and_data_type(GDSRecord::Path, GDSDataType::NoData),
@@ -57,8 +83,26 @@ impl ToGds for Path {
             file.write_all(&bytes)?;
         }
 
+        if self.path_type == Some(PathType::Custom) {
+            for (record, extension) in [
+                (GDSRecord::BgnExtn, self.begin_extension),
+                (GDSRecord::EndExtn, self.end_extension),
+            ] {
+                let mut extension_head = [
+                    8,
+                    combine_record_and_data_type(record, GDSDataType::FourByteSignedInteger),
+                ];
+
+                file = write_u16_array_to_file(file, &mut extension_head)?;
+
+                file.write_all(&((extension * scale).round() as i32).to_be_bytes())?;
+            }
+        }
+
         file = write_points_to_file(file, &self.points, scale)?;
 
+        file = write_properties_to_file(file, &self.properties)?;
+
         file = write_element_tail_to_file(file)?;
 
         Ok(file)
@@ -7,6 +7,7 @@ pub enum PathType {
     Square = 0,
     Round = 1,
     Overlap = 2,
+    Custom = 4,
 }
 
 impl std::fmt::Display for PathType {
@@ -29,6 +30,7 @@ impl PathType {
             0 => Ok(PathType::Square),
             1 => Ok(PathType::Round),
             2 => Ok(PathType::Overlap),
+            4 => Ok(PathType::Custom),
             _ => Err(PyValueError::new_err("Invalid value for PathType")),
         }
     }
@@ -39,6 +41,7 @@ impl PathType {
             PathType::Square => Ok("Square Ends".to_string()),
             PathType::Round => Ok("Round Ends".to_string()),
             PathType::Overlap => Ok("Overlap Ends".to_string()),
+            PathType::Custom => Ok("Custom Ends".to_string()),
         }
     }
 
@@ -49,7 +52,12 @@ impl PathType {
 
     #[staticmethod]
     pub fn values() -> Vec<PathType> {
-        vec![PathType::Square, PathType::Round, PathType::Overlap]
+        vec![
+            PathType::Square,
+            PathType::Round,
+            PathType::Overlap,
+            PathType::Custom,
+        ]
     }
 
     fn __str__(&self) -> PyResult<String> {
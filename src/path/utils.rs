@@ -0,0 +1,135 @@
+use i_overlay::mesh::{
+    stroke::offset::StrokeOffset,
+    style::{LineCap, StrokeStyle},
+};
+
+use pyo3::prelude::*;
+
+use crate::{
+    config::max_points_mode::MAX_POINTS, offset::OffsetJoin, point::Point, polygon::Polygon,
+    utils::overlay::OverlayGrid,
+};
+
+use super::{path_type::PathType, Path};
+
+const ROUND_CAP_SEGMENT_ANGLE: f64 = 0.1;
+const JOIN_MITER_LIMIT: f64 = 2.0;
+
+pub fn split_path(path: &Path) -> Vec<Path> {
+    let mut paths = Vec::new();
+
+    let Some(&first) = path.points.first() else {
+        return vec![path.clone()];
+    };
+
+    let mut current = vec![first];
+
+    for (i, window) in path.points.windows(2).enumerate() {
+        let is_last_point = i + 2 == path.points.len();
+
+        // Splitting in the middle of a segment lets the chained paths meet end to end
+        if current.len() == MAX_POINTS - 1 && !is_last_point {
+            let midpoint = (window[0] + window[1]) * 0.5;
+            current.push(midpoint);
+            paths.push(Path {
+                points: current,
+                ..path.clone()
+            });
+            current = vec![midpoint];
+        }
+
+        current.push(window[1]);
+    }
+
+    paths.push(Path {
+        points: current,
+        ..path.clone()
+    });
+
+    // Custom extensions only belong on the outer ends of the chain
+    if paths.len() > 1 {
+        let last = paths.len() - 1;
+        for (i, piece) in paths.iter_mut().enumerate() {
+            if i != 0 {
+                piece.begin_extension = 0.0;
+            }
+            if i != last {
+                piece.end_extension = 0.0;
+            }
+        }
+    }
+
+    paths
+}
+
+fn extend_end(end: Point, previous: Point, extension: f64) -> Point {
+    let direction = end - previous;
+    let length = direction.x.hypot(direction.y);
+    end + direction * (extension / length)
+}
+
+/// Strokes the path into outline shapes, each an outer contour followed by its holes.
+pub fn path_outline(path: &Path) -> Vec<Vec<Vec<[f64; 2]>>> {
+    let width = path.width.unwrap_or(0.0).abs();
+
+    let mut points = path.points.clone();
+    points.dedup_by(|a, b| a.epsilon_is_close(*b));
+
+    if width == 0.0 || points.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut style =
+        StrokeStyle::new(width).line_join(OffsetJoin::Miter(JOIN_MITER_LIMIT).line_join());
+
+    match path.path_type.unwrap_or_default() {
+        PathType::Square => {}
+        PathType::Round => {
+            style = style
+                .start_cap(LineCap::Round(ROUND_CAP_SEGMENT_ANGLE))
+                .end_cap(LineCap::Round(ROUND_CAP_SEGMENT_ANGLE));
+        }
+        PathType::Overlap => {
+            style = style.start_cap(LineCap::Square).end_cap(LineCap::Square);
+        }
+        PathType::Custom => {
+            let last = points.len() - 1;
+            points[0] = extend_end(points[0], points[1], path.begin_extension);
+            points[last] = extend_end(points[last], points[last - 1], path.end_extension);
+        }
+    }
+
+    let contour = points
+        .iter()
+        .map(|point| [point.x, point.y])
+        .collect::<Vec<[f64; 2]>>();
+
+    contour.stroke(style, false)
+}
+
+pub fn path_to_polygons(path: &Path, grid: f64) -> PyResult<Vec<Polygon>> {
+    let outline = path_outline(path);
+    let grid = OverlayGrid::fit_shapes(&outline, grid)?;
+    Ok(grid
+        .shapes_to_polygons(grid.snap_shapes(outline), path.layer, path.data_type)?
+        .into_iter()
+        .map(|polygon| Polygon {
+            properties: path.properties.clone(),
+            ..polygon
+        })
+        .collect())
+}
+
+/// Points of the stroked outline of the path, or its centre-line when it has no area.
+pub fn path_outline_points(path: &Path) -> Vec<Point> {
+    let outline = path_outline(path);
+    if outline.is_empty() {
+        return path.points.clone();
+    }
+    outline
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|[x, y]| Point::new(x, y))
+        .collect()
+}
@@ -1,94 +1,159 @@
-use std::{f64::consts::PI, ops::DerefMut};
+use std::{collections::HashMap, f64::consts::PI};
 
 use plotly::{common::Mode, layout::Margin, plot::Plot, Layout, Scatter};
 
 use pyo3::prelude::*;
 
 use crate::{
+    boolean::{boolean_polygons, BooleanOperation},
+    config::DEFAULT_GRID,
+    offset::{offset_polygons, OffsetJoin},
     point::Point,
+    shared::Shared,
+    text::{
+        presentation::{HorizontalPresentation, VerticalPresentation},
+        utils::text_to_polygons,
+        Text,
+    },
     traits::{Dimensions, LayerDataTypeMatches, Movable, Rotatable, Scalable},
     utils::{
         geometry::{area, is_point_inside, is_point_on_edge, perimeter},
         transformations::{py_any_to_point, py_any_to_points_vec},
     },
-    validation::input::{check_data_type_valid, check_layer_valid},
+    validation::input::{
+        check_data_type_valid, check_grid_valid, check_layer_valid, check_properties_valid,
+    },
 };
 
-use super::{utils::get_correct_polygon_points_format, Polygon};
+use super::{utils::get_correct_polygon_points_format, Polygon, PyPolygon};
 
-#[pymethods]
 impl Polygon {
-    #[new]
-    #[pyo3(signature = (points, layer=0, data_type=0))]
     pub fn new(
-        #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
+        points: Vec<Point>,
         layer: i32,
         data_type: i32,
+        properties: Option<HashMap<i32, String>>,
     ) -> PyResult<Self> {
         check_layer_valid(layer)?;
         check_data_type_valid(data_type)?;
+        let properties = properties.unwrap_or_default();
+        check_properties_valid(&properties)?;
 
         Ok(Self {
             points: get_correct_polygon_points_format(points),
             layer,
             data_type,
+            properties,
         })
     }
+}
+
+#[pymethods]
+impl PyPolygon {
+    #[new]
+    #[pyo3(signature = (points, layer=0, data_type=0, properties=None))]
+    fn new(
+        #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
+        layer: i32,
+        data_type: i32,
+        properties: Option<HashMap<i32, String>>,
+    ) -> PyResult<Self> {
+        Polygon::new(points, layer, data_type, properties)
+            .map(|value| PyPolygon(Shared::new(value)))
+    }
+
+    #[getter]
+    fn points(&self) -> Vec<Point> {
+        self.0.read().points.clone()
+    }
+
+    #[getter]
+    fn layer(&self) -> i32 {
+        self.0.read().layer
+    }
+
+    #[getter]
+    fn data_type(&self) -> i32 {
+        self.0.read().data_type
+    }
+
+    #[getter]
+    fn properties(&self) -> HashMap<i32, String> {
+        self.0.read().properties.clone()
+    }
 
     #[setter(points)]
-    fn setter_points(&mut self, #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>) {
-        self.points = get_correct_polygon_points_format(points);
+    fn setter_points(&self, #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>) {
+        self.0.write().points = get_correct_polygon_points_format(points);
     }
 
     fn set_points(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
-    ) -> PyRefMut<'_, Self> {
+    ) -> PyRef<'_, Self> {
         slf.setter_points(points);
         slf
     }
 
     #[setter(layer)]
-    fn setter_layer(&mut self, layer: i32) -> PyResult<()> {
+    fn setter_layer(&self, layer: i32) -> PyResult<()> {
         check_layer_valid(layer)?;
-        self.layer = layer;
+        self.0.write().layer = layer;
         Ok(())
     }
 
-    fn set_layer(mut slf: PyRefMut<'_, Self>, layer: i32) -> PyRefMut<'_, Self> {
+    fn set_layer(slf: PyRef<'_, Self>, layer: i32) -> PyRef<'_, Self> {
         slf.setter_layer(layer).unwrap();
         slf
     }
 
     #[setter(data_type)]
-    fn setter_data_type(&mut self, data_type: i32) -> PyResult<()> {
+    fn setter_data_type(&self, data_type: i32) -> PyResult<()> {
         check_data_type_valid(data_type)?;
-        self.data_type = data_type;
+        self.0.write().data_type = data_type;
         Ok(())
     }
 
-    fn set_data_type(mut slf: PyRefMut<'_, Self>, data_type: i32) -> PyRefMut<'_, Self> {
+    fn set_data_type(slf: PyRef<'_, Self>, data_type: i32) -> PyRef<'_, Self> {
         slf.setter_data_type(data_type).unwrap();
         slf
     }
 
+    #[setter(properties)]
+    fn setter_properties(&self, properties: HashMap<i32, String>) -> PyResult<()> {
+        check_properties_valid(&properties)?;
+        self.0.write().properties = properties;
+        Ok(())
+    }
+
+    fn set_properties(
+        slf: PyRef<'_, Self>,
+        properties: HashMap<i32, String>,
+    ) -> PyResult<PyRef<'_, Self>> {
+        slf.setter_properties(properties)?;
+        Ok(slf)
+    }
+
     #[getter]
     fn bounding_box(&self) -> (Point, Point) {
-        Dimensions::bounding_box(self)
+        Dimensions::bounding_box(&*self.0.read())
     }
 
     #[getter]
     fn area(&self) -> PyResult<f64> {
-        area(&self.points)
+        let polygon = self.0.read();
+        area(&polygon.points)
     }
 
     #[getter]
     fn perimeter(&self) -> PyResult<f64> {
-        perimeter(&self.points)
+        let polygon = self.0.read();
+        perimeter(&polygon.points)
     }
 
     fn contains(&self, #[pyo3(from_py_with = "py_any_to_point")] point: Point) -> bool {
-        is_point_inside(&point, &self.points)
+        let polygon = self.0.read();
+        is_point_inside(&point, &polygon.points)
     }
 
     #[pyo3(signature = (*points))]
@@ -96,7 +161,8 @@ impl Polygon {
         &self,
         #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
     ) -> bool {
-        points.iter().all(|p| is_point_inside(p, &self.points))
+        let polygon = self.0.read();
+        points.iter().all(|p| is_point_inside(p, &polygon.points))
     }
 
     #[pyo3(signature = (*points))]
@@ -104,11 +170,13 @@ impl Polygon {
         &self,
         #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
     ) -> bool {
-        points.iter().any(|p| is_point_inside(p, &self.points))
+        let polygon = self.0.read();
+        points.iter().any(|p| is_point_inside(p, &polygon.points))
     }
 
     fn on_edge(&self, #[pyo3(from_py_with = "py_any_to_point")] point: Point) -> bool {
-        is_point_on_edge(&point, &self.points)
+        let polygon = self.0.read();
+        is_point_on_edge(&point, &polygon.points)
     }
 
     #[pyo3(signature = (*points))]
@@ -116,7 +184,8 @@ impl Polygon {
         &self,
         #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
     ) -> bool {
-        points.iter().all(|p| is_point_on_edge(p, &self.points))
+        let polygon = self.0.read();
+        points.iter().all(|p| is_point_on_edge(p, &polygon.points))
     }
 
     #[pyo3(signature = (*points))]
@@ -124,22 +193,60 @@ impl Polygon {
         &self,
         #[pyo3(from_py_with = "py_any_to_points_vec")] points: Vec<Point>,
     ) -> bool {
-        points.iter().any(|p| is_point_on_edge(p, &self.points))
+        let polygon = self.0.read();
+        points.iter().any(|p| is_point_on_edge(p, &polygon.points))
     }
 
     fn intersects(&self, other: Polygon) -> bool {
-        self.points
+        let polygon = self.0.read();
+        polygon
+            .points
             .iter()
             .any(|p| is_point_inside(p, &other.points))
             || other
                 .points
                 .iter()
-                .any(|p| is_point_inside(p, &self.points))
+                .any(|p| is_point_inside(p, &polygon.points))
+    }
+
+    #[pyo3(signature = (other, operation, grid=DEFAULT_GRID))]
+    fn boolean(&self, other: Polygon, operation: &str, grid: f64) -> PyResult<Vec<Polygon>> {
+        let polygon = self.0.read();
+        check_grid_valid(grid)?;
+        boolean_polygons(
+            std::slice::from_ref(&*polygon),
+            std::slice::from_ref(&other),
+            BooleanOperation::new(operation)?,
+            polygon.layer,
+            polygon.data_type,
+            grid,
+        )
+    }
+
+    #[pyo3(signature = (distance, join="miter", miter_limit=2.0, grid=DEFAULT_GRID))]
+    fn offset(
+        &self,
+        distance: f64,
+        join: &str,
+        miter_limit: f64,
+        grid: f64,
+    ) -> PyResult<Vec<Polygon>> {
+        let polygon = self.0.read();
+        check_grid_valid(grid)?;
+        offset_polygons(
+            std::slice::from_ref(&*polygon),
+            distance,
+            OffsetJoin::new(join, miter_limit)?,
+            polygon.layer,
+            polygon.data_type,
+            grid,
+        )
     }
 
     fn visualize(&self) -> PyResult<()> {
-        let x: Vec<f64> = self.points.iter().map(|p| p.x).collect();
-        let y: Vec<f64> = self.points.iter().map(|p| p.y).collect();
+        let polygon = self.0.read();
+        let x: Vec<f64> = polygon.points.iter().map(|p| p.x).collect();
+        let y: Vec<f64> = polygon.points.iter().map(|p| p.y).collect();
 
         let trace = Scatter::new(x, y).mode(Mode::Lines).name("Polygon");
 
@@ -155,48 +262,48 @@ impl Polygon {
         Ok(())
     }
     pub fn copy(&self) -> Self {
-        self.clone()
+        Self(self.0.copy())
     }
 
     fn move_to(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] point: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_to(slf.deref_mut(), point);
+    ) -> PyRef<'_, Self> {
+        Movable::move_to(&mut *slf.0.write(), point);
         slf
     }
 
     fn move_by(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] vector: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_by(slf.deref_mut(), vector);
+    ) -> PyRef<'_, Self> {
+        Movable::move_by(&mut *slf.0.write(), vector);
         slf
     }
 
     #[pyo3(signature = (angle, centre=Point::default()))]
     fn rotate(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         angle: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Rotatable::rotate(slf.deref_mut(), angle, centre);
+    ) -> PyRef<'_, Self> {
+        Rotatable::rotate(&mut *slf.0.write(), angle, centre);
         slf
     }
 
     #[pyo3(signature = (factor, centre=Point::default()))]
     fn scale(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         factor: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Scalable::scale(slf.deref_mut(), factor, centre);
+    ) -> PyRef<'_, Self> {
+        Scalable::scale(&mut *slf.0.write(), factor, centre);
         slf
     }
 
     #[pyo3(signature = (*layer_data_types))]
     pub fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
-        LayerDataTypeMatches::is_on(self, layer_data_types)
+        LayerDataTypeMatches::is_on(&*self.0.read(), layer_data_types)
     }
 
     #[staticmethod]
@@ -221,7 +328,7 @@ impl Polygon {
 
         points.push(points[0]);
 
-        Polygon::new(points, layer, data_type)
+        Polygon::new(points, layer, data_type, None)
     }
 
     #[staticmethod]
@@ -263,14 +370,45 @@ impl Polygon {
             points,
             layer,
             data_type,
+            properties: HashMap::new(),
         }
     }
 
+    #[staticmethod]
+    #[pyo3(signature = (text, size=1.0, origin=Point::default(), angle=0.0, layer=0, data_type=0, grid=DEFAULT_GRID))]
+    pub fn text(
+        text: String,
+        size: f64,
+        #[pyo3(from_py_with = "py_any_to_point")] origin: Point,
+        angle: f64,
+        layer: i32,
+        data_type: i32,
+        grid: f64,
+    ) -> PyResult<Vec<Polygon>> {
+        check_layer_valid(layer)?;
+        check_data_type_valid(data_type)?;
+        check_grid_valid(grid)?;
+
+        let text = Text {
+            text,
+            origin,
+            layer,
+            text_type: data_type,
+            magnification: size,
+            angle,
+            vertical_presentation: VerticalPresentation::Bottom,
+            horizontal_presentation: HorizontalPresentation::Left,
+            ..Text::default()
+        };
+
+        text_to_polygons(&text, None, grid)
+    }
+
     fn __str__(&self) -> PyResult<String> {
-        Ok(format!("{}", self))
+        Ok(format!("{}", self.0))
     }
 
     fn __repr__(&self) -> PyResult<String> {
-        Ok(format!("{:?}", self))
+        Ok(format!("{:?}", self.0))
     }
 }
@@ -2,19 +2,40 @@ use pyo3::{exceptions::PyValueError, prelude::*};
 use std::fs::File;
 
 use crate::{
-    config::gds_file_types::{combine_record_and_data_type, GDSDataType, GDSRecord},
+    config::{
+        gds_file_types::{combine_record_and_data_type, GDSDataType, GDSRecord},
+        max_points_mode::{MaxPointsMode, MAX_POINTS},
+    },
     traits::ToGds,
-    utils::io::{write_element_tail_to_file, write_points_to_file, write_u16_array_to_file},
+    utils::io::{
+        write_element_tail_to_file, write_points_to_file, write_properties_to_file,
+        write_u16_array_to_file,
+    },
 };
 
-use super::Polygon;
+use super::{utils::fracture_polygon, Polygon};
 
 impl ToGds for Polygon {
-    fn _to_gds(&self, mut file: File, scale: f64) -> PyResult<File> {
-        if self.points.len() > 8191 {
-            Err(PyValueError::new_err(
-                "A polygon can only have a maximum of 8191 points",
-            ))?;
+    fn _to_gds(
+        &self,
+        mut file: File,
+        scale: f64,
+        max_points_mode: MaxPointsMode,
+    ) -> PyResult<File> {
+        if self.points.len() > MAX_POINTS {
+            match max_points_mode {
+                MaxPointsMode::Error => Err(PyValueError::new_err(
+                    "A polygon can only have a maximum of 8191 points",
+                ))?,
+                MaxPointsMode::Fracture => {
+                    // Pieces are snapped to the database unit they are written in
+                    for polygon in fracture_polygon(self, 1.0 / scale)? {
+                        file = polygon._to_gds(file, scale, MaxPointsMode::Error)?;
+                    }
+                    return Ok(file);
+                }
+                MaxPointsMode::Extended => {}
+            }
         }
 
         let mut polygon_head = [
@@ -32,6 +53,8 @@ impl ToGds for Polygon {
 
         file = write_points_to_file(file, &self.points, scale)?;
 
+        file = write_properties_to_file(file, &self.properties)?;
+
         file = write_element_tail_to_file(file)?;
 
         Ok(file)
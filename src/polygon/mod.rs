@@ -1,5 +1,12 @@
+use std::collections::HashMap;
+
 use crate::{
     point::Point,
+    shared::{
+        revision::Tracked,
+        wrapper::{extract_value, value_into_py, Wrapped, Wrapper},
+        Shared,
+    },
     traits::{Dimensions, LayerDataTypeMatches, Movable, Reflect, Rotatable, Scalable},
     utils::geometry::bounding_box,
 };
@@ -7,17 +14,48 @@ use pyo3::prelude::*;
 
 mod general;
 mod io;
-mod utils;
+pub mod utils;
 
-#[pyclass(eq)]
 #[derive(Clone, Default)]
 pub struct Polygon {
-    #[pyo3(get)]
     pub points: Vec<Point>,
-    #[pyo3(get)]
     pub layer: i32,
-    #[pyo3(get)]
     pub data_type: i32,
+    pub properties: HashMap<i32, String>,
+}
+
+#[pyclass(name = "Polygon", eq, frozen, weakref)]
+#[derive(PartialEq)]
+pub struct PyPolygon(pub Shared<Polygon>);
+
+impl Wrapper for PyPolygon {
+    type Inner = Polygon;
+
+    fn wrap(shared: Shared<Polygon>) -> Self {
+        PyPolygon(shared)
+    }
+
+    fn shared(&self) -> &Shared<Polygon> {
+        &self.0
+    }
+}
+
+impl Wrapped for Polygon {
+    type Wrapper = PyPolygon;
+}
+
+impl Tracked for Polygon {}
+
+impl IntoPy<PyObject> for Polygon {
+    fn into_py(self, py: Python) -> PyObject {
+        value_into_py(self, py)
+    }
+}
+
+impl FromPyObject<'_> for Polygon {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        extract_value(ob)
+    }
 }
 
 impl PartialEq for Polygon {
@@ -32,7 +70,9 @@ impl PartialEq for Polygon {
             }
         }
 
-        self.layer == other.layer && self.data_type == other.data_type
+        self.layer == other.layer
+            && self.data_type == other.data_type
+            && self.properties == other.properties
     }
 }
 
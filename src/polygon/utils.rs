@@ -1,6 +1,14 @@
 use log::info;
+use pyo3::prelude::*;
 
-use crate::point::Point;
+use crate::{
+    boolean::{boolean_polygons, BooleanOperation},
+    config::max_points_mode::MAX_POINTS,
+    point::Point,
+    utils::geometry::{bounding_box, rectangle},
+};
+
+use super::Polygon;
 
 fn are_points_closed(points: &[Point]) -> bool {
     points.first() == points.last()
@@ -20,3 +28,48 @@ pub fn close_points(points: &[Point]) -> Vec<Point> {
 pub fn get_correct_polygon_points_format(points: Vec<Point>) -> Vec<Point> {
     close_points(&points)
 }
+
+/// Splits a polygon in half until every piece has at most `MAX_POINTS` points, snapping
+/// the pieces to `grid`.
+pub fn fracture_polygon(polygon: &Polygon, grid: f64) -> PyResult<Vec<Polygon>> {
+    if polygon.points.len() <= MAX_POINTS {
+        return Ok(vec![polygon.clone()]);
+    }
+
+    let (min, max) = bounding_box(&polygon.points);
+
+    let halves = if max.x - min.x >= max.y - min.y {
+        let middle = (min.x + max.x) / 2.0;
+        [
+            (min, Point::new(middle, max.y)),
+            (Point::new(middle, min.y), max),
+        ]
+    } else {
+        let middle = (min.y + max.y) / 2.0;
+        [
+            (min, Point::new(max.x, middle)),
+            (Point::new(min.x, middle), max),
+        ]
+    };
+
+    let mut pieces = Vec::new();
+    for (half_min, half_max) in halves {
+        for mut piece in boolean_polygons(
+            std::slice::from_ref(polygon),
+            &[rectangle(
+                half_min,
+                half_max,
+                polygon.layer,
+                polygon.data_type,
+            )],
+            BooleanOperation::Intersection,
+            polygon.layer,
+            polygon.data_type,
+            grid,
+        )? {
+            piece.properties = polygon.properties.clone();
+            pieces.extend(fracture_polygon(&piece, grid)?);
+        }
+    }
+    Ok(pieces)
+}
@@ -0,0 +1,68 @@
+use pyo3::prelude::*;
+
+use crate::{cell::Cell, element::Element, utils::transformations::py_any_path_to_string};
+
+use super::{GdsCellIterator, GdsElementIterator, GdsEvent, GdsFilter, GdsReader};
+
+#[pymethods]
+impl GdsCellIterator {
+    #[new]
+    #[pyo3(signature=(file_name, cells=None, layers=None))]
+    pub fn new(
+        #[pyo3(from_py_with = "py_any_path_to_string")] file_name: String,
+        cells: Option<Vec<String>>,
+        layers: Option<Vec<(i32, i32)>>,
+    ) -> PyResult<Self> {
+        Ok(GdsCellIterator {
+            reader: GdsReader::open(file_name, GdsFilter::new(cells, layers))?,
+        })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<Cell>> {
+        self.reader.next_cell()
+    }
+}
+
+#[pymethods]
+impl GdsElementIterator {
+    #[new]
+    #[pyo3(signature=(file_name, cells=None, layers=None))]
+    pub fn new(
+        #[pyo3(from_py_with = "py_any_path_to_string")] file_name: String,
+        cells: Option<Vec<String>>,
+        layers: Option<Vec<(i32, i32)>>,
+    ) -> PyResult<Self> {
+        Ok(GdsElementIterator {
+            reader: GdsReader::open(file_name, GdsFilter::new(cells, layers))?,
+            cell_name: None,
+        })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<(String, Element)>> {
+        while let Some(event) = self.reader.next_event()? {
+            match event {
+                GdsEvent::BeginCell(cell) => self.cell_name = Some(cell.name),
+                GdsEvent::EndCell => self.cell_name = None,
+                GdsEvent::Element(element) => {
+                    if let Some(cell_name) = &self.cell_name {
+                        return Ok(Some((cell_name.clone(), element)));
+                    }
+                }
+                GdsEvent::BeginLibrary(..)
+                | GdsEvent::LibraryName(_)
+                | GdsEvent::Units(..)
+                | GdsEvent::EndLibrary => {}
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,519 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+use chrono::NaiveDateTime;
+use pyo3::prelude::*;
+
+use crate::{
+    cell::Cell,
+    config::{
+        gds_file_types::{GDSRecord, GDSRecordData},
+        timestamp::gds_fields_to_dates,
+    },
+    element::Element,
+    gds_box::GdsBox,
+    node::Node,
+    path::{path_type::PathType, Path},
+    point::{get_points_from_i32_vec, Point},
+    polygon::Polygon,
+    reference::{Instance, Reference},
+    shared::Shared,
+    text::{utils::get_presentations_from_value, Text},
+    traits::LayerDataTypeMatches,
+    utils::{
+        geometry::{round_to_decimals, round_to_significant_digits},
+        io::RecordReader,
+    },
+};
+
+mod general;
+
+pub enum GdsEvent {
+    BeginLibrary(Option<NaiveDateTime>, Option<NaiveDateTime>),
+    LibraryName(String),
+    Units(f64, f64),
+    BeginCell(Box<Cell>),
+    Element(Element),
+    EndCell,
+    EndLibrary,
+}
+
+#[derive(Clone, Default)]
+pub struct GdsFilter {
+    pub cells: Option<HashSet<String>>,
+    pub layers: Option<Vec<(i32, i32)>>,
+}
+
+impl GdsFilter {
+    pub fn new(cells: Option<Vec<String>>, layers: Option<Vec<(i32, i32)>>) -> Self {
+        GdsFilter {
+            cells: cells.map(|cells| cells.into_iter().collect()),
+            layers,
+        }
+    }
+
+    pub(crate) fn includes_cell(&self, name: &str) -> bool {
+        self.cells.as_ref().is_none_or(|cells| cells.contains(name))
+    }
+
+    fn includes_element<T: LayerDataTypeMatches>(&self, element: &T) -> bool {
+        self.layers
+            .as_ref()
+            .is_none_or(|layers| element.is_on(layers.clone()))
+    }
+}
+
+enum PendingElement {
+    Box(GdsBox),
+    Node(Node),
+    Polygon(Polygon),
+    Path(Path),
+    Reference(Reference),
+    Text(Text),
+}
+
+pub struct GdsReader<R: Read> {
+    records: RecordReader<R>,
+    filter: GdsFilter,
+    scale: f64,
+    rounding_digits: u32,
+    in_cell: bool,
+    skip_cell: bool,
+    cell_dates: (Option<NaiveDateTime>, Option<NaiveDateTime>),
+    element: Option<PendingElement>,
+    property_attribute: Option<i32>,
+}
+
+impl GdsReader<File> {
+    pub fn open(file_name: String, filter: GdsFilter) -> PyResult<Self> {
+        let file = File::open(file_name)?;
+        Ok(GdsReader::new(BufReader::new(file), filter))
+    }
+}
+
+impl<R: Read> GdsReader<R> {
+    pub fn new(reader: BufReader<R>, filter: GdsFilter) -> Self {
+        GdsReader {
+            records: RecordReader::new(reader),
+            filter,
+            scale: 1.0,
+            rounding_digits: 0,
+            in_cell: false,
+            skip_cell: false,
+            cell_dates: (None, None),
+            element: None,
+            property_attribute: None,
+        }
+    }
+
+    pub fn next_event(&mut self) -> PyResult<Option<GdsEvent>> {
+        while let Some(record) = self.records.next() {
+            let (record_type, data) = record?;
+
+            if self.skip_cell && !matches!(record_type, GDSRecord::EndStr) {
+                continue;
+            }
+
+            if let Some(event) = self.handle_record(record_type, data)? {
+                return Ok(Some(event));
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn next_cell(&mut self) -> PyResult<Option<Cell>> {
+        let mut cell: Option<Cell> = None;
+
+        while let Some(event) = self.next_event()? {
+            match event {
+                GdsEvent::BeginCell(new_cell) => cell = Some(*new_cell),
+                GdsEvent::Element(element) => {
+                    if let Some(cell) = &mut cell {
+                        cell.add(vec![element]);
+                    }
+                }
+                GdsEvent::EndCell => {
+                    if let Some(cell) = cell.take() {
+                        return Ok(Some(cell));
+                    }
+                }
+                GdsEvent::BeginLibrary(..)
+                | GdsEvent::LibraryName(_)
+                | GdsEvent::Units(..)
+                | GdsEvent::EndLibrary => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn handle_record(
+        &mut self,
+        record_type: GDSRecord,
+        data: GDSRecordData,
+    ) -> PyResult<Option<GdsEvent>> {
+        match record_type {
+            GDSRecord::LibName => {
+                if let GDSRecordData::Str(name) = data {
+                    return Ok(Some(GdsEvent::LibraryName(name)));
+                }
+            }
+            GDSRecord::Units => {
+                if let GDSRecordData::F64(units) = data {
+                    self.scale = units[0];
+                    self.rounding_digits = -(units[1] / units[0]).log10() as u32 - 1;
+                    return Ok(Some(GdsEvent::Units(
+                        round_to_significant_digits(units[1] / units[0]),
+                        round_to_significant_digits(units[1]),
+                    )));
+                }
+            }
+            GDSRecord::BgnLib => {
+                if let GDSRecordData::I16(fields) = data {
+                    let (modification_date, access_date) = gds_fields_to_dates(&fields);
+                    return Ok(Some(GdsEvent::BeginLibrary(modification_date, access_date)));
+                }
+            }
+            GDSRecord::EndLib => return Ok(Some(GdsEvent::EndLibrary)),
+            GDSRecord::BgnStr => {
+                self.in_cell = true;
+                self.cell_dates = match data {
+                    GDSRecordData::I16(fields) => gds_fields_to_dates(&fields),
+                    _ => (None, None),
+                };
+            }
+            GDSRecord::StrName => {
+                if let GDSRecordData::Str(cell_name) = data {
+                    if self.in_cell {
+                        if self.filter.includes_cell(&cell_name) {
+                            let (modification_date, access_date) = self.cell_dates;
+                            return Ok(Some(GdsEvent::BeginCell(Box::new(Cell {
+                                modification_date,
+                                access_date,
+                                ..Cell::new(cell_name)
+                            }))));
+                        }
+                        self.skip_cell = true;
+                    }
+                }
+            }
+            GDSRecord::EndStr => {
+                let skipped = self.skip_cell;
+                self.in_cell = false;
+                self.skip_cell = false;
+                if !skipped {
+                    return Ok(Some(GdsEvent::EndCell));
+                }
+            }
+            GDSRecord::Boundary => {
+                self.element = Some(PendingElement::Polygon(Polygon::default()));
+            }
+            GDSRecord::Box => {
+                self.element = Some(PendingElement::Box(GdsBox::default()));
+            }
+            GDSRecord::Node => {
+                self.element = Some(PendingElement::Node(Node::default()));
+            }
+            GDSRecord::Path | GDSRecord::RaithMbmsPath => {
+                self.element = Some(PendingElement::Path(Path::default()));
+            }
+            GDSRecord::ARef | GDSRecord::SRef => {
+                self.element = Some(PendingElement::Reference(Reference::default()));
+            }
+            GDSRecord::Text => {
+                self.element = Some(PendingElement::Text(Text::default()));
+            }
+            GDSRecord::Layer => {
+                if let GDSRecordData::I16(layer) = data {
+                    let layer_value = layer[0] as i32;
+                    match &mut self.element {
+                        Some(PendingElement::Polygon(polygon)) => polygon.layer = layer_value,
+                        Some(PendingElement::Path(path)) => path.layer = layer_value,
+                        Some(PendingElement::Text(text)) => text.layer = layer_value,
+                        Some(PendingElement::Box(gds_box)) => gds_box.layer = layer_value,
+                        Some(PendingElement::Node(node)) => node.layer = layer_value,
+                        _ => {}
+                    }
+                }
+            }
+            GDSRecord::BoxType => {
+                if let GDSRecordData::I16(box_type) = data {
+                    if let Some(PendingElement::Box(gds_box)) = &mut self.element {
+                        gds_box.box_type = box_type[0] as i32;
+                    }
+                }
+            }
+            GDSRecord::NodeType => {
+                if let GDSRecordData::I16(node_type) = data {
+                    if let Some(PendingElement::Node(node)) = &mut self.element {
+                        node.node_type = node_type[0] as i32;
+                    }
+                }
+            }
+            GDSRecord::DataType => {
+                if let GDSRecordData::I16(data_type) = data {
+                    let data_type_val = data_type[0] as i32;
+                    match &mut self.element {
+                        Some(PendingElement::Polygon(polygon)) => polygon.data_type = data_type_val,
+                        Some(PendingElement::Path(path)) => path.data_type = data_type_val,
+                        _ => {}
+                    }
+                }
+            }
+            GDSRecord::TextType => {
+                if let GDSRecordData::I16(text_type) = data {
+                    if let Some(PendingElement::Text(text)) = &mut self.element {
+                        text.text_type = text_type[0] as i32;
+                    }
+                }
+            }
+            GDSRecord::Width => {
+                if let GDSRecordData::I32(width) = data {
+                    let path_width =
+                        round_to_decimals(width[0] as f64 * self.scale, self.rounding_digits);
+                    if let Some(PendingElement::Path(path)) = &mut self.element {
+                        path.width = Some(path_width);
+                    }
+                }
+            }
+            GDSRecord::BgnExtn | GDSRecord::EndExtn => {
+                if let GDSRecordData::I32(extension) = data {
+                    let extension =
+                        round_to_decimals(extension[0] as f64 * self.scale, self.rounding_digits);
+                    if let Some(PendingElement::Path(path)) = &mut self.element {
+                        if matches!(record_type, GDSRecord::BgnExtn) {
+                            path.begin_extension = extension;
+                        } else {
+                            path.end_extension = extension;
+                        }
+                    }
+                }
+            }
+            GDSRecord::XY => {
+                if let GDSRecordData::I32(xy) = data {
+                    self.handle_points(xy);
+                }
+            }
+            GDSRecord::EndEl => {
+                if let Some(element) = self.element.take() {
+                    return Ok(self.finish_element(element));
+                }
+            }
+            GDSRecord::SName => {
+                if let GDSRecordData::Str(cell_name) = data {
+                    if let Some(PendingElement::Reference(reference)) = &mut self.element {
+                        if let Instance::Cell(cell) = &reference.instance {
+                            cell.write().name = cell_name;
+                        }
+                    }
+                }
+            }
+            GDSRecord::ColRow => {
+                if let GDSRecordData::I16(col_row) = data {
+                    if let Some(PendingElement::Reference(reference)) = &mut self.element {
+                        let mut grid = reference.grid.write();
+                        grid.columns = col_row[0] as u32;
+                        grid.rows = col_row[1] as u32;
+                    }
+                }
+            }
+            GDSRecord::Presentation => {
+                if let GDSRecordData::I16(flags) = data {
+                    if let Some(PendingElement::Text(text)) = &mut self.element {
+                        (text.vertical_presentation, text.horizontal_presentation) =
+                            get_presentations_from_value(flags[0])?;
+                    }
+                }
+            }
+            GDSRecord::String => {
+                if let GDSRecordData::Str(string) = data {
+                    if let Some(PendingElement::Text(text)) = &mut self.element {
+                        text.text = string;
+                    }
+                }
+            }
+            GDSRecord::STrans => {
+                if let GDSRecordData::I16(flags) = data {
+                    let x_reflection = flags[0] & 0x8000u16 as i16 != 0;
+                    let absolute_magnification = flags[0] & 0x0004 != 0;
+                    let absolute_angle = flags[0] & 0x0002 != 0;
+                    match &mut self.element {
+                        Some(PendingElement::Text(text)) => {
+                            text.x_reflection = x_reflection;
+                            text.absolute_magnification = absolute_magnification;
+                            text.absolute_angle = absolute_angle;
+                        }
+                        Some(PendingElement::Reference(reference)) => {
+                            let mut grid = reference.grid.write();
+                            grid.x_reflection = x_reflection;
+                            grid.absolute_magnification = absolute_magnification;
+                            grid.absolute_angle = absolute_angle;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            GDSRecord::Mag => {
+                if let GDSRecordData::F64(magnification) = data {
+                    match &mut self.element {
+                        Some(PendingElement::Text(text)) => text.magnification = magnification[0],
+                        Some(PendingElement::Reference(reference)) => {
+                            reference.grid.write().magnification = magnification[0];
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            GDSRecord::Angle => {
+                if let GDSRecordData::F64(angle) = data {
+                    match &mut self.element {
+                        Some(PendingElement::Text(text)) => text.angle = angle[0],
+                        Some(PendingElement::Reference(reference)) => {
+                            reference.grid.write().angle = angle[0];
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            GDSRecord::PropAttr => {
+                if let GDSRecordData::I16(attribute) = data {
+                    self.property_attribute = Some(attribute[0] as i32);
+                }
+            }
+            GDSRecord::PropValue => {
+                if let (GDSRecordData::Str(value), Some(attribute)) =
+                    (data, self.property_attribute.take())
+                {
+                    let properties = match &mut self.element {
+                        Some(PendingElement::Polygon(polygon)) => &mut polygon.properties,
+                        Some(PendingElement::Path(path)) => &mut path.properties,
+                        Some(PendingElement::Text(text)) => &mut text.properties,
+                        Some(PendingElement::Reference(reference)) => &mut reference.properties,
+                        Some(PendingElement::Box(gds_box)) => &mut gds_box.properties,
+                        Some(PendingElement::Node(node)) => &mut node.properties,
+                        None => return Ok(None),
+                    };
+                    properties.insert(attribute, value);
+                }
+            }
+            GDSRecord::PathType => {
+                if let GDSRecordData::I16(path_type) = data {
+                    if let Some(PendingElement::Path(path)) = &mut self.element {
+                        path.path_type = Some(PathType::new(path_type[0] as i32)?);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    fn handle_points(&mut self, xy: Vec<i32>) {
+        let points = get_points_from_i32_vec(xy)
+            .iter()
+            .map(|p| {
+                p.scale(self.scale, Point::default())
+                    .round(self.rounding_digits)
+            })
+            .collect::<Vec<Point>>();
+
+        match &mut self.element {
+            Some(PendingElement::Polygon(polygon)) => polygon.points.extend(points),
+            Some(PendingElement::Path(path)) => path.points.extend(points),
+            Some(PendingElement::Box(gds_box)) => gds_box.points.extend(points),
+            Some(PendingElement::Node(node)) => node.points.extend(points),
+            Some(PendingElement::Reference(reference)) => {
+                let mut grid = reference.grid.write();
+                match points.len() {
+                    1 => {
+                        grid.origin = points[0];
+                    }
+                    3 => {
+                        let origin = points[0];
+                        let rotated_points = points
+                            .iter()
+                            .map(|&p| p.rotate(-grid.angle, origin))
+                            .collect::<Vec<Point>>();
+
+                        grid.origin = rotated_points[0].round(self.rounding_digits);
+                        grid.spacing_x = if grid.columns > 0 {
+                            ((rotated_points[1] - rotated_points[0]) / grid.columns as f64)
+                                .round(self.rounding_digits)
+                        } else {
+                            Point::default()
+                        };
+                        grid.spacing_y = if grid.rows > 0 {
+                            ((rotated_points[2] - rotated_points[0]) / grid.rows as f64)
+                                .round(self.rounding_digits)
+                        } else {
+                            Point::default()
+                        };
+                    }
+                    _ => {}
+                }
+            }
+            Some(PendingElement::Text(text)) => {
+                if let Some(&first_point) = points.first() {
+                    text.origin = first_point;
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn finish_element(&self, element: PendingElement) -> Option<GdsEvent> {
+        if !self.in_cell {
+            return None;
+        }
+
+        let element = match element {
+            PendingElement::Polygon(polygon) => {
+                if !self.filter.includes_element(&polygon) {
+                    return None;
+                }
+                Element::Polygon(Shared::new(polygon))
+            }
+            PendingElement::Path(path) => {
+                if !self.filter.includes_element(&path) {
+                    return None;
+                }
+                Element::Path(Shared::new(path))
+            }
+            PendingElement::Text(text) => {
+                if !self.filter.includes_element(&text) {
+                    return None;
+                }
+                Element::Text(Shared::new(text))
+            }
+            PendingElement::Box(gds_box) => {
+                if !self.filter.includes_element(&gds_box) {
+                    return None;
+                }
+                Element::Box(Shared::new(gds_box))
+            }
+            PendingElement::Node(node) => {
+                if !self.filter.includes_element(&node) {
+                    return None;
+                }
+                Element::Node(Shared::new(node))
+            }
+            PendingElement::Reference(reference) => Element::Reference(Shared::new(reference)),
+        };
+
+        Some(GdsEvent::Element(element))
+    }
+}
+
+#[pyclass]
+pub struct GdsCellIterator {
+    reader: GdsReader<File>,
+}
+
+#[pyclass]
+pub struct GdsElementIterator {
+    reader: GdsReader<File>,
+    cell_name: Option<String>,
+}
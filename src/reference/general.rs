@@ -1,201 +1,304 @@
-use std::ops::DerefMut;
+use std::{collections::HashMap, sync::Arc};
 
 use pyo3::prelude::*;
 
 use crate::{
-    config::FLOATING_POINT_INACCURACY_ROUND_DECIMALS,
     element::Element,
     grid::Grid,
     point::Point,
+    shared::{revision::Revision, Shared},
     traits::{Dimensions, LayerDataTypeMatches, Movable, Reflect, Rotatable, Scalable},
     utils::transformations::py_any_to_point,
+    validation::input::check_properties_valid,
 };
 
-use super::{Instance, Reference};
+use super::{Instance, PyReference, Reference};
 
 #[pymethods]
-impl Reference {
+impl PyReference {
     #[new]
-    #[pyo3(signature=(instance, grid=None))]
-    pub fn new(instance: Instance, grid: Option<Py<Grid>>) -> Self {
-        let grid =
-            grid.unwrap_or_else(|| Python::with_gil(|py| Py::new(py, Grid::default()).unwrap()));
-        match instance {
-            Instance::Cell(cell) => Python::with_gil(|py| Reference {
-                instance: Instance::Cell(cell.clone_ref(py)),
-                grid,
-            }),
-            Instance::Element(_) => Reference { instance, grid },
-        }
+    #[pyo3(signature=(instance, grid=None, properties=None))]
+    fn new(
+        instance: Instance,
+        grid: Option<Shared<Grid>>,
+        properties: Option<HashMap<i32, String>>,
+    ) -> PyResult<Self> {
+        Reference::new(instance, grid, properties).map(|value| PyReference(Shared::new(value)))
+    }
+
+    #[getter]
+    fn instance(&self) -> Instance {
+        self.0.read().instance.clone()
+    }
+
+    #[getter]
+    fn grid(&self) -> Shared<Grid> {
+        self.0.read().grid.clone()
+    }
+
+    #[getter]
+    fn properties(&self) -> HashMap<i32, String> {
+        self.0.read().properties.clone()
+    }
+
+    #[setter(instance)]
+    fn setter_instance(&self, instance: Instance) {
+        self.0.set_instance(instance);
+    }
+
+    #[setter(grid)]
+    fn setter_grid(&self, grid: Shared<Grid>) {
+        self.0.set_grid(grid);
+    }
+
+    #[setter(properties)]
+    fn setter_properties(&self, properties: HashMap<i32, String>) -> PyResult<()> {
+        check_properties_valid(&properties)?;
+        self.0.write().properties = properties;
+        Ok(())
+    }
+
+    fn set_properties(
+        slf: PyRef<'_, Self>,
+        properties: HashMap<i32, String>,
+    ) -> PyResult<PyRef<'_, Self>> {
+        slf.setter_properties(properties)?;
+        Ok(slf)
     }
 
     #[getter]
     fn bounding_box(&self) -> (Point, Point) {
-        Dimensions::bounding_box(self)
+        Dimensions::bounding_box(&*self.0.read())
     }
 
     pub fn copy(&self) -> Self {
-        Python::with_gil(|py| Self {
-            instance: match &self.instance {
-                Instance::Cell(cell) => {
-                    Instance::Cell(Py::new(py, cell.borrow(py).clone()).unwrap())
-                }
-                Instance::Element(element) => Instance::Element(element.copy()),
-            },
-            grid: Py::new(py, self.grid.borrow(py).clone()).unwrap(),
-        })
+        PyReference(Shared::new(self.0.read().copy()))
     }
 
     fn move_to(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] point: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_to(slf.deref_mut(), point);
+    ) -> PyRef<'_, Self> {
+        Movable::move_to(&mut *slf.0.write(), point);
         slf
     }
 
     fn move_by(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] vector: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_by(slf.deref_mut(), vector);
+    ) -> PyRef<'_, Self> {
+        Movable::move_by(&mut *slf.0.write(), vector);
         slf
     }
 
     #[pyo3(signature = (angle, centre=Point::default()))]
     fn rotate(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         angle: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Rotatable::rotate(slf.deref_mut(), angle, centre);
+    ) -> PyRef<'_, Self> {
+        Rotatable::rotate(&mut *slf.0.write(), angle, centre);
         slf
     }
 
     #[pyo3(signature = (factor, centre=Point::default()))]
     fn scale(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         factor: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Scalable::scale(slf.deref_mut(), factor, centre);
+    ) -> PyRef<'_, Self> {
+        Scalable::scale(&mut *slf.0.write(), factor, centre);
         slf
     }
 
     #[pyo3(signature = (*layer_data_types, depth=None))]
-    pub fn flatten(
-        &mut self,
-        layer_data_types: Vec<(i32, i32)>,
-        depth: Option<usize>,
-        py: Python,
-    ) -> Vec<Element> {
+    pub fn flatten(&self, layer_data_types: Vec<(i32, i32)>, depth: Option<usize>) -> Vec<Element> {
+        self.0.read().flatten(layer_data_types, depth)
+    }
+
+    #[pyo3(signature = (*layer_data_types))]
+    pub fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
+        LayerDataTypeMatches::is_on(&*self.0.read(), layer_data_types)
+    }
+
+    fn __str__(&self) -> PyResult<String> {
+        Ok(format!("{}", self.0))
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("{:?}", self.0))
+    }
+}
+
+impl Reference {
+    pub fn new(
+        instance: Instance,
+        grid: Option<Shared<Grid>>,
+        properties: Option<HashMap<i32, String>>,
+    ) -> PyResult<Self> {
+        let properties = properties.unwrap_or_default();
+        check_properties_valid(&properties)?;
+        Ok(Reference {
+            instance,
+            grid: grid.unwrap_or_default(),
+            properties,
+        })
+    }
+
+    pub fn copy(&self) -> Self {
+        Reference {
+            instance: self.instance.copy(),
+            grid: self.grid.copy(),
+            properties: self.properties.clone(),
+        }
+    }
+
+    /// Records `revision` as depending on the grid and instance of the reference.
+    pub fn add_dependent(&self, revision: &Arc<Revision>) {
+        self.grid.revision().add_dependent(revision);
+        self.instance.add_dependent(revision);
+    }
+
+    pub fn remove_dependent(&self, revision: &Arc<Revision>) {
+        self.grid.revision().remove_dependent(revision);
+        self.instance.remove_dependent(revision);
+    }
+
+    pub fn flatten(&self, layer_data_types: Vec<(i32, i32)>, depth: Option<usize>) -> Vec<Element> {
         let depth = depth.unwrap_or(usize::MAX);
         let flatten_all = layer_data_types.is_empty();
         let mut elements: Vec<Element> = Vec::new();
         if depth == 0 {
-            return [Element::Reference(Py::new(py, self.copy()).unwrap())].to_vec();
+            return [Element::Reference(Shared::new(self.copy()))].to_vec();
         }
         match &self.instance {
             Instance::Cell(cell) => {
-                let flattened_cell_elements =
-                    cell.borrow_mut(py)
-                        .get_elements(layer_data_types, Some(depth - 1), py);
-                for cell_element in flattened_cell_elements {
+                let cell = cell.read();
+                for cell_element in cell.direct_elements(&layer_data_types) {
                     elements.extend(self._get_elements_in_grid(cell_element));
                 }
-            }
-            Instance::Element(element) => match element {
-                Element::Path(element) => {
-                    if element.borrow(py).is_on(layer_data_types) || flatten_all {
-                        elements.extend(
-                            self._get_elements_in_grid(Element::Path(element.clone_ref(py))),
-                        );
-                    };
-                }
-                Element::Polygon(element) => {
-                    if element.borrow(py).is_on(layer_data_types) || flatten_all {
-                        elements.extend(
-                            self._get_elements_in_grid(Element::Polygon(element.clone_ref(py))),
-                        );
-                    }
-                }
-                Element::Text(element) => {
-                    if element.borrow(py).is_on(layer_data_types) || flatten_all {
-                        elements.extend(
-                            self._get_elements_in_grid(Element::Text(element.clone_ref(py))),
-                        );
-                    }
+                for reference in &cell.references {
+                    elements.extend(self._flatten_placed_reference(
+                        reference,
+                        &layer_data_types,
+                        depth - 1,
+                    ));
                 }
-                Element::Reference(element) => {
-                    let flattened_reference_elements = Python::with_gil(|py| {
-                        element
-                            .borrow_mut(py)
-                            .flatten(layer_data_types, Some(depth - 1), py)
-                    });
-
-                    let flattened_copied_elements = flattened_reference_elements
-                        .iter()
-                        .map(|element| element.copy())
-                        .collect::<Vec<Element>>();
-
-                    for reference_element in flattened_copied_elements {
-                        elements.extend(self._get_elements_in_grid(reference_element).into_iter());
-                    }
+            }
+            Instance::Element(Element::Reference(reference)) => {
+                elements.extend(self._flatten_placed_reference(
+                    reference,
+                    &layer_data_types,
+                    depth - 1,
+                ));
+            }
+            Instance::Element(element) => {
+                if element.is_on(layer_data_types) || flatten_all {
+                    elements.extend(self._get_elements_in_grid(element.clone()));
                 }
-            },
+            }
         }
 
         elements
     }
 
-    #[pyo3(signature = (*layer_data_types))]
-    pub fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
-        LayerDataTypeMatches::is_on(self, layer_data_types)
-    }
-
-    fn __str__(&self) -> PyResult<String> {
-        Ok(format!("{}", self))
+    /// Returns a copy of this reference placed by one instance of `parent` at `origin`.
+    pub fn placed_in(&self, parent: &Grid, origin: Point) -> Reference {
+        let instance = match &self.instance {
+            Instance::Cell(cell) => Instance::Cell(cell.clone()),
+            Instance::Element(element) => Instance::Element(element.copy()),
+        };
+        let grid = self.grid.read().placed_in(parent, origin);
+        Reference {
+            instance,
+            grid: Shared::new(grid),
+            properties: self.properties.clone(),
+        }
     }
 
-    fn __repr__(&self) -> PyResult<String> {
-        Ok(format!("{:?}", self))
+    fn _flatten_placed_reference(
+        &self,
+        reference: &Shared<Reference>,
+        layer_data_types: &[(i32, i32)],
+        depth: usize,
+    ) -> Vec<Element> {
+        let mut elements = Vec::new();
+        for placed in self._get_elements_in_grid(Element::Reference(reference.clone())) {
+            let Element::Reference(placed) = placed else {
+                continue;
+            };
+            let flattened = placed
+                .read()
+                .flatten(layer_data_types.to_vec(), Some(depth));
+            elements.extend(
+                flattened
+                    .into_iter()
+                    .filter(|element| element.is_on(layer_data_types.to_vec())),
+            );
+        }
+        elements
     }
-}
 
-impl Reference {
     pub fn _get_elements_in_grid(&self, element: Element) -> Vec<Element> {
-        Python::with_gil(|py| {
-            let binding = Py::new(py, self.grid.borrow_mut(py).clone()).unwrap();
-            let grid = binding.borrow_mut(py);
+        let grid = self.grid.read().clone();
 
-            let mut elements: Vec<Element> =
-                Vec::with_capacity((grid.columns * grid.rows) as usize);
-
-            for column_index in 0..grid.columns {
-                let column_origin = grid.origin + grid.spacing_x * column_index as f64;
-                for row_index in 0..grid.rows {
-                    let origin = (column_origin + grid.spacing_y * row_index as f64).copy();
+        grid.instance_origins()
+            .into_iter()
+            .map(|origin| Reference::place_element(&grid, &element, origin))
+            .collect()
+    }
 
-                    let mut new_element = element.copy();
+    /// Returns a copy of `element` placed by the instance of `grid` at `origin`.
+    pub fn place_element(grid: &Grid, element: &Element, origin: Point) -> Element {
+        if let Element::Reference(reference) = element {
+            let placed = reference.read().placed_in(grid, origin);
+            return Element::Reference(Shared::new(placed));
+        }
 
-                    if grid.x_reflection {
-                        new_element.reflect(0.0, Point::new(1.0, 0.0));
-                    }
+        let mut new_element = element.copy();
 
-                    new_element.rotate(grid.angle, Point::default());
-                    new_element.scale(grid.magnification, Point::default());
+        if grid.x_reflection {
+            new_element.reflect(0.0, Point::new(1.0, 0.0));
+        }
 
-                    new_element.move_by(
-                        origin
-                            .rotate(grid.angle, grid.origin)
-                            .round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS),
-                    );
+        new_element.rotate(grid.angle, Point::default());
+        new_element.scale(grid.magnification, Point::default());
+        new_element.move_by(origin);
 
-                    elements.push(new_element.copy());
-                }
+        if let (Element::Text(new_text), Element::Text(text)) = (&new_element, element) {
+            let text = text.read();
+            let mut new_text = new_text.write();
+            if text.absolute_magnification {
+                new_text.magnification = text.magnification;
             }
+            if text.absolute_angle {
+                new_text.angle = text.angle;
+            }
+        }
 
-            elements
-        })
+        new_element
+    }
+}
+
+impl Shared<Reference> {
+    /// Places `instance` instead, moving the links of the cells that hold the reference to it.
+    pub fn set_instance(&self, instance: Instance) {
+        let dependents = self.revision().dependents();
+        let mut reference = self.write();
+        for dependent in &dependents {
+            reference.instance.remove_dependent(dependent);
+            instance.add_dependent(dependent);
+        }
+        reference.instance = instance;
+    }
+
+    pub fn set_grid(&self, grid: Shared<Grid>) {
+        let dependents = self.revision().dependents();
+        let mut reference = self.write();
+        for dependent in &dependents {
+            reference.grid.revision().remove_dependent(dependent);
+            grid.revision().add_dependent(dependent);
+        }
+        reference.grid = grid;
     }
 }
@@ -1,25 +1,28 @@
+use std::sync::Arc;
+
 use pyo3::{exceptions::PyTypeError, prelude::*};
 
 use crate::{
     cell::Cell,
     element::Element,
     point::Point,
+    shared::{revision::Revision, Shared},
     traits::{Dimensions, Movable, Rotatable, Scalable},
 };
 
 #[derive(Clone)]
 pub enum Instance {
-    Cell(Py<Cell>),
+    Cell(Shared<Cell>),
     Element(Element),
 }
 
 impl PartialEq for Instance {
     fn eq(&self, other: &Self) -> bool {
-        Python::with_gil(|py| match (self, other) {
-            (Instance::Cell(a), Instance::Cell(b)) => a.borrow(py).eq(&b.borrow(py)),
-            (Instance::Element(a), Instance::Element(b)) => a.clone().eq(&b.clone()),
+        match (self, other) {
+            (Instance::Cell(a), Instance::Cell(b)) => a == b,
+            (Instance::Element(a), Instance::Element(b)) => a == b,
             _ => false,
-        })
+        }
     }
 }
 
@@ -34,7 +37,7 @@ impl IntoPy<PyObject> for Instance {
 
 impl FromPyObject<'_> for Instance {
     fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
-        if let Ok(cell) = ob.extract::<Py<Cell>>() {
+        if let Ok(cell) = ob.extract::<Shared<Cell>>() {
             Ok(Instance::Cell(cell))
         } else if let Ok(element) = ob.extract::<Element>() {
             Ok(Instance::Element(element))
@@ -48,7 +51,7 @@ impl FromPyObject<'_> for Instance {
 
 impl Default for Instance {
     fn default() -> Self {
-        Python::with_gil(|py| Instance::Cell(Py::new(py, Cell::default()).unwrap()))
+        Instance::Cell(Shared::default())
     }
 }
 
@@ -63,80 +66,86 @@ impl std::fmt::Display for Instance {
 
 impl Movable for Instance {
     fn move_to(&mut self, point: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            match self {
-                Instance::Cell(cell) => {
-                    cell.borrow_mut(py).move_to(point);
-                }
-                Instance::Element(element) => {
-                    element.move_to(point);
-                }
-            };
-            self
-        })
+        match self {
+            Instance::Cell(cell) => {
+                cell.write().move_to(point);
+            }
+            Instance::Element(element) => {
+                element.move_to(point);
+            }
+        };
+        self
     }
 
     fn move_by(&mut self, vector: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            match self {
-                Instance::Cell(cell) => {
-                    cell.borrow_mut(py).move_by(vector);
-                }
-                Instance::Element(element) => {
-                    element.move_by(vector);
-                }
-            };
-            self
-        })
+        match self {
+            Instance::Cell(cell) => {
+                cell.write().move_by(vector);
+            }
+            Instance::Element(element) => {
+                element.move_by(vector);
+            }
+        };
+        self
     }
 }
 
 impl Rotatable for Instance {
     fn rotate(&mut self, angle: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            match self {
-                Instance::Cell(cell) => {
-                    cell.borrow_mut(py).rotate(angle, centre);
-                }
-                Instance::Element(element) => {
-                    element.rotate(angle, centre);
-                }
-            };
-            self
-        })
+        match self {
+            Instance::Cell(cell) => {
+                cell.write().rotate(angle, centre);
+            }
+            Instance::Element(element) => {
+                element.rotate(angle, centre);
+            }
+        };
+        self
     }
 }
 
 impl Scalable for Instance {
     fn scale(&mut self, factor: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            match self {
-                Instance::Cell(cell) => {
-                    cell.borrow_mut(py).scale(factor, centre);
-                }
-                Instance::Element(element) => {
-                    element.scale(factor, centre);
-                }
-            };
-            self
-        })
+        match self {
+            Instance::Cell(cell) => {
+                cell.write().scale(factor, centre);
+            }
+            Instance::Element(element) => {
+                element.scale(factor, centre);
+            }
+        };
+        self
     }
 }
 
 impl Dimensions for Instance {
     fn bounding_box(&self) -> (Point, Point) {
-        Python::with_gil(|py| match self {
-            Instance::Cell(cell) => cell.borrow(py).bounding_box(),
+        match self {
+            Instance::Cell(cell) => cell.read().bounding_box(),
             Instance::Element(element) => element.bounding_box(),
-        })
+        }
     }
 }
 
 impl Instance {
     pub fn copy(&self) -> Self {
-        Python::with_gil(|py| match self {
-            Instance::Cell(cell) => Instance::Cell(Py::new(py, cell.borrow(py).clone()).unwrap()),
+        match self {
+            Instance::Cell(cell) => Instance::Cell(cell.copy()),
             Instance::Element(element) => Instance::Element(element.copy()),
-        })
+        }
+    }
+
+    pub fn add_dependent(&self, revision: &Arc<Revision>) {
+        match self {
+            Instance::Cell(cell) => cell.revision().add_dependent(revision),
+            Instance::Element(element) => element.add_dependent(revision),
+        }
+    }
+
+    pub fn remove_dependent(&self, revision: &Arc<Revision>) {
+        match self {
+            Instance::Cell(cell) => cell.revision().remove_dependent(revision),
+            Instance::Element(element) => element.remove_dependent(revision),
+        }
     }
 }
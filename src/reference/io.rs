@@ -2,30 +2,36 @@ use pyo3::prelude::*;
 use std::fs::File;
 
 use crate::{
-    config::gds_file_types::{combine_record_and_data_type, GDSDataType, GDSRecord},
+    config::{
+        gds_file_types::{combine_record_and_data_type, GDSDataType, GDSRecord},
+        max_points_mode::MaxPointsMode,
+    },
     element::Element,
     traits::ToGds,
     utils::io::{
-        write_element_tail_to_file, write_points_to_file, write_string_with_record_to_file,
-        write_transformation_to_file, write_u16_array_to_file,
+        write_element_tail_to_file, write_points_to_file, write_properties_to_file,
+        write_string_with_record_to_file, write_transformation_to_file, write_u16_array_to_file,
     },
 };
 
 use super::{Instance, Reference};
 
 impl ToGds for Reference {
-    fn _to_gds(&self, mut file: File, scale: f64) -> PyResult<File> {
-        Python::with_gil(|py| {
-            match &self.instance {
-                Instance::Cell(cell) => {
-                    file = self._to_gds_with_cell(file, scale, &cell.borrow(py).name)?;
-                }
-                Instance::Element(element) => {
-                    file = self._to_gds_with_element(file, scale, element)?;
-                }
+    fn _to_gds(
+        &self,
+        mut file: File,
+        scale: f64,
+        max_points_mode: MaxPointsMode,
+    ) -> PyResult<File> {
+        match &self.instance {
+            Instance::Cell(cell) => {
+                file = self._to_gds_with_cell(file, scale, &cell.read().name)?;
             }
-            Ok(file)
-        })
+            Instance::Element(element) => {
+                file = self._to_gds_with_element(file, scale, max_points_mode, element)?;
+            }
+        }
+        Ok(file)
     }
 }
 
@@ -34,10 +40,11 @@ impl Reference {
         &self,
         mut file: File,
         scale: f64,
+        max_points_mode: MaxPointsMode,
         element: &Element,
     ) -> PyResult<File> {
         for element in self._get_elements_in_grid(element.copy()) {
-            file = element._to_gds(file, scale)?;
+            file = element._to_gds(file, scale, max_points_mode)?;
         }
 
         Ok(file)
@@ -53,10 +60,16 @@ impl Reference {
 
         file = write_string_with_record_to_file(file, GDSRecord::SName, cell_name)?;
 
-        let grid = Python::with_gil(|py| self.grid.borrow(py).clone());
+        let grid = self.grid.read().clone();
 
-        file =
-            write_transformation_to_file(file, grid.angle, grid.magnification, grid.x_reflection)?;
+        file = write_transformation_to_file(
+            file,
+            grid.angle,
+            grid.magnification,
+            grid.x_reflection,
+            grid.absolute_magnification,
+            grid.absolute_angle,
+        )?;
 
         let mut buffer_array = [
             8,
@@ -80,6 +93,8 @@ impl Reference {
 
         file = write_points_to_file(file, &points, scale)?;
 
+        file = write_properties_to_file(file, &self.properties)?;
+
         file = write_element_tail_to_file(file)?;
 
         Ok(file)
@@ -1,9 +1,16 @@
+use std::collections::HashMap;
+
 use pyo3::prelude::*;
 
 use crate::{
-    cell::Cell,
+    cell::bounding_box::reference_bounding_box,
     grid::Grid,
     point::Point,
+    shared::{
+        revision::Tracked,
+        wrapper::{extract_value, value_into_py, Wrapped, Wrapper},
+        Shared,
+    },
     traits::{Dimensions, LayerDataTypeMatches, Movable, Reflect, Rotatable, Scalable},
 };
 
@@ -13,150 +20,105 @@ mod io;
 
 pub use instance::Instance;
 
-#[pyclass(eq)]
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct Reference {
-    #[pyo3(get, set)]
     pub instance: Instance,
-    #[pyo3(get, set)]
-    pub grid: Py<Grid>,
+    pub grid: Shared<Grid>,
+    pub properties: HashMap<i32, String>,
+}
+
+#[pyclass(name = "Reference", eq, frozen, weakref)]
+#[derive(PartialEq)]
+pub struct PyReference(pub Shared<Reference>);
+
+impl Wrapper for PyReference {
+    type Inner = Reference;
+
+    fn wrap(shared: Shared<Reference>) -> Self {
+        PyReference(shared)
+    }
+
+    fn shared(&self) -> &Shared<Reference> {
+        &self.0
+    }
+}
+
+impl Wrapped for Reference {
+    type Wrapper = PyReference;
+}
+
+impl Tracked for Reference {}
+
+impl IntoPy<PyObject> for Reference {
+    fn into_py(self, py: Python) -> PyObject {
+        value_into_py(self, py)
+    }
+}
+
+impl FromPyObject<'_> for Reference {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        extract_value(ob)
+    }
 }
 
 impl std::fmt::Display for Reference {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match &self.instance {
-            Instance::Cell(cell) => Python::with_gil(|py| {
-                write!(
-                    f,
-                    "Reference of {} with {}",
-                    cell.borrow(py).clone(),
-                    self.grid
-                )
-            }),
-            Instance::Element(element) => {
-                write!(f, "Reference of {} with {}", element, self.grid)
-            }
-        }
+        write!(f, "Reference of {} with {}", self.instance, self.grid)
     }
 }
 
 impl std::fmt::Debug for Reference {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match &self.instance {
-            Instance::Cell(cell) => {
-                Python::with_gil(|py| write!(f, "Reference({:?})", cell.borrow(py).clone()))
-            }
+            Instance::Cell(cell) => write!(f, "Reference({:?})", cell),
             Instance::Element(element) => write!(f, "Reference({:?})", element),
         }
     }
 }
 
-impl Default for Reference {
-    fn default() -> Self {
-        Python::with_gil(|py| Reference {
-            instance: Instance::Cell(Py::new(py, Cell::default()).unwrap()),
-            grid: Py::new(py, Grid::default()).unwrap(),
-        })
-    }
-}
-
 impl PartialEq for Reference {
     fn eq(&self, other: &Self) -> bool {
-        Python::with_gil(|py| {
-            if self.grid.borrow(py).clone() != other.grid.borrow(py).clone() {
-                return false;
-            }
-
-            match (&self.instance, &other.instance) {
-                (Instance::Cell(cell1), Instance::Cell(cell2)) => {
-                    cell1.borrow(py).eq(&cell2.borrow(py))
-                }
-                (Instance::Element(element1), Instance::Element(element2)) => element1.eq(element2),
-                _ => false,
-            }
-        })
+        self.grid == other.grid
+            && self.properties == other.properties
+            && self.instance == other.instance
     }
 }
 
 impl Movable for Reference {
     fn move_to(&mut self, point: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            self.grid.borrow_mut(py).move_to(point);
-        });
+        self.grid.write().move_to(point);
         self
     }
 
     fn move_by(&mut self, vector: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            self.grid.borrow_mut(py).move_by(vector);
-        });
+        self.grid.write().move_by(vector);
         self
     }
 }
 
 impl Rotatable for Reference {
     fn rotate(&mut self, angle: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            self.grid.borrow_mut(py).rotate(angle, centre);
-        });
+        self.grid.write().rotate(angle, centre);
         self
     }
 }
 
 impl Scalable for Reference {
     fn scale(&mut self, factor: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            self.grid.borrow_mut(py).scale(factor, centre);
-        });
+        self.grid.write().scale(factor, centre);
         self
     }
 }
 
 impl Dimensions for Reference {
     fn bounding_box(&self) -> (Point, Point) {
-        let mut min = Point::new(f64::INFINITY, f64::INFINITY);
-        let mut max = Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY);
-
-        let grid = Python::with_gil(|py| self.grid.borrow(py).clone());
-
-        let corners = vec![
-            grid.origin,
-            grid.origin + grid.spacing_x * grid.columns as f64,
-            grid.origin + grid.spacing_y * grid.rows as f64,
-            grid.origin + grid.spacing_x * grid.columns as f64 + grid.spacing_y * grid.rows as f64,
-        ];
-
-        for corner in corners {
-            let new_element = self
-                .instance
-                .clone()
-                .scale(if grid.x_reflection { -1.0 } else { 1.0 }, grid.origin)
-                .scale(grid.magnification, grid.origin)
-                .rotate(grid.angle, grid.origin)
-                .move_by(
-                    corner
-                        .rotate(grid.angle, grid.origin)
-                        .scale(if grid.x_reflection { -1.0 } else { 1.0 }, grid.origin),
-                )
-                .clone();
-
-            let (new_element_min, new_element_max) = new_element.bounding_box();
-
-            min.x = min.x.min(new_element_min.x);
-            min.y = min.y.min(new_element_min.y);
-            max.x = max.x.max(new_element_max.x);
-            max.y = max.y.max(new_element_max.y);
-        }
-
-        (min, max)
+        reference_bounding_box(self, &None)
     }
 }
 
 impl Reflect for Reference {
     fn reflect(&mut self, angle: f64, centre: Point) -> &mut Self {
-        Python::with_gil(|py| {
-            self.grid.borrow_mut(py).reflect(angle, centre);
-        });
+        self.grid.write().reflect(angle, centre);
         self
     }
 }
@@ -164,7 +126,7 @@ impl Reflect for Reference {
 impl LayerDataTypeMatches for Reference {
     fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
         match &self.instance {
-            Instance::Cell(cell) => Python::with_gil(|py| cell.borrow(py).is_on(layer_data_types)),
+            Instance::Cell(cell) => cell.read().is_on(layer_data_types),
             Instance::Element(element) => element.is_on(layer_data_types),
         }
     }
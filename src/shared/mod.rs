@@ -0,0 +1,93 @@
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use revision::{Revision, Tracked};
+
+pub mod revision;
+pub mod wrapper;
+
+/// A layout object that can be held by several cells, references and Python objects at once.
+///
+/// Changes made through one handle are seen by every other handle, the same way Python
+/// objects added to several cells are shared. Nothing here needs a Python interpreter.
+pub struct Shared<T>(Arc<SharedValue<T>>);
+
+struct SharedValue<T> {
+    value: RwLock<T>,
+    revision: Arc<Revision>,
+}
+
+impl<T: Tracked> Shared<T> {
+    pub fn new(value: T) -> Self {
+        let revision = value.revision();
+        Shared(Arc::new(SharedValue {
+            value: RwLock::new(value),
+            revision,
+        }))
+    }
+}
+
+impl<T> Shared<T> {
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.0.value.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Write access to the object, which invalidates the caches of every cell above it.
+    pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+        let guard = self.0.value.write().unwrap_or_else(PoisonError::into_inner);
+        // Bumped while the lock is held, so a cache built for the new revision waits for the
+        // change to finish
+        self.0.revision.invalidate();
+        guard
+    }
+
+    pub fn revision(&self) -> &Arc<Revision> {
+        &self.0.revision
+    }
+
+    /// Whether both handles refer to the same object.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Address of the shared object, unique for as long as any handle to it is alive.
+    pub fn id(&self) -> usize {
+        Arc::as_ptr(&self.0) as *const () as usize
+    }
+}
+
+impl<T: Clone + Tracked> Shared<T> {
+    /// A new object holding a copy of the current value.
+    pub fn copy(&self) -> Self {
+        Shared::new(self.read().clone())
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Shared(Arc::clone(&self.0))
+    }
+}
+
+impl<T: Default + Tracked> Default for Shared<T> {
+    fn default() -> Self {
+        Shared::new(T::default())
+    }
+}
+
+impl<T: PartialEq> PartialEq for Shared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || *self.read() == *other.read()
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Shared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", *self.read())
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", *self.read())
+    }
+}
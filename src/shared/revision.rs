@@ -0,0 +1,90 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError, Weak,
+    },
+};
+
+/// Change counter of a shared object, along with the revisions of the cells that depend on it.
+///
+/// Cells record themselves as dependents of the elements they hold and of everything their
+/// references place, so a change bumps the revision of the changed object and of every cell
+/// above it, and leaves the cached bounding boxes and spatial indexes of other cells alone.
+#[derive(Default)]
+pub struct Revision {
+    value: AtomicU64,
+    dependents: Mutex<Vec<(Weak<Revision>, usize)>>,
+}
+
+impl Revision {
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Acquire)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<(Weak<Revision>, usize)>> {
+        self.dependents
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records that `dependent` has to change whenever this revision does.
+    pub fn add_dependent(&self, dependent: &Arc<Revision>) {
+        let mut dependents = self.lock();
+        match dependents
+            .iter_mut()
+            .find(|(weak, _)| weak.as_ptr() == Arc::as_ptr(dependent))
+        {
+            Some((_, count)) => *count += 1,
+            None => dependents.push((Arc::downgrade(dependent), 1)),
+        }
+    }
+
+    /// Undoes one [`Revision::add_dependent`] call for `dependent`.
+    pub fn remove_dependent(&self, dependent: &Arc<Revision>) {
+        let mut dependents = self.lock();
+        if let Some(index) = dependents
+            .iter()
+            .position(|(weak, _)| weak.as_ptr() == Arc::as_ptr(dependent))
+        {
+            dependents[index].1 -= 1;
+            if dependents[index].1 == 0 {
+                dependents.remove(index);
+            }
+        }
+    }
+
+    /// The revisions that currently depend on this one.
+    pub fn dependents(&self) -> Vec<Arc<Revision>> {
+        let mut dependents = self.lock();
+        dependents.retain(|(weak, _)| weak.strong_count() > 0);
+        dependents
+            .iter()
+            .filter_map(|(weak, _)| weak.upgrade())
+            .collect()
+    }
+
+    /// Bumps this revision and every revision that depends on it, directly or through other
+    /// dependents.
+    pub fn invalidate(&self) {
+        self.value.fetch_add(1, Ordering::AcqRel);
+
+        // Reference cycles are only rejected when writing, so they can be met here
+        let mut visited = HashSet::new();
+        let mut pending = self.dependents();
+        while let Some(revision) = pending.pop() {
+            if visited.insert(Arc::as_ptr(&revision)) {
+                revision.value.fetch_add(1, Ordering::AcqRel);
+                pending.extend(revision.dependents());
+            }
+        }
+    }
+}
+
+/// Layout object that can be held by a [`Shared`](super::Shared) handle.
+pub trait Tracked {
+    /// The revision every handle to the object shares, a new one unless the object keeps its own.
+    fn revision(&self) -> Arc<Revision> {
+        Arc::default()
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, Mutex, PoisonError},
+};
+
+use pyo3::{
+    prelude::*,
+    pyclass::boolean_struct::True,
+    pyclass_init::PyClassInitializer,
+    types::{PyWeakrefMethods, PyWeakrefReference},
+    PyClass,
+};
+
+use super::{revision::Tracked, Shared};
+
+/// Python class exposing a [`Shared`] layout object.
+pub trait Wrapper: PyClass<Frozen = True> + Sync + Into<PyClassInitializer<Self>> {
+    type Inner;
+
+    fn wrap(shared: Shared<Self::Inner>) -> Self;
+
+    fn shared(&self) -> &Shared<Self::Inner>;
+}
+
+/// Layout object that is exposed to Python through a [`Wrapper`].
+pub trait Wrapped: Tracked + Sized {
+    type Wrapper: Wrapper<Inner = Self>;
+}
+
+const MIN_PRUNE_SIZE: usize = 1024;
+
+/// The Python object exposing each shared object, so reading the same object twice, for
+/// example through `cell.polygons`, gives back the same Python object.
+struct Registry {
+    wrappers: HashMap<usize, Py<PyWeakrefReference>>,
+    prune_at: usize,
+}
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(|| {
+    Mutex::new(Registry {
+        wrappers: HashMap::new(),
+        prune_at: MIN_PRUNE_SIZE,
+    })
+});
+
+fn registry() -> std::sync::MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn registered<'py, W: Wrapper>(py: Python<'py>, id: usize) -> Option<Bound<'py, W>> {
+    let reference = registry().wrappers.get(&id)?.clone_ref(py);
+    reference.bind(py).upgrade_as::<W>().ok().flatten()
+}
+
+fn register<W: Wrapper>(id: usize, wrapper: &Bound<'_, W>) -> PyResult<()> {
+    let reference = PyWeakrefReference::new_bound(wrapper.as_any())?.unbind();
+    let py = wrapper.py();
+
+    let mut registry = registry();
+    // Entries of Python objects that no longer exist are dropped once the map has doubled
+    if registry.wrappers.len() >= registry.prune_at {
+        registry
+            .wrappers
+            .retain(|_, reference| reference.bind(py).upgrade().is_some());
+        registry.prune_at = (registry.wrappers.len() * 2).max(MIN_PRUNE_SIZE);
+    }
+    registry.wrappers.insert(id, reference);
+    Ok(())
+}
+
+/// The Python object exposing `shared`, created if there is none yet.
+pub fn wrapper_object<T: Wrapped>(
+    shared: Shared<T>,
+    py: Python<'_>,
+) -> PyResult<Bound<'_, T::Wrapper>> {
+    let id = shared.id();
+    if let Some(wrapper) = registered(py, id) {
+        return Ok(wrapper);
+    }
+    let wrapper = Bound::new(py, T::Wrapper::wrap(shared))?;
+    register(id, &wrapper)?;
+    Ok(wrapper)
+}
+
+/// Copies the value held by a Python object.
+pub fn extract_value<T: Wrapped + Clone>(ob: &Bound<'_, PyAny>) -> PyResult<T> {
+    let wrapper = ob.downcast::<T::Wrapper>()?;
+    let value = wrapper.get().shared().read().clone();
+    Ok(value)
+}
+
+/// Moves a value into a new Python object.
+pub fn value_into_py<T: Wrapped>(value: T, py: Python<'_>) -> PyObject {
+    Shared::new(value).into_py(py)
+}
+
+impl<T: Wrapped> IntoPy<PyObject> for Shared<T> {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        wrapper_object(self, py).unwrap().into_any().unbind()
+    }
+}
+
+impl<T: Wrapped> FromPyObject<'_> for Shared<T> {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let wrapper = ob.downcast::<T::Wrapper>()?;
+        let shared = wrapper.get().shared().clone();
+        // Objects created from Python are only recorded once they are stored somewhere
+        let id = shared.id();
+        if registered::<T::Wrapper>(ob.py(), id).is_none() {
+            register(id, wrapper)?;
+        }
+        Ok(shared)
+    }
+}
@@ -0,0 +1,155 @@
+use crate::point::Point;
+
+// Glyphs are drawn on a grid four units wide with the baseline at zero and capitals six units tall
+pub const GLYPH_WIDTH: f64 = 4.0;
+pub const ADVANCE: f64 = 6.0;
+pub const CAP_HEIGHT: f64 = 6.0;
+pub const DESCENT: f64 = 2.0;
+pub const LINE_HEIGHT: f64 = 10.0;
+pub const STROKE_WIDTH: f64 = 1.0;
+
+// Strokes are separated by `|`, each stroke is a polyline of `x,y` points
+fn glyph_definition(character: char) -> Option<&'static str> {
+    let definition = match character {
+        ' ' => "",
+        '!' => "2,6 2,2|2,0.5 2,0",
+        '"' => "1,6 1,4|3,6 3,4",
+        '#' => "1,0 1,6|3,0 3,6|0,2 4,2|0,4 4,4",
+        '$' => "4,5 3,6 1,6 0,5 0,4 1,3 3,3 4,2 4,1 3,0 1,0 0,1|2,6.5 2,-0.5",
+        '%' => "0,0 4,6|0,6 0,5 1,5 1,6 0,6|3,0 3,1 4,1 4,0 3,0",
+        '&' => "4,0 1,4 1,5 2,6 3,5 3,4 0,2 0,1 1,0 2,0 4,2",
+        '\'' => "2,6 2,4",
+        '(' => "3,6 1,4 1,2 3,0",
+        ')' => "1,6 3,4 3,2 1,0",
+        '*' => "2,1 2,5|0,2 4,4|0,4 4,2",
+        '+' => "2,1 2,5|0,3 4,3",
+        ',' => "2,1 2,0 1,-1",
+        '-' => "0,3 4,3",
+        '.' => "2,0.5 2,0",
+        '/' => "0,0 4,6",
+        '0' => "1,0 0,1 0,5 1,6 3,6 4,5 4,1 3,0 1,0|0,1 4,5",
+        '1' => "1,5 2,6 2,0|1,0 3,0",
+        '2' => "0,5 1,6 3,6 4,5 4,4 0,0 4,0",
+        '3' => "0,5 1,6 3,6 4,5 4,4 3,3 4,2 4,1 3,0 1,0 0,1|1,3 3,3",
+        '4' => "3,0 3,6 0,2 4,2",
+        '5' => "4,6 0,6 0,3 3,3 4,2 4,1 3,0 0,0",
+        '6' => "4,5 3,6 1,6 0,5 0,1 1,0 3,0 4,1 4,2 3,3 0,3",
+        '7' => "0,6 4,6 1,0",
+        '8' => "1,3 0,4 0,5 1,6 3,6 4,5 4,4 3,3 1,3 0,2 0,1 1,0 3,0 4,1 4,2 3,3",
+        '9' => "0,1 1,0 3,0 4,1 4,5 3,6 1,6 0,5 0,4 1,3 4,3",
+        ':' => "2,4 2,3.5|2,0.5 2,0",
+        ';' => "2,4 2,3.5|2,1 2,0 1,-1",
+        '<' => "4,6 0,3 4,0",
+        '=' => "0,2 4,2|0,4 4,4",
+        '>' => "0,6 4,3 0,0",
+        '?' => "0,5 1,6 3,6 4,5 4,4 2,2 2,1.5|2,0.5 2,0",
+        '@' => "3,2 1,2 1,4 3,4 3,1 4,1 4,5 3,6 1,6 0,5 0,1 1,0 4,0",
+        'A' => "0,0 0,4 2,6 4,4 4,0|0,3 4,3",
+        'B' => "0,0 0,6 3,6 4,5 4,4 3,3 0,3|3,3 4,2 4,1 3,0 0,0",
+        'C' => "4,5 3,6 1,6 0,5 0,1 1,0 3,0 4,1",
+        'D' => "0,0 0,6 2,6 4,4 4,2 2,0 0,0",
+        'E' => "4,6 0,6 0,0 4,0|0,3 3,3",
+        'F' => "4,6 0,6 0,0|0,3 3,3",
+        'G' => "4,5 3,6 1,6 0,5 0,1 1,0 3,0 4,1 4,3 2,3",
+        'H' => "0,0 0,6|4,0 4,6|0,3 4,3",
+        'I' => "1,6 3,6|2,6 2,0|1,0 3,0",
+        'J' => "4,6 4,1 3,0 1,0 0,1",
+        'K' => "0,0 0,6|4,6 0,2|1,3 4,0",
+        'L' => "0,6 0,0 4,0",
+        'M' => "0,0 0,6 2,3 4,6 4,0",
+        'N' => "0,0 0,6 4,0 4,6",
+        'O' => "1,0 0,1 0,5 1,6 3,6 4,5 4,1 3,0 1,0",
+        'P' => "0,0 0,6 3,6 4,5 4,4 3,3 0,3",
+        'Q' => "1,0 0,1 0,5 1,6 3,6 4,5 4,1 3,0 1,0|2,2 4,0",
+        'R' => "0,0 0,6 3,6 4,5 4,4 3,3 0,3|2,3 4,0",
+        'S' => "4,5 3,6 1,6 0,5 0,4 1,3 3,3 4,2 4,1 3,0 1,0 0,1",
+        'T' => "0,6 4,6|2,6 2,0",
+        'U' => "0,6 0,1 1,0 3,0 4,1 4,6",
+        'V' => "0,6 2,0 4,6",
+        'W' => "0,6 1,0 2,3 3,0 4,6",
+        'X' => "0,0 4,6|0,6 4,0",
+        'Y' => "0,6 2,3 4,6|2,3 2,0",
+        'Z' => "0,6 4,6 0,0 4,0",
+        '[' => "3,6 1,6 1,0 3,0",
+        '\\' => "0,6 4,0",
+        ']' => "1,6 3,6 3,0 1,0",
+        '^' => "0,4 2,6 4,4",
+        '_' => "0,-1 4,-1",
+        '`' => "1,6 2,5",
+        'a' => "1,4 3,4 4,3 4,0|4,2 1,2 0,1 1,0 3,0 4,1",
+        'b' => "0,6 0,0|0,3 1,4 3,4 4,3 4,1 3,0 1,0 0,1",
+        'c' => "4,3 3,4 1,4 0,3 0,1 1,0 3,0 4,1",
+        'd' => "4,6 4,0|4,3 3,4 1,4 0,3 0,1 1,0 3,0 4,1",
+        'e' => "0,2 4,2 4,3 3,4 1,4 0,3 0,1 1,0 3,0 4,1",
+        'f' => "4,5.5 3,6 2,6 1,5 1,0|0,4 3,4",
+        'g' => "4,4 4,-1 3,-2 1,-2 0,-1|4,3 3,4 1,4 0,3 0,1 1,0 3,0 4,1",
+        'h' => "0,6 0,0|0,3 1,4 3,4 4,3 4,0",
+        'i' => "2,4 2,0|2,6 2,5.5",
+        'j' => "3,4 3,-1 2,-2 1,-2 0,-1|3,6 3,5.5",
+        'k' => "0,6 0,0|4,4 0,1|1,2 4,0",
+        'l' => "1,6 2,6 2,0|1,0 3,0",
+        'm' => "0,4 0,0|0,3 1,4 2,3 2,0|2,3 3,4 4,3 4,0",
+        'n' => "0,4 0,0|0,3 1,4 3,4 4,3 4,0",
+        'o' => "1,0 0,1 0,3 1,4 3,4 4,3 4,1 3,0 1,0",
+        'p' => "0,4 0,-2|0,3 1,4 3,4 4,3 4,1 3,0 1,0 0,1",
+        'q' => "4,4 4,-2|4,3 3,4 1,4 0,3 0,1 1,0 3,0 4,1",
+        'r' => "0,4 0,0|0,2 2,4 4,4",
+        's' => "4,3 3,4 1,4 0,3 1,2 3,2 4,1 3,0 1,0 0,1",
+        't' => "1,6 1,1 2,0 3,0 4,1|0,4 3,4",
+        'u' => "0,4 0,1 1,0 3,0 4,1|4,4 4,0",
+        'v' => "0,4 2,0 4,4",
+        'w' => "0,4 1,0 2,2 3,0 4,4",
+        'x' => "0,0 4,4|0,4 4,0",
+        'y' => "0,4 0,1 1,0 3,0 4,1|4,4 4,-1 3,-2 1,-2 0,-1",
+        'z' => "0,4 4,4 0,0 4,0",
+        '{' => "3,6 2,6 2,3.5 1,3 2,2.5 2,0 3,0",
+        '|' => "2,-1 2,6",
+        '}' => "1,6 2,6 2,3.5 3,3 2,2.5 2,0 1,0",
+        '~' => "0,3 1,4 3,2 4,3",
+        _ => return None,
+    };
+    Some(definition)
+}
+
+/// Strokes of a glyph in font units, characters without a glyph are drawn as `?`.
+pub fn glyph_strokes(character: char) -> Vec<Vec<Point>> {
+    let definition = glyph_definition(character)
+        .or_else(|| glyph_definition('?'))
+        .unwrap_or_default();
+
+    definition
+        .split('|')
+        .filter(|stroke| !stroke.is_empty())
+        .map(|stroke| {
+            stroke
+                .split(' ')
+                .filter_map(|point| {
+                    let (x, y) = point.split_once(',')?;
+                    Some(Point::new(x.parse().ok()?, y.parse().ok()?))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Width of a single line of text in font units, without trailing letter spacing.
+pub fn line_width(line: &str) -> f64 {
+    let characters = line.chars().count();
+    if characters == 0 {
+        return 0.0;
+    }
+    characters as f64 * ADVANCE - (ADVANCE - GLYPH_WIDTH)
+}
+
+/// Lower left and upper right of the text block in font units,
+/// with the first baseline at zero and every line left aligned.
+pub fn text_bounds(text: &str) -> (Point, Point) {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let width = lines
+        .iter()
+        .map(|line| line_width(line))
+        .fold(0.0, f64::max);
+    let bottom = -((lines.len() - 1) as f64) * LINE_HEIGHT - DESCENT;
+
+    (Point::new(0.0, bottom), Point::new(width, CAP_HEIGHT))
+}
@@ -1,201 +1,371 @@
-use std::ops::DerefMut;
+use std::collections::HashMap;
 
 use pyo3::prelude::*;
 
 use crate::{
+    config::DEFAULT_GRID,
     point::Point,
+    polygon::Polygon,
+    shared::Shared,
     text::presentation::{HorizontalPresentation, VerticalPresentation},
     traits::{Dimensions, LayerDataTypeMatches, Movable, Rotatable, Scalable},
     utils::transformations::py_any_to_point,
-    validation::input::check_layer_valid,
+    validation::input::{
+        check_data_type_valid, check_grid_valid, check_layer_valid, check_properties_valid,
+    },
 };
 
-use super::Text;
+use super::{utils::text_to_polygons, PyText, Text};
 
-#[pymethods]
 impl Text {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        text: String,
+        origin: Point,
+        layer: i32,
+        text_type: i32,
+        magnification: f64,
+        angle: f64,
+        x_reflection: bool,
+        vertical_presentation: VerticalPresentation,
+        horizontal_presentation: HorizontalPresentation,
+        properties: Option<HashMap<i32, String>>,
+        absolute_magnification: bool,
+        absolute_angle: bool,
+    ) -> PyResult<Self> {
+        check_layer_valid(layer)?;
+        check_data_type_valid(text_type)?;
+        let properties = properties.unwrap_or_default();
+        check_properties_valid(&properties)?;
+
+        Ok(Text {
+            text,
+            origin,
+            layer,
+            text_type,
+            magnification,
+            angle,
+            x_reflection,
+            vertical_presentation,
+            horizontal_presentation,
+            properties,
+            absolute_magnification,
+            absolute_angle,
+        })
+    }
+}
+
+#[pymethods]
+impl PyText {
     #[new]
     #[pyo3(signature = (
         text,
         origin=Point::default(),
         layer=0,
+        text_type=0,
         magnification=1.0,
         angle=0.0,
         x_reflection=false,
         vertical_presentation=VerticalPresentation::default(),
-        horizontal_presentation=HorizontalPresentation::default()
+        horizontal_presentation=HorizontalPresentation::default(),
+        properties=None,
+        absolute_magnification=false,
+        absolute_angle=false
     ))]
     #[allow(clippy::too_many_arguments)]
-    pub fn new(
+    fn new(
         text: String,
         #[pyo3(from_py_with = "py_any_to_point")] origin: Point,
         layer: i32,
+        text_type: i32,
         magnification: f64,
         angle: f64,
         x_reflection: bool,
         vertical_presentation: VerticalPresentation,
         horizontal_presentation: HorizontalPresentation,
+        properties: Option<HashMap<i32, String>>,
+        absolute_magnification: bool,
+        absolute_angle: bool,
     ) -> PyResult<Self> {
-        check_layer_valid(layer)?;
-
-        Ok(Text {
+        Text::new(
             text,
             origin,
             layer,
+            text_type,
             magnification,
             angle,
             x_reflection,
             vertical_presentation,
             horizontal_presentation,
-        })
+            properties,
+            absolute_magnification,
+            absolute_angle,
+        )
+        .map(|value| PyText(Shared::new(value)))
+    }
+
+    #[getter]
+    fn text(&self) -> String {
+        self.0.read().text.clone()
+    }
+
+    #[getter]
+    fn origin(&self) -> Point {
+        self.0.read().origin
+    }
+
+    #[getter]
+    fn layer(&self) -> i32 {
+        self.0.read().layer
+    }
+
+    #[getter]
+    fn text_type(&self) -> i32 {
+        self.0.read().text_type
+    }
+
+    #[getter]
+    fn magnification(&self) -> f64 {
+        self.0.read().magnification
+    }
+
+    #[getter]
+    fn angle(&self) -> f64 {
+        self.0.read().angle
+    }
+
+    #[getter]
+    fn x_reflection(&self) -> bool {
+        self.0.read().x_reflection
+    }
+
+    #[getter]
+    fn vertical_presentation(&self) -> VerticalPresentation {
+        self.0.read().vertical_presentation
+    }
+
+    #[getter]
+    fn horizontal_presentation(&self) -> HorizontalPresentation {
+        self.0.read().horizontal_presentation
+    }
+
+    #[getter]
+    fn properties(&self) -> HashMap<i32, String> {
+        self.0.read().properties.clone()
+    }
+
+    #[getter]
+    fn absolute_magnification(&self) -> bool {
+        self.0.read().absolute_magnification
+    }
+
+    #[getter]
+    fn absolute_angle(&self) -> bool {
+        self.0.read().absolute_angle
     }
 
     #[setter(text)]
-    fn setter_text(&mut self, text: String) {
-        self.text = text;
+    fn setter_text(&self, text: String) {
+        self.0.write().text = text;
     }
 
-    fn set_text(mut slf: PyRefMut<'_, Self>, text: String) -> PyRefMut<'_, Self> {
+    fn set_text(slf: PyRef<'_, Self>, text: String) -> PyRef<'_, Self> {
         slf.setter_text(text);
         slf
     }
 
     #[setter(origin)]
-    fn setter_origin(&mut self, #[pyo3(from_py_with = "py_any_to_point")] origin: Point) {
-        self.origin = origin;
+    fn setter_origin(&self, #[pyo3(from_py_with = "py_any_to_point")] origin: Point) {
+        self.0.write().origin = origin;
     }
 
     fn set_origin(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] origin: Point,
-    ) -> PyRefMut<'_, Self> {
+    ) -> PyRef<'_, Self> {
         slf.setter_origin(origin);
         slf
     }
 
     #[setter(layer)]
-    fn setter_layer(&mut self, layer: i32) -> PyResult<()> {
+    fn setter_layer(&self, layer: i32) -> PyResult<()> {
         check_layer_valid(layer)?;
-        self.layer = layer;
+        self.0.write().layer = layer;
         Ok(())
     }
 
-    fn set_layer(mut slf: PyRefMut<'_, Self>, layer: i32) -> PyRefMut<'_, Self> {
+    fn set_layer(slf: PyRef<'_, Self>, layer: i32) -> PyRef<'_, Self> {
         slf.setter_layer(layer).unwrap();
         slf
     }
 
+    #[setter(text_type)]
+    fn setter_text_type(&self, text_type: i32) -> PyResult<()> {
+        check_data_type_valid(text_type)?;
+        self.0.write().text_type = text_type;
+        Ok(())
+    }
+
+    fn set_text_type(slf: PyRef<'_, Self>, text_type: i32) -> PyRef<'_, Self> {
+        slf.setter_text_type(text_type).unwrap();
+        slf
+    }
+
     #[setter(magnification)]
-    fn setter_magnification(&mut self, magnification: f64) {
-        self.magnification = magnification;
+    fn setter_magnification(&self, magnification: f64) {
+        self.0.write().magnification = magnification;
     }
 
-    fn set_magnification(mut slf: PyRefMut<'_, Self>, magnification: f64) -> PyRefMut<'_, Self> {
+    fn set_magnification(slf: PyRef<'_, Self>, magnification: f64) -> PyRef<'_, Self> {
         slf.setter_magnification(magnification);
         slf
     }
 
     #[setter(angle)]
-    fn setter_angle(&mut self, angle: f64) {
-        self.angle = angle;
+    fn setter_angle(&self, angle: f64) {
+        self.0.write().angle = angle;
     }
 
-    fn set_angle(mut slf: PyRefMut<'_, Self>, angle: f64) -> PyRefMut<'_, Self> {
+    fn set_angle(slf: PyRef<'_, Self>, angle: f64) -> PyRef<'_, Self> {
         slf.setter_angle(angle);
         slf
     }
 
     #[setter(x_reflection)]
-    fn setter_x_reflection(&mut self, x_reflection: bool) {
-        self.x_reflection = x_reflection;
+    fn setter_x_reflection(&self, x_reflection: bool) {
+        self.0.write().x_reflection = x_reflection;
     }
 
-    fn set_x_reflection(mut slf: PyRefMut<'_, Self>, x_reflection: bool) -> PyRefMut<'_, Self> {
+    fn set_x_reflection(slf: PyRef<'_, Self>, x_reflection: bool) -> PyRef<'_, Self> {
         slf.setter_x_reflection(x_reflection);
         slf
     }
 
+    #[setter(absolute_magnification)]
+    fn setter_absolute_magnification(&self, absolute_magnification: bool) {
+        self.0.write().absolute_magnification = absolute_magnification;
+    }
+
+    fn set_absolute_magnification(
+        slf: PyRef<'_, Self>,
+        absolute_magnification: bool,
+    ) -> PyRef<'_, Self> {
+        slf.setter_absolute_magnification(absolute_magnification);
+        slf
+    }
+
+    #[setter(absolute_angle)]
+    fn setter_absolute_angle(&self, absolute_angle: bool) {
+        self.0.write().absolute_angle = absolute_angle;
+    }
+
+    fn set_absolute_angle(slf: PyRef<'_, Self>, absolute_angle: bool) -> PyRef<'_, Self> {
+        slf.setter_absolute_angle(absolute_angle);
+        slf
+    }
+
     #[setter(vertical_presentation)]
-    fn setter_vertical_presentation(&mut self, vertical_presentation: VerticalPresentation) {
-        self.vertical_presentation = vertical_presentation;
+    fn setter_vertical_presentation(&self, vertical_presentation: VerticalPresentation) {
+        self.0.write().vertical_presentation = vertical_presentation;
     }
 
     fn set_vertical_presentation(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         vertical_presentation: VerticalPresentation,
-    ) -> PyRefMut<'_, Self> {
+    ) -> PyRef<'_, Self> {
         slf.setter_vertical_presentation(vertical_presentation);
         slf
     }
 
     #[setter(horizontal_presentation)]
-    fn setter_horizontal_presentation(&mut self, horizontal_presentation: HorizontalPresentation) {
-        self.horizontal_presentation = horizontal_presentation;
+    fn setter_horizontal_presentation(&self, horizontal_presentation: HorizontalPresentation) {
+        self.0.write().horizontal_presentation = horizontal_presentation;
     }
 
     fn set_horizontal_presentation(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         horizontal_presentation: HorizontalPresentation,
-    ) -> PyRefMut<'_, Self> {
+    ) -> PyRef<'_, Self> {
         slf.setter_horizontal_presentation(horizontal_presentation);
         slf
     }
 
+    #[setter(properties)]
+    fn setter_properties(&self, properties: HashMap<i32, String>) -> PyResult<()> {
+        check_properties_valid(&properties)?;
+        self.0.write().properties = properties;
+        Ok(())
+    }
+
+    fn set_properties(
+        slf: PyRef<'_, Self>,
+        properties: HashMap<i32, String>,
+    ) -> PyResult<PyRef<'_, Self>> {
+        slf.setter_properties(properties)?;
+        Ok(slf)
+    }
+
     #[getter]
     fn bounding_box(&self) -> (Point, Point) {
-        Dimensions::bounding_box(self)
+        Dimensions::bounding_box(&*self.0.read())
+    }
+
+    #[pyo3(signature = (width=None, grid=DEFAULT_GRID))]
+    pub fn to_polygons(&self, width: Option<f64>, grid: f64) -> PyResult<Vec<Polygon>> {
+        check_grid_valid(grid)?;
+        text_to_polygons(&self.0.read(), width, grid)
     }
 
     pub fn copy(&self) -> Self {
-        self.clone()
+        Self(self.0.copy())
     }
 
     fn move_to(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] point: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_to(slf.deref_mut(), point);
+    ) -> PyRef<'_, Self> {
+        Movable::move_to(&mut *slf.0.write(), point);
         slf
     }
 
     fn move_by(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         #[pyo3(from_py_with = "py_any_to_point")] vector: Point,
-    ) -> PyRefMut<'_, Self> {
-        Movable::move_by(slf.deref_mut(), vector);
+    ) -> PyRef<'_, Self> {
+        Movable::move_by(&mut *slf.0.write(), vector);
         slf
     }
 
     #[pyo3(signature = (angle, centre=Point::default()))]
     fn rotate(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         angle: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Rotatable::rotate(slf.deref_mut(), angle, centre);
+    ) -> PyRef<'_, Self> {
+        Rotatable::rotate(&mut *slf.0.write(), angle, centre);
         slf
     }
 
     #[pyo3(signature = (factor, centre=Point::default()))]
     fn scale(
-        mut slf: PyRefMut<'_, Self>,
+        slf: PyRef<'_, Self>,
         factor: f64,
         #[pyo3(from_py_with = "py_any_to_point")] centre: Point,
-    ) -> PyRefMut<'_, Self> {
-        Scalable::scale(slf.deref_mut(), factor, centre);
+    ) -> PyRef<'_, Self> {
+        Scalable::scale(&mut *slf.0.write(), factor, centre);
         slf
     }
 
     #[pyo3(signature = (*layer_data_types))]
     pub fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
-        LayerDataTypeMatches::is_on(self, layer_data_types)
+        LayerDataTypeMatches::is_on(&*self.0.read(), layer_data_types)
     }
 
     fn __str__(&self) -> PyResult<String> {
-        Ok(format!("{}", self))
+        Ok(format!("{}", self.0))
     }
 
     fn __repr__(&self) -> PyResult<String> {
-        Ok(format!("{:?}", self))
+        Ok(format!("{:?}", self.0))
     }
 }
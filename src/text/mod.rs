@@ -1,33 +1,73 @@
-use log::warn;
+use std::collections::HashMap;
+
 use pyo3::prelude::*;
 
+use crate::config::FLOATING_POINT_INACCURACY_ROUND_DECIMALS;
 use crate::point::Point;
+use crate::shared::{
+    revision::Tracked,
+    wrapper::{extract_value, value_into_py, Wrapped, Wrapper},
+    Shared,
+};
 use crate::traits::{Dimensions, LayerDataTypeMatches, Movable, Reflect, Rotatable, Scalable};
+use crate::utils::geometry::bounding_box;
+
+use presentation::{HorizontalPresentation, VerticalPresentation};
 
+pub mod font;
 mod general;
 mod io;
 pub mod presentation;
 pub mod utils;
 
-#[pyclass(eq)]
 #[derive(Clone)]
 pub struct Text {
-    #[pyo3(get)]
     pub text: String,
-    #[pyo3(get)]
     pub origin: Point,
-    #[pyo3(get)]
     pub layer: i32,
-    #[pyo3(get)]
+    pub text_type: i32,
     pub magnification: f64,
-    #[pyo3(get)]
     pub angle: f64,
-    #[pyo3(get)]
     pub x_reflection: bool,
-    #[pyo3(get)]
     pub vertical_presentation: presentation::VerticalPresentation,
-    #[pyo3(get)]
     pub horizontal_presentation: presentation::HorizontalPresentation,
+    pub properties: HashMap<i32, String>,
+    pub absolute_magnification: bool,
+    pub absolute_angle: bool,
+}
+
+#[pyclass(name = "Text", eq, frozen, weakref)]
+#[derive(PartialEq)]
+pub struct PyText(pub Shared<Text>);
+
+impl Wrapper for PyText {
+    type Inner = Text;
+
+    fn wrap(shared: Shared<Text>) -> Self {
+        PyText(shared)
+    }
+
+    fn shared(&self) -> &Shared<Text> {
+        &self.0
+    }
+}
+
+impl Wrapped for Text {
+    type Wrapper = PyText;
+}
+
+impl Tracked for Text {}
+
+impl IntoPy<PyObject> for Text {
+    fn into_py(self, py: Python) -> PyObject {
+        value_into_py(self, py)
+    }
+}
+
+impl FromPyObject<'_> for Text {
+    fn extract_bound(ob: &Bound<'_, PyAny>) -> PyResult<Self> {
+        extract_value(ob)
+    }
 }
 
 impl Default for Text {
@@ -36,11 +76,15 @@ impl Default for Text {
             text: String::from(""),
             origin: Point::default(),
             layer: 0,
+            text_type: 0,
             magnification: 1.0,
             angle: 0.0,
             x_reflection: false,
             vertical_presentation: presentation::VerticalPresentation::default(),
             horizontal_presentation: presentation::HorizontalPresentation::default(),
+            properties: HashMap::new(),
+            absolute_magnification: false,
+            absolute_angle: false,
         }
     }
 }
@@ -50,11 +94,15 @@ impl PartialEq for Text {
         self.text == other.text
             && self.origin.epsilon_is_close(other.origin)
             && self.layer == other.layer
+            && self.text_type == other.text_type
             && self.magnification == other.magnification
             && self.angle == other.angle
             && self.x_reflection == other.x_reflection
             && self.vertical_presentation == other.vertical_presentation
             && self.horizontal_presentation == other.horizontal_presentation
+            && self.properties == other.properties
+            && self.absolute_magnification == other.absolute_magnification
+            && self.absolute_angle == other.absolute_angle
     }
 }
 
@@ -113,18 +161,56 @@ impl Scalable for Text {
     }
 }
 
-impl Dimensions for Text {
-    fn bounding_box(&self) -> (Point, Point) {
-        warn!("Bounding box of text is not implemented yet. Returning a box around the text.");
-        let width = self.text.len() as f64 * self.magnification;
-        let height = self.magnification;
-        let half_width = width / 2.0;
-        let half_height = height / 2.0;
+impl Text {
+    // Moves the left aligned font block so that the origin sits on the presentation anchor
+    fn presentation_offset(&self) -> Point {
+        let (lower_left, upper_right) = font::text_bounds(&self.text);
+        let x = match self.horizontal_presentation {
+            HorizontalPresentation::Left => 0.0,
+            HorizontalPresentation::Centre => -upper_right.x / 2.0,
+            HorizontalPresentation::Right => -upper_right.x,
+        };
+        let y = match self.vertical_presentation {
+            VerticalPresentation::Top => -upper_right.y,
+            VerticalPresentation::Middle => -(lower_left.y + upper_right.y) / 2.0,
+            VerticalPresentation::Bottom => -lower_left.y,
+        };
+        Point::new(x, y)
+    }
 
-        let lower_left = self.origin - Point::new(half_width, half_height);
-        let upper_right = self.origin + Point::new(half_width, half_height);
+    /// Maps a point in font units onto the text placement, magnification sets the capital height.
+    pub fn font_to_world(&self, point: Point) -> Point {
+        let point = (point + self.presentation_offset()) * (self.magnification / font::CAP_HEIGHT);
+        let point = if self.x_reflection {
+            Point::new(point.x, -point.y)
+        } else {
+            point
+        };
+        (point.rotate(self.angle, Point::default()) + self.origin)
+            .round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS)
+    }
+}
 
-        (lower_left, upper_right)
+impl Dimensions for Text {
+    fn bounding_box(&self) -> (Point, Point) {
+        let (lower_left, upper_right) = font::text_bounds(&self.text);
+        // Miter joins reach furthest past the glyph centre-lines, square caps stay within that
+        let reach = font::STROKE_WIDTH / 2.0 * utils::JOIN_MITER_LIMIT;
+        let (lower_left, upper_right) = (
+            lower_left - Point::new(reach, reach),
+            upper_right + Point::new(reach, reach),
+        );
+        let corners = vec![
+            lower_left,
+            Point::new(upper_right.x, lower_left.y),
+            upper_right,
+            Point::new(lower_left.x, upper_right.y),
+        ]
+        .into_iter()
+        .map(|corner| self.font_to_world(corner))
+        .collect();
+
+        bounding_box(&corners)
     }
 }
 
@@ -138,10 +224,6 @@ impl Reflect for Text {
 
 impl LayerDataTypeMatches for Text {
     fn is_on(&self, layer_data_types: Vec<(i32, i32)>) -> bool {
-        let all_layers = layer_data_types
-            .iter()
-            .map(|(layer, _)| *layer)
-            .collect::<Vec<i32>>();
-        all_layers.contains(&self.layer) || layer_data_types.is_empty()
+        layer_data_types.contains(&(self.layer, self.text_type)) || layer_data_types.is_empty()
     }
 }
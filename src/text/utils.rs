@@ -1,6 +1,18 @@
+use i_overlay::mesh::{
+    stroke::offset::StrokeOffset,
+    style::{LineCap, StrokeStyle},
+};
 use pyo3::PyResult;
 
-use super::presentation::{HorizontalPresentation, VerticalPresentation};
+use crate::{offset::OffsetJoin, point::Point, polygon::Polygon, utils::overlay::OverlayGrid};
+
+use super::{
+    font,
+    presentation::{HorizontalPresentation, VerticalPresentation},
+    Text,
+};
+
+pub const JOIN_MITER_LIMIT: f64 = 2.0;
 
 pub fn get_presentation_value(
     vertical_presentation: VerticalPresentation,
@@ -52,3 +64,58 @@ pub fn get_presentations_from_value(
 
     Ok((vertical_presentation, horizontal_presentation))
 }
+
+pub fn text_to_polygons(text: &Text, width: Option<f64>, grid: f64) -> PyResult<Vec<Polygon>> {
+    let scale = text.magnification.abs() / font::CAP_HEIGHT;
+    let width = width.unwrap_or(font::STROKE_WIDTH * scale).abs();
+
+    let block_width = font::text_bounds(&text.text).1.x;
+    let alignment = match text.horizontal_presentation {
+        HorizontalPresentation::Left => 0.0,
+        HorizontalPresentation::Centre => 0.5,
+        HorizontalPresentation::Right => 1.0,
+    };
+
+    let mut strokes = Vec::new();
+    for (line_index, line) in text.text.split('\n').enumerate() {
+        let line_origin = Point::new(
+            (block_width - font::line_width(line)) * alignment,
+            -(line_index as f64) * font::LINE_HEIGHT,
+        );
+        for (character_index, character) in line.chars().enumerate() {
+            let glyph_origin =
+                line_origin + Point::new(character_index as f64 * font::ADVANCE, 0.0);
+            for stroke in font::glyph_strokes(character) {
+                strokes.push(
+                    stroke
+                        .into_iter()
+                        .map(|point| {
+                            let point = text.font_to_world(point + glyph_origin);
+                            [point.x, point.y]
+                        })
+                        .collect::<Vec<[f64; 2]>>(),
+                );
+            }
+        }
+    }
+
+    if width == 0.0 || strokes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let style = StrokeStyle::new(width)
+        .line_join(OffsetJoin::Miter(JOIN_MITER_LIMIT).line_join())
+        .start_cap(LineCap::Square)
+        .end_cap(LineCap::Square);
+
+    let outline = strokes.stroke(style, false);
+    let grid = OverlayGrid::fit_shapes(&outline, grid)?;
+    Ok(grid
+        .shapes_to_polygons(grid.snap_shapes(outline), text.layer, text.text_type)?
+        .into_iter()
+        .map(|polygon| Polygon {
+            properties: text.properties.clone(),
+            ..polygon
+        })
+        .collect())
+}
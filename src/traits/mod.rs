@@ -1,10 +1,10 @@
 use pyo3::prelude::*;
 use std::fs::File;
 
-use crate::point::Point;
+use crate::{config::max_points_mode::MaxPointsMode, point::Point};
 
 pub trait ToGds {
-    fn _to_gds(&self, file: File, scale: f64) -> PyResult<File>;
+    fn _to_gds(&self, file: File, scale: f64, max_points_mode: MaxPointsMode) -> PyResult<File>;
 }
 
 pub trait Movable {
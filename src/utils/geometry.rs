@@ -1,9 +1,28 @@
-use pyo3::prelude::*;
+use pyo3::{exceptions::PyValueError, prelude::*};
 
-use crate::point::Point;
+use crate::{point::Point, polygon::Polygon};
 
 use super::general::check_points_vec_not_empty;
 
+/// Relative tolerance below which areas, and the overlaps derived from them, count as zero.
+pub const AREA_TOLERANCE: f64 = 1e-9;
+
+/// Closed axis-aligned rectangle from `min` to `max`.
+pub fn rectangle(min: Point, max: Point, layer: i32, data_type: i32) -> Polygon {
+    Polygon {
+        points: vec![
+            min,
+            Point::new(max.x, min.y),
+            max,
+            Point::new(min.x, max.y),
+            min,
+        ],
+        layer,
+        data_type,
+        ..Polygon::default()
+    }
+}
+
 pub fn bounding_box(points: &Vec<Point>) -> (Point, Point) {
     let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
     let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
@@ -124,3 +143,92 @@ pub fn round_to_decimals(value: f64, ndigits: u32) -> f64 {
     let factor = 10f64.powi(ndigits as i32);
     (value * factor).round() / factor
 }
+
+// Removes floating point noise such as 9.999999999999999e-7 from unit conversions
+pub fn round_to_significant_digits(value: f64) -> f64 {
+    format!("{:.12e}", value).parse().unwrap_or(value)
+}
+
+pub fn signed_area(points: &[Point]) -> f64 {
+    let length = points.len();
+    let mut area = 0.0;
+
+    for index in 0..length {
+        let current = points[index];
+        let next = points[(index + 1) % length];
+        area += current.x * next.y - next.x * current.y;
+    }
+
+    area / 2.0
+}
+
+/// Joins holes into an outer contour with zero-width cuts so the result can be
+/// written as a single GDS boundary. The outer contour and holes must be open
+/// (no repeated closing point) and the holes must lie inside the outer contour.
+pub fn keyhole(outer: &[Point], holes: &[Vec<Point>]) -> PyResult<Vec<Point>> {
+    let mut result = outer.to_vec();
+
+    let mut holes = holes
+        .iter()
+        .filter(|hole| !hole.is_empty())
+        .collect::<Vec<_>>();
+    holes.sort_by(|a, b| {
+        let (a_min, _) = bounding_box(a);
+        let (b_min, _) = bounding_box(b);
+        a_min.x.total_cmp(&b_min.x)
+    });
+
+    for hole in holes {
+        let hole_index = (0..hole.len())
+            .min_by(|&a, &b| {
+                hole[a]
+                    .x
+                    .total_cmp(&hole[b].x)
+                    .then(hole[a].y.total_cmp(&hole[b].y))
+            })
+            .unwrap_or(0);
+        let hole_point = hole[hole_index];
+
+        let length = result.len();
+        let mut bridge: Option<(usize, Point)> = None;
+
+        for index in 0..length {
+            let start = result[index];
+            let end = result[(index + 1) % length];
+
+            if (start.y > hole_point.y) == (end.y > hole_point.y) {
+                continue;
+            }
+
+            let x = start.x + (hole_point.y - start.y) * (end.x - start.x) / (end.y - start.y);
+
+            if x <= hole_point.x && bridge.is_none_or(|(_, point)| x > point.x) {
+                bridge = Some((index, Point::new(x, hole_point.y)));
+            }
+        }
+
+        let Some((edge_index, bridge_point)) = bridge else {
+            return Err(PyValueError::new_err(format!(
+                "Hole at {} lies outside its outer contour",
+                hole_point
+            )));
+        };
+
+        let mut joined = Vec::with_capacity(length + hole.len() + 3);
+        joined.extend_from_slice(&result[..=edge_index]);
+        joined.push(bridge_point);
+        joined.extend_from_slice(&hole[hole_index..]);
+        joined.extend_from_slice(&hole[..=hole_index]);
+        joined.push(bridge_point);
+        joined.extend_from_slice(&result[edge_index + 1..]);
+
+        result = joined;
+    }
+
+    result.dedup();
+    while result.len() > 1 && result.first() == result.last() {
+        result.pop();
+    }
+
+    Ok(result)
+}
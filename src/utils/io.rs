@@ -1,56 +1,43 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{self, BufReader, Read, Write};
 
 use bytemuck::cast_slice;
-use chrono::{Datelike, Local, Timelike};
+use chrono::{Local, NaiveDateTime};
 use pyo3::{exceptions::PyIOError, prelude::*};
 use tempfile::Builder;
 
-use crate::cell::Cell;
+use crate::cell::{hierarchy::CellHierarchy, Cell};
 use crate::config::gds_file_types::GDSRecordData;
 use crate::config::gds_file_types::{combine_record_and_data_type, GDSDataType, GDSRecord};
+use crate::config::max_points_mode::{MaxPointsMode, MAX_POINTS};
+use crate::config::timestamp::{dates_to_gds_fields, Timestamp};
 use crate::library::Library;
-use crate::path::path_type::PathType;
-use crate::path::Path;
-use crate::point::{get_points_from_i32_vec, Point};
-use crate::polygon::Polygon;
-use crate::reference::{Instance, Reference};
-use crate::text::utils::get_presentations_from_value;
-use crate::text::Text;
+use crate::point::Point;
+use crate::reader::{GdsEvent, GdsFilter, GdsReader};
+use crate::reference::Instance;
+use crate::shared::Shared;
 
 use super::gds_format::{eight_byte_real, u16_array_to_big_endian};
-use super::geometry::round_to_decimals;
 
 pub fn write_gds_head_to_file(
     library_name: &str,
     units: f64,
     precision: f64,
+    dates: (NaiveDateTime, NaiveDateTime),
     mut file: File,
-) -> PyResult<File> {
-    let now = Local::now();
-    let timestamp = now.naive_utc();
-
+) -> io::Result<File> {
     let mut head_start = [
         6,
         combine_record_and_data_type(GDSRecord::Header, GDSDataType::TwoByteSignedInteger),
         0x0258,
         28,
         combine_record_and_data_type(GDSRecord::BgnLib, GDSDataType::TwoByteSignedInteger),
-        timestamp.year() as u16,
-        timestamp.month() as u16,
-        timestamp.day() as u16,
-        timestamp.hour() as u16,
-        timestamp.minute() as u16,
-        timestamp.second() as u16,
-        timestamp.year() as u16,
-        timestamp.month() as u16,
-        timestamp.day() as u16,
-        timestamp.hour() as u16,
-        timestamp.minute() as u16,
-        timestamp.second() as u16,
-    ];
+    ]
+    .into_iter()
+    .chain(dates_to_gds_fields(dates.0, dates.1))
+    .collect::<Vec<u16>>();
 
     file = write_u16_array_to_file(file, &mut head_start)?;
 
@@ -68,7 +55,7 @@ pub fn write_gds_head_to_file(
     Ok(file)
 }
 
-pub fn write_gds_tail_to_file(mut file: File) -> PyResult<File> {
+pub fn write_gds_tail_to_file(mut file: File) -> io::Result<File> {
     let mut tail = [
         4,
         combine_record_and_data_type(GDSRecord::EndLib, GDSDataType::NoData),
@@ -78,50 +65,73 @@ pub fn write_gds_tail_to_file(mut file: File) -> PyResult<File> {
     Ok(file)
 }
 
-pub fn write_u16_array_to_file(mut file: File, array: &mut [u16]) -> PyResult<File> {
+pub fn write_u16_array_to_file(mut file: File, array: &mut [u16]) -> io::Result<File> {
     u16_array_to_big_endian(array);
     file.write_all(cast_slice(array))?;
 
     Ok(file)
 }
 
-pub fn write_float_to_eight_byte_real_to_file(mut file: File, value: f64) -> PyResult<File> {
+pub fn write_float_to_eight_byte_real_to_file(mut file: File, value: f64) -> io::Result<File> {
     let value = eight_byte_real(value);
     file.write_all(&value)?;
 
     Ok(file)
 }
 
-pub fn write_points_to_file(mut file: File, points: &[Point], scale: f64) -> PyResult<File> {
-    const MAX_POINTS: usize = 8191;
-    let points_to_write = points.get(..MAX_POINTS).unwrap_or(points);
+pub fn write_points_to_file(mut file: File, points: &[Point], scale: f64) -> io::Result<File> {
+    // Point lists above the record limit are written as consecutive XY records
+    for points_to_write in points.chunks(MAX_POINTS) {
+        let points_length = points_to_write.len();
+        let record_length = 4 + 8 * points_length;
 
-    let points_length = points_to_write.len();
-    let record_length = 4 + 8 * points_length;
+        let mut points_buffer = Vec::with_capacity(8 * points_length);
 
-    let mut points_buffer = Vec::with_capacity(8 * points_length);
+        let mut xy_header_buffer = [
+            (record_length as u16),
+            combine_record_and_data_type(GDSRecord::XY, GDSDataType::FourByteSignedInteger),
+        ];
 
-    let mut xy_header_buffer = [
-        (record_length as u16),
-        combine_record_and_data_type(GDSRecord::XY, GDSDataType::FourByteSignedInteger),
-    ];
+        file = write_u16_array_to_file(file, &mut xy_header_buffer)?;
 
-    file = write_u16_array_to_file(file, &mut xy_header_buffer)?;
+        for point in points_to_write {
+            let scaled_x = (point.x * scale).round() as i32;
+            let scaled_y = (point.y * scale).round() as i32;
 
-    for point in points_to_write {
-        let scaled_x = (point.x * scale).round() as i32;
-        let scaled_y = (point.y * scale).round() as i32;
+            points_buffer.extend_from_slice(&scaled_x.to_be_bytes());
+            points_buffer.extend_from_slice(&scaled_y.to_be_bytes());
+        }
 
-        points_buffer.extend_from_slice(&scaled_x.to_be_bytes());
-        points_buffer.extend_from_slice(&scaled_y.to_be_bytes());
+        file.write_all(&points_buffer)?;
     }
 
-    file.write_all(&points_buffer)?;
+    Ok(file)
+}
+
+pub fn write_properties_to_file(
+    mut file: File,
+    properties: &HashMap<i32, String>,
+) -> io::Result<File> {
+    let mut attributes = properties.keys().collect::<Vec<&i32>>();
+    attributes.sort();
+
+    for attribute in attributes {
+        let mut property_attribute = [
+            6,
+            combine_record_and_data_type(GDSRecord::PropAttr, GDSDataType::TwoByteSignedInteger),
+            *attribute as u16,
+        ];
+
+        file = write_u16_array_to_file(file, &mut property_attribute)?;
+
+        file =
+            write_string_with_record_to_file(file, GDSRecord::PropValue, &properties[attribute])?;
+    }
 
     Ok(file)
 }
 
-pub fn write_element_tail_to_file(mut file: File) -> PyResult<File> {
+pub fn write_element_tail_to_file(mut file: File) -> io::Result<File> {
     let mut tail = [
         4,
         combine_record_and_data_type(GDSRecord::EndEl, GDSDataType::NoData),
@@ -135,14 +145,14 @@ pub fn write_string_with_record_to_file(
     mut file: File,
     record: GDSRecord,
     string: &str,
-) -> PyResult<File> {
+) -> io::Result<File> {
     let mut len = string.len();
-    if len % 2 != 0 {
+    if !len.is_multiple_of(2) {
         len += 1;
     }
 
     let mut lib_name_bytes = string.as_bytes().to_vec();
-    if string.len() % 2 != 0 {
+    if !string.len().is_multiple_of(2) {
         lib_name_bytes.push(0);
     }
     let mut string_start = [
@@ -157,25 +167,34 @@ pub fn write_string_with_record_to_file(
     Ok(file)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn write_gds(
     file_name: String,
     library_name: &str,
     units: f64,
     precision: f64,
-    cells: Vec<Cell>,
+    max_points_mode: MaxPointsMode,
+    timestamp: Timestamp,
+    library_dates: (Option<NaiveDateTime>, Option<NaiveDateTime>),
+    cells: Vec<Shared<Cell>>,
 ) -> PyResult<String> {
     let mut file = File::create(file_name.clone())
         .map_err(|_| PyIOError::new_err("Could not open file for writing"))?;
 
-    file = write_gds_head_to_file(library_name, units, precision, file)?;
+    let now = Local::now().naive_utc();
 
-    let mut written_cell_names: HashSet<String> = HashSet::new();
+    file = write_gds_head_to_file(
+        library_name,
+        units,
+        precision,
+        timestamp.dates(now, library_dates.0, library_dates.1),
+        file,
+    )?;
 
-    for cell in cells {
-        if !written_cell_names.contains(&cell.name) {
-            written_cell_names.insert(cell.name.clone());
-            file = cell._to_gds(file, units, precision, &mut written_cell_names)?;
-        }
+    for cell in CellHierarchy::order(&cells)? {
+        file = cell
+            .read()
+            ._to_gds(file, units, precision, max_points_mode, timestamp, now)?;
     }
 
     file = write_gds_tail_to_file(file)?;
@@ -190,13 +209,29 @@ pub fn write_transformation_to_file(
     angle: f64,
     magnification: f64,
     x_reflection: bool,
-) -> PyResult<File> {
-    let transform_applied = angle != 0.0 || magnification != 1.0 || x_reflection;
+    absolute_magnification: bool,
+    absolute_angle: bool,
+) -> io::Result<File> {
+    let transform_applied = angle != 0.0
+        || magnification != 1.0
+        || x_reflection
+        || absolute_magnification
+        || absolute_angle;
     if transform_applied {
+        let mut flags = 0x0000;
+        if x_reflection {
+            flags |= 0x8000;
+        }
+        if absolute_magnification {
+            flags |= 0x0004;
+        }
+        if absolute_angle {
+            flags |= 0x0002;
+        }
         let mut buffer_flags = [
             6,
             combine_record_and_data_type(GDSRecord::STrans, GDSDataType::BitArray),
-            if x_reflection { 0x8000 } else { 0x0000 },
+            flags,
         ];
 
         file = write_u16_array_to_file(file, &mut buffer_flags)?;
@@ -223,325 +258,62 @@ pub fn write_transformation_to_file(
     Ok(file)
 }
 
-pub fn from_gds(py: Python, file_name: String) -> PyResult<Library> {
-    let mut library = Library::new("Library".to_string());
+pub fn from_gds(file_name: String, filter: GdsFilter) -> PyResult<Library> {
+    let mut library = Library::default();
 
-    let file = File::open(file_name)?;
-    let reader = RecordReader::new(BufReader::new(file));
+    let mut reader = GdsReader::open(file_name, filter.clone())?;
 
     let mut cell: Option<Cell> = None;
-    let mut path: Option<Path> = None;
-    let mut polygon: Option<Polygon> = None;
-    let mut text: Option<Text> = None;
-    let mut reference: Option<Reference> = None;
-
-    let mut scale = 1.0;
-    let mut rounding_digits = 0;
-
-    for record in reader {
-        match record {
-            Ok((record_type, data)) => match record_type {
-                GDSRecord::LibName => {
-                    if let GDSRecordData::Str(name) = data {
-                        library.name = name;
-                    }
-
-                    continue;
-                }
-                GDSRecord::Units => {
-                    if let GDSRecordData::F64(units) = data {
-                        scale = units[0];
-                        rounding_digits = -(units[1] / units[0]).log10() as u32 - 1;
-                    }
-
-                    continue;
-                }
-                GDSRecord::EndLib => {
-                    update_references(&mut library);
-                    continue;
-                }
-                GDSRecord::BgnStr => {
-                    cell = Some(Cell::default());
-
-                    continue;
-                }
-                GDSRecord::StrName => {
-                    if let GDSRecordData::Str(cell_name) = data {
-                        if let Some(cell) = &mut cell {
-                            cell.name = cell_name;
-                        }
-                    }
-
-                    continue;
-                }
-                GDSRecord::EndStr => {
-                    if let Some(cell) = cell.take() {
-                        library
-                            .cells
-                            .insert(cell.name.clone(), Py::new(py, cell).unwrap());
-                    }
-
-                    continue;
-                }
-                GDSRecord::Boundary | GDSRecord::Box => {
-                    polygon = Some(Polygon::default());
-
-                    continue;
-                }
-                GDSRecord::Path | GDSRecord::RaithMbmsPath => {
-                    path = Some(Path::default());
-
-                    continue;
-                }
-                GDSRecord::ARef | GDSRecord::SRef => {
-                    reference = Some(Reference::default());
-
-                    continue;
-                }
-                GDSRecord::Text => {
-                    text = Some(Text::default());
-
-                    continue;
-                }
-                GDSRecord::Layer => {
-                    if let GDSRecordData::I16(layer) = data {
-                        let layer_value = layer[0] as i32;
-                        if let Some(polygon) = &mut polygon {
-                            polygon.layer = layer_value;
-                        } else if let Some(path) = &mut path {
-                            path.layer = layer_value;
-                        } else if let Some(text) = &mut text {
-                            text.layer = layer_value;
-                        }
-                    }
-                    continue;
-                }
-                GDSRecord::DataType | GDSRecord::BoxType => {
-                    if let GDSRecordData::I16(data_type) = data {
-                        let data_type_val = data_type[0] as i32;
-                        if let Some(polygon) = &mut polygon {
-                            polygon.data_type = data_type_val;
-                        } else if let Some(path) = &mut path {
-                            path.data_type = data_type_val;
-                        }
-                    }
-
-                    continue;
-                }
-                GDSRecord::Width => {
-                    if let GDSRecordData::I32(width) = data {
-                        let path_width =
-                            round_to_decimals(width[0] as f64 * scale, rounding_digits);
-                        if let Some(path) = &mut path {
-                            path.width = Some(path_width);
-                        }
-                    }
-
-                    continue;
-                }
-                GDSRecord::XY => {
-                    if let GDSRecordData::I32(xy) = data {
-                        let points = get_points_from_i32_vec(xy)
-                            .iter()
-                            .map(|p| p.scale(scale, Point::default()).round(rounding_digits))
-                            .collect::<Vec<Point>>();
-
-                        if let Some(polygon) = &mut polygon {
-                            polygon.points = points;
-                        } else if let Some(path) = &mut path {
-                            path.points = points;
-                        } else if let Some(reference) = &mut reference {
-                            Python::with_gil(|py| {
-                                let mut grid = reference.grid.borrow_mut(py);
-                                match points.len() {
-                                    1 => {
-                                        grid.origin = points[0];
-                                    }
-                                    3 => {
-                                        let origin = points[0];
-                                        let rotated_points = points
-                                            .iter()
-                                            .map(|&p| p.rotate(-grid.angle, origin))
-                                            .collect::<Vec<Point>>();
-
-                                        grid.origin = rotated_points[0].round(rounding_digits);
-                                        grid.spacing_x = if grid.columns > 0 {
-                                            ((rotated_points[1] - rotated_points[0])
-                                                / grid.columns as f64)
-                                                .round(rounding_digits)
-                                        } else {
-                                            Point::default()
-                                        };
-                                        grid.spacing_y = if grid.rows > 0 {
-                                            ((rotated_points[2] - rotated_points[0])
-                                                / grid.rows as f64)
-                                                .round(rounding_digits)
-                                        } else {
-                                            Point::default()
-                                        };
-                                    }
-                                    _ => {}
-                                }
-                            });
-                        } else if let Some(text) = &mut text {
-                            if let Some(&first_point) = points.first() {
-                                text.origin = first_point;
-                            }
-                        }
-                    }
-
-                    continue;
-                }
-                GDSRecord::EndEl => {
-                    Python::with_gil(|py| {
-                        if let Some(cell) = &mut cell {
-                            if let Some(polygon) = polygon.take() {
-                                cell.polygons.push(Py::new(py, polygon).unwrap());
-                            } else if let Some(path) = path.take() {
-                                cell.paths.push(Py::new(py, path).unwrap());
-                            } else if let Some(reference) = reference.take() {
-                                cell.references.push(Py::new(py, reference).unwrap());
-                            } else if let Some(text) = text.take() {
-                                cell.texts.push(Py::new(py, text).unwrap());
-                            }
-                        }
-                    });
-                    polygon = None;
-                    path = None;
-                    text = None;
-                    reference = None;
-
-                    continue;
-                }
-                GDSRecord::SName => {
-                    if let GDSRecordData::Str(cell_name) = data {
-                        if let Some(reference) = &mut reference {
-                            if let Instance::Cell(cell) = &reference.instance {
-                                Python::with_gil(|py| {
-                                    cell.borrow_mut(py).name = cell_name;
-                                });
-                            }
-                        }
-                    }
-
-                    continue;
-                }
-                GDSRecord::ColRow => {
-                    if let GDSRecordData::I16(col_row) = data {
-                        if let Some(reference) = &mut reference {
-                            Python::with_gil(|py| {
-                                let mut grid = reference.grid.borrow_mut(py);
-                                grid.columns = col_row[0] as u32;
-                                grid.rows = col_row[1] as u32;
-                            });
-                        }
-                    }
-
-                    continue;
-                }
-                GDSRecord::Presentation => {
-                    if let GDSRecordData::I16(flags) = data {
-                        if let Some(text) = &mut text {
-                            (text.vertical_presentation, text.horizontal_presentation) =
-                                get_presentations_from_value(flags[0])?;
-                        }
-                    }
-
-                    continue;
-                }
-                GDSRecord::String => {
-                    if let GDSRecordData::Str(string) = data {
-                        if let Some(text) = &mut text {
-                            text.text = string;
-                        }
-                    }
 
-                    continue;
-                }
-                GDSRecord::STrans => {
-                    if let GDSRecordData::I16(flags) = data {
-                        let x_reflection = flags[0] & 0x8000u16 as i16 != 0;
-                        if let Some(text) = &mut text {
-                            text.x_reflection = x_reflection;
-                        }
-                        if let Some(reference) = &mut reference {
-                            Python::with_gil(|py| {
-                                let mut grid = reference.grid.borrow_mut(py);
-                                grid.x_reflection = x_reflection;
-                            });
-                        }
-                    }
-
-                    continue;
-                }
-                GDSRecord::Mag => {
-                    if let GDSRecordData::F64(magnification) = data {
-                        if let Some(text) = &mut text {
-                            text.magnification = magnification[0]
-                        } else if let Some(reference) = &mut reference {
-                            Python::with_gil(|py| {
-                                let mut grid = reference.grid.borrow_mut(py);
-                                grid.magnification = magnification[0];
-                            });
-                        }
-                    }
-
-                    continue;
-                }
-                GDSRecord::Angle => {
-                    if let GDSRecordData::F64(angle) = data {
-                        if let Some(text) = &mut text {
-                            text.angle = angle[0];
-                        } else if let Some(reference) = &mut reference {
-                            Python::with_gil(|py| {
-                                let mut grid = reference.grid.borrow_mut(py);
-                                grid.angle = angle[0];
-                            });
-                        }
-                    }
-
-                    continue;
+    while let Some(event) = reader.next_event()? {
+        match event {
+            GdsEvent::BeginLibrary(modification_date, access_date) => {
+                library.modification_date = modification_date;
+                library.access_date = access_date;
+            }
+            GdsEvent::LibraryName(name) => library.name = name,
+            GdsEvent::Units(units, precision) => {
+                library.units = units;
+                library.precision = precision;
+            }
+            GdsEvent::BeginCell(new_cell) => cell = Some(*new_cell),
+            GdsEvent::Element(element) => {
+                if let Some(cell) = &mut cell {
+                    cell.add(vec![element]);
                 }
-                GDSRecord::PathType => {
-                    if let GDSRecordData::I16(path_type) = data {
-                        if let Some(path) = &mut path {
-                            path.path_type = Some(PathType::new(path_type[0] as i32)?);
-                        }
-                    }
-
-                    continue;
+            }
+            GdsEvent::EndCell => {
+                if let Some(cell) = cell.take() {
+                    library.cells.insert(cell.name.clone(), Shared::new(cell));
                 }
-                _ => {}
-            },
-            Err(e) => return Err(e),
+            }
+            GdsEvent::EndLibrary => update_references(&mut library, &filter),
         }
     }
 
     Ok(library)
 }
 
-fn update_references(library: &mut Library) {
-    Python::with_gil(|py| {
-        let cell_references: Vec<Reference> = library
-            .cells
-            .values()
-            .flat_map(|cell| {
-                cell.borrow_mut(py)
-                    .references
-                    .clone()
-                    .into_iter()
-                    .map(|r| r.borrow_mut(py).clone())
-            })
-            .collect();
-
-        for mut reference in cell_references {
-            if let Instance::Cell(referenced_name) = reference.instance {
-                if let Some(referenced_cell) = library.cells.get(&referenced_name.borrow(py).name) {
-                    reference.instance = Instance::Cell(referenced_cell.clone_ref(py));
-                }
+fn update_references(library: &mut Library, filter: &GdsFilter) {
+    for cell in library.cells.values() {
+        // References to cells the filter excluded would otherwise be written back
+        // as empty structures, so they are dropped with the cells
+        cell.write()
+            .references
+            .retain(|reference| match &reference.read().instance {
+                Instance::Cell(placeholder) => filter.includes_cell(&placeholder.read().name),
+                Instance::Element(_) => true,
+            });
+        for reference in &cell.read().references {
+            let referenced_cell = match &reference.read().instance {
+                Instance::Cell(placeholder) => library.cells.get(&placeholder.read().name).cloned(),
+                Instance::Element(_) => None,
+            };
+            if let Some(referenced_cell) = referenced_cell {
+                reference.set_instance(Instance::Cell(referenced_cell));
             }
         }
-    });
+    }
 }
 
 pub struct RecordReader<R: Read> {
@@ -555,7 +327,7 @@ impl<R: Read> RecordReader<R> {
 }
 
 impl<R: Read> Iterator for RecordReader<R> {
-    type Item = PyResult<(GDSRecord, GDSRecordData)>;
+    type Item = io::Result<(GDSRecord, GDSRecordData)>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let mut header = [0u8; 4];
@@ -563,7 +335,7 @@ impl<R: Read> Iterator for RecordReader<R> {
             if e.kind() == io::ErrorKind::UnexpectedEof {
                 return None;
             } else {
-                return Some(Err(PyErr::from(e)));
+                return Some(Err(e));
             }
         }
 
@@ -574,7 +346,7 @@ impl<R: Read> Iterator for RecordReader<R> {
         let data = if size > 4 {
             let mut buf = vec![0u8; size - 4];
             if let Err(e) = self.reader.read_exact(&mut buf) {
-                return Some(Err(PyErr::from(e)));
+                return Some(Err(e));
             }
 
             let result = match GDSDataType::try_from(data_type) {
@@ -615,10 +387,10 @@ impl<R: Read> Iterator for RecordReader<R> {
         let record = match GDSRecord::try_from(record_type) {
             Ok(record) => record,
             Err(_) => {
-                return Some(Err(PyIOError::new_err(format!(
-                    "Invalid record type: {}",
-                    record_type
-                ))));
+                return Some(Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid record type: {}", record_type),
+                )));
             }
         };
 
@@ -696,7 +468,11 @@ fn eight_byte_real_to_float(bytes: u64) -> f64 {
 }
 
 pub fn create_temp_file() -> PyResult<String> {
-    let temp_file = Builder::new().suffix(".gds").tempfile()?;
+    create_temp_file_with_suffix(".gds")
+}
+
+pub fn create_temp_file_with_suffix(suffix: &str) -> PyResult<String> {
+    let temp_file = Builder::new().suffix(suffix).tempfile()?;
     let temp_path = temp_file.path().to_string_lossy().to_string();
     Ok(temp_path)
 }
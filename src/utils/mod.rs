@@ -2,4 +2,5 @@ pub mod gds_format;
 pub mod general;
 pub mod geometry;
 pub mod io;
+pub mod overlay;
 pub mod transformations;
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use i_overlay::{
+    core::{fill_rule::FillRule, overlay::Overlay, overlay_rule::OverlayRule},
+    i_float::int::point::IntPoint,
+    i_shape::int::shape::{IntContour, IntShapes},
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    config::FLOATING_POINT_INACCURACY_ROUND_DECIMALS,
+    point::Point,
+    polygon::Polygon,
+    utils::geometry::{bounding_box, keyhole, signed_area},
+};
+
+// Scaled coordinates stay well inside the integer range i_overlay works with
+const MAX_GRID_COORDINATE: f64 = (1 << 29) as f64;
+
+/// Maps coordinates to integer multiples of the grid around an origin on the grid, so
+/// overlay results land exactly on the grid whatever the extent of the input.
+pub struct OverlayGrid {
+    origin: Point,
+    step: f64,
+}
+
+impl OverlayGrid {
+    /// Fits a grid of step `grid` around `polygons`, leaving room for results that grow by
+    /// `margin`.
+    pub fn fit<'a>(
+        polygons: impl IntoIterator<Item = &'a Polygon>,
+        margin: f64,
+        grid: f64,
+    ) -> PyResult<Self> {
+        let points = polygons
+            .into_iter()
+            .flat_map(|polygon| polygon.points.iter().copied())
+            .collect::<Vec<Point>>();
+        Self::fit_points(points, margin, grid)
+    }
+
+    /// Fits a grid of step `grid` around `shapes` of contours in user units.
+    pub fn fit_shapes(shapes: &[Vec<Vec<[f64; 2]>>], grid: f64) -> PyResult<Self> {
+        let points = shapes
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|[x, y]| Point::new(*x, *y))
+            .collect::<Vec<Point>>();
+        Self::fit_points(points, 0.0, grid)
+    }
+
+    fn fit_points(points: Vec<Point>, margin: f64, grid: f64) -> PyResult<Self> {
+        if points.is_empty() {
+            return Ok(Self {
+                origin: Point::new(0.0, 0.0),
+                step: grid,
+            });
+        }
+
+        let (min, max) = bounding_box(&points);
+        let centre = (min + max) / 2.0;
+        let origin = Point::new(
+            (centre.x / grid).round() * grid,
+            (centre.y / grid).round() * grid,
+        );
+
+        let half_extent = (max.x - min.x).max(max.y - min.y) / 2.0 + margin.abs() + grid;
+        if half_extent / grid > MAX_GRID_COORDINATE {
+            return Err(PyValueError::new_err(format!(
+                "Geometry is too large to fit on a grid of {}, use a coarser grid",
+                grid
+            )));
+        }
+
+        Ok(Self { origin, step: grid })
+    }
+
+    fn to_int(&self, x: f64, y: f64) -> IntPoint {
+        IntPoint::new(
+            ((x - self.origin.x) / self.step).round() as i32,
+            ((y - self.origin.y) / self.step).round() as i32,
+        )
+    }
+
+    fn to_point(&self, point: &IntPoint) -> Point {
+        let point = Point::new(
+            self.origin.x + point.x as f64 * self.step,
+            self.origin.y + point.y as f64 * self.step,
+        )
+        .round(FLOATING_POINT_INACCURACY_ROUND_DECIMALS);
+        // Adding zero normalises negative zero coordinates
+        Point::new(point.x + 0.0, point.y + 0.0)
+    }
+
+    /// Snaps each polygon to the grid as a counter-clockwise contour without a closing point.
+    pub fn contours(&self, polygons: &[Polygon]) -> Vec<IntContour> {
+        polygons
+            .iter()
+            .map(|polygon| {
+                let mut contour = points_to_contour(&polygon.points)
+                    .iter()
+                    .map(|[x, y]| self.to_int(*x, *y))
+                    .collect::<IntContour>();
+                contour.dedup();
+                if contour.len() > 1 && contour.first() == contour.last() {
+                    contour.pop();
+                }
+                contour
+            })
+            .filter(|contour| contour.len() >= 3)
+            .collect()
+    }
+
+    pub fn overlay(
+        &self,
+        subject: &[IntContour],
+        clip: &[IntContour],
+        rule: OverlayRule,
+    ) -> IntShapes {
+        Overlay::with_contours(subject, clip).overlay(rule, FillRule::NonZero)
+    }
+
+    /// Converts shapes on the grid to contours in user units, relative to the grid origin.
+    pub fn to_relative_shapes(&self, shapes: &IntShapes) -> Vec<Vec<Vec<[f64; 2]>>> {
+        shapes
+            .iter()
+            .map(|shape| {
+                shape
+                    .iter()
+                    .map(|contour| {
+                        contour
+                            .iter()
+                            .map(|point| [point.x as f64 * self.step, point.y as f64 * self.step])
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Snaps shapes relative to the grid origin back onto the grid and resolves any overlaps
+    /// the snapping introduced.
+    pub fn snap_relative_shapes(&self, shapes: Vec<Vec<Vec<[f64; 2]>>>) -> IntShapes {
+        self.snap(shapes, self.origin)
+    }
+
+    /// Snaps shapes in user units onto the grid and resolves any overlaps the snapping
+    /// introduced.
+    pub fn snap_shapes(&self, shapes: Vec<Vec<Vec<[f64; 2]>>>) -> IntShapes {
+        self.snap(shapes, Point::new(0.0, 0.0))
+    }
+
+    fn snap(&self, shapes: Vec<Vec<Vec<[f64; 2]>>>, offset: Point) -> IntShapes {
+        let contours = shapes
+            .into_iter()
+            .flatten()
+            .map(|contour| {
+                let mut contour = contour
+                    .iter()
+                    .map(|[x, y]| self.to_int(x + offset.x, y + offset.y))
+                    .collect::<IntContour>();
+                contour.dedup();
+                contour
+            })
+            .filter(|contour| contour.len() >= 3)
+            .collect::<Vec<IntContour>>();
+
+        self.overlay(&contours, &[], OverlayRule::Subject)
+    }
+
+    pub fn contour_points(&self, contour: &[IntPoint]) -> Vec<Point> {
+        remove_collinear_points(contour.iter().map(|point| self.to_point(point)).collect())
+    }
+
+    pub fn shapes_to_polygons(
+        &self,
+        shapes: IntShapes,
+        layer: i32,
+        data_type: i32,
+    ) -> PyResult<Vec<Polygon>> {
+        point_shapes_to_polygons(
+            shapes.iter().map(|shape| {
+                shape
+                    .iter()
+                    .map(|contour| self.contour_points(contour))
+                    .collect()
+            }),
+            layer,
+            data_type,
+        )
+    }
+}
+
+pub fn points_to_contour(points: &[Point]) -> Vec<[f64; 2]> {
+    let mut points = points.to_vec();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    if signed_area(&points) < 0.0 {
+        points.reverse();
+    }
+
+    points.iter().map(|point| [point.x, point.y]).collect()
+}
+
+fn remove_collinear_points(mut points: Vec<Point>) -> Vec<Point> {
+    let mut i = 0;
+    while points.len() > 3 && i < points.len() {
+        let previous = points[(i + points.len() - 1) % points.len()];
+        let current = points[i];
+        let next = points[(i + 1) % points.len()];
+
+        let cross = (current.x - previous.x) * (next.y - current.y)
+            - (current.y - previous.y) * (next.x - current.x);
+
+        if cross == 0.0 {
+            points.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+    points
+}
+
+fn point_shapes_to_polygons(
+    shapes: impl Iterator<Item = Vec<Vec<Point>>>,
+    layer: i32,
+    data_type: i32,
+) -> PyResult<Vec<Polygon>> {
+    let mut polygons = Vec::new();
+    for shape in shapes {
+        let Some((outer, holes)) = shape.split_first() else {
+            continue;
+        };
+
+        let mut points = keyhole(outer, holes)?;
+        if points.len() < 3 {
+            continue;
+        }
+        points.push(points[0]);
+
+        polygons.push(Polygon {
+            points,
+            layer,
+            data_type,
+            properties: HashMap::new(),
+        });
+    }
+    Ok(polygons)
+}
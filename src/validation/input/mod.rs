@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use pyo3::{exceptions::PyValueError, prelude::*};
 
 use crate::point::Point;
@@ -13,9 +15,62 @@ pub fn check_data_type_valid(_: i32) -> PyResult<()> {
     Ok(())
 }
 
+pub fn check_properties_valid(properties: &HashMap<i32, String>) -> PyResult<()> {
+    if properties
+        .keys()
+        .any(|attribute| !(0..=i16::MAX as i32).contains(attribute))
+    {
+        return Err(PyValueError::new_err(
+            "Property attribute must be in the range 0-32767",
+        ));
+    }
+    Ok(())
+}
+
+pub fn check_units_valid(units: f64, precision: f64) -> PyResult<()> {
+    if !(units > 0.0 && precision > 0.0) {
+        return Err(PyValueError::new_err(
+            "Units and precision must be positive",
+        ));
+    }
+    if precision > units {
+        return Err(PyValueError::new_err(
+            "Precision must not be larger than units",
+        ));
+    }
+    Ok(())
+}
+
+pub fn check_grid_valid(grid: f64) -> PyResult<()> {
+    if !grid.is_finite() || grid <= 0.0 {
+        return Err(PyValueError::new_err("Grid must be positive"));
+    }
+    Ok(())
+}
+
 pub fn check_points_vec_has_at_least_two_points(points: &[Point]) -> PyResult<()> {
     if points.len() < 2 {
         return Err(PyValueError::new_err("Path must have at least two points"));
     }
     Ok(())
 }
+
+pub fn check_node_points_valid(points: &[Point]) -> PyResult<()> {
+    if !(1..=50).contains(&points.len()) {
+        return Err(PyValueError::new_err(
+            "Node must have between 1 and 50 points",
+        ));
+    }
+    Ok(())
+}
+
+pub fn check_box_points_valid(points: &[Point]) -> PyResult<()> {
+    let corners = match points {
+        [first, .., last] if first == last => points.len() - 1,
+        _ => points.len(),
+    };
+    if corners != 4 {
+        return Err(PyValueError::new_err("Box must have exactly four corners"));
+    }
+    Ok(())
+}
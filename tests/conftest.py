@@ -3,12 +3,14 @@ from hypothesis import HealthCheck, settings
 from hypothesis import strategies as st
 
 from gdsr import (
+    Box,
     Cell,
     Element,
     Grid,
     HorizontalPresentation,
     Instance,
     Library,
+    Node,
     Path,
     PathType,
     Point,
@@ -87,6 +89,17 @@ def data_type_strategy(draw: st.DrawFn) -> int:
     return draw(st.integers(min_value=0, max_value=32767))
 
 
+@st.composite
+def properties_strategy(draw: st.DrawFn) -> dict[int, str]:
+    return draw(
+        st.dictionaries(
+            st.integers(min_value=1, max_value=127),
+            string_strategy(max_size=64),
+            max_size=5,
+        )
+    )
+
+
 @st.composite
 def cell_strategy(draw: st.DrawFn, *, cell_name: str | None = None) -> Cell:
     if cell_name is not None:
@@ -107,6 +120,8 @@ def randomly_populated_cell_strategy(
                 polygon_strategy(),
                 path_strategy(),
                 text_strategy(),
+                box_strategy(),
+                node_strategy(),
                 reference_strategy(),
             )
         )
@@ -155,7 +170,40 @@ def polygon_strategy(draw: st.DrawFn) -> Polygon:
     points = draw(st.lists(point_strategy(), min_size=4, max_size=8191))
     if points[0] != points[-1]:
         points.append(points[0])
-    return Polygon(points, draw(layer_strategy()), draw(data_type_strategy()))
+    return Polygon(
+        points,
+        draw(layer_strategy()),
+        draw(data_type_strategy()),
+        draw(properties_strategy()),
+    )
+
+
+@st.composite
+def box_strategy(draw: st.DrawFn) -> Box:
+    lower_left = draw(point_strategy())
+    width = draw(float_strategy(min_value=1, max_value=1000))
+    height = draw(float_strategy(min_value=1, max_value=1000))
+    return Box(
+        [
+            lower_left,
+            lower_left + (width, 0),
+            lower_left + (width, height),
+            lower_left + (0, height),
+        ],
+        draw(layer_strategy()),
+        draw(data_type_strategy()),
+        draw(properties_strategy()),
+    )
+
+
+@st.composite
+def node_strategy(draw: st.DrawFn) -> Node:
+    return Node(
+        draw(st.lists(point_strategy(), min_size=1, max_size=50)),
+        draw(layer_strategy()),
+        draw(data_type_strategy()),
+        draw(properties_strategy()),
+    )
 
 
 @st.composite
@@ -184,6 +232,7 @@ def path_strategy(draw: st.DrawFn) -> Path:
         draw(data_type_strategy()),
         draw(st.sampled_from(PathType.values()) | st.none()),
         width,
+        draw(properties_strategy()),
     )
 
 
@@ -193,17 +242,19 @@ def text_strategy(draw: st.DrawFn) -> Text:
         draw(string_strategy()),
         draw(point_strategy()),
         draw(layer_strategy()),
+        draw(data_type_strategy()),
         draw(st.integers(min_value=1)),
         draw(st.integers()),
         draw(st.booleans()),
         draw(st.sampled_from(VerticalPresentation.values())),
         draw(st.sampled_from(HorizontalPresentation.values())),
+        draw(properties_strategy()),
     )
 
 
 @st.composite
 def reference_strategy(draw: st.DrawFn) -> "Reference[Instance]":
-    return Reference(draw(cell_strategy()))
+    return Reference(draw(cell_strategy()), properties=draw(properties_strategy()))
 
 
 @st.composite
@@ -227,6 +278,8 @@ def element_param_strategy(draw: st.DrawFn) -> "Element":
             polygon_strategy(),
             path_strategy(),
             text_strategy(),
+            box_strategy(),
+            node_strategy(),
             reference_strategy(),
         )
     )
@@ -241,6 +294,10 @@ def check_references(library: Library, instance: "Instance", new_cell: Cell):
         assert instance == new_cell.paths[0]
     elif isinstance(instance, Text):
         assert instance == new_cell.texts[0]
+    elif isinstance(instance, Box):
+        assert instance == new_cell.boxes[0]
+    elif isinstance(instance, Node):
+        assert instance == new_cell.nodes[0]
     else:
         check_references(library, instance.instance, new_cell)
 
@@ -0,0 +1,126 @@
+import pytest
+
+from gdsr import Polygon, boolean
+
+
+def square(x: float, y: float, size: float, layer: int = 0) -> Polygon:
+    return Polygon(
+        [(x, y), (x + size, y), (x + size, y + size), (x, y + size)], layer=layer
+    )
+
+
+def total_area(polygons: list[Polygon]) -> float:
+    return sum(abs(polygon.area) for polygon in polygons)
+
+
+def test_boolean_union_overlapping():
+    result = boolean([square(0, 0, 2)], [square(1, 1, 2)], "union")
+    assert len(result) == 1
+    assert total_area(result) == pytest.approx(7)
+
+
+def test_boolean_union_disjoint():
+    result = boolean([square(0, 0, 1)], [square(5, 5, 1)], "or")
+    assert len(result) == 2
+    assert total_area(result) == pytest.approx(2)
+
+
+def test_boolean_intersection():
+    result = boolean([square(0, 0, 2)], [square(1, 1, 2)], "intersection")
+    assert len(result) == 1
+    assert total_area(result) == pytest.approx(1)
+    assert result[0].bounding_box == ((1, 1), (2, 2))
+
+
+def test_boolean_intersection_disjoint_is_empty():
+    assert boolean([square(0, 0, 1)], [square(5, 5, 1)], "and") == []
+
+
+def test_boolean_difference():
+    result = boolean([square(0, 0, 2)], [square(1, 1, 2)], "difference")
+    assert len(result) == 1
+    assert total_area(result) == pytest.approx(3)
+
+
+def test_boolean_xor():
+    result = boolean([square(0, 0, 2)], [square(1, 1, 2)], "xor")
+    assert total_area(result) == pytest.approx(6)
+
+
+def test_boolean_difference_with_hole_is_keyholed():
+    result = boolean([square(0, 0, 10)], [square(4, 4, 2)], "not")
+    assert len(result) == 1
+    polygon = result[0]
+    assert polygon.points[0] == polygon.points[-1]
+    assert total_area(result) == pytest.approx(96)
+    assert not polygon.contains((5, 5))
+    assert polygon.contains((1, 1))
+
+
+def test_boolean_difference_with_multiple_holes():
+    holes = [square(1, 1, 2), square(5, 5, 2), square(1, 6, 1)]
+    result = boolean([square(0, 0, 10)], holes, "difference")
+    assert len(result) == 1
+    assert total_area(result) == pytest.approx(100 - 4 - 4 - 1)
+
+
+def test_boolean_merges_polygons_within_group():
+    result = boolean([square(0, 0, 2), square(1, 0, 2)], [], "union")
+    assert len(result) == 1
+    assert total_area(result) == pytest.approx(6)
+
+
+def test_boolean_clockwise_input():
+    clockwise = Polygon([(0, 0), (0, 2), (2, 2), (2, 0)])
+    result = boolean([clockwise], [square(1, 1, 2)], "intersection")
+    assert total_area(result) == pytest.approx(1)
+
+
+def test_boolean_layer_and_data_type():
+    result = boolean(
+        [square(0, 0, 2)], [square(1, 1, 2)], "union", layer=3, data_type=4
+    )
+    assert result[0].layer == 3
+    assert result[0].data_type == 4
+
+
+def test_boolean_invalid_operation():
+    with pytest.raises(ValueError, match="Invalid boolean operation"):
+        boolean([square(0, 0, 1)], [square(0, 0, 1)], "invalid")  # type: ignore
+
+
+def test_boolean_invalid_layer():
+    with pytest.raises(ValueError, match="Layer must be in the range 0-255"):
+        boolean([square(0, 0, 1)], [square(0, 0, 1)], "union", layer=256)
+
+
+def test_boolean_output_is_on_the_grid():
+    result = boolean(
+        [square(0, 0, 0.2)],
+        [square(0.1, 0.1, 0.2), square(5000, 5000, 1)],
+        "intersection",
+    )
+    assert len(result) == 1
+    assert result[0].bounding_box == ((0.1, 0.1), (0.2, 0.2))
+
+
+def test_boolean_snaps_to_the_given_grid():
+    result = boolean([square(0, 0, 1.2)], [], "union", grid=0.5)
+    assert result[0].bounding_box == ((0, 0), (1, 1))
+
+
+def test_polygon_boolean_snaps_to_the_given_grid():
+    result = square(0, 0, 1.2).boolean(square(0, 0, 2), "intersection", grid=0.5)
+    assert result[0].bounding_box == ((0, 0), (1, 1))
+
+
+def test_boolean_grid_invalid():
+    with pytest.raises(ValueError, match="Grid must be positive"):
+        boolean([square(0, 0, 1)], [], "union", grid=0)
+
+
+def test_boolean_too_large_for_the_grid():
+    with pytest.raises(ValueError, match="too large to fit on a grid"):
+        boolean([square(0, 0, 1), square(1e6, 1e6, 1)], [], "union", grid=1e-4)
+    result = boolean([square(0, 0, 1), square(1e6, 1e6, 1)], [], "union", grid=1e-2)
+    assert len(result) == 2
@@ -0,0 +1,131 @@
+import pytest
+
+from gdsr import Box, Cell, Library, Point, Polygon, Reference
+
+RECTANGLE = [(0, 0), (2, 0), (2, 1), (0, 1)]
+
+# Box init
+
+
+def test_box_init():
+    gds_box = Box(RECTANGLE)
+    assert gds_box.points == [(0, 0), (2, 0), (2, 1), (0, 1), (0, 0)]
+    assert gds_box.layer == 0
+    assert gds_box.box_type == 0
+    assert gds_box.properties == {}
+
+
+def test_box_init_with_layer_and_box_type():
+    gds_box = Box(RECTANGLE, layer=5, box_type=10, properties={1: "box"})
+    assert gds_box.layer == 5
+    assert gds_box.box_type == 10
+    assert gds_box.properties == {1: "box"}
+
+
+def test_box_init_closed_points():
+    assert Box([*RECTANGLE, (0, 0)]) == Box(RECTANGLE)
+
+
+@pytest.mark.parametrize(
+    "points",
+    [
+        [(0, 0)],
+        [(0, 0), (1, 0), (1, 1)],
+        [(0, 0), (1, 0), (1, 1), (0, 1), (0, 2), (0, 3)],
+    ],
+)
+def test_box_invalid_number_of_points(points: list[tuple[int, int]]):
+    with pytest.raises(ValueError, match="Box must have exactly four corners"):
+        Box(points)
+
+
+def test_box_empty_points_raises_error():
+    with pytest.raises(TypeError, match="Points cannot be empty"):
+        Box([])
+
+
+def test_box_invalid_layer():
+    with pytest.raises(ValueError, match="Layer must be in the range 0-255"):
+        Box(RECTANGLE, layer=256)
+
+
+# Box setters
+
+
+def test_box_setters():
+    gds_box = Box(RECTANGLE)
+    assert gds_box.set_layer(2).set_box_type(3) is gds_box
+    assert gds_box.layer == 2
+    assert gds_box.box_type == 3
+    gds_box.points = [(0, 0), (1, 0), (1, 1), (0, 1)]
+    assert gds_box.points[-1] == Point(0, 0)
+    assert gds_box.bounding_box == (Point(0, 0), Point(1, 1))
+
+
+def test_box_points_setter_invalid():
+    gds_box = Box(RECTANGLE)
+    with pytest.raises(ValueError, match="Box must have exactly four corners"):
+        gds_box.points = [(0, 0), (1, 1)]
+
+
+# Box transformations
+
+
+def test_box_move_and_rotate():
+    gds_box = Box(RECTANGLE).move_by((1, 1)).rotate(90, (1, 1))
+    lower_left, upper_right = gds_box.bounding_box
+    assert lower_left.is_close((0, 1))
+    assert upper_right.is_close((1, 3))
+
+
+def test_box_scale():
+    gds_box = Box(RECTANGLE).scale(2)
+    assert gds_box.bounding_box == (Point(0, 0), Point(4, 2))
+
+
+# Box is_on
+
+
+def test_box_is_on():
+    gds_box = Box(RECTANGLE, layer=1, box_type=2)
+    assert gds_box.is_on((1, 2))
+    assert not gds_box.is_on((1, 0))
+    assert gds_box.is_on()
+
+
+# Box eq
+
+
+def test_box_is_not_polygon():
+    assert Box(RECTANGLE) != Polygon(RECTANGLE)
+
+
+# Box read write
+
+
+def test_box_read_write():
+    cell = Cell("cell")
+    cell.add(Box(RECTANGLE, layer=3, box_type=7, properties={1: "box"}))
+    library = Library("library")
+    library.add(cell)
+    new_cell = Library.from_gds(library.to_gds()).cells["cell"]
+    assert new_cell.boxes == cell.boxes
+    assert new_cell.polygons == []
+
+
+def test_box_flatten_reference():
+    child = Cell("child")
+    child.add(Box(RECTANGLE, layer=1))
+    cell = Cell("cell")
+    cell.add(Reference(child).move_by((5, 0)))
+    cell.flatten()
+    assert cell.boxes == [Box(RECTANGLE, layer=1).move_by((5, 0))]
+
+
+# Box str
+
+
+def test_box_str():
+    assert str(Box(RECTANGLE, layer=1, box_type=2)) == (
+        "Box with 5 point(s) on layer 1, box type 2"
+    )
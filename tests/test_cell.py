@@ -2,7 +2,7 @@ import hypothesis.strategies as st
 import pytest
 from hypothesis import HealthCheck, assume, given, settings
 
-from gdsr import Cell, Element, Polygon, Reference
+from gdsr import Cell, Element, Grid, Path, Point, Polygon, Reference, Text
 
 from .conftest import (
     data_type_strategy,
@@ -163,6 +163,635 @@ def test_cell_flatten_depth_zero_no_change(cell: Cell):
     assert cell == cell_copy
 
 
+# Cell bounding_box
+
+
+def test_bounding_box_of_elements():
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (1, 0), (1, 1)]), Path([(5, 5), (8, 5)], width=2))
+    assert cell.bounding_box() == ((0, 0), (8, 6))
+
+
+def test_bounding_box_updates_after_add_and_remove():
+    cell = Cell("cell")
+    polygon = Polygon([(0, 0), (1, 0), (1, 1)])
+    cell.add(polygon)
+    assert cell.bounding_box() == ((0, 0), (1, 1))
+    other = Polygon([(0, 0), (5, 0), (5, 5)])
+    cell.add(other)
+    assert cell.bounding_box() == ((0, 0), (5, 5))
+    cell.remove(other)
+    assert cell.bounding_box() == ((0, 0), (1, 1))
+
+
+def test_bounding_box_updates_after_element_mutation():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    parent = Cell("parent")
+    parent.add(Reference(child))
+    assert parent.bounding_box() == ((0, 0), (1, 1))
+
+    child.polygons[0].move_by((10, 0))
+    assert parent.bounding_box() == ((10, 0), (11, 1))
+
+    parent.references[0].grid.columns = 2
+    parent.references[0].grid.spacing_x = (5, 0)
+    assert parent.bounding_box() == ((10, 0), (16, 1))
+
+
+def test_bounding_box_updates_after_instance_and_grid_change():
+    first = Cell("first")
+    first.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    second = Cell("second")
+    second.add(Polygon([(0, 0), (2, 0), (2, 2)]))
+    reference = Reference(first)
+    parent = Cell("parent")
+    parent.add(reference)
+    assert parent.bounding_box() == ((0, 0), (1, 1))
+
+    reference.instance = second
+    assert parent.bounding_box() == ((0, 0), (2, 2))
+    first.polygons[0].move_by((100, 0))
+    second.polygons[0].move_by((10, 0))
+    assert parent.bounding_box() == ((10, 0), (12, 2))
+
+    old_grid = reference.grid
+    reference.grid = Grid((5, 5))
+    assert parent.bounding_box() == ((15, 5), (17, 7))
+    old_grid.origin = (100, 100)
+    reference.grid.origin = (0, 5)
+    assert parent.bounding_box() == ((10, 5), (12, 7))
+
+
+def test_bounding_box_updates_for_every_cell_holding_an_element():
+    polygon = Polygon([(0, 0), (1, 0), (1, 1)])
+    first = Cell("first")
+    first.add(polygon)
+    second = Cell("second")
+    second.add(polygon)
+    copy = first.copy()
+    assert first.bounding_box() == second.bounding_box() == copy.bounding_box()
+
+    polygon.move_by((3, 0))
+    for cell in (first, second, copy):
+        assert cell.bounding_box() == ((3, 0), (4, 1))
+
+    first.remove(polygon)
+    polygon.move_by((3, 0))
+    assert second.bounding_box() == ((6, 0), (7, 1))
+
+
+def test_bounding_box_updates_through_element_references():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    inner = Reference(child)
+    parent = Cell("parent")
+    parent.add(Reference(inner, Grid((0, 10))))
+    assert parent.bounding_box() == ((0, 10), (1, 11))
+
+    child.add(Polygon([(0, 0), (5, 0), (5, 5)]))
+    assert parent.bounding_box() == ((0, 10), (5, 15))
+    inner.grid.origin = (1, 0)
+    assert parent.bounding_box() == ((1, 10), (6, 15))
+
+
+def test_bounding_box_updates_after_flatten():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    parent = Cell("parent")
+    parent.add(Reference(child))
+    parent.flatten()
+    child.polygons[0].move_by((10, 0))
+    assert parent.bounding_box() == ((0, 0), (1, 1))
+
+
+def test_bounding_box_of_reference_array():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1), (0, 1)]))
+    parent = Cell("parent")
+    parent.add(
+        Reference(child, Grid((0, 0), 3, 2, spacing_x=(2, 0), spacing_y=(0, 3)))
+    )
+    assert parent.bounding_box() == ((0, 0), (5, 4))
+
+
+def test_bounding_box_of_rotated_reference_is_exact():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (2, 0), (2, 2), (0, 2)]))
+    parent = Cell("parent")
+    parent.add(Reference(child, Grid(angle=45)))
+    lower_left, upper_right = parent.bounding_box()
+    assert lower_left.is_close(Point(-(2**0.5), 0))
+    assert upper_right.is_close(Point(2**0.5, 2 * 2**0.5))
+
+
+def test_bounding_box_of_nested_transformed_references():
+    inner = Cell("inner")
+    inner.add(Polygon([(0, 0), (1, 0), (1, 2), (0, 2)]))
+    middle = Cell("middle")
+    middle.add(Reference(inner, Grid((10, 0), magnification=2, angle=90)))
+    outer = Cell("outer")
+    outer.add(Reference(middle, Grid((0, 5), x_reflection=True)))
+
+    flattened = Cell("flattened")
+    flattened.add(*outer.references[0].flatten())
+    assert outer.bounding_box() == flattened.bounding_box()
+    assert outer.bounding_box() == ((6, 3), (10, 5))
+
+
+def test_bounding_box_of_nested_absolute_transforms():
+    inner = Cell("inner")
+    inner.add(Polygon([(0, 0), (1, 0), (1, 2), (0, 2)]))
+    middle = Cell("middle")
+    middle.add(
+        Reference(inner, Grid(magnification=3, absolute_magnification=True)),
+        Reference(inner, Grid((10, 0), angle=90, absolute_angle=True)),
+    )
+    outer = Cell("outer")
+    outer.add(Reference(middle, Grid(magnification=2, angle=180)))
+
+    # Caches the box of the middle cell placed on its own
+    assert middle.bounding_box() == ((0, 0), (10, 6))
+
+    flattened = Cell("flattened")
+    flattened.add(*outer.references[0].flatten())
+    assert outer.bounding_box() == flattened.bounding_box()
+    assert outer.bounding_box() == ((-24, -6), (0, 2))
+
+
+def test_bounding_box_does_not_mutate_referenced_cell():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    reference = Reference(child, Grid((5, 5), 2, 2, (3, 0), (0, 3), 2, 90, True))
+    reference.bounding_box
+    assert child.polygons[0].points == [(0, 0), (1, 0), (1, 1), (0, 0)]
+
+
+def test_bounding_box_per_layer():
+    child = Cell("child")
+    child.add(
+        Polygon([(0, 0), (1, 0), (1, 1)], layer=1),
+        Polygon([(10, 10), (11, 10), (11, 11)], layer=2),
+        Text("label", (50, 50), layer=1, text_type=3),
+    )
+    parent = Cell("parent")
+    parent.add(Reference(child, Grid((100, 0))))
+    assert parent.bounding_box([(1, 0)]) == ((100, 0), (101, 1))
+    assert parent.bounding_box(layers=[(2, 0)]) == ((110, 10), (111, 11))
+
+
+def test_bounding_box_with_no_matching_layer_is_empty():
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (1, 0), (1, 1)], layer=1))
+    lower_left, upper_right = cell.bounding_box([(5, 0)])
+    assert lower_left.x > upper_right.x
+
+
+def test_bounding_box_copy_does_not_share_cache():
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    cell.bounding_box()
+    copy = cell.copy()
+    copy.add(Polygon([(0, 0), (4, 0), (4, 4)]))
+    assert cell.bounding_box() == ((0, 0), (1, 1))
+    assert copy.bounding_box() == ((0, 0), (4, 4))
+
+
+# Cell query
+
+
+def test_query_direct_elements():
+    cell = Cell("cell")
+    inside = Polygon([(0, 0), (1, 0), (1, 1)])
+    outside = Polygon([(10, 10), (11, 10), (11, 11)])
+    cell.add(inside, outside)
+    assert cell.query((0, 0), (2, 2)) == [inside]
+    assert cell.query((2, 2), (0, 0)) == [inside]
+    assert cell.query((3, 3), (4, 4)) == []
+
+
+def test_query_touching_counts_as_overlap():
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    assert len(cell.query((1, 1), (2, 2))) == 1
+
+
+def test_query_layers():
+    cell = Cell("cell")
+    polygon = Polygon([(0, 0), (1, 0), (1, 1)], layer=1)
+    cell.add(polygon, Polygon([(0, 0), (1, 0), (1, 1)], layer=2))
+    assert cell.query((0, 0), (1, 1), layers=[(1, 0)]) == [polygon]
+
+
+def test_query_paths_and_texts():
+    cell = Cell("cell")
+    path = Path([(0, 0), (10, 0)], width=2)
+    text = Text("label", (50, 50))
+    cell.add(path, text)
+    assert cell.query((5, 0.5), (6, 0.8)) == [path]
+    assert cell.query((49, 49), (51, 51)) == [text]
+
+
+def test_query_through_rotated_reference():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1), (0, 1)]))
+    parent = Cell("parent")
+    parent.add(Reference(child, Grid((100, 0), angle=90)))
+    elements = parent.query((99.5, 0.5), (99.6, 0.6))
+    assert len(elements) == 1
+    assert elements[0].bounding_box == ((99, 0), (100, 1))
+    assert parent.query((0, 0), (1, 1)) == []
+
+
+def test_query_reference_array():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1), (0, 1)]))
+    parent = Cell("parent")
+    parent.add(Reference(child, Grid(columns=3, spacing_x=(10, 0))))
+    elements = parent.query((15, 0), (25, 1))
+    assert len(elements) == 1
+    assert elements[0].bounding_box == ((20, 0), (21, 1))
+
+
+def test_query_rotated_reference_array_matches_get_elements():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (2, 0), (2, 1), (0, 1)]))
+    parent = Cell("parent")
+    parent.add(
+        Reference(
+            child,
+            Grid(
+                (3, -2),
+                columns=7,
+                rows=5,
+                spacing_x=(4, 1),
+                spacing_y=(-1, 3),
+                angle=30,
+                x_reflection=True,
+            ),
+        )
+    )
+    region = ((0, 0), (12, 9))
+
+    def overlaps(element):
+        (min_x, min_y), (max_x, max_y) = element.bounding_box
+        return (
+            min_x <= region[1][0]
+            and region[0][0] <= max_x
+            and min_y <= region[1][1]
+            and region[0][1] <= max_y
+        )
+
+    expected = sorted(
+        element.bounding_box
+        for element in parent.get_elements()
+        if overlaps(element)
+    )
+    assert expected
+    assert sorted(element.bounding_box for element in parent.query(*region)) == expected
+
+
+def test_query_nested_references():
+    leaf = Cell("leaf")
+    leaf.add(Polygon([(0, 0), (1, 0), (1, 1), (0, 1)], layer=3))
+    middle = Cell("middle")
+    middle.add(Reference(leaf, Grid((10, 0))))
+    top = Cell("top")
+    top.add(Reference(middle, Grid((0, 10))))
+    elements = top.query((10, 10), (11, 11), layers=[(3, 0)])
+    assert len(elements) == 1
+    assert elements[0].bounding_box == ((10, 10), (11, 11))
+    assert top.query((10, 10), (11, 11), layers=[(4, 0)]) == []
+
+
+def test_query_depth_returns_references():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1), (0, 1)]))
+    parent = Cell("parent")
+    reference = Reference(child, Grid((5, 5)))
+    parent.add(reference)
+    assert parent.query((5, 5), (6, 6), depth=0) == [reference]
+    assert isinstance(parent.query((5, 5), (6, 6), depth=1)[0], Polygon)
+
+
+def test_query_sees_changes():
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    assert cell.query((5, 5), (6, 6)) == []
+    polygon = Polygon([(5, 5), (6, 5), (6, 6)])
+    cell.add(polygon)
+    assert cell.query((5, 5), (6, 6)) == [polygon]
+    polygon.move_by((100, 0))
+    assert cell.query((5, 5), (6, 6)) == []
+
+
+def test_query_sees_changes_below_references():
+    first = Cell("first")
+    first.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    second = Cell("second")
+    second.add(Polygon([(50, 0), (51, 0), (51, 1)]))
+    reference = Reference(first)
+    middle = Cell("middle")
+    middle.add(reference)
+    top = Cell("top")
+    top.add(Reference(middle))
+    other = Cell("other")
+    other.add(first.polygons[0])
+    assert len(top.query((0, 0), (1, 1))) == 1
+    assert len(other.query((0, 0), (1, 1))) == 1
+
+    first.polygons[0].move_by((20, 0))
+    assert top.query((0, 0), (1, 1)) == []
+    assert len(top.query((20, 0), (21, 1))) == 1
+    assert len(other.query((20, 0), (21, 1))) == 1
+
+    reference.instance = second
+    assert top.query((20, 0), (21, 1)) == []
+    assert len(top.query((50, 0), (51, 1))) == 1
+
+    reference.grid.origin = (0, 30)
+    assert top.query((50, 0), (51, 1)) == []
+    assert len(top.query((50, 30), (51, 31))) == 1
+
+
+# Cell clip
+
+
+def test_clip_keeps_inside_and_drops_outside():
+    cell = Cell("cell")
+    inside = Polygon([(1, 1), (2, 1), (2, 2), (1, 2)], layer=1)
+    cell.add(inside, Polygon([(20, 20), (21, 20), (21, 21)]))
+    clipped = cell.clip((0, 0), (10, 10))
+    assert clipped.name == "cell"
+    assert clipped.polygons == [inside]
+    assert clipped.polygons[0] is not inside
+    assert len(cell.polygons) == 2
+
+
+def test_clip_cuts_polygons():
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (4, 0), (4, 4), (0, 4)], layer=2, properties={1: "a"}))
+    clipped = cell.clip((2, 2), (10, 10))
+    assert len(clipped.polygons) == 1
+    polygon = clipped.polygons[0]
+    assert polygon.bounding_box == ((2, 2), (4, 4))
+    assert polygon.area == 4
+    assert polygon.layer == 2
+    assert polygon.properties == {1: "a"}
+
+
+def test_clip_cuts_paths_into_polygons():
+    cell = Cell("cell")
+    path = Path([(0, 0), (10, 0)], width=2)
+    cell.add(path, Path([(2, 5), (4, 5)], width=2))
+    clipped = cell.clip((5, -5), (20, 10))
+    assert len(clipped.polygons) == 1
+    assert clipped.polygons[0].bounding_box == ((5, -1), (10, 1))
+    assert clipped.paths == []
+
+
+def test_clip_keeps_paths_inside():
+    cell = Cell("cell")
+    path = Path([(2, 5), (4, 5)], width=2)
+    cell.add(path)
+    assert cell.clip((0, 0), (10, 10)).paths == [path]
+
+
+def test_clip_texts_by_origin():
+    cell = Cell("cell")
+    text = Text("inside", (5, 5))
+    cell.add(text, Text("outside", (50, 50)))
+    assert cell.clip((0, 0), (10, 10)).texts == [text]
+
+
+def test_clip_with_polygon_region():
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (4, 0), (4, 4), (0, 4)]))
+    clipped = cell.clip(Polygon([(0, 0), (4, 0), (0, 4)]))
+    assert len(clipped.polygons) == 1
+    assert clipped.polygons[0].area == 8
+
+
+def test_clip_invalid_region():
+    with pytest.raises(TypeError):
+        Cell("cell").clip(5)
+
+
+def test_clip_reduces_reference_arrays():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1), (0, 1)]))
+    parent = Cell("parent")
+    parent.add(
+        Reference(
+            child,
+            Grid(columns=10, rows=10, spacing_x=(2, 0), spacing_y=(0, 2)),
+        )
+    )
+    clipped = parent.clip((3.5, 3.5), (9.5, 9.5))
+    assert clipped.polygons == []
+    assert len(clipped.references) == 1
+    grid = clipped.references[0].grid
+    assert grid.origin == Point(4, 4)
+    assert (grid.columns, grid.rows) == (3, 3)
+    assert clipped.references[0].instance is child
+
+
+def test_clip_flattens_instances_on_the_boundary():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (2, 0), (2, 2), (0, 2)]))
+    parent = Cell("parent")
+    parent.add(Reference(child, Grid(columns=3, spacing_x=(4, 0))))
+    clipped = parent.clip((-1, -1), (5, 3))
+    assert len(clipped.references) == 1
+    assert clipped.references[0].grid.columns == 1
+    assert len(clipped.polygons) == 1
+    assert clipped.polygons[0].bounding_box == ((4, 0), (5, 2))
+
+
+def test_clip_irregular_reference_selection():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1), (0, 1)]))
+    parent = Cell("parent")
+    parent.add(
+        Reference(child, Grid(columns=3, rows=3, spacing_x=(2, 0), spacing_y=(0, 2)))
+    )
+    clipped = parent.clip(Polygon([(-0.5, -0.5), (5.5, -0.5), (-0.5, 5.5)]))
+    instances = sum(
+        reference.grid.columns * reference.grid.rows
+        for reference in clipped.references
+    )
+    assert instances == 3
+    assert len(clipped.references) == 2
+    assert len(clipped.polygons) == 3
+
+
+# Cell fill
+
+
+def fill_boundary(width: float, height: float) -> Cell:
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (width, 0), (width, height), (0, height)], layer=2))
+    return cell
+
+
+def fill_tiles(fill: Cell) -> int:
+    return sum(
+        reference.grid.columns * reference.grid.rows for reference in fill.references
+    )
+
+
+def test_fill_whole_lattice():
+    fill = fill_boundary(10, 10).fill((1, 0), (1, 1), (2, 2), 0.25)
+    assert fill.name == "cell_FILL"
+    assert len(fill.references) == 1
+    reference = fill.references[0]
+    assert reference.grid.origin == Point(0, 0)
+    assert (reference.grid.columns, reference.grid.rows) == (5, 5)
+    assert reference.grid.spacing_x == Point(2, 0)
+    assert reference.grid.spacing_y == Point(0, 2)
+    tile = reference.instance
+    assert tile.name == "cell_FILL_TILE"
+    assert len(tile.polygons) == 1
+    assert tile.polygons[0].area == 1
+    assert tile.polygons[0].layer == 1
+
+
+def test_fill_reaches_target_density():
+    fill = fill_boundary(20, 20).fill((1, 0), (1, 1), (2, 2), 0.1)
+    assert fill_tiles(fill) == 40
+    for reference in fill.references:
+        assert reference.grid.columns * reference.grid.rows > 1
+
+
+def test_fill_counts_existing_geometry():
+    cell = fill_boundary(20, 20)
+    cell.add(Polygon([(0, 0), (10, 0), (10, 20), (0, 20)], layer=1))
+    assert cell.fill((1, 0), (1, 1), (2, 2), 0.5).references == []
+    fill = cell.fill((1, 0), (1, 1), (2, 2), 0.6)
+    assert fill_tiles(fill) == 40
+    for reference in fill.references:
+        assert reference.bounding_box[0].x >= 10
+
+
+def test_fill_keep_out_layers_and_spacing():
+    cell = fill_boundary(10, 10)
+    cell.add(Polygon([(0, 0), (10, 0), (10, 5), (0, 5)], layer=3))
+    fill = cell.fill((1, 0), (1, 1), (2, 2), 0.25, keep_out_layers=[(3, 0)], spacing=1)
+    assert fill_tiles(fill) == 10
+    assert all(reference.grid.origin.y >= 6 for reference in fill.references)
+
+
+def test_fill_density_windows():
+    cell = fill_boundary(20, 10)
+    cell.add(Polygon([(0, 0), (10, 0), (10, 5), (0, 5)], layer=1))
+    fill = cell.fill((1, 0), (1, 1), (2, 2), 0.25, window_size=10)
+    assert fill_tiles(fill) == 25
+    assert all(reference.grid.origin.x >= 10 for reference in fill.references)
+
+
+def test_fill_layer_name_and_tile_polygon():
+    tile = Polygon([(0, 0), (2, 0), (0, 2)], layer=5)
+    fill = fill_boundary(10, 10).fill(
+        (1, 0), tile, (2, 2), 0.1, fill_layer=(1, 1), name="dummy"
+    )
+    assert fill.name == "dummy"
+    tile_cell = fill.references[0].instance
+    assert tile_cell.name == "dummy_TILE"
+    assert tile_cell.polygons[0].area == 2
+    assert (tile_cell.polygons[0].layer, tile_cell.polygons[0].data_type) == (1, 1)
+    assert fill_tiles(fill) == 5
+
+
+def test_fill_empty_cell():
+    assert Cell("cell").fill((1, 0), (1, 1), (2, 2), 0.5).references == []
+
+
+def test_fill_invalid_arguments():
+    cell = fill_boundary(10, 10)
+    with pytest.raises(ValueError, match="Fill pitch must be at least the tile size"):
+        cell.fill((1, 0), (2, 2), (1, 1), 0.5)
+    with pytest.raises(ValueError, match="Target density"):
+        cell.fill((1, 0), (1, 1), (2, 2), 0)
+    with pytest.raises(ValueError, match="Target density"):
+        cell.fill((1, 0), (1, 1), (2, 2), 1.5)
+    with pytest.raises(ValueError, match="Window size must be positive"):
+        cell.fill((1, 0), (1, 1), (2, 2), 0.5, window_size=0)
+    with pytest.raises(TypeError, match="Fill tile must be a Polygon"):
+        cell.fill((1, 0), "tile", (2, 2), 0.5)
+
+
+# Cell xor
+
+
+def xor_square(size: float, layer: int = 1, origin: float = 0) -> Polygon:
+    return Polygon(
+        [
+            (origin, origin),
+            (origin + size, origin),
+            (origin + size, origin + size),
+            (origin, origin + size),
+        ],
+        layer,
+    )
+
+
+def test_xor_equivalent_cells():
+    cell = Cell("cell")
+    cell.add(xor_square(10))
+    child = Cell("child")
+    child.add(
+        Polygon([(0, 5), (0, 0), (10, 0), (10, 5)], 1),
+        Polygon([(10, 10), (0, 10), (0, 5), (10, 5)], 1),
+    )
+    other = Cell("other")
+    other.add(Reference(child))
+    assert cell.xor(other) == {}
+    assert cell.xor(cell) == {}
+
+
+def test_xor_differences_per_layer():
+    cell = Cell("cell")
+    cell.add(xor_square(10), xor_square(4, 2))
+    other = Cell("other")
+    other.add(xor_square(10, origin=5), Path([(0, 1), (4, 1)], layer=3, width=2))
+
+    difference = cell.xor(other)
+    assert difference.keys() == {(1, 0), (2, 0), (3, 0)}
+    polygons, area = difference[(1, 0)]
+    assert area == 150
+    assert sum(polygon.area for polygon in polygons) == 150
+    assert all(polygon.layer == 1 for polygon in polygons)
+    assert difference[(2, 0)][1] == 16
+    assert difference[(3, 0)][1] == 8
+
+
+def test_xor_layers():
+    cell = Cell("cell")
+    cell.add(xor_square(10), xor_square(4, 2))
+    other = Cell("other")
+    other.add(xor_square(10))
+    assert cell.xor(other, layers=[(1, 0)]) == {}
+    assert cell.xor(other, layers=[(2, 0)]).keys() == {(2, 0)}
+
+
+def test_xor_tolerance():
+    cell = Cell("cell")
+    cell.add(xor_square(10))
+    other = Cell("other")
+    other.add(Polygon([(0, 0), (10.01, 0), (10.01, 10), (0, 10)], 1))
+    assert cell.xor(other).keys() == {(1, 0)}
+    assert cell.xor(other, tolerance=0.1) == {}
+
+    other.add(xor_square(1, origin=20))
+    difference = cell.xor(other, tolerance=0.1)
+    assert difference[(1, 0)][1] == pytest.approx(1)
+
+
+def test_xor_negative_tolerance():
+    with pytest.raises(ValueError, match="XOR tolerance must not be negative"):
+        Cell("cell").xor(Cell("other"), tolerance=-1)
+
+
 # Cell get_elements
 
 
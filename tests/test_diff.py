@@ -0,0 +1,193 @@
+from pathlib import Path as FilePath
+
+import pytest
+
+from gdsr import Cell, Grid, Library, Path, Polygon, Reference, Text
+from gdsr.cli.diff import main
+
+
+def make_library(*cells: Cell) -> Library:
+    library = Library()
+    library.add(*cells)
+    return library
+
+
+def square(size: float = 10, layer: int = 1, data_type: int = 0) -> Polygon:
+    return Polygon([(0, 0), (size, 0), (size, size), (0, size)], layer, data_type)
+
+
+# Library diff
+
+
+def test_diff_identical_libraries():
+    cell = Cell("top")
+    cell.add(square(), Path([(0, 0), (10, 0)], width=1), Text("label"))
+    difference = make_library(cell).diff(make_library(cell.copy()))
+    assert not difference
+    assert difference.cells == []
+    assert str(difference) == "Libraries are identical\n"
+
+
+def test_diff_ignores_element_order_and_polygon_start_point():
+    cell = Cell("top")
+    cell.add(square(), square(5, 2))
+    other = Cell("top")
+    other.add(
+        Polygon([(5, 0), (5, 5), (0, 5), (0, 0)], 2),
+        Polygon([(10, 10), (0, 10), (0, 0), (10, 0)], 1),
+    )
+    assert not make_library(cell).diff(make_library(other))
+
+
+def test_diff_added_and_removed_cells():
+    first = Cell("first")
+    first.add(square())
+    second = Cell("second")
+    second.add(square(20))
+    difference = make_library(first).diff(make_library(second))
+    assert difference
+    assert difference.added_cells == ["second"]
+    assert difference.removed_cells == ["first"]
+    assert difference.renamed_cells == []
+
+
+def test_diff_renamed_cell():
+    child = Cell("child")
+    child.add(square())
+    parent = Cell("parent")
+    parent.add(Reference(child))
+
+    renamed_child = Cell("renamed")
+    renamed_child.add(square())
+    other_parent = Cell("parent")
+    other_parent.add(Reference(renamed_child))
+
+    difference = make_library(parent, child).diff(
+        make_library(other_parent, renamed_child)
+    )
+    assert difference.renamed_cells == [("child", "renamed")]
+    assert difference.added_cells == []
+    assert difference.removed_cells == []
+    assert difference.cells == []
+    assert str(difference) == "Renamed cell: child -> renamed\n"
+
+
+def test_diff_element_changes_by_layer():
+    cell = Cell("top")
+    cell.add(square(), square(5), Path([(0, 0), (10, 0)], layer=3, width=1))
+    other = Cell("top")
+    other.add(square(), square(5, 2, 1), Text("label", layer=4))
+
+    difference = make_library(cell).diff(make_library(other))
+    assert len(difference.cells) == 1
+    cell_difference = difference.cells[0]
+    assert cell_difference.name == cell_difference.other_name == "top"
+    assert cell_difference.changes == {
+        ("polygon", (1, 0)): (0, 1),
+        ("polygon", (2, 1)): (1, 0),
+        ("path", (3, 0)): (0, 1),
+        ("text", (4, 0)): (1, 0),
+    }
+    assert len(cell_difference.added) == 2
+    assert len(cell_difference.removed) == 2
+    assert cell_difference.removed[0] in (square(5), cell.paths[0])
+    assert str(difference) == (
+        "Cell 'top':\n"
+        "  path 3/0: -1\n"
+        "  polygon 1/0: -1\n"
+        "  polygon 2/1: +1\n"
+        "  text 4/0: +1\n"
+    )
+
+
+def test_diff_reference_changes():
+    child = Cell("child")
+    child.add(square())
+    cell = Cell("top")
+    cell.add(Reference(child))
+    other = Cell("top")
+    other.add(Reference(child, Grid(origin=(5, 0))))
+
+    difference = make_library(cell, child).diff(make_library(other, child))
+    assert difference.cells[0].changes == {("reference", None): (1, 1)}
+    assert "  reference: +1 -1\n" in str(difference)
+
+
+def test_diff_library_with_itself():
+    cell = Cell("top")
+    cell.add(square())
+    library = make_library(cell)
+    assert not library.diff(library, xor=True)
+
+
+# Library diff xor
+
+
+def test_diff_xor_areas():
+    cell = Cell("top")
+    cell.add(square(10))
+    other = Cell("top")
+    other.add(Polygon([(0, 0), (10, 0), (10, 5), (0, 5)], 1), square(2, 2))
+
+    difference = make_library(cell).diff(make_library(other), xor=True)
+    assert difference.cells[0].xor_areas == {(1, 0): 50, (2, 0): 4}
+    assert "  XOR area 1/0: 50\n  XOR area 2/0: 4\n" in str(difference)
+
+
+def test_diff_xor_same_geometry_different_polygons():
+    cell = Cell("top")
+    cell.add(square(10))
+    other = Cell("top")
+    other.add(
+        Polygon([(0, 0), (5, 0), (5, 10), (0, 10)], 1),
+        Polygon([(5, 0), (10, 0), (10, 10), (5, 10)], 1),
+    )
+
+    difference = make_library(cell).diff(make_library(other), xor=True)
+    assert difference.cells[0].changes == {("polygon", (1, 0)): (2, 1)}
+    assert difference.cells[0].xor_areas == {}
+
+
+def test_diff_xor_reports_referencing_cells():
+    child = Cell("child")
+    child.add(square(10))
+    parent = Cell("parent")
+    parent.add(Reference(child))
+
+    other_child = Cell("child")
+    other_child.add(square(5))
+    other_parent = Cell("parent")
+    other_parent.add(Reference(other_child))
+
+    difference = make_library(parent, child).diff(
+        make_library(other_parent, other_child), xor=True
+    )
+    cells = {cell.name: cell for cell in difference.cells}
+    assert cells.keys() == {"child", "parent"}
+    assert cells["parent"].changes == {}
+    assert cells["parent"].xor_areas == {(1, 0): 75}
+
+
+# gdsr-diff
+
+
+def test_diff_command_identical(tmp_path: FilePath, capsys: pytest.CaptureFixture):
+    cell = Cell("top")
+    cell.add(square())
+    old = make_library(cell).to_gds(tmp_path / "old.gds")
+    new = make_library(cell).to_gds(tmp_path / "new.gds")
+    assert main([old, new]) == 0
+    assert capsys.readouterr().out == "Libraries are identical\n"
+
+
+def test_diff_command_differences(tmp_path: FilePath, capsys: pytest.CaptureFixture):
+    cell = Cell("top")
+    cell.add(square())
+    other = Cell("top")
+    other.add(square(5))
+    old = make_library(cell).to_gds(tmp_path / "old.gds")
+    new = make_library(other).to_oasis(tmp_path / "new.oas")
+    assert main([old, new, "--xor"]) == 1
+    assert capsys.readouterr().out == (
+        "Cell 'top':\n  polygon 1/0: +1 -1\n  XOR area 1/0: 75\n"
+    )
@@ -0,0 +1,545 @@
+from pathlib import Path as FilePath
+
+import pytest
+
+from gdsr import (
+    Box,
+    Cell,
+    DrcRule,
+    DrcViolation,
+    Library,
+    Path,
+    Polygon,
+    Reference,
+    drc,
+)
+
+
+def rectangle(
+    x: float, y: float, width: float, height: float, layer: int = 1
+) -> Polygon:
+    return Polygon(
+        [(x, y), (x + width, y), (x + width, y + height), (x, y + height)],
+        layer=layer,
+    )
+
+
+# Rules
+
+
+def test_rule_default_name():
+    assert DrcRule.width((1, 0), 0.5).name == "width 1/0 0.5"
+    assert (
+        DrcRule.enclosure((2, 0), (1, 0), 0.25).name == "enclosure 2/0 1/0 0.25"
+    )
+
+
+def test_rule_custom_name():
+    rule = DrcRule.spacing((1, 0), 2, name="M1.S.1")
+    assert rule.name == "M1.S.1"
+    assert rule.check == "spacing"
+    assert rule.layer == (1, 0)
+    assert rule.other_layer is None
+    assert rule.value == 2
+
+
+def test_rule_enclosure_layers():
+    rule = DrcRule.enclosure((2, 0), (1, 0), 1)
+    assert rule.check == "enclosure"
+    assert rule.layer == (2, 0)
+    assert rule.other_layer == (1, 0)
+
+
+def test_rule_equality():
+    assert DrcRule.area((1, 0), 4) == DrcRule.area((1, 0), 4)
+    assert DrcRule.area((1, 0), 4) != DrcRule.area((1, 0), 5)
+
+
+def test_rule_value_must_be_positive():
+    with pytest.raises(ValueError, match="DRC rule value must be positive"):
+        DrcRule.width((1, 0), 0)
+    with pytest.raises(ValueError, match="DRC rule value must be positive"):
+        DrcRule.notch((1, 0), -1)
+
+
+def test_rule_invalid_layer():
+    with pytest.raises(ValueError):
+        DrcRule.width((-1, 0), 1)
+    with pytest.raises(ValueError):
+        DrcRule.spacing((1, 0), 1, other_layer=(256, 0))
+
+
+def test_rule_str():
+    assert str(DrcRule.width((1, 0), 2, name="W")) == (
+        "DRC rule 'W': width of (1, 0) at least 2"
+    )
+
+
+# Width
+
+
+def test_width_violation():
+    violations = drc([rectangle(0, 0, 10, 1)], [DrcRule.width((1, 0), 2)])
+    assert len(violations) == 1
+    violation = violations[0]
+    assert violation.rule == "width 1/0 2"
+    assert violation.cell is None
+    assert violation.value == pytest.approx(1)
+    assert violation.marker.bounding_box == ((0, 0), (10, 1))
+    assert violation.marker.layer == 1
+    assert len(violation.edges) == 2
+
+
+def test_width_no_violation():
+    assert drc([rectangle(0, 0, 10, 2)], [DrcRule.width((1, 0), 2)]) == []
+
+
+def test_width_measured_on_merged_shapes():
+    polygons = [rectangle(0, 0, 10, 1), rectangle(0, 1, 10, 1)]
+    assert drc(polygons, [DrcRule.width((1, 0), 2)]) == []
+
+
+def test_width_ignores_other_layers():
+    assert drc([rectangle(0, 0, 10, 1, layer=2)], [DrcRule.width((1, 0), 2)]) == []
+
+
+def test_width_exactly_at_limit():
+    polygons = [rectangle(0.3, 0.1, 0.2, 5), rectangle(5000, 5000, 1, 1)]
+    assert drc(polygons, [DrcRule.width((1, 0), 0.2)]) == []
+
+
+def test_width_value_is_on_the_grid():
+    polygons = [rectangle(0.1, 0.1, 0.15, 5), rectangle(5000, 5000, 1, 1)]
+    violations = drc(polygons, [DrcRule.width((1, 0), 0.2)])
+    assert len(violations) == 1
+    assert violations[0].value == 0.15
+    assert violations[0].marker.bounding_box == ((0.1, 0.1), (0.25, 5.1))
+
+
+def test_width_narrow_neck():
+    polygon = Polygon(
+        [
+            (0, 0),
+            (4, 0),
+            (4, 4),
+            (6, 4),
+            (6, 0),
+            (10, 0),
+            (10, 5),
+            (0, 5),
+        ],
+        layer=1,
+    )
+    violations = drc([polygon], [DrcRule.width((1, 0), 2)])
+    assert len(violations) == 1
+    assert violations[0].value == pytest.approx(1)
+    assert violations[0].marker.bounding_box == ((4, 4), (6, 5))
+
+
+# Spacing
+
+
+def test_spacing_violation():
+    polygons = [rectangle(0, 0, 5, 5), rectangle(6, 0, 5, 5)]
+    violations = drc(polygons, [DrcRule.spacing((1, 0), 2)])
+    assert len(violations) == 1
+    assert violations[0].value == pytest.approx(1)
+    assert violations[0].marker.bounding_box == ((5, 0), (6, 5))
+
+
+def test_spacing_no_violation():
+    polygons = [rectangle(0, 0, 5, 5), rectangle(7, 0, 5, 5)]
+    assert drc(polygons, [DrcRule.spacing((1, 0), 2)]) == []
+
+
+def test_spacing_exactly_at_limit():
+    polygons = [
+        rectangle(0.1, 0, 0.1, 5),
+        rectangle(0.4, 0, 0.1, 5),
+        rectangle(5000, 5000, 1, 1),
+    ]
+    assert drc(polygons, [DrcRule.spacing((1, 0), 0.2)]) == []
+
+
+def test_spacing_value_is_on_the_grid():
+    polygons = [
+        rectangle(0.7, 0, 0.3, 5),
+        rectangle(1.2, 0, 0.1, 5),
+        rectangle(-3000, -3000, 1, 1),
+    ]
+    violations = drc(polygons, [DrcRule.spacing((1, 0), 0.5)])
+    assert len(violations) == 1
+    assert violations[0].value == 0.2
+    assert violations[0].marker.bounding_box == ((1, 0), (1.2, 5))
+
+
+def test_spacing_corner_to_corner():
+    polygons = [rectangle(0, 0, 5, 5), rectangle(6, 6, 5, 5)]
+    violations = drc(polygons, [DrcRule.spacing((1, 0), 2)])
+    assert len(violations) >= 1
+    assert min(violation.value for violation in violations) == pytest.approx(
+        2**0.5
+    )
+
+
+def test_spacing_cross_layer():
+    polygons = [rectangle(0, 0, 5, 5), rectangle(5.5, 0, 5, 5, layer=2)]
+    rule = DrcRule.spacing((1, 0), 1, other_layer=(2, 0))
+    violations = drc(polygons, [rule])
+    assert len(violations) == 1
+    assert violations[0].value == pytest.approx(0.5)
+    assert drc(polygons, [DrcRule.spacing((1, 0), 1)]) == []
+
+
+# Enclosure
+
+
+def test_enclosure_no_violation():
+    polygons = [rectangle(0, 0, 10, 10), rectangle(2, 2, 6, 6, layer=2)]
+    assert drc(polygons, [DrcRule.enclosure((2, 0), (1, 0), 1)]) == []
+
+
+def test_enclosure_violation():
+    polygons = [rectangle(0, 0, 10, 10), rectangle(2, 2, 7.5, 6, layer=2)]
+    violations = drc(polygons, [DrcRule.enclosure((2, 0), (1, 0), 1)])
+    assert len(violations) == 1
+    assert violations[0].value == pytest.approx(0.5)
+    assert violations[0].marker.bounding_box == ((9.5, 2), (10, 8))
+    assert violations[0].marker.layer == 2
+
+
+def test_enclosure_exactly_at_limit():
+    polygons = [
+        rectangle(0.1, 0.1, 0.7, 0.7),
+        rectangle(0.3, 0.3, 0.3, 0.3, layer=2),
+        rectangle(5000, 5000, 1, 1),
+        rectangle(5000, 5000, 1, 1, layer=2),
+    ]
+    assert drc(polygons, [DrcRule.enclosure((2, 0), (1, 0), 0.2)]) == []
+
+
+def test_enclosure_inner_outside():
+    polygons = [rectangle(0, 0, 10, 10), rectangle(8, 2, 4, 4, layer=2)]
+    violations = drc(polygons, [DrcRule.enclosure((2, 0), (1, 0), 1)])
+    uncovered = [violation for violation in violations if violation.value == 0]
+    assert len(uncovered) == 1
+    assert uncovered[0].marker.bounding_box == ((10, 2), (12, 6))
+
+
+# Area
+
+
+def test_area_violation():
+    polygons = [rectangle(0, 0, 1, 1), rectangle(5, 0, 3, 3)]
+    violations = drc(polygons, [DrcRule.area((1, 0), 4)])
+    assert len(violations) == 1
+    assert violations[0].value == pytest.approx(1)
+    assert violations[0].marker.bounding_box == ((0, 0), (1, 1))
+
+
+def test_area_merged_shapes():
+    polygons = [rectangle(0, 0, 1, 2), rectangle(1, 0, 1, 2)]
+    assert drc(polygons, [DrcRule.area((1, 0), 4)]) == []
+
+
+# Notch
+
+
+def test_notch_violation():
+    polygon = Polygon(
+        [(0, 0), (10, 0), (10, 5), (5.5, 5), (5.5, 2), (4.5, 2), (4.5, 5), (0, 5)],
+        layer=1,
+    )
+    violations = drc([polygon], [DrcRule.notch((1, 0), 2)])
+    assert len(violations) == 1
+    assert violations[0].value == pytest.approx(1)
+    assert violations[0].marker.bounding_box == ((4.5, 2), (5.5, 5))
+    assert drc([polygon], [DrcRule.spacing((1, 0), 2)]) == []
+
+
+def test_notch_no_violation():
+    polygon = Polygon(
+        [(0, 0), (10, 0), (10, 5), (6, 5), (6, 2), (4, 2), (4, 5), (0, 5)],
+        layer=1,
+    )
+    assert drc([polygon], [DrcRule.notch((1, 0), 2)]) == []
+
+
+# Cell drc
+
+
+def test_cell_drc_through_reference():
+    child = Cell("child")
+    child.add(rectangle(0, 0, 10, 1))
+    top = Cell("top")
+    top.add(Reference(child), rectangle(0, 10, 10, 5))
+    violations = top.drc([DrcRule.width((1, 0), 2)])
+    assert len(violations) == 1
+    assert violations[0].cell == "top"
+    assert violations[0].marker.bounding_box == ((0, 0), (10, 1))
+
+
+def test_cell_drc_paths():
+    cell = Cell("cell")
+    cell.add(Path([(0, 0), (10, 0)], width=1, layer=1))
+    violations = cell.drc([DrcRule.width((1, 0), 2)])
+    assert len(violations) == 1
+    assert violations[0].value == pytest.approx(1)
+
+
+def test_cell_drc_boxes():
+    cell = Cell("cell")
+    cell.add(Box([(0, 0), (10, 0), (10, 1), (0, 1)], layer=1))
+    violations = cell.drc([DrcRule.width((1, 0), 2)])
+    assert len(violations) == 1
+    assert violations[0].value == pytest.approx(1)
+
+
+def test_cell_drc_multiple_rules():
+    cell = Cell("cell")
+    cell.add(rectangle(0, 0, 1, 1), rectangle(1.5, 0, 1, 1))
+    rules = [DrcRule.width((1, 0), 2), DrcRule.spacing((1, 0), 1)]
+    violations = cell.drc(rules)
+    assert {violation.rule for violation in violations} == {
+        "width 1/0 2",
+        "spacing 1/0 1",
+    }
+
+
+# Violations
+
+
+def test_violation_init():
+    marker = rectangle(0, 0, 1, 1)
+    violation = DrcViolation("rule", marker, value=0.5, cell="top")
+    assert violation.rule == "rule"
+    assert violation.marker == marker
+    assert violation.value == 0.5
+    assert violation.edges == []
+    assert violation.cell == "top"
+    assert str(violation) == "DRC violation of 'rule' in cell top with value 0.5"
+
+
+# Results databases
+
+
+def results_library() -> tuple[Library, list[DrcViolation]]:
+    child = Cell("child")
+    child.add(rectangle(0, 0, 10, 1))
+    top = Cell("top")
+    top.add(Reference(child), rectangle(20, 0, 2, 2))
+    library = Library()
+    library.add(top, child)
+    rules = [DrcRule.width((1, 0), 2), DrcRule.area((1, 0), 5, name="M1.A.1")]
+    return library, top.drc(rules) + child.drc(rules[:1])
+
+
+def assert_same_violations(read: list[DrcViolation], written: list[DrcViolation]):
+    assert len(read) == len(written)
+    for read_violation, violation in zip(read, written):
+        assert read_violation.rule == violation.rule
+        assert read_violation.cell == violation.cell
+        assert read_violation.marker.bounding_box == violation.marker.bounding_box
+        assert read_violation.marker.area == pytest.approx(violation.marker.area)
+        assert read_violation.edges == violation.edges
+
+
+def by_rule(violations: list[DrcViolation]) -> list[DrcViolation]:
+    rules = list(dict.fromkeys(violation.rule for violation in violations))
+    return sorted(violations, key=lambda violation: rules.index(violation.rule))
+
+
+def test_lyrdb_round_trip(tmp_path: FilePath):
+    library, violations = results_library()
+    file_name = library.export_lyrdb(violations, tmp_path / "results.lyrdb")
+    read = library.import_lyrdb(file_name)
+    assert_same_violations(read, violations)
+    assert [violation.value for violation in read] == [
+        violation.value for violation in violations
+    ]
+
+
+def test_lyrdb_contents():
+    library, violations = results_library()
+    with open(library.export_lyrdb(violations)) as file:
+        contents = file.read()
+    assert "<top-cell>top</top-cell>" in contents
+    assert "<name>width 1/0 2</name>" in contents
+    assert "<category>'width 1/0 2'</category>" in contents
+    assert "<category>'M1.A.1'</category>" in contents
+    assert "<cell>child</cell>" in contents
+    assert "float: 1" in contents
+
+
+def test_lyrdb_violation_without_cell_uses_top_cell():
+    library, _ = results_library()
+    violations = drc([rectangle(0, 0, 10, 1)], [DrcRule.width((1, 0), 2)])
+    read = library.import_lyrdb(library.export_lyrdb(violations))
+    assert [violation.cell for violation in read] == ["top"]
+
+
+def test_lyrdb_violation_without_cell_needs_top_cell():
+    library = Library()
+    library.add(Cell("a"), Cell("b"))
+    violations = drc([rectangle(0, 0, 10, 1)], [DrcRule.width((1, 0), 2)])
+    with pytest.raises(ValueError, match="single top cell"):
+        library.export_lyrdb(violations)
+
+
+def test_export_unknown_cell():
+    library, _ = results_library()
+    violation = DrcViolation("rule", rectangle(0, 0, 1, 1), cell="missing")
+    with pytest.raises(ValueError, match="Cell 'missing' is not in the library"):
+        library.export_lyrdb([violation])
+    with pytest.raises(ValueError, match="Cell 'missing' is not in the library"):
+        library.export_rdb([violation])
+
+
+def test_import_lyrdb_klayout_items(tmp_path: FilePath):
+    file_name = tmp_path / "klayout.lyrdb"
+    file_name.write_text(
+        """<?xml version="1.0" encoding="utf-8"?>
+<report-database>
+ <categories>
+  <category><name>M1</name><categories>
+   <category><name>W</name></category>
+  </categories></category>
+ </categories>
+ <items>
+  <item>
+   <category>M1.W</category>
+   <cell>child:1</cell>
+   <values>
+    <value>edge-pair: (0,0;10,0)/(10,1;0,1)</value>
+    <value>float: 1</value>
+   </values>
+  </item>
+  <item>
+   <category>'a &amp; b'</category>
+   <cell>'top'</cell>
+   <values>
+    <value>box: (0,0;2,3)</value>
+   </values>
+  </item>
+ </items>
+</report-database>
+"""
+    )
+    library, _ = results_library()
+    first, second = library.import_lyrdb(file_name)
+    assert first.rule == "M1.W"
+    assert first.cell == "child"
+    assert first.value == 1
+    assert first.edges == [((0, 0), (10, 0)), ((10, 1), (0, 1))]
+    assert first.marker.bounding_box == ((0, 0), (10, 1))
+    assert second.rule == "a & b"
+    assert second.cell == "top"
+    assert second.value == 0
+    assert second.marker.area == 6
+
+
+def test_import_lyrdb_hole_outside_polygon(tmp_path: FilePath):
+    file_name = tmp_path / "hole.lyrdb"
+    file_name.write_text(
+        """<?xml version="1.0" encoding="utf-8"?>
+<report-database>
+ <items>
+  <item>
+   <category>M1.W</category>
+   <cell>top</cell>
+   <values>
+    <value>polygon: (0,0;10,0;10,10;0,10/-5,5;-4,5;-4,6)</value>
+   </values>
+  </item>
+ </items>
+</report-database>
+"""
+    )
+    library, _ = results_library()
+    with pytest.raises(ValueError, match="lies outside its outer contour"):
+        library.import_lyrdb(file_name)
+
+
+def test_import_lyrdb_unknown_cell():
+    library, violations = results_library()
+    file_name = library.export_lyrdb(violations)
+    with pytest.raises(ValueError, match="is not in the library"):
+        Library().import_lyrdb(file_name)
+
+
+def test_rdb_round_trip(tmp_path: FilePath):
+    library, violations = results_library()
+    file_name = library.export_rdb(violations, tmp_path / "results.rdb")
+    read = library.import_rdb(file_name)
+    assert_same_violations(read, by_rule(violations))
+    assert [violation.value for violation in read] == [
+        violation.value for violation in by_rule(violations)
+    ]
+
+
+def test_rdb_contents():
+    library, violations = results_library()
+    with open(library.export_rdb(violations)) as file:
+        lines = file.read().splitlines()
+    assert lines[0] == "top 10000"
+    assert lines[1] == "width 1/0 2"
+    assert lines[2].startswith("4 4 0 ")
+    assert lines[3] == "p 1 4"
+    assert lines[4] == "CN top c 1 0 0 1 0 0"
+    assert lines[9] == "VALUE 1"
+    assert "CN child c 1 0 0 1 0 0" in lines
+    assert "M1.A.1" in lines
+
+
+def test_rdb_uses_library_units():
+    library, violations = results_library()
+    library.set_units(1e-6, 1e-9)
+    read = library.import_rdb(library.export_rdb(violations))
+    assert_same_violations(read, by_rule(violations))
+
+
+def test_rdb_rounds_precision_for_coordinates():
+    library, violations = results_library()
+    library.set_units(1e-6, 3e-9)
+    file_name = library.export_rdb(violations)
+    with open(file_name) as file:
+        lines = file.read().splitlines()
+    assert lines[0] == "top 333"
+    assert lines[5:9] == ["0 0", "3330 0", "3330 333", "0 333"]
+    read = library.import_rdb(file_name)
+    assert read[0].marker.bounding_box == violations[0].marker.bounding_box
+
+
+def test_import_rdb_calibre_results(tmp_path: FilePath):
+    file_name = tmp_path / "calibre.rdb"
+    file_name.write_text(
+        """top 1000
+M1.S.1
+2 2 2 Oct 18 12:00:00 2026
+Rule File Pathname: rules.svrf
+M1 spacing < 2
+e 1 1
+0 0 0 1000
+p 2 4
+CN child c 1 0 0 1 0 0
+0 0
+500 0
+500 500
+0 500
+"""
+    )
+    library, _ = results_library()
+    first, second = library.import_rdb(file_name)
+    assert first.rule == "M1.S.1"
+    assert first.cell == "top"
+    assert first.edges == [((0, 0), (0, 1))]
+    assert second.cell == "child"
+    assert second.edges == []
+    assert second.marker.area == pytest.approx(0.25)
+
+
+def test_import_rdb_invalid(tmp_path: FilePath):
+    file_name = tmp_path / "invalid.rdb"
+    file_name.write_text("top 1000\nrule\n1 1 0\np 1 4\n0 0\n")
+    with pytest.raises(ValueError, match="Invalid RDB file"):
+        Library().import_rdb(file_name)
@@ -100,6 +100,20 @@ def test_grid_set_x_reflection(grid: Grid, x_reflection: bool):
     assert grid.x_reflection == x_reflection
 
 
+def test_grid_absolute_flags_default_false():
+    grid = Grid()
+    assert not grid.absolute_magnification
+    assert not grid.absolute_angle
+
+
+def test_grid_set_absolute_flags():
+    grid = Grid()
+    grid.absolute_magnification = True
+    grid.absolute_angle = True
+    assert grid == Grid(absolute_magnification=True, absolute_angle=True)
+    assert grid != Grid()
+
+
 # Grid copy
 
 
@@ -209,7 +223,9 @@ def test_grid_str(grid: Grid):
         f"Grid at {grid.origin!r} with {grid.columns} columns and {grid.rows} rows, "
         f"spacing ({grid.spacing_x!r}, {grid.spacing_y!r}), magnification "
         f"{grid.magnification}, angle {grid.angle}, x_reflection "
-        f"{str(grid.x_reflection).lower()}"
+        f"{str(grid.x_reflection).lower()}, absolute_magnification "
+        f"{str(grid.absolute_magnification).lower()}, absolute_angle "
+        f"{str(grid.absolute_angle).lower()}"
     )
 
 
@@ -221,5 +237,7 @@ def test_grid_repr(grid: Grid):
     assert repr(grid) == (
         f"Grid({grid.origin!r}, {grid.columns}, {grid.rows}, {grid.spacing_x!r}, "
         f"{grid.spacing_y!r}, {grid.magnification}, {grid.angle}, "
-        f"{str(grid.x_reflection).lower()})"
+        f"{str(grid.x_reflection).lower()}, "
+        f"{str(grid.absolute_magnification).lower()}, "
+        f"{str(grid.absolute_angle).lower()})"
     )
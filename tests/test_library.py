@@ -1,8 +1,21 @@
+from datetime import datetime, timezone
+from pathlib import Path as FilePath
+
 import pytest
 from hypothesis import assume, given, settings
 from hypothesis import strategies as st
 
-from gdsr import Cell, Element, Library, Reference
+from gdsr import (
+    Cell,
+    Element,
+    GdsCellIterator,
+    Grid,
+    Library,
+    Path,
+    Polygon,
+    Reference,
+    Text,
+)
 
 from .conftest import (
     cell_strategy,
@@ -168,6 +181,451 @@ def test_library_read_write(library: Library, cell: Cell, element: Element):
     assert library == new_library
 
 
+def large_polygon_library() -> Library:
+    library = Library("library")
+    cell = Cell("cell")
+    cell.add(Polygon.ellipse((0, 0), 100, n_sides=20000))
+    library.add(cell)
+    return library
+
+
+def long_path_library() -> Library:
+    library = Library("library")
+    cell = Cell("cell")
+    cell.add(Path([(i, i % 2) for i in range(20000)], width=0.5))
+    library.add(cell)
+    return library
+
+
+def test_library_write_fractures_large_polygons():
+    library = large_polygon_library()
+    polygon = library.cells["cell"].polygons[0]
+    new_library = Library.from_gds(library.to_gds())
+    polygons = new_library.cells["cell"].polygons
+    assert len(polygons) > 1
+    assert all(len(p.points) <= 8191 for p in polygons)
+    assert sum(p.area for p in polygons) == pytest.approx(polygon.area, rel=1e-6)
+
+
+def test_library_write_large_polygon_error():
+    with pytest.raises(ValueError, match="maximum of 8191 points"):
+        large_polygon_library().to_gds(max_points_mode="error")
+
+
+def test_library_write_large_polygon_extended():
+    library = large_polygon_library()
+    new_library = Library.from_gds(library.to_gds(max_points_mode="extended"))
+    assert new_library == library
+
+
+def test_library_write_splits_long_paths():
+    library = long_path_library()
+    new_library = Library.from_gds(library.to_gds())
+    paths = new_library.cells["cell"].paths
+    assert len(paths) == 3
+    assert all(len(p.points) <= 8191 for p in paths)
+    for path, next_path in zip(paths, paths[1:]):
+        assert path.points[-1] == next_path.points[0]
+    assert paths[0].points[0] == (0, 0)
+    assert paths[-1].points[-1] == (19999, 1)
+
+
+def test_library_write_long_path_error():
+    with pytest.raises(ValueError, match="maximum of 8191 points"):
+        long_path_library().to_gds(max_points_mode="error")
+
+
+def test_library_write_long_path_extended():
+    library = long_path_library()
+    new_library = Library.from_gds(library.to_gds(max_points_mode="extended"))
+    assert new_library == library
+
+
+def test_library_write_invalid_max_points_mode():
+    with pytest.raises(ValueError, match="Invalid max points mode"):
+        Library("library").to_gds(max_points_mode="invalid")  # type: ignore
+
+
+
+def test_library_read_write_properties():
+    library = Library("library")
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)], properties={1: "net", 2: "VDD"}))
+    cell = Cell("cell")
+    cell.add(
+        Polygon([(0, 0), (1, 0), (1, 1)], properties={1: "odd"}),
+        Path([(0, 0), (1, 0)], width=0.5, properties={127: "path"}),
+        Text("label", properties={3: "pin"}),
+        Reference(child, properties={4: "X1"}),
+    )
+    library.add(cell, child)
+    new_library = Library.from_gds(library.to_gds())
+    new_cell = new_library.cells["cell"]
+    assert new_cell.polygons[0].properties == {1: "odd"}
+    assert new_cell.paths[0].properties == {127: "path"}
+    assert new_cell.texts[0].properties == {3: "pin"}
+    assert new_cell.references[0].properties == {4: "X1"}
+    assert new_library.cells["child"].polygons[0].properties == {1: "net", 2: "VDD"}
+
+
+def test_library_from_gds_filters_cells():
+    library = Library("library")
+    library.add(Cell("a"), Cell("b"), Cell("c"))
+    new_library = Library.from_gds(library.to_gds(), cells=["a", "c"])
+    assert set(new_library.cells) == {"a", "c"}
+
+
+def test_library_from_gds_references_follow_changes_to_read_cells():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    top = Cell("top")
+    top.add(Reference(child))
+    library = Library("library")
+    library.add(child, top)
+
+    new_library = Library.from_gds(library.to_gds())
+    new_top = new_library.cells["top"]
+    assert new_top.bounding_box() == ((0, 0), (1, 1))
+    new_library.cells["child"].polygons[0].move_by((10, 0))
+    assert new_top.bounding_box() == ((10, 0), (11, 1))
+
+
+def test_library_from_gds_filters_references_to_excluded_cells():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    excluded = Cell("excluded")
+    excluded.add(Polygon([(0, 0), (2, 0), (2, 2)]))
+    top = Cell("top")
+    top.add(
+        Reference(child),
+        Reference(excluded, grid=Grid(columns=2)),
+        Reference(child),
+    )
+    library = Library("library")
+    library.add(child, excluded, top)
+
+    new_library = Library.from_gds(library.to_gds(), cells=["top", "child"])
+    assert set(new_library.cells) == {"top", "child"}
+    references = new_library.cells["top"].references
+    assert [reference.instance.name for reference in references] == ["child", "child"]
+    for reference in references:
+        assert reference.instance is new_library.cells["child"]
+
+    round_tripped = Library.from_gds(new_library.to_gds())
+    assert set(round_tripped.cells) == {"top", "child"}
+    assert round_tripped.cells == new_library.cells
+
+
+def test_library_read_write_text_type():
+    cell = Cell("cell")
+    cell.add(Text("label", layer=1, text_type=4))
+    library = Library("library")
+    library.add(cell)
+    new_library = Library.from_gds(library.to_gds())
+    assert new_library.cells["cell"].texts[0].text_type == 4
+
+
+def test_library_from_gds_filters_text_type():
+    cell = Cell("cell")
+    cell.add(Text("keep", layer=1, text_type=2), Text("drop", layer=1))
+    library = Library("library")
+    library.add(cell)
+    new_library = Library.from_gds(library.to_gds(), layers=[(1, 2)])
+    assert [text.text for text in new_library.cells["cell"].texts] == ["keep"]
+
+
+def test_library_from_gds_filters_layers():
+    cell = Cell("cell")
+    cell.add(
+        Polygon([(0, 0), (1, 0), (1, 1)], layer=1),
+        Polygon([(0, 0), (1, 0), (1, 1)], layer=1, data_type=1),
+        Path([(0, 0), (1, 0)], layer=2, width=1),
+        Text("label", layer=1),
+    )
+    library = Library("library")
+    library.add(cell)
+    new_library = Library.from_gds(library.to_gds(), layers=[(1, 0)])
+    new_cell = new_library.cells["cell"]
+    assert new_cell.polygons == [Polygon([(0, 0), (1, 0), (1, 1)], layer=1)]
+    assert new_cell.paths == []
+    assert len(new_cell.texts) == 1
+
+
+# Library write hierarchy
+
+
+def written_cell_names(file_name: str) -> list[str]:
+    return [cell.name for cell in GdsCellIterator(file_name)]
+
+
+def test_library_write_emits_each_cell_once():
+    leaf = Cell("leaf")
+    leaf.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    middle = Cell("middle")
+    middle.add(Reference(leaf), Reference(leaf, grid=Grid(origin=(5, 0))))
+    top = Cell("top")
+    top.add(Reference(middle), Reference(leaf))
+    library = Library("library")
+    library.add(top, middle, leaf)
+    names = written_cell_names(library.to_gds())
+    assert sorted(names) == ["leaf", "middle", "top"]
+
+
+def test_library_write_emits_children_before_parents():
+    leaf = Cell("leaf")
+    middle = Cell("middle")
+    middle.add(Reference(leaf))
+    top = Cell("top")
+    top.add(Reference(middle))
+    library = Library("library")
+    library.add(top, middle, leaf)
+    assert written_cell_names(library.to_gds()) == ["leaf", "middle", "top"]
+
+
+def test_library_write_includes_unlisted_children():
+    leaf = Cell("leaf")
+    top = Cell("top")
+    top.add(Reference(Reference(leaf)))
+    library = Library("library")
+    library.add(top)
+    assert written_cell_names(library.to_gds()) == ["leaf", "top"]
+
+
+def test_library_write_equal_cells_with_same_name():
+    leaf = Cell("leaf")
+    leaf.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    top = Cell("top")
+    top.add(Reference(leaf.copy()))
+    library = Library("library")
+    library.add(top, leaf)
+    assert written_cell_names(library.to_gds()) == ["leaf", "top"]
+
+
+def test_library_write_different_cells_with_same_name_error():
+    leaf = Cell("leaf")
+    other_leaf = Cell("leaf")
+    other_leaf.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    top = Cell("top")
+    top.add(Reference(other_leaf))
+    library = Library("library")
+    library.add(top, leaf)
+    with pytest.raises(ValueError, match="Multiple different cells are named 'leaf'"):
+        library.to_gds()
+
+
+def test_library_write_reference_cycle_error():
+    a = Cell("a")
+    b = Cell("b")
+    b.add(Reference(a))
+    a.add(Reference(b))
+    library = Library("library")
+    library.add(a)
+    with pytest.raises(ValueError, match="Cell reference cycle detected"):
+        library.to_gds()
+
+
+def test_cell_write_emits_each_cell_once():
+    leaf = Cell("leaf")
+    top = Cell("top")
+    top.add(Reference(leaf), Reference(leaf))
+    assert written_cell_names(top.to_gds()) == ["leaf", "top"]
+
+
+def test_library_read_links_references_to_library_cells():
+    leaf = Cell("leaf")
+    top = Cell("top")
+    top.add(Reference(leaf))
+    library = Library("library")
+    library.add(top, leaf)
+    new_library = Library.from_gds(library.to_gds())
+    new_library.cells["leaf"].add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    instance = new_library.cells["top"].references[0].instance
+    assert instance == new_library.cells["leaf"]
+    assert len(instance.polygons) == 1
+
+
+# Library timestamps
+
+
+def timestamp_library() -> Library:
+    library = Library("library")
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    library.add(cell)
+    return library
+
+
+def test_library_write_fixed_timestamp_is_deterministic(tmp_path: FilePath):
+    timestamp = datetime(2024, 5, 6, 7, 8, 9)
+    first = timestamp_library().to_gds(tmp_path / "first.gds", timestamp=timestamp)
+    second = timestamp_library().to_gds(tmp_path / "second.gds", timestamp=timestamp)
+    assert FilePath(first).read_bytes() == FilePath(second).read_bytes()
+    new_library = Library.from_gds(first)
+    assert new_library.modification_date == timestamp
+    assert new_library.access_date == timestamp
+    assert new_library.cells["cell"].modification_date == timestamp
+
+
+def test_library_write_timezone_aware_timestamp():
+    timestamp = datetime(2024, 5, 6, 7, 8, 9, tzinfo=timezone.utc)
+    new_library = Library.from_gds(timestamp_library().to_gds(timestamp=timestamp))
+    assert new_library.modification_date == datetime(2024, 5, 6, 7, 8, 9)
+
+
+def test_library_write_epoch_timestamp():
+    new_library = Library.from_gds(timestamp_library().to_gds(timestamp="epoch"))
+    assert new_library.modification_date == datetime(1970, 1, 1)
+    assert new_library.cells["cell"].access_date == datetime(1970, 1, 1)
+
+
+def test_library_write_preserve_timestamp():
+    library = timestamp_library()
+    library.modification_date = datetime(2001, 2, 3, 4, 5, 6)
+    library.access_date = datetime(2002, 3, 4, 5, 6, 7)
+    library.cells["cell"].modification_date = datetime(2003, 4, 5, 6, 7, 8)
+    new_library = Library.from_gds(library.to_gds(timestamp="preserve"))
+    assert new_library.modification_date == datetime(2001, 2, 3, 4, 5, 6)
+    assert new_library.access_date == datetime(2002, 3, 4, 5, 6, 7)
+    new_cell = new_library.cells["cell"]
+    assert new_cell.modification_date == datetime(2003, 4, 5, 6, 7, 8)
+    assert new_cell.access_date == datetime(2003, 4, 5, 6, 7, 8)
+    rewritten = Library.from_gds(new_library.to_gds(timestamp="preserve"))
+    assert rewritten.cells["cell"].modification_date == new_cell.modification_date
+
+
+def test_library_write_preserve_timestamp_without_dates_uses_one_write_time():
+    library = Library("library")
+    for index in range(50):
+        library.add(Cell(f"cell_{index}"))
+    new_library = Library.from_gds(library.to_gds(timestamp="preserve"))
+    dates = {new_library.modification_date, new_library.access_date}
+    for cell in new_library.cells.values():
+        dates |= {cell.modification_date, cell.access_date}
+    assert len(dates) == 1
+
+
+def test_library_dates_default_to_none():
+    library = timestamp_library()
+    assert library.modification_date is None
+    assert library.access_date is None
+    assert library.cells["cell"].modification_date is None
+
+
+def test_library_write_invalid_timestamp():
+    with pytest.raises(ValueError, match="Invalid timestamp"):
+        timestamp_library().to_gds(timestamp="yesterday")  # type: ignore
+    with pytest.raises(ValueError, match="Invalid timestamp"):
+        timestamp_library().to_gds(timestamp=5)  # type: ignore
+
+
+def test_cell_write_fixed_timestamp():
+    timestamp = datetime(2020, 1, 2, 3, 4, 5)
+    cell = timestamp_library().cells["cell"]
+    new_library = Library.from_gds(cell.to_gds(timestamp=timestamp))
+    assert new_library.modification_date == timestamp
+    assert new_library.cells["cell"].modification_date == timestamp
+
+
+# Library units
+
+
+def test_library_default_units():
+    library = Library("library")
+    assert library.units == 1e-6
+    assert library.precision == 1e-10
+
+
+@pytest.mark.parametrize("units, precision", [(0, 1e-9), (1e-6, -1), (1e-9, 1e-6)])
+def test_library_invalid_units(units: float, precision: float):
+    with pytest.raises(ValueError):
+        Library("library", units=units, precision=precision)
+
+
+def test_library_read_preserves_units():
+    library = Library("library", units=1e-9, precision=1e-12)
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (1.5, 0), (1.5, 1)]))
+    library.add(cell)
+    new_library = Library.from_gds(library.to_gds())
+    assert new_library.units == 1e-9
+    assert new_library.precision == 1e-12
+    assert new_library == library
+    rewritten = Library.from_gds(new_library.to_gds())
+    assert rewritten.units == 1e-9
+    assert rewritten.precision == 1e-12
+
+
+def test_library_write_units_override():
+    library = Library("library")
+    new_library = Library.from_gds(library.to_gds(units=1e-9, precision=1e-12))
+    assert new_library.units == 1e-9
+    assert new_library.precision == 1e-12
+
+
+def test_library_set_units_rescales_geometry():
+    child = Cell("child")
+    child.add(
+        Polygon([(0, 0), (1, 0), (1, 1)]),
+        Path([(0, 0), (2, 0)], width=0.5),
+        Text("label", (1, 1)),
+    )
+    cell = Cell("cell")
+    cell.add(
+        Reference(child, Grid((1, 2), columns=2, spacing_x=(3, 0), magnification=2)),
+        Reference(child, Grid((5, 5))),
+    )
+    library = Library("library")
+    library.add(cell, child)
+    library.set_units(1e-9)
+    assert library.units == 1e-9
+    assert library.precision == 1e-10
+    assert child.polygons[0].points == [(0, 0), (1000, 0), (1000, 1000), (0, 0)]
+    assert child.paths[0].points == [(0, 0), (2000, 0)]
+    assert child.paths[0].width == 500
+    assert child.texts[0].origin == (1000, 1000)
+    grid = cell.references[0].grid
+    assert grid.origin == (1000, 2000)
+    assert grid.spacing_x == (3000, 0)
+    assert grid.magnification == 2
+    assert cell.references[1].grid.origin == (5000, 5000)
+
+
+def test_library_set_units_rescales_unlisted_cells_once():
+    child = Cell("child")
+    polygon = Polygon([(0, 0), (1, 0), (1, 1)])
+    child.add(polygon)
+    first = Cell("first")
+    first.add(Reference(child), Reference(polygon))
+    second = Cell("second")
+    second.add(Reference(child))
+    library = Library("library")
+    library.add(first, second)
+    library.set_units(1e-3, precision=1e-6)
+    assert library.precision == 1e-6
+    assert polygon.points == [(0, 0), (0.001, 0), (0.001, 0.001), (0, 0)]
+
+
+def test_library_set_units_round_trip():
+    library = Library("library")
+    cell = Cell("cell")
+    cell.add(Polygon([(0, 0), (1.25, 0), (1.25, 1)]))
+    library.add(cell)
+    library.set_units(1e-9, precision=1e-12)
+    new_library = Library.from_gds(library.to_gds())
+    assert new_library.cells["cell"].polygons[0].points == [
+        (0, 0),
+        (1250, 0),
+        (1250, 1000),
+        (0, 0),
+    ]
+
+
+def test_library_set_units_invalid():
+    library = Library("library")
+    with pytest.raises(ValueError):
+        library.set_units(1e-12)
+
+
 # Library eq
 
 
@@ -0,0 +1,111 @@
+import pytest
+
+from gdsr import Cell, Library, Node, Point, Reference
+
+# Node init
+
+
+def test_node_init():
+    node = Node([(0, 0), (1, 1)])
+    assert node.points == [(0, 0), (1, 1)]
+    assert node.layer == 0
+    assert node.node_type == 0
+    assert node.properties == {}
+
+
+def test_node_init_with_layer_and_node_type():
+    node = Node([(0, 0)], layer=5, node_type=10, properties={1: "net"})
+    assert node.layer == 5
+    assert node.node_type == 10
+    assert node.properties == {1: "net"}
+
+
+def test_node_too_many_points():
+    with pytest.raises(ValueError, match="Node must have between 1 and 50 points"):
+        Node([(i, i) for i in range(51)])
+
+
+def test_node_empty_points_raises_error():
+    with pytest.raises(TypeError, match="Points cannot be empty"):
+        Node([])
+
+
+def test_node_invalid_layer():
+    with pytest.raises(ValueError, match="Layer must be in the range 0-255"):
+        Node([(0, 0)], layer=-1)
+
+
+# Node setters
+
+
+def test_node_setters():
+    node = Node([(0, 0)])
+    assert node.set_layer(2).set_node_type(3) is node
+    assert node.layer == 2
+    assert node.node_type == 3
+    node.points = [(1, 1), (2, 3)]
+    assert node.bounding_box == (Point(1, 1), Point(2, 3))
+
+
+# Node transformations
+
+
+def test_node_move_to():
+    node = Node([(1, 1), (2, 2)]).move_to((0, 0))
+    assert node.points == [(0, 0), (1, 1)]
+
+
+def test_node_rotate():
+    node = Node([(1, 0)]).rotate(90)
+    assert node.points[0].is_close((0, 1))
+
+
+# Node is_on
+
+
+def test_node_is_on():
+    node = Node([(0, 0)], layer=1, node_type=2)
+    assert node.is_on((1, 2))
+    assert not node.is_on((1, 0))
+    assert node.is_on()
+
+
+# Node read write
+
+
+def test_node_read_write():
+    cell = Cell("cell")
+    cell.add(Node([(0, 0), (1.5, 2)], layer=4, node_type=6, properties={2: "net"}))
+    library = Library("library")
+    library.add(cell)
+    new_cell = Library.from_gds(library.to_gds()).cells["cell"]
+    assert new_cell.nodes == cell.nodes
+
+
+def test_node_read_filters_layers():
+    cell = Cell("cell")
+    cell.add(Node([(0, 0)], layer=1, node_type=2), Node([(0, 0)], layer=1))
+    library = Library("library")
+    library.add(cell)
+    new_cell = Library.from_gds(library.to_gds(), layers=[(1, 2)]).cells["cell"]
+    assert new_cell.nodes == [Node([(0, 0)], layer=1, node_type=2)]
+
+
+def test_node_in_reference():
+    node = Node([(0, 0)])
+    cell = Cell("cell")
+    cell.add(Reference(node).move_by((1, 1)))
+    assert cell.get_elements() == [Node([(1, 1)])]
+
+
+# Node cell membership
+
+
+def test_cell_add_remove_node():
+    node = Node([(0, 0)])
+    cell = Cell("cell")
+    cell.add(node)
+    assert node in cell
+    assert not cell.is_empty()
+    cell.remove(node)
+    assert cell.is_empty()
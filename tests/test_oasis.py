@@ -0,0 +1,381 @@
+import zlib
+from pathlib import Path as FilePath
+
+import pytest
+
+from gdsr import (
+    Box,
+    Cell,
+    Grid,
+    Library,
+    Node,
+    Path,
+    PathType,
+    Polygon,
+    Reference,
+    Text,
+)
+
+
+def read_write(library: Library) -> Library:
+    return Library.from_oasis(library.to_oasis())
+
+
+def single_cell_library(*elements) -> Library:
+    library = Library("library")
+    cell = Cell("cell")
+    cell.add(*elements)
+    library.add(cell)
+    return library
+
+
+def encode_unsigned(value: int) -> bytes:
+    result = bytearray()
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if value == 0:
+            result.append(byte)
+            return bytes(result)
+        result.append(byte | 0x80)
+
+
+def encode_string(value: str) -> bytes:
+    return encode_unsigned(len(value)) + value.encode()
+
+
+def oasis_file(body: bytes) -> bytes:
+    start = encode_unsigned(1) + encode_string("1.0") + bytes([0, 100, 0]) + bytes(12)
+    end = encode_unsigned(2) + encode_unsigned(252) + bytes(252) + bytes([0])
+    return b"%SEMI-OASIS\r\n" + start + body + end
+
+
+# OASIS read write
+
+
+def test_oasis_read_write_rectangles():
+    library = single_cell_library(
+        Polygon([(0, 0), (2, 0), (2, 1), (0, 1)], layer=1, data_type=2),
+        Polygon([(1, 1), (4, 1), (4, 4), (1, 4)], layer=1, data_type=2),
+    )
+    new_library = read_write(library)
+    assert new_library.cells == library.cells
+
+
+def test_oasis_read_write_polygons():
+    library = single_cell_library(
+        Polygon([(0, 0), (1, 0), (1, 1)]),
+        Polygon([(0, 0), (3, 1), (2, 5), (-1, 2)], layer=3),
+        Polygon([(0, 0), (0, 2), (2, 2), (2, 0)], layer=4, data_type=5),
+        Polygon([(0.5, 0.25), (1.125, -3.5), (7.75, 2)], layer=4),
+    )
+    new_library = read_write(library)
+    assert new_library.cells == library.cells
+
+
+def test_oasis_read_write_paths():
+    library = single_cell_library(
+        Path([(0, 0), (10, 0), (10, 5)], width=1, path_type=PathType.Square),
+        Path([(0, 0), (3, 4), (-2, 7)], layer=2, width=0.5, path_type=PathType.Overlap),
+        Path(
+            [(0, 0), (5, 0)],
+            width=1,
+            path_type=PathType.Custom,
+            begin_extension=0.25,
+            end_extension=-1,
+        ),
+    )
+    new_library = read_write(library)
+    assert new_library.cells == library.cells
+
+
+def test_oasis_read_write_texts():
+    library = single_cell_library(
+        Text("first", (1, 2), layer=3),
+        Text("second", (-4, 5.5), layer=3),
+        Text("third", (0, 1), layer=3, text_type=7),
+    )
+    new_library = read_write(library)
+    assert new_library.cells == library.cells
+
+
+def test_oasis_write_boxes_as_polygons():
+    library = single_cell_library(
+        Box([(0, 0), (2, 0), (2, 1), (0, 1)], layer=1, box_type=3),
+    )
+    new_cell = read_write(library).cells["cell"]
+    assert new_cell.polygons == [
+        Polygon([(0, 0), (2, 0), (2, 1), (0, 1)], layer=1, data_type=3)
+    ]
+    assert new_cell.boxes == []
+
+
+def test_oasis_read_write_nodes():
+    library = single_cell_library(
+        Node([(0, 0)], layer=1),
+        Node([(1, 2), (3, -4), (0.5, 6)], layer=2, node_type=5, properties={1: "net"}),
+    )
+    new_cell = read_write(library).cells["cell"]
+    assert new_cell.nodes == library.cells["cell"].nodes
+    assert new_cell.nodes[1].properties == {1: "net"}
+
+
+def test_oasis_write_round_path_raises():
+    library = single_cell_library(
+        Path([(0, 0), (1, 0)], width=1, path_type=PathType.Round)
+    )
+    with pytest.raises(ValueError, match="OASIS has no round path ends"):
+        library.to_oasis()
+
+
+def test_oasis_write_odd_path_width_raises():
+    library = single_cell_library(Path([(0, 0), (1, 0)], width=0.0003))
+    with pytest.raises(ValueError, match="not an even number of database units"):
+        library.to_oasis()
+
+
+@pytest.mark.parametrize(
+    "offsets",
+    [
+        [(0, 0), (3, 0), (6, 0)],
+        [(0, 0), (0, 3), (0, 6)],
+        [(0, 0), (2, 1), (4, 2)],
+        [(x, y) for x in range(0, 9, 3) for y in range(0, 8, 4)],
+        [(x + y, y) for x in range(0, 9, 3) for y in range(0, 8, 4)],
+        [(0, 0), (1, 5), (-3, 2), (7, 7)],
+    ],
+)
+def test_oasis_write_repeated_geometry(offsets: list[tuple[int, int]]):
+    cell = Cell("cell")
+    for x, y in offsets:
+        cell.add(
+            Polygon(
+                [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)], properties={1: "a"}
+            ),
+            Path([(x, y), (x + 2, y + 1)], width=0.5, path_type=PathType.Square),
+            Text("label", (x, y)),
+        )
+    library = Library("library")
+    library.add(cell)
+    file_name = library.to_oasis()
+    new_library = Library.from_oasis(file_name)
+    assert new_library.cells == library.cells
+
+
+def test_oasis_write_compresses_cells(tmp_path: FilePath):
+    cell = Cell("cell")
+    cell.add(*[Polygon([(0, 0), (x + 1, 0), (0, 1)]) for x in range(100)])
+    library = Library("library")
+    library.add(cell)
+    file_name = tmp_path / "library.oas"
+    library.to_oasis(file_name)
+    data = file_name.read_bytes()
+    cell_reference = encode_unsigned(13) + encode_unsigned(0)
+    assert cell_reference + encode_unsigned(34) + encode_unsigned(0) in data
+    assert Library.from_oasis(file_name).cells == library.cells
+
+
+@pytest.mark.parametrize(
+    "grid",
+    [
+        Grid(origin=(5, 5)),
+        Grid(origin=(1, 2), columns=3, rows=4, spacing_x=(10, 0), spacing_y=(0, 20)),
+        Grid(columns=5, spacing_x=(3, 0)),
+        Grid(rows=5, spacing_y=(0, 3)),
+        Grid(columns=2, rows=3, spacing_x=(4, 1), spacing_y=(-1, 6)),
+        Grid(columns=3, rows=2, spacing_x=(5, 0), spacing_y=(0, 5), angle=90),
+        Grid(columns=2, rows=2, spacing_x=(5, 0), spacing_y=(0, 5), angle=30),
+        Grid(columns=2, spacing_x=(5, 0), magnification=2, x_reflection=True),
+    ],
+)
+def test_oasis_read_write_references(grid: Grid):
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    cell = Cell("cell")
+    cell.add(Reference(child, grid))
+    library = Library("library")
+    library.add(cell, child)
+    new_library = read_write(library)
+    assert new_library.cells == library.cells
+    reference = new_library.cells["cell"].references[0]
+    assert reference.instance is new_library.cells["child"]
+
+
+def test_oasis_write_includes_referenced_cells():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)]))
+    cell = Cell("cell")
+    cell.add(Reference(child))
+    library = Library("library")
+    library.add(cell)
+    new_library = read_write(library)
+    assert set(new_library.cells) == {"cell", "child"}
+
+
+def test_oasis_write_flattens_element_references():
+    polygon = Polygon([(0, 0), (1, 0), (1, 1)])
+    library = single_cell_library(
+        Reference(polygon, Grid(columns=2, rows=2, spacing_x=(5, 0), spacing_y=(0, 5)))
+    )
+    new_library = read_write(library)
+    polygons = new_library.cells["cell"].polygons
+    assert len(polygons) == 4
+    assert new_library.cells["cell"].references == []
+
+
+def test_oasis_read_write_properties():
+    child = Cell("child")
+    child.add(Polygon([(0, 0), (1, 0), (1, 1)], properties={1: "net", 2: "VDD"}))
+    cell = Cell("cell")
+    cell.add(
+        Polygon([(0, 0), (2, 0), (2, 1), (0, 1)], properties={1: "odd"}),
+        Path([(0, 0), (1, 0)], width=0.5, properties={127: "path"}),
+        Text("label", properties={3: "pin"}),
+        Reference(child, properties={4: "X1"}),
+    )
+    library = Library("library")
+    library.add(cell, child)
+    new_library = read_write(library)
+    new_cell = new_library.cells["cell"]
+    assert new_cell.polygons[0].properties == {1: "odd"}
+    assert new_cell.paths[0].properties == {127: "path"}
+    assert new_cell.texts[0].properties == {3: "pin"}
+    assert new_cell.references[0].properties == {4: "X1"}
+    assert new_library.cells["child"].polygons[0].properties == {1: "net", 2: "VDD"}
+
+
+def test_oasis_write_returns_file_name(tmp_path: FilePath):
+    file_name = tmp_path / "library.oas"
+    assert Library("library").to_oasis(file_name) == str(file_name)
+    assert file_name.read_bytes().startswith(b"%SEMI-OASIS\r\n")
+
+
+# OASIS read
+
+
+def test_oasis_read_cblock(tmp_path: FilePath):
+    records = (
+        encode_unsigned(14)
+        + encode_string("cell")
+        + bytes([20, 0x80 | 0x40 | 0x10 | 0x08 | 0x02 | 0x01, 1, 0, 100, 0, 0])
+    )
+    compressor = zlib.compressobj(wbits=-15)
+    compressed = compressor.compress(records) + compressor.flush()
+    cblock = (
+        encode_unsigned(34)
+        + encode_unsigned(0)
+        + encode_unsigned(len(records))
+        + encode_unsigned(len(compressed))
+        + compressed
+    )
+    file_name = tmp_path / "cblock.oas"
+    file_name.write_bytes(oasis_file(cblock))
+    library = Library.from_oasis(file_name)
+    assert library.cells["cell"].polygons == [
+        Polygon([(0, 0), (1, 0), (1, 1), (0, 1)], layer=1)
+    ]
+
+
+def test_oasis_read_cblock_invalid_data(tmp_path: FilePath):
+    compressed = bytes([0xFF, 0xFF, 0xFF])
+    cblock = (
+        encode_unsigned(34)
+        + encode_unsigned(0)
+        + encode_unsigned(10)
+        + encode_unsigned(len(compressed))
+        + compressed
+    )
+    file_name = tmp_path / "cblock.oas"
+    file_name.write_bytes(oasis_file(cblock))
+    with pytest.raises(ValueError, match="invalid CBLOCK data"):
+        Library.from_oasis(file_name)
+
+
+def test_oasis_read_repeated_geometry(tmp_path: FilePath):
+    info_byte = 0x80 | 0x40 | 0x10 | 0x08 | 0x04 | 0x02 | 0x01
+    rectangle = bytes([20, info_byte, 1, 0, 100, 0, 0])
+    repetition = encode_unsigned(2) + encode_unsigned(1) + encode_unsigned(200)
+    body = encode_unsigned(14) + encode_string("cell") + rectangle + repetition
+    file_name = tmp_path / "repetition.oas"
+    file_name.write_bytes(oasis_file(body))
+    library = Library.from_oasis(file_name)
+    polygons = library.cells["cell"].polygons
+    assert [polygon.bounding_box[0] for polygon in polygons] == [(0, 0), (2, 0), (4, 0)]
+
+
+def test_oasis_read_invalid_file(tmp_path: FilePath):
+    file_name = tmp_path / "invalid.oas"
+    file_name.write_bytes(b"not an oasis file")
+    with pytest.raises(ValueError, match="Invalid OASIS file"):
+        Library.from_oasis(file_name)
+
+
+@pytest.mark.parametrize(
+    "trapezoid, points",
+    [
+        (
+            bytes([23, 0x40 | 0x20 | 0x02 | 0x01, 1, 0, 10, 10, 2, 2]),
+            [(0.01, 0.1), (0.1, 0.1), (0.09, 0), (0, 0)],
+        ),
+        (
+            bytes([23, 0x80 | 0x40 | 0x20 | 0x02 | 0x01, 1, 0, 10, 20, 3, 4]),
+            [(0, 0), (0, 0.2), (0.1, 0.18), (0.1, 0.01)],
+        ),
+        (
+            bytes([24, 0x40 | 0x20 | 0x02 | 0x01, 1, 0, 10, 10, 10]),
+            [(0.05, 0.1), (0.1, 0.1), (0.1, 0), (0, 0)],
+        ),
+        (
+            bytes([25, 0x40 | 0x20 | 0x02 | 0x01, 1, 0, 10, 10, 20]),
+            [(0, 0.1), (0.1, 0.1), (0, 0)],
+        ),
+    ],
+)
+def test_oasis_read_trapezoid(
+    tmp_path: FilePath, trapezoid: bytes, points: list[tuple[float, float]]
+):
+    body = encode_unsigned(14) + encode_string("cell") + trapezoid
+    file_name = tmp_path / "trapezoid.oas"
+    file_name.write_bytes(oasis_file(body))
+    library = Library.from_oasis(file_name)
+    assert library.cells["cell"].polygons == [Polygon(points, layer=1)]
+
+
+@pytest.mark.parametrize(
+    "ctrapezoid, points",
+    [
+        (
+            bytes([26, 0x80 | 0x40 | 0x20 | 0x02 | 0x01, 1, 0, 0, 10, 4]),
+            [(0, 0), (0, 0.04), (0.06, 0.04), (0.1, 0)],
+        ),
+        (
+            bytes([26, 0x80 | 0x40 | 0x02 | 0x01, 1, 0, 16, 10]),
+            [(0, 0), (0, 0.1), (0.1, 0)],
+        ),
+        (
+            bytes([26, 0x80 | 0x20 | 0x02 | 0x01, 1, 0, 20, 5]),
+            [(0, 0), (0.05, 0.05), (0.1, 0)],
+        ),
+        (
+            bytes([26, 0x80 | 0x40 | 0x20 | 0x02 | 0x01, 1, 0, 24, 10, 4]),
+            [(0, 0), (0, 0.04), (0.1, 0.04), (0.1, 0)],
+        ),
+    ],
+)
+def test_oasis_read_ctrapezoid(
+    tmp_path: FilePath, ctrapezoid: bytes, points: list[tuple[float, float]]
+):
+    body = encode_unsigned(14) + encode_string("cell") + ctrapezoid
+    file_name = tmp_path / "ctrapezoid.oas"
+    file_name.write_bytes(oasis_file(body))
+    library = Library.from_oasis(file_name)
+    assert library.cells["cell"].polygons == [Polygon(points, layer=1)]
+
+
+def test_oasis_read_ctrapezoid_invalid_type(tmp_path: FilePath):
+    ctrapezoid = bytes([26, 0x80 | 0x40 | 0x20 | 0x02 | 0x01, 1, 0, 26, 10, 4])
+    body = encode_unsigned(14) + encode_string("cell") + ctrapezoid
+    file_name = tmp_path / "ctrapezoid.oas"
+    file_name.write_bytes(oasis_file(body))
+    with pytest.raises(ValueError, match="unknown ctrapezoid type 26"):
+        Library.from_oasis(file_name)
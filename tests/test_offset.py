@@ -0,0 +1,99 @@
+import math
+
+import pytest
+
+from gdsr import Polygon, boolean, offset
+
+
+def square(x: float, y: float, size: float) -> Polygon:
+    return Polygon([(x, y), (x + size, y), (x + size, y + size), (x, y + size)])
+
+
+def test_offset_grow_miter():
+    result = offset([square(0, 0, 10)], 1)
+    assert len(result) == 1
+    assert result[0].bounding_box == ((-1, -1), (11, 11))
+    assert result[0].area == pytest.approx(144)
+    assert len(result[0].points) == 5
+
+
+def test_offset_grow_bevel():
+    result = offset([square(0, 0, 10)], 1, join="bevel")
+    assert len(result) == 1
+    assert result[0].area == pytest.approx(144 - 4 * 0.5)
+
+
+def test_offset_grow_round():
+    result = offset([square(0, 0, 10)], 1, join="round")
+    assert len(result) == 1
+    assert result[0].area == pytest.approx(140 + math.pi, abs=0.1)
+    assert result[0].bounding_box == ((-1, -1), (11, 11))
+
+
+def test_offset_shrink():
+    result = offset([square(0, 0, 10)], -1)
+    assert len(result) == 1
+    assert result[0].bounding_box == ((1, 1), (9, 9))
+    assert result[0].area == pytest.approx(64)
+
+
+def test_offset_shrink_to_nothing():
+    assert offset([square(0, 0, 10)], -6) == []
+
+
+def test_offset_zero_distance_merges():
+    result = offset([square(0, 0, 2), square(1, 0, 2)], 0)
+    assert len(result) == 1
+    assert result[0].area == pytest.approx(6)
+
+
+def test_offset_merges_overlapping_results():
+    result = offset([square(0, 0, 4), square(5, 0, 4)], 1)
+    assert len(result) == 1
+    assert result[0].bounding_box == ((-1, -1), (10, 5))
+
+
+def test_offset_polygon_with_hole():
+    ring = boolean([square(0, 0, 10)], [square(3, 3, 4)], "difference")
+    grown = offset(ring, 1)
+    assert len(grown) == 1
+    assert grown[0].area == pytest.approx(144 - 4)
+    shrunk = offset(ring, -0.5)
+    assert len(shrunk) == 1
+    assert shrunk[0].area == pytest.approx(81 - 25)
+
+
+def test_offset_miter_limit():
+    triangle = Polygon([(0, 0), (10, 0), (5, 1)])
+    limited = offset([triangle], 1, miter_limit=1.5)[0]
+    unlimited = offset([triangle], 1, miter_limit=100)[0]
+    assert limited.bounding_box[1].x < unlimited.bounding_box[1].x
+
+
+def test_offset_output_is_on_the_grid():
+    result = offset([square(0, 0, 0.2), square(5000, 5000, 1)], 0.1)
+    small = [polygon for polygon in result if polygon.area < 1]
+    assert len(small) == 1
+    assert small[0].bounding_box == ((-0.1, -0.1), (0.3, 0.3))
+    assert small[0].area == 0.16
+
+
+def test_offset_layer_and_data_type():
+    result = offset([square(0, 0, 1)], 1, layer=2, data_type=3)
+    assert result[0].layer == 2
+    assert result[0].data_type == 3
+
+
+def test_offset_invalid_join():
+    with pytest.raises(ValueError, match="Invalid join"):
+        offset([square(0, 0, 1)], 1, join="invalid")  # type: ignore
+
+
+def test_offset_invalid_miter_limit():
+    with pytest.raises(ValueError, match="Miter limit must be greater"):
+        offset([square(0, 0, 1)], 1, miter_limit=0.5)
+
+
+def test_offset_snaps_to_the_given_grid():
+    result = offset([square(0, 0, 1)], 0.3, grid=0.5)
+    assert result[0].bounding_box == ((-0.5, -0.5), (1.5, 1.5))
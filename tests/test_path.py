@@ -1,7 +1,7 @@
 import pytest
 from hypothesis import given
 
-from gdsr import InputPointsLike, Path, PathType, Point
+from gdsr import Cell, InputPointsLike, Library, Path, PathType, Point, Polygon
 from tests.conftest import path_strategy
 
 
@@ -205,6 +205,117 @@ def test_bounding_box_with_overlap_type_complex_path():
     assert bounding_box[1].is_close((4.25, 3.25))
 
 
+def test_bounding_box_with_custom_type():
+    path = Path(
+        [(0, 0), (1, 0)],
+        width=0.5,
+        path_type=PathType.Custom,
+        begin_extension=0.5,
+        end_extension=1,
+    )
+    bounding_box = path.bounding_box
+    assert bounding_box[0].is_close((-0.5, -0.25))
+    assert bounding_box[1].is_close((2, 0.25))
+
+
+def test_bounding_box_with_square_type_is_flush():
+    path = Path([(0, 0), (1, 0)], width=0.5, path_type=PathType.Square)
+    bounding_box = path.bounding_box
+    assert bounding_box[0].is_close((0, -0.25))
+    assert bounding_box[1].is_close((1, 0.25))
+
+
+# Path to_polygon
+
+
+def test_to_polygon_square_ends():
+    path = Path([(0, 0), (2, 0)], layer=3, data_type=4, width=1)
+    polygon = path.to_polygon()
+    assert isinstance(polygon, Polygon)
+    assert (polygon.layer, polygon.data_type) == (3, 4)
+    assert polygon.area == pytest.approx(2)
+    assert polygon.bounding_box == ((0, -0.5), (2, 0.5))
+
+
+def test_to_polygon_overlap_ends():
+    path = Path([(0, 0), (2, 0)], width=1, path_type=PathType.Overlap)
+    polygon = path.to_polygon()
+    assert polygon.area == pytest.approx(3)
+    assert polygon.bounding_box == ((-0.5, -0.5), (2.5, 0.5))
+
+
+def test_to_polygon_round_ends():
+    path = Path([(0, 0), (2, 0)], width=1, path_type=PathType.Round)
+    polygon = path.to_polygon()
+    assert polygon.area == pytest.approx(2 + 3.14159 * 0.25, rel=1e-2)
+    lower_left, upper_right = polygon.bounding_box
+    assert lower_left.x == pytest.approx(-0.5, abs=1e-2)
+    assert upper_right.x == pytest.approx(2.5, abs=1e-2)
+
+
+def test_to_polygon_custom_ends():
+    path = Path(
+        [(0, 0), (2, 0)],
+        width=1,
+        path_type=PathType.Custom,
+        begin_extension=1,
+        end_extension=-0.5,
+    )
+    polygon = path.to_polygon()
+    assert polygon.area == pytest.approx(2.5)
+    assert polygon.bounding_box == ((-1, -0.5), (1.5, 0.5))
+
+
+def test_to_polygon_right_angle_join():
+    path = Path([(0, 0), (2, 0), (2, 2)], width=1)
+    polygon = path.to_polygon()
+    assert polygon.area == pytest.approx(4)
+    assert polygon.bounding_box == ((0, -0.5), (2.5, 2))
+
+
+def test_to_polygon_acute_join_is_bevelled():
+    path = Path([(0, 0), (10, 0), (0, 1)], width=1)
+    polygon = path.to_polygon()
+    assert polygon.bounding_box[1].x < 11
+
+
+def test_to_polygon_keeps_properties():
+    path = Path([(0, 0), (2, 0)], width=1, properties={1: "net"})
+    assert path.to_polygon().properties == {1: "net"}
+
+
+def test_to_polygon_without_width_raises():
+    with pytest.raises(ValueError, match="Path has no area"):
+        Path([(0, 0), (2, 0)]).to_polygon()
+
+
+def test_to_polygons_without_width_is_empty():
+    assert Path([(0, 0), (2, 0)]).to_polygons() == []
+
+
+def test_to_polygons_self_overlapping_path():
+    path = Path([(0, 0), (4, 0), (4, 4), (2, 4), (2, -2)], width=1)
+    assert len(path.to_polygons()) == 1
+
+
+def test_to_polygon_is_on_the_grid():
+    path = Path([(1000.1, 0), (1000.3, 0.1)], width=0.1, path_type=PathType.Round)
+    for point in path.to_polygon().points:
+        assert point.x == round(point.x, 4)
+        assert point.y == round(point.y, 4)
+
+
+def test_to_polygon_snaps_to_the_given_grid():
+    path = Path([(0, 0), (2.2, 0)], width=1)
+    polygon = path.to_polygon(grid=0.5)
+    assert polygon.bounding_box == ((0, -0.5), (2, 0.5))
+
+
+def test_to_polygon_grid_invalid():
+    with pytest.raises(ValueError, match="Grid must be positive"):
+        Path([(0, 0), (2, 0)], width=1).to_polygon(grid=0)
+
+
 # Path move_to
 
 
@@ -362,3 +473,99 @@ def test_path_not_eq_to_different_points():
     path1 = Path([(0, 0), (1, 1)])
     path2 = Path([(0, 0), (1, 2)])
     assert path1 != path2
+
+
+# Path properties
+
+
+def test_path_properties_default_empty():
+    assert Path([(0, 0), (1, 0)]).properties == {}
+
+
+def test_path_init_with_properties():
+    path = Path([(0, 0), (1, 0)], properties={1: "net"})
+    assert path.properties == {1: "net"}
+
+
+def test_path_set_properties():
+    path = Path([(0, 0), (1, 0)])
+    assert path.set_properties({2: "VSS"}) is path
+    assert path.properties == {2: "VSS"}
+
+
+def test_path_invalid_property_attribute():
+    with pytest.raises(ValueError, match="Property attribute must be in the range"):
+        Path([(0, 0), (1, 0)], properties={40000: "net"})
+
+
+def test_path_properties_in_eq():
+    assert Path([(0, 0), (1, 0)], properties={1: "a"}) != Path([(0, 0), (1, 0)])
+
+
+# Path custom extensions
+
+
+def test_path_extensions_default_zero():
+    path = Path([(0, 0), (1, 0)])
+    assert path.begin_extension == 0
+    assert path.end_extension == 0
+
+
+def test_path_set_extensions():
+    path = Path([(0, 0), (1, 0)], path_type=PathType.Custom)
+    assert path.set_begin_extension(1.5) is path
+    assert path.set_end_extension(-0.5) is path
+    assert path.begin_extension == 1.5
+    assert path.end_extension == -0.5
+    assert path != Path([(0, 0), (1, 0)], path_type=PathType.Custom)
+
+
+def test_path_custom_type_value():
+    assert PathType(4) == PathType.Custom
+    assert PathType.Custom.value == 4
+    assert PathType.Custom in PathType.values()
+
+
+def test_path_scale_scales_width_and_extensions():
+    path = Path(
+        [(0, 0), (1, 0)],
+        path_type=PathType.Custom,
+        width=1,
+        begin_extension=0.5,
+        end_extension=2,
+    )
+    path.scale(-2)
+    assert path.width == 2
+    assert path.begin_extension == 1
+    assert path.end_extension == 4
+
+
+def test_path_custom_extensions_read_write():
+    cell = Cell("cell")
+    path = Path(
+        [(0, 0), (10, 0)],
+        path_type=PathType.Custom,
+        width=1,
+        begin_extension=0.25,
+        end_extension=-0.5,
+    )
+    cell.add(path)
+    new_cell = Library.from_gds(cell.to_gds()).cells["cell"]
+    assert new_cell.paths[0] == path
+
+
+def test_path_custom_extensions_fracture():
+    cell = Cell("cell")
+    cell.add(
+        Path(
+            [(i, i % 2) for i in range(10000)],
+            path_type=PathType.Custom,
+            width=0.5,
+            begin_extension=1,
+            end_extension=2,
+        )
+    )
+    paths = Library.from_gds(cell.to_gds()).cells["cell"].paths
+    assert len(paths) == 2
+    assert (paths[0].begin_extension, paths[0].end_extension) == (1, 0)
+    assert (paths[1].begin_extension, paths[1].end_extension) == (0, 2)
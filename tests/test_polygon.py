@@ -801,3 +801,125 @@ def test_ellipse(
 
     assert polygon.layer == layer
     assert polygon.data_type == data_type
+
+
+# Polygon text
+
+
+def test_text_polygons():
+    polygons = Polygon.text("I", 6, layer=2, data_type=3)
+    assert len(polygons) == 1
+    assert polygons[0].bounding_box == ((0.5, 1.5), (3.5, 8.5))
+    assert polygons[0].area == 11
+    assert polygons[0].layer == 2
+    assert polygons[0].data_type == 3
+
+
+def test_text_polygons_one_per_glyph():
+    assert len(Polygon.text("LOT A1", 6)) == 5
+
+
+def test_text_polygons_rotated():
+    polygons = Polygon.text("I", 6, (10, 0), angle=90)
+    assert polygons[0].bounding_box == ((1.5, 0.5), (8.5, 3.5))
+
+
+def test_text_polygons_whitespace_is_empty():
+    assert Polygon.text("   ", 6) == []
+
+
+def test_text_polygons_invalid_layer():
+    with pytest.raises(ValueError):
+        Polygon.text("I", layer=-1)
+
+
+# Polygon boolean
+
+
+def test_polygon_boolean_union():
+    polygon = Polygon([(0, 0), (2, 0), (2, 2), (0, 2)], layer=1, data_type=2)
+    other = Polygon([(1, 1), (3, 1), (3, 3), (1, 3)], layer=5)
+    result = polygon.boolean(other, "union")
+    assert len(result) == 1
+    assert result[0].area == pytest.approx(7)
+    assert result[0].layer == 1
+    assert result[0].data_type == 2
+
+
+def test_polygon_boolean_intersection():
+    polygon = Polygon([(0, 0), (2, 0), (2, 2), (0, 2)])
+    other = Polygon([(1, 1), (3, 1), (3, 3), (1, 3)])
+    result = polygon.boolean(other, "intersection")
+    assert len(result) == 1
+    assert result[0].bounding_box == ((1, 1), (2, 2))
+
+
+def test_polygon_boolean_difference():
+    polygon = Polygon([(0, 0), (2, 0), (2, 2), (0, 2)])
+    other = Polygon([(1, 1), (3, 1), (3, 3), (1, 3)])
+    result = polygon.boolean(other, "difference")
+    assert sum(p.area for p in result) == pytest.approx(3)
+
+
+def test_polygon_boolean_does_not_modify_inputs():
+    polygon = Polygon([(0, 0), (2, 0), (2, 2), (0, 2)])
+    other = Polygon([(1, 1), (3, 1), (3, 3), (1, 3)])
+    polygon.boolean(other, "xor")
+    assert polygon.points == [(0, 0), (2, 0), (2, 2), (0, 2), (0, 0)]
+    assert other.points == [(1, 1), (3, 1), (3, 3), (1, 3), (1, 1)]
+
+
+# Polygon offset
+
+
+def test_polygon_offset_grow():
+    polygon = Polygon([(0, 0), (10, 0), (10, 10), (0, 10)], layer=1, data_type=2)
+    result = polygon.offset(1)
+    assert len(result) == 1
+    assert result[0].bounding_box == ((-1, -1), (11, 11))
+    assert result[0].layer == 1
+    assert result[0].data_type == 2
+
+
+def test_polygon_offset_shrink():
+    polygon = Polygon([(0, 0), (10, 0), (10, 10), (0, 10)])
+    result = polygon.offset(-2, join="round")
+    assert len(result) == 1
+    assert result[0].bounding_box == ((2, 2), (8, 8))
+
+
+def test_polygon_offset_does_not_modify_polygon():
+    polygon = Polygon([(0, 0), (10, 0), (10, 10), (0, 10)])
+    polygon.offset(1)
+    assert polygon.points == [(0, 0), (10, 0), (10, 10), (0, 10), (0, 0)]
+
+
+# Polygon properties
+
+
+def test_polygon_properties_default_empty():
+    assert Polygon([(0, 0), (1, 0), (1, 1)]).properties == {}
+
+
+def test_polygon_init_with_properties():
+    polygon = Polygon([(0, 0), (1, 0), (1, 1)], properties={1: "net", 2: "VDD"})
+    assert polygon.properties == {1: "net", 2: "VDD"}
+
+
+def test_polygon_set_properties():
+    polygon = Polygon([(0, 0), (1, 0), (1, 1)])
+    assert polygon.set_properties({5: "device"}) is polygon
+    assert polygon.properties == {5: "device"}
+    polygon.properties = {6: "other"}
+    assert polygon.properties == {6: "other"}
+
+
+def test_polygon_invalid_property_attribute():
+    with pytest.raises(ValueError, match="Property attribute must be in the range"):
+        Polygon([(0, 0), (1, 0), (1, 1)], properties={-1: "net"})
+
+
+def test_polygon_properties_in_eq():
+    points = [(0, 0), (1, 0), (1, 1)]
+    assert Polygon(points, properties={1: "a"}) != Polygon(points, properties={1: "b"})
+    assert Polygon(points, properties={1: "a"}) == Polygon(points, properties={1: "a"})
@@ -0,0 +1,105 @@
+import pytest
+
+from gdsr import (
+    Cell,
+    GdsCellIterator,
+    GdsElementIterator,
+    Library,
+    Path,
+    Polygon,
+    Reference,
+    Text,
+)
+
+
+@pytest.fixture
+def gds_file() -> str:
+    child = Cell("child")
+    child.add(
+        Polygon([(0, 0), (1, 0), (1, 1)], layer=1),
+        Polygon([(0, 0), (2, 0), (2, 2)], layer=2),
+    )
+    top = Cell("top")
+    top.add(
+        Reference(child),
+        Path([(0, 0), (5, 0)], layer=1, width=1),
+        Text("label", layer=3),
+    )
+    other = Cell("other")
+    other.add(Polygon([(0, 0), (3, 0), (3, 3)], layer=1, data_type=5))
+    library = Library("library")
+    library.add(child, top, other)
+    return library.to_gds()
+
+
+# GdsCellIterator
+
+
+def test_cell_iterator_yields_every_cell(gds_file: str):
+    cells = list(GdsCellIterator(gds_file))
+    assert sorted(cell.name for cell in cells) == ["child", "other", "top"]
+    library = Library.from_gds(gds_file)
+    for cell in cells:
+        expected = library.cells[cell.name]
+        assert cell.polygons == expected.polygons
+        assert cell.paths == expected.paths
+        assert cell.texts == expected.texts
+        # Streamed references point at placeholder cells that only carry the name
+        assert [reference.instance.name for reference in cell.references] == [
+            reference.instance.name for reference in expected.references
+        ]
+
+
+def test_cell_iterator_filters_cells(gds_file: str):
+    cells = list(GdsCellIterator(gds_file, cells=["top"]))
+    assert [cell.name for cell in cells] == ["top"]
+    assert len(cells[0].references) == 1
+
+
+def test_cell_iterator_filters_layers(gds_file: str):
+    cells = {cell.name: cell for cell in GdsCellIterator(gds_file, layers=[(1, 0)])}
+    assert [polygon.layer for polygon in cells["child"].polygons] == [1]
+    assert cells["other"].polygons == []
+    assert len(cells["top"].paths) == 1
+    assert cells["top"].texts == []
+    assert len(cells["top"].references) == 1
+
+
+def test_cell_iterator_is_lazy(gds_file: str):
+    iterator = GdsCellIterator(gds_file)
+    assert iter(iterator) is iterator
+    first = next(iterator)
+    second = next(iterator)
+    third = next(iterator)
+    assert {first.name, second.name, third.name} == {"child", "other", "top"}
+    with pytest.raises(StopIteration):
+        next(iterator)
+
+
+def test_cell_iterator_missing_file():
+    with pytest.raises(OSError):
+        GdsCellIterator("does_not_exist.gds")
+
+
+# GdsElementIterator
+
+
+def test_element_iterator_yields_cell_names(gds_file: str):
+    elements = list(GdsElementIterator(gds_file))
+    assert len(elements) == 6
+    assert sorted(name for name, _ in elements) == [
+        "child",
+        "child",
+        "other",
+        "top",
+        "top",
+        "top",
+    ]
+
+
+def test_element_iterator_filters(gds_file: str):
+    elements = list(GdsElementIterator(gds_file, cells=["child"], layers=[(2, 0)]))
+    assert len(elements) == 1
+    name, element = elements[0]
+    assert name == "child"
+    assert element == Polygon([(0, 0), (2, 0), (2, 2)], layer=2)
@@ -1,7 +1,8 @@
 import hypothesis.strategies as st
+import pytest
 from hypothesis import assume, given, settings
 
-from gdsr import Cell, Element, Grid, Instance, Library, Reference
+from gdsr import Cell, Element, Grid, Instance, Library, Point, Reference, Text
 
 from .conftest import (
     check_references,
@@ -185,6 +186,75 @@ def test_flatten_reference_with_element_depth_one(
     assert all(element in output_cell for element in elements)
 
 
+def test_flatten_composes_nested_grids():
+    inner = Cell("inner")
+    inner.add(Text("A", origin=(1, 0)))
+    middle = Cell("middle")
+    middle.add(Reference(inner, Grid(origin=(10, 0), magnification=2, angle=90)))
+    reference = Reference(middle, Grid(origin=(0, 5), magnification=3, angle=90))
+
+    (text,) = reference.flatten()
+    assert text.origin.is_close(Point(-6, 35))
+    assert text.magnification == 6
+    assert text.angle == 180
+
+
+def test_flatten_keeps_absolute_magnification_and_angle():
+    inner = Cell("inner")
+    inner.add(Text("A", origin=(1, 0)))
+    middle = Cell("middle")
+    middle.add(
+        Reference(
+            inner,
+            Grid(
+                origin=(10, 0),
+                magnification=2,
+                angle=90,
+                absolute_magnification=True,
+                absolute_angle=True,
+            ),
+        )
+    )
+    reference = Reference(middle, Grid(origin=(0, 5), magnification=3, angle=90))
+
+    (text,) = reference.flatten()
+    assert text.origin.is_close(Point(0, 37))
+    assert text.magnification == 2
+    assert text.angle == 90
+
+
+def test_flatten_keeps_absolute_text_transformation():
+    cell = Cell("cell")
+    cell.add(
+        Text(
+            "A",
+            magnification=2,
+            angle=45,
+            absolute_magnification=True,
+            absolute_angle=True,
+        )
+    )
+    reference = Reference(cell, Grid(magnification=3, angle=90))
+
+    (text,) = reference.flatten()
+    assert text.magnification == 2
+    assert text.angle == 45
+
+
+def test_flatten_depth_keeps_absolute_grid():
+    inner = Cell("inner")
+    middle = Cell("middle")
+    middle.add(
+        Reference(inner, Grid(magnification=2, angle=30, absolute_angle=True))
+    )
+    reference = Reference(middle, Grid(magnification=3, angle=90))
+
+    (flattened,) = reference.flatten(depth=1)
+    assert flattened.grid.magnification == 6
+    assert flattened.grid.angle == 30
+    assert flattened.grid.absolute_angle
+
+
 # Reference str
 
 
@@ -255,3 +325,59 @@ def test_cell_with_element_same_as_element(element: Element, grid: Grid):
     cell_with_element_reference.flatten()
 
     assert cell_with_cell_reference == cell_with_element_reference
+
+
+def test_read_write_absolute_flags():
+    inner = Cell("inner")
+    cell = Cell("cell")
+    cell.add(
+        Reference(
+            inner,
+            Grid(magnification=2, absolute_magnification=True, absolute_angle=True),
+        )
+    )
+    library = Library("library")
+    library.add(inner, cell)
+
+    new_library = Library.from_gds(library.to_gds())
+
+    grid = new_library.cells["cell"].references[0].grid
+    assert grid.absolute_magnification
+    assert grid.absolute_angle
+    assert library == new_library
+
+
+# Reference properties
+
+
+def test_reference_properties_default_empty():
+    assert Reference(Cell("cell")).properties == {}
+
+
+def test_reference_init_with_properties():
+    reference = Reference(Cell("cell"), properties={1: "instance"})
+    assert reference.properties == {1: "instance"}
+
+
+def test_reference_set_properties():
+    reference = Reference(Cell("cell"))
+    reference.properties = {2: "X1"}
+    assert reference.properties == {2: "X1"}
+    assert reference.set_properties({3: "X2"}) is reference
+    assert reference.properties == {3: "X2"}
+
+
+def test_reference_set_properties_invalid():
+    reference = Reference(Cell("cell"))
+    with pytest.raises(ValueError, match="Property attribute must be in the range"):
+        reference.set_properties({-1: "X1"})
+
+
+def test_reference_invalid_property_attribute():
+    with pytest.raises(ValueError, match="Property attribute must be in the range"):
+        Reference(Cell("cell"), properties={-1: "instance"})
+
+
+def test_reference_copy_keeps_properties():
+    reference = Reference(Cell("cell"), properties={1: "instance"})
+    assert reference.copy().properties == {1: "instance"}
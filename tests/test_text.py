@@ -2,9 +2,23 @@ import hypothesis.strategies as st
 import pytest
 from hypothesis import assume, given
 
-from gdsr import HorizontalPresentation, Point, Text, VerticalPresentation
+from gdsr import (
+    Cell,
+    HorizontalPresentation,
+    Library,
+    Point,
+    Polygon,
+    Text,
+    VerticalPresentation,
+)
 
-from .conftest import float_strategy, layer_strategy, point_strategy, text_strategy
+from .conftest import (
+    data_type_strategy,
+    float_strategy,
+    layer_strategy,
+    point_strategy,
+    text_strategy,
+)
 
 # Text init
 
@@ -13,6 +27,7 @@ from .conftest import float_strategy, layer_strategy, point_strategy, text_strat
     text=st.text(),
     origin=point_strategy(),
     layer=layer_strategy(),
+    text_type=data_type_strategy(),
     magnification=float_strategy(),
     angle=float_strategy(),
     x_reflection=st.booleans(),
@@ -23,6 +38,7 @@ def test_text_init_with_all_parameters(
     text: str,
     origin: Point,
     layer: int,
+    text_type: int,
     magnification: float,
     angle: float,
     x_reflection: bool,
@@ -33,6 +49,7 @@ def test_text_init_with_all_parameters(
         text,
         origin=origin,
         layer=layer,
+        text_type=text_type,
         magnification=magnification,
         angle=angle,
         x_reflection=x_reflection,
@@ -42,6 +59,7 @@ def test_text_init_with_all_parameters(
     assert text_obj.text == text
     assert text_obj.origin == origin
     assert text_obj.layer == layer
+    assert text_obj.text_type == text_type
     assert text_obj.magnification == magnification
     assert text_obj.angle == angle
     assert text_obj.x_reflection == x_reflection
@@ -103,6 +121,43 @@ def test_text_layer_setter_method(layer: int):
     assert new_text is text
 
 
+def test_text_text_type_defaults_to_zero():
+    assert Text("Hello, World!").text_type == 0
+
+
+def test_text_text_type_setter():
+    text = Text("Hello, World!", text_type=3)
+    assert text.text_type == 3
+    text.text_type = 5
+    assert text.text_type == 5
+
+
+def test_text_text_type_setter_method():
+    text = Text("Hello, World!")
+    new_text = text.set_text_type(7)
+    assert text.text_type == 7
+    assert new_text is text
+
+
+def test_text_eq_compares_text_type():
+    assert Text("Hello", text_type=1) != Text("Hello", text_type=2)
+
+
+# Text is_on
+
+
+def test_text_is_on_matches_layer_and_text_type():
+    text = Text("Hello", layer=1, text_type=2)
+    assert text.is_on((1, 2))
+    assert text.is_on((3, 0), (1, 2))
+    assert not text.is_on((1, 0))
+    assert not text.is_on((2, 2))
+
+
+def test_text_is_on_without_pairs():
+    assert Text("Hello", layer=1, text_type=2).is_on()
+
+
 @given(magnification=float_strategy())
 def test_text_magnification_setter(magnification: float):
     text = Text("Hello, World!")
@@ -300,3 +355,185 @@ def test_move_by_returns_self():
     assert text is new_text
     assert text == new_text
     assert text.origin == (1, 1)
+
+
+# Text properties
+
+
+def test_text_properties_default_empty():
+    assert Text("Hello").properties == {}
+
+
+def test_text_init_with_properties():
+    text = Text("Hello", properties={1: "label"})
+    assert text.properties == {1: "label"}
+
+
+def test_text_set_properties():
+    text = Text("Hello")
+    assert text.set_properties({3: "pin"}) is text
+    assert text.properties == {3: "pin"}
+
+
+def test_text_invalid_property_attribute():
+    with pytest.raises(ValueError, match="Property attribute must be in the range"):
+        Text("Hello", properties={-5: "label"})
+
+
+def test_text_properties_in_eq():
+    assert Text("Hello", properties={1: "a"}) != Text("Hello")
+
+
+# Text bounding box
+
+
+def test_text_bounding_box_is_centred_by_default():
+    assert Text("AB", magnification=6).bounding_box == ((-6, -5), (6, 5))
+    assert Text("AB", magnification=3).bounding_box == ((-3, -2.5), (3, 2.5))
+
+
+def test_text_bounding_box_left_bottom():
+    text = Text(
+        "AB",
+        (10, 20),
+        magnification=6,
+        vertical_presentation=VerticalPresentation.Bottom,
+        horizontal_presentation=HorizontalPresentation.Left,
+    )
+    assert text.bounding_box == ((9, 19), (21, 29))
+
+
+def test_text_bounding_box_right_top():
+    text = Text(
+        "AB",
+        magnification=6,
+        vertical_presentation=VerticalPresentation.Top,
+        horizontal_presentation=HorizontalPresentation.Right,
+    )
+    assert text.bounding_box == ((-11, -9), (1, 1))
+
+
+def test_text_bounding_box_rotated():
+    text = Text(
+        "AB",
+        magnification=6,
+        angle=90,
+        vertical_presentation=VerticalPresentation.Bottom,
+        horizontal_presentation=HorizontalPresentation.Left,
+    )
+    assert text.bounding_box == ((-9, -1), (1, 11))
+
+
+def test_text_bounding_box_x_reflection():
+    text = Text(
+        "AB",
+        magnification=6,
+        x_reflection=True,
+        vertical_presentation=VerticalPresentation.Bottom,
+        horizontal_presentation=HorizontalPresentation.Left,
+    )
+    assert text.bounding_box == ((-1, -9), (11, 1))
+
+
+def test_text_bounding_box_multiple_lines():
+    text = Text(
+        "A\nBC",
+        magnification=6,
+        vertical_presentation=VerticalPresentation.Top,
+        horizontal_presentation=HorizontalPresentation.Left,
+    )
+    assert text.bounding_box == ((-1, -19), (11, 1))
+
+
+def test_text_bounding_box_in_cell():
+    cell = Cell("cell")
+    cell.add(Text("AB", (100, 100), magnification=6))
+    assert cell.bounding_box() == ((94, 95), (106, 105))
+
+
+def test_text_bounding_box_contains_polygons():
+    printable = "".join(chr(code) for code in range(32, 127))
+    text = Text(printable + "\n" + printable[::-1], magnification=6, angle=30)
+    (min_x, min_y), (max_x, max_y) = text.bounding_box
+    for polygon in text.to_polygons():
+        (lower_x, lower_y), (upper_x, upper_y) = polygon.bounding_box
+        assert min_x <= lower_x and min_y <= lower_y
+        assert upper_x <= max_x and upper_y <= max_y
+
+
+# Text to polygons
+
+
+def test_text_to_polygons_follows_presentation():
+    polygons = Text("I", magnification=6).to_polygons()
+    assert len(polygons) == 1
+    assert polygons[0].bounding_box == ((-1.5, -2.5), (1.5, 4.5))
+
+
+def test_text_to_polygons_keeps_layer_and_properties():
+    text = Text("I", layer=4, text_type=5, properties={1: "label"})
+    polygon = text.to_polygons()[0]
+    assert polygon.layer == 4
+    assert polygon.data_type == 5
+    assert polygon.properties == {1: "label"}
+
+
+def test_text_to_polygons_width():
+    polygon = Text("I", magnification=6).to_polygons(width=2)[0]
+    assert polygon.area == 24
+
+
+def test_text_to_polygons_multiple_lines():
+    assert len(Text("I\nI", magnification=6).to_polygons()) == 2
+
+
+def test_text_to_polygons_empty():
+    assert Text("").to_polygons() == []
+
+
+def test_text_to_polygons_is_on_the_grid():
+    text = Text("HI", magnification=3, angle=30, origin=(1000.05, 0))
+    for polygon in text.to_polygons():
+        for point in polygon.points:
+            assert point.x == round(point.x, 4)
+            assert point.y == round(point.y, 4)
+
+
+def test_text_to_polygons_snaps_to_the_given_grid():
+    text = Text("I", magnification=6, origin=(0.3, 0))
+    assert text.to_polygons(grid=1)[0].bounding_box == ((-1, -3), (2, 5))
+
+
+def test_text_to_polygons_grid_invalid():
+    with pytest.raises(ValueError, match="Grid must be positive"):
+        Text("I").to_polygons(grid=0)
+
+
+def test_polygon_text_snaps_to_the_given_grid():
+    polygon = Polygon.text("I", size=6, origin=(0.3, 0), grid=1)[0]
+    assert polygon.bounding_box == ((1, 1), (4, 9))
+
+
+# Text absolute transformation
+
+
+def test_text_absolute_flags_default_false():
+    text = Text("Hello")
+    assert not text.absolute_magnification
+    assert not text.absolute_angle
+
+
+def test_text_set_absolute_flags():
+    text = Text("Hello")
+    assert text.set_absolute_magnification(True) is text
+    assert text.set_absolute_angle(True) is text
+    assert text == Text("Hello", absolute_magnification=True, absolute_angle=True)
+    assert text != Text("Hello")
+
+
+def test_text_absolute_flags_read_write():
+    cell = Cell("cell")
+    cell.add(Text("Hello", absolute_magnification=True, absolute_angle=True))
+    new_cell = Library.from_gds(cell.to_gds()).cells["cell"]
+    assert new_cell.texts[0].absolute_magnification
+    assert new_cell.texts[0].absolute_angle